@@ -0,0 +1,55 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use gammar::{
+    backend::mock::{monitor, MockBackend},
+    windows::display::DisplaySettings,
+    worker::{ApplyPriority, ApplyRequest, ApplyWorker, HardwareControl},
+};
+use std::sync::Arc;
+
+fn bench_apply_worker(c: &mut Criterion) {
+    let worker = ApplyWorker::spawn(Arc::new(MockBackend::multi()));
+    let monitors = ["1", "2", "3"].map(|id| monitor(id, id, id == "1"));
+
+    let mut group = c.benchmark_group("apply_worker");
+
+    group.bench_function("enqueue_flush", |b| {
+        b.iter(|| {
+            worker.enqueue(ApplyRequest {
+                monitor: monitors[0].clone(),
+                settings: DisplaySettings::new(1.2, 0.0, 1.0),
+                priority: ApplyPriority::High,
+                transition: None,
+                hardware_control: HardwareControl::None,
+                lut: None,
+                verify: false,
+            });
+            worker.flush();
+        })
+    });
+
+    // A burst of slider updates across monitors, most of which coalesce
+    group.bench_function("burst_coalesced", |b| {
+        b.iter(|| {
+            for step in 0..30 {
+                worker.enqueue(ApplyRequest {
+                    monitor: monitors[step % monitors.len()].clone(),
+                    settings: DisplaySettings::new(1.0 + step as f32 * 0.01, 0.0, 1.0),
+                    priority: ApplyPriority::Normal,
+                    transition: None,
+                    hardware_control: HardwareControl::None,
+                    lut: None,
+                    verify: false,
+                });
+            }
+            worker.flush();
+        })
+    });
+
+    group.finish();
+
+    // Results accumulate while benchmarking; drop them
+    worker.poll_results();
+}
+
+criterion_group!(benches, bench_apply_worker);
+criterion_main!(benches);
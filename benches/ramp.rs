@@ -0,0 +1,24 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use gammar::windows::display::{compute_gamma_ramp, DisplaySettings};
+use std::hint::black_box;
+
+fn bench_compute_gamma_ramp(c: &mut Criterion) {
+    let cases = [
+        ("identity", DisplaySettings::default()),
+        ("gamma", DisplaySettings::new(2.2, 0.0, 1.0)),
+        ("clipping", DisplaySettings::new(0.6, 0.4, 2.5)),
+    ];
+
+    let mut group = c.benchmark_group("compute_gamma_ramp");
+
+    for (name, settings) in cases {
+        group.bench_with_input(BenchmarkId::from_parameter(name), &settings, |b, s| {
+            b.iter(|| compute_gamma_ramp(black_box(s)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_compute_gamma_ramp);
+criterion_main!(benches);
@@ -5,3 +5,6 @@ fn main() {
 
     res.compile().unwrap();
 }
+
+#[cfg(not(windows))]
+fn main() {}
@@ -0,0 +1,181 @@
+use crate::windows::display::{
+    compute_gamma_ramp, DisplaySettings, GammaError, GammaRamp, MonitorInfo,
+};
+use std::collections::HashMap;
+
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+
+/// Where the machine draws its power from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerSource {
+    Ac,
+    Battery,
+}
+
+/// Platform interface for enumerating monitors and writing gamma ramps to them.
+pub trait GammaBackend: Send + Sync {
+    /// List the monitors currently attached, primary first.
+    fn enumerate_monitors(&self) -> Vec<MonitorInfo>;
+
+    /// Write a gamma ramp to the given monitor.
+    fn apply_ramp(&self, monitor: &MonitorInfo, ramp: &GammaRamp) -> Result<(), GammaError>;
+
+    /// Write ramps to several monitors, returning each monitor's result by id. Backends that can
+    /// write them concurrently do, so every monitor changes in the same frame.
+    fn apply_ramps(
+        &self,
+        batch: &[(&MonitorInfo, &GammaRamp)],
+    ) -> HashMap<String, Result<(), GammaError>> {
+        batch
+            .iter()
+            .map(|(monitor, ramp)| (monitor.id.clone(), self.apply_ramp(monitor, ramp)))
+            .collect()
+    }
+
+    /// Read the gamma ramp currently loaded for the given monitor.
+    fn read_ramp(&self, monitor: &MonitorInfo) -> Result<GammaRamp, GammaError> {
+        Err(GammaError::Unsupported(format!(
+            "Reading the gamma ramp of {} is not supported",
+            monitor.name
+        )))
+    }
+
+    /// Check whether the monitor's own brightness and contrast controls can be driven, e.g. over
+    /// DDC/CI.
+    fn supports_hardware_control(&self, _monitor: &MonitorInfo) -> bool {
+        false
+    }
+
+    /// Write brightness and contrast to the monitor's own controls.
+    fn apply_hardware(
+        &self,
+        monitor: &MonitorInfo,
+        _settings: &DisplaySettings,
+    ) -> Result<(), GammaError> {
+        Err(GammaError::Unsupported(format!(
+            "Hardware control is not supported for {}",
+            monitor.name
+        )))
+    }
+
+    /// Check whether ramps for the monitor go to every monitor at once, because only the generic
+    /// display device accepted them.
+    fn applied_globally(&self, _monitor: &MonitorInfo) -> bool {
+        false
+    }
+
+    /// Allow or forbid applying through the generic display device when a monitor's own device
+    /// can't be opened.
+    fn set_global_fallback(&self, _enabled: bool) {}
+
+    /// Read the monitor's MCCS capabilities string over DDC/CI.
+    fn read_capabilities(&self, monitor: &MonitorInfo) -> Result<String, GammaError> {
+        Err(GammaError::Unsupported(format!(
+            "Reading the capabilities of {} is not supported",
+            monitor.name
+        )))
+    }
+
+    /// Check whether the monitor's backlight can be set directly, as on most laptop panels.
+    fn supports_backlight(&self, _monitor: &MonitorInfo) -> bool {
+        false
+    }
+
+    /// Set the monitor's backlight from the brightness setting.
+    fn apply_backlight(
+        &self,
+        monitor: &MonitorInfo,
+        _settings: &DisplaySettings,
+    ) -> Result<(), GammaError> {
+        Err(GammaError::Unsupported(format!(
+            "Backlight control is not supported for {}",
+            monitor.name
+        )))
+    }
+
+    /// Check whether ramps far from identity are accepted. Only Windows restricts them, unless
+    /// the `GdiIcmGammaRange` registry value is set.
+    fn gamma_range_unlocked(&self) -> bool {
+        true
+    }
+
+    /// Check whether this is a remote desktop session, where gamma ramps can't be set.
+    fn is_remote_session(&self) -> bool {
+        false
+    }
+
+    /// Get the power source, or `None` on machines without a battery or when it's unknown.
+    fn power_source(&self) -> Option<PowerSource> {
+        None
+    }
+
+    /// Get the mouse cursor's position on the virtual desktop, if it's known.
+    fn cursor_position(&self) -> Option<(i32, i32)> {
+        None
+    }
+
+    /// Lift the restriction on ramps far from identity. Takes effect after a reboot.
+    fn unlock_gamma_range(&self) -> Result<(), GammaError> {
+        Err(GammaError::Unsupported(
+            "The gamma range can't be unlocked on this platform".to_string(),
+        ))
+    }
+
+    /// Compute the ramp for the given settings and write it to the monitor.
+    fn apply_settings(
+        &self,
+        monitor: &MonitorInfo,
+        settings: &DisplaySettings,
+    ) -> Result<(), GammaError> {
+        self.apply_ramp(monitor, &compute_gamma_ramp(settings))
+    }
+}
+
+/// Backend used when gamma control isn't available, e.g. on unsupported platforms or under
+/// Wayland. It reports no monitors and fails every apply with the given reason.
+pub struct UnsupportedBackend {
+    reason: String,
+}
+
+impl UnsupportedBackend {
+    /// Create a new UnsupportedBackend explaining why gamma control isn't available.
+    pub fn new(reason: impl Into<String>) -> Self {
+        Self {
+            reason: reason.into(),
+        }
+    }
+}
+
+impl GammaBackend for UnsupportedBackend {
+    fn enumerate_monitors(&self) -> Vec<MonitorInfo> {
+        Vec::new()
+    }
+
+    fn apply_ramp(&self, _monitor: &MonitorInfo, _ramp: &GammaRamp) -> Result<(), GammaError> {
+        Err(GammaError::Unsupported(self.reason.clone()))
+    }
+}
+
+/// Get the backend for the platform the application is running on.
+pub fn platform_backend() -> Box<dyn GammaBackend> {
+    #[cfg(windows)]
+    {
+        Box::new(crate::windows::display::WindowsBackend)
+    }
+
+    #[cfg(all(target_os = "linux", feature = "x11"))]
+    {
+        match crate::linux::display::XRandrBackend::connect() {
+            Ok(backend) => Box::new(backend),
+            Err(e) => Box::new(UnsupportedBackend::new(e.to_string())),
+        }
+    }
+
+    #[cfg(not(any(windows, all(target_os = "linux", feature = "x11"))))]
+    {
+        Box::new(UnsupportedBackend::new(
+            "Gamma control is not supported on this platform",
+        ))
+    }
+}
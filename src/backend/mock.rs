@@ -0,0 +1,399 @@
+use crate::{
+    backend::{GammaBackend, PowerSource},
+    windows::{
+        backlight,
+        display::{
+            compute_gamma_ramp, DisplayMode, DisplaySettings, GammaError, GammaRamp, MonitorInfo,
+            MonitorRect,
+        },
+        icm,
+    },
+};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+/// Scriptable backend for tests and benchmarks.
+///
+/// Clones share state, so a test can keep a handle after boxing one into a controller and use
+/// it to change the topology or inspect the applied ramps.
+#[derive(Clone, Default)]
+pub struct MockBackend {
+    state: Arc<Mutex<MockState>>,
+}
+
+#[derive(Default)]
+struct MockState {
+    monitors: Vec<MonitorInfo>,
+    pending: VecDeque<Vec<MonitorInfo>>,
+    failing: HashSet<String>,
+    applied: Vec<(String, GammaRamp)>,
+    hardware: HashMap<String, GammaRamp>,
+    ddc: HashSet<String>,
+    ddc_applied: Vec<(String, DisplaySettings)>,
+    capabilities: HashMap<String, String>,
+    capability_reads: usize,
+    backlight: HashSet<String>,
+    backlight_applied: Vec<(String, u8)>,
+    range_restricted: bool,
+    unlock_requests: usize,
+    remote_session: bool,
+    power_source: Option<PowerSource>,
+    cursor_position: Option<(i32, i32)>,
+    ignoring: HashSet<String>,
+    global_only: HashSet<String>,
+    global_fallback_disabled: bool,
+    batches: Vec<usize>,
+}
+
+impl MockBackend {
+    /// Create a new MockBackend reporting the given monitors.
+    pub fn new(monitors: Vec<MonitorInfo>) -> Self {
+        let backend = Self::default();
+        backend.set_monitors(monitors);
+        backend
+    }
+
+    /// A single primary monitor.
+    pub fn single() -> Self {
+        Self::new(vec![monitor("1", "\\\\.\\DISPLAY1", true)])
+    }
+
+    /// A primary monitor and two secondary monitors.
+    pub fn multi() -> Self {
+        Self::new(vec![
+            monitor("1", "\\\\.\\DISPLAY1", true),
+            monitor("2", "\\\\.\\DISPLAY2", false),
+            monitor("3", "\\\\.\\DISPLAY3", false),
+        ])
+    }
+
+    /// Two monitors, neither of which is flagged as primary.
+    pub fn primary_missing() -> Self {
+        Self::new(vec![
+            monitor("1", "\\\\.\\DISPLAY1", false),
+            monitor("2", "\\\\.\\DISPLAY2", false),
+        ])
+    }
+
+    /// Two monitors with different ids sharing one device name, as happens when displays are
+    /// cloned.
+    pub fn duplicate_device_names() -> Self {
+        Self::new(vec![
+            monitor("1", "\\\\.\\DISPLAY1", true),
+            monitor("2", "\\\\.\\DISPLAY1", false),
+        ])
+    }
+
+    /// Replace the current topology.
+    pub fn set_monitors(&self, monitors: Vec<MonitorInfo>) {
+        let mut state = self.state.lock().unwrap();
+
+        state.monitors = monitors;
+        state.pending.clear();
+    }
+
+    /// Queue topologies that successive enumerations report, simulating hot-plug events. The
+    /// last queued topology keeps being reported once the queue is drained.
+    pub fn queue_topologies(&self, topologies: impl IntoIterator<Item = Vec<MonitorInfo>>) {
+        self.state.lock().unwrap().pending.extend(topologies);
+    }
+
+    /// Make every apply to the given monitor id fail.
+    pub fn fail_monitor(&self, id: &str) {
+        self.state.lock().unwrap().failing.insert(id.to_string());
+    }
+
+    /// Get every ramp applied so far along with the id of the monitor it was applied to.
+    pub fn applied(&self) -> Vec<(String, GammaRamp)> {
+        self.state.lock().unwrap().applied.clone()
+    }
+
+    /// Get the number of applies performed so far.
+    pub fn apply_count(&self) -> usize {
+        self.state.lock().unwrap().applied.len()
+    }
+
+    /// Make the given monitor id support hardware brightness and contrast control.
+    pub fn enable_ddc(&self, id: &str) {
+        self.state.lock().unwrap().ddc.insert(id.to_string());
+    }
+
+    /// Get every hardware apply performed so far along with the id of the monitor.
+    pub fn ddc_applied(&self) -> Vec<(String, DisplaySettings)> {
+        self.state.lock().unwrap().ddc_applied.clone()
+    }
+
+    /// Get the number of monitors in each batch applied so far.
+    pub fn batch_sizes(&self) -> Vec<usize> {
+        self.state.lock().unwrap().batches.clone()
+    }
+
+    /// Make the given monitor id only accept ramps through the generic display device, which
+    /// writes them to every monitor.
+    pub fn require_global_dc(&self, id: &str) {
+        self.state
+            .lock()
+            .unwrap()
+            .global_only
+            .insert(id.to_string());
+    }
+
+    /// Make the given monitor id reply with a capabilities string. Others time out.
+    pub fn set_capabilities(&self, id: &str, capabilities: &str) {
+        let mut state = self.state.lock().unwrap();
+        state
+            .capabilities
+            .insert(id.to_string(), capabilities.to_string());
+    }
+
+    /// Get the number of capabilities strings read so far.
+    pub fn capability_reads(&self) -> usize {
+        self.state.lock().unwrap().capability_reads
+    }
+
+    /// Make the given monitor id report a settable backlight.
+    pub fn enable_backlight(&self, id: &str) {
+        self.state.lock().unwrap().backlight.insert(id.to_string());
+    }
+
+    /// Get every backlight percentage set so far along with the id of the monitor.
+    pub fn backlight_applied(&self) -> Vec<(String, u8)> {
+        self.state.lock().unwrap().backlight_applied.clone()
+    }
+
+    /// Reject ramps outside the default range, like Windows without `GdiIcmGammaRange`.
+    pub fn restrict_gamma_range(&self) {
+        self.state.lock().unwrap().range_restricted = true;
+    }
+
+    /// Get the number of times unlocking the gamma range was requested.
+    pub fn unlock_requests(&self) -> usize {
+        self.state.lock().unwrap().unlock_requests
+    }
+
+    /// Make the given monitor accept ramps without loading them, like some buggy drivers.
+    pub fn ignore_ramps(&self, id: &str) {
+        self.state.lock().unwrap().ignoring.insert(id.to_string());
+    }
+
+    /// Pretend the session moved to or from remote desktop.
+    pub fn set_remote_session(&self, remote: bool) {
+        self.state.lock().unwrap().remote_session = remote;
+    }
+
+    /// Pretend the machine switched power source, or has no battery with `None`.
+    pub fn set_power_source(&self, source: Option<PowerSource>) {
+        self.state.lock().unwrap().power_source = source;
+    }
+
+    /// Pretend the mouse cursor moved to a point on the virtual desktop.
+    pub fn set_cursor_position(&self, x: i32, y: i32) {
+        self.state.lock().unwrap().cursor_position = Some((x, y));
+    }
+
+    /// Pretend another tool loaded the given ramp on a monitor.
+    pub fn set_hardware_ramp(&self, id: &str, ramp: GammaRamp) {
+        self.state
+            .lock()
+            .unwrap()
+            .hardware
+            .insert(id.to_string(), ramp);
+    }
+}
+
+impl GammaBackend for MockBackend {
+    fn enumerate_monitors(&self) -> Vec<MonitorInfo> {
+        let mut state = self.state.lock().unwrap();
+
+        if let Some(next) = state.pending.pop_front() {
+            state.monitors = next;
+        }
+
+        state.monitors.clone()
+    }
+
+    fn apply_ramp(&self, monitor: &MonitorInfo, ramp: &GammaRamp) -> Result<(), GammaError> {
+        let mut state = self.state.lock().unwrap();
+
+        if state.failing.contains(&monitor.id) {
+            // ERROR_GEN_FAILURE, which drivers commonly report for rejected ramps
+            return Err(GammaError::RampRejected {
+                monitor: monitor.name.clone(),
+                win32: 31,
+            });
+        }
+
+        if state.range_restricted && icm::exceeds_default_range(ramp) {
+            return Err(icm::range_restricted_error(monitor));
+        }
+
+        state.applied.push((monitor.id.clone(), *ramp));
+
+        if state.global_only.contains(&monitor.id) {
+            if state.global_fallback_disabled {
+                state.applied.pop();
+                return Err(GammaError::DcCreationFailed {
+                    monitor: monitor.name.clone(),
+                    device: monitor.device_name.clone(),
+                    adapter: monitor.adapter.clone(),
+                });
+            }
+
+            let ids: Vec<String> = state.monitors.iter().map(|m| m.id.clone()).collect();
+            for id in ids {
+                state.hardware.insert(id, *ramp);
+            }
+
+            return Ok(());
+        }
+
+        if !state.ignoring.contains(&monitor.id) {
+            state.hardware.insert(monitor.id.clone(), *ramp);
+        }
+
+        Ok(())
+    }
+
+    fn apply_ramps(
+        &self,
+        batch: &[(&MonitorInfo, &GammaRamp)],
+    ) -> HashMap<String, Result<(), GammaError>> {
+        self.state.lock().unwrap().batches.push(batch.len());
+
+        batch
+            .iter()
+            .map(|(monitor, ramp)| (monitor.id.clone(), self.apply_ramp(monitor, ramp)))
+            .collect()
+    }
+
+    fn gamma_range_unlocked(&self) -> bool {
+        !self.state.lock().unwrap().range_restricted
+    }
+
+    fn is_remote_session(&self) -> bool {
+        self.state.lock().unwrap().remote_session
+    }
+
+    fn power_source(&self) -> Option<PowerSource> {
+        self.state.lock().unwrap().power_source
+    }
+
+    fn cursor_position(&self) -> Option<(i32, i32)> {
+        self.state.lock().unwrap().cursor_position
+    }
+
+    fn unlock_gamma_range(&self) -> Result<(), GammaError> {
+        // Like the registry value, this only takes effect after a reboot
+        self.state.lock().unwrap().unlock_requests += 1;
+
+        Ok(())
+    }
+
+    fn supports_hardware_control(&self, monitor: &MonitorInfo) -> bool {
+        self.state.lock().unwrap().ddc.contains(&monitor.id)
+    }
+
+    fn apply_hardware(
+        &self,
+        monitor: &MonitorInfo,
+        settings: &DisplaySettings,
+    ) -> Result<(), GammaError> {
+        let mut state = self.state.lock().unwrap();
+
+        if !state.ddc.contains(&monitor.id) || state.failing.contains(&monitor.id) {
+            return Err(GammaError::Ddc {
+                monitor: monitor.name.clone(),
+                action: "set brightness".to_string(),
+                detail: "no response".to_string(),
+            });
+        }
+
+        state.ddc_applied.push((monitor.id.clone(), *settings));
+
+        Ok(())
+    }
+
+    fn read_capabilities(&self, monitor: &MonitorInfo) -> Result<String, GammaError> {
+        let mut state = self.state.lock().unwrap();
+        state.capability_reads += 1;
+
+        state
+            .capabilities
+            .get(&monitor.id)
+            .cloned()
+            .ok_or_else(|| GammaError::Ddc {
+                monitor: monitor.name.clone(),
+                action: "read capabilities".to_string(),
+                detail: "no response".to_string(),
+            })
+    }
+
+    fn applied_globally(&self, monitor: &MonitorInfo) -> bool {
+        let state = self.state.lock().unwrap();
+
+        state.global_only.contains(&monitor.id) && !state.global_fallback_disabled
+    }
+
+    fn set_global_fallback(&self, enabled: bool) {
+        self.state.lock().unwrap().global_fallback_disabled = !enabled;
+    }
+
+    fn supports_backlight(&self, monitor: &MonitorInfo) -> bool {
+        self.state.lock().unwrap().backlight.contains(&monitor.id)
+    }
+
+    fn apply_backlight(
+        &self,
+        monitor: &MonitorInfo,
+        settings: &DisplaySettings,
+    ) -> Result<(), GammaError> {
+        let mut state = self.state.lock().unwrap();
+
+        if !state.backlight.contains(&monitor.id) || state.failing.contains(&monitor.id) {
+            return Err(GammaError::Backlight {
+                monitor: monitor.name.clone(),
+                detail: "no response".to_string(),
+            });
+        }
+
+        let percent = backlight::brightness_to_percent(settings.brightness);
+        state.backlight_applied.push((monitor.id.clone(), percent));
+
+        Ok(())
+    }
+
+    fn read_ramp(&self, monitor: &MonitorInfo) -> Result<GammaRamp, GammaError> {
+        let state = self.state.lock().unwrap();
+
+        // Monitors nothing was applied to report the identity ramp, like a fresh session
+        Ok(state
+            .hardware
+            .get(&monitor.id)
+            .copied()
+            .unwrap_or_else(|| compute_gamma_ramp(&DisplaySettings::default())))
+    }
+}
+
+/// Build a monitor description for fixtures.
+pub fn monitor(id: &str, device_name: &str, is_primary: bool) -> MonitorInfo {
+    MonitorInfo {
+        id: id.to_string(),
+        name: if is_primary {
+            format!("{} (Primary)", device_name)
+        } else {
+            device_name.to_string()
+        },
+        device_name: device_name.to_string(),
+        is_primary,
+        legacy_id: None,
+        bounds: MonitorRect::default(),
+        work_area: MonitorRect::default(),
+        dpi: 0,
+        mode: DisplayMode::default(),
+        is_hdr: false,
+        supports_gamma: true,
+        adapter: String::new(),
+    }
+}
@@ -0,0 +1,126 @@
+use crate::windows::display::DisplaySettings;
+
+/// A step of the guided calibration, in the order they're walked through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalibrationStep {
+    /// Lift or lower black until the darkest patches can just be told apart.
+    BlackLevel,
+    /// Raise or lower contrast until the brightest patches can just be told apart.
+    WhiteLevel,
+    /// Adjust gamma until a solid grey matches a pattern of black and white lines.
+    Gamma,
+    /// Review the result and save it as a profile.
+    Finish,
+}
+
+impl CalibrationStep {
+    pub const ALL: [CalibrationStep; 4] = [
+        CalibrationStep::BlackLevel,
+        CalibrationStep::WhiteLevel,
+        CalibrationStep::Gamma,
+        CalibrationStep::Finish,
+    ];
+
+    /// Get the step's position, counting from 0.
+    pub fn index(&self) -> usize {
+        Self::ALL.iter().position(|s| s == self).unwrap_or_default()
+    }
+
+    /// Get the following step, if any.
+    pub fn next(&self) -> Option<Self> {
+        Self::ALL.get(self.index() + 1).copied()
+    }
+
+    /// Get the preceding step, if any.
+    pub fn previous(&self) -> Option<Self> {
+        self.index().checked_sub(1).map(|i| Self::ALL[i])
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            CalibrationStep::BlackLevel => "Black level",
+            CalibrationStep::WhiteLevel => "White level",
+            CalibrationStep::Gamma => "Gamma",
+            CalibrationStep::Finish => "Done",
+        }
+    }
+
+    pub fn instructions(&self) -> &'static str {
+        match self {
+            CalibrationStep::BlackLevel => {
+                "Each patch is a little lighter than the one before. Make it lighter until the \
+                 second patch is just visible against the first, then darker until it almost \
+                 disappears again"
+            }
+            CalibrationStep::WhiteLevel => {
+                "Each patch is a little darker than the last, which is pure white. Make it darker \
+                 until every patch can be told apart from its neighbor"
+            }
+            CalibrationStep::Gamma => {
+                "Step back or squint. Make the picture lighter or darker until the solid grey in \
+                 the middle blends in with the striped surround"
+            }
+            CalibrationStep::Finish => {
+                "Your display is calibrated. Save the result as a profile to switch back to it at \
+                 any time"
+            }
+        }
+    }
+
+    /// Nudge the setting this step controls, making the picture lighter or darker.
+    pub fn adjust(&self, settings: DisplaySettings, lighter: bool) -> DisplaySettings {
+        let direction = if lighter { 1.0 } else { -1.0 };
+
+        match self {
+            CalibrationStep::BlackLevel => DisplaySettings {
+                brightness: (settings.brightness + 0.01 * direction).clamp(-1.0, 1.0),
+                ..settings
+            },
+            // Contrast scales around the pivot, so reducing it pulls white back from clipping
+            CalibrationStep::WhiteLevel => DisplaySettings {
+                contrast: (settings.contrast + 0.02 * direction).clamp(0.1, 3.0),
+                ..settings
+            },
+            CalibrationStep::Gamma => DisplaySettings {
+                gamma: (settings.gamma + 0.02 * direction).clamp(0.1, 3.0),
+                ..settings
+            },
+            CalibrationStep::Finish => settings,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_run_in_order() {
+        assert_eq!(CalibrationStep::BlackLevel.previous(), None);
+        assert_eq!(
+            CalibrationStep::BlackLevel.next(),
+            Some(CalibrationStep::WhiteLevel)
+        );
+        assert_eq!(
+            CalibrationStep::Finish.previous(),
+            Some(CalibrationStep::Gamma)
+        );
+        assert_eq!(CalibrationStep::Finish.next(), None);
+    }
+
+    #[test]
+    fn each_step_adjusts_its_setting() {
+        let settings = DisplaySettings::default();
+
+        let black = CalibrationStep::BlackLevel.adjust(settings, true);
+        assert!(black.brightness > 0.0 && black.gamma == 1.0);
+
+        let white = CalibrationStep::WhiteLevel.adjust(settings, false);
+        assert!(white.contrast < 1.0 && white.brightness == 0.0);
+
+        let gamma = CalibrationStep::Gamma.adjust(DisplaySettings::new(3.0, 0.0, 1.0), true);
+        assert_eq!(gamma.gamma, 3.0);
+
+        assert_eq!(CalibrationStep::Finish.adjust(settings, true), settings);
+    }
+}
@@ -1,2 +1,5 @@
 pub mod header;
+pub mod identify;
 pub mod slider;
+pub mod status_bar;
+pub mod test_pattern;
@@ -5,6 +5,7 @@ pub enum Tab {
     Settings,
     Keybinds,
     Profiles,
+    Calibrate,
 }
 
 #[component]
@@ -30,6 +31,11 @@ pub fn Header(active_tab: Signal<Tab>, on_tab_change: EventHandler<Tab>) -> Elem
                     onclick: move |_| on_tab_change.call(Tab::Profiles),
                     "Profiles"
                 }
+                button {
+                    class: if active_tab() == Tab::Calibrate { "tab active" } else { "tab" },
+                    onclick: move |_| on_tab_change.call(Tab::Calibrate),
+                    "Calibrate"
+                }
             }
         }
     }
@@ -0,0 +1,67 @@
+use crate::windows::display::MonitorInfo;
+use dioxus::{
+    core::spawn_forever,
+    desktop::{
+        tao::dpi::{PhysicalPosition, PhysicalSize},
+        window, Config, WindowBuilder,
+    },
+    prelude::*,
+};
+use std::time::Duration;
+
+/// How long the identify overlays stay on screen.
+const IDENTIFY_DURATION: Duration = Duration::from_secs(2);
+
+/// Side length of an overlay, in physical pixels.
+const OVERLAY_SIZE: u32 = 220;
+
+/// Inset of an overlay from its monitor's top-left corner, in physical pixels.
+const OVERLAY_INSET: i32 = 40;
+
+/// Briefly show each monitor's position in the list on the monitor itself. Monitors with unknown
+/// bounds are skipped.
+pub fn identify_monitors(monitors: &[MonitorInfo]) {
+    let desktop = window();
+
+    for (index, monitor) in monitors.iter().enumerate() {
+        let bounds = monitor.bounds;
+
+        if bounds.width == 0 || bounds.height == 0 {
+            continue;
+        }
+
+        let builder = WindowBuilder::new()
+            .with_title(format!("Gammar: {}", monitor.name))
+            .with_decorations(false)
+            .with_always_on_top(true)
+            .with_resizable(false)
+            .with_focused(false)
+            .with_position(PhysicalPosition::new(
+                bounds.x + OVERLAY_INSET,
+                bounds.y + OVERLAY_INSET,
+            ))
+            .with_inner_size(PhysicalSize::new(OVERLAY_SIZE, OVERLAY_SIZE));
+
+        let dom =
+            VirtualDom::new_with_props(IdentifyOverlay, IdentifyOverlayProps { number: index + 1 });
+        let pending = desktop.new_window(dom, Config::new().with_window(builder).with_menu(None));
+
+        // Outlive the settings tab, so switching tabs doesn't strand an overlay
+        spawn_forever(async move {
+            let overlay = pending.resolve().await;
+            tokio::time::sleep(IDENTIFY_DURATION).await;
+            overlay.close();
+        });
+    }
+}
+
+/// The large number shown on a monitor while identifying.
+#[component]
+fn IdentifyOverlay(number: usize) -> Element {
+    rsx! {
+        div {
+            style: "display: flex; align-items: center; justify-content: center; width: 100vw; height: 100vh; margin: 0; background: #6c5ce7; color: white; font-family: 'Segoe UI', sans-serif; font-size: 140px; font-weight: 600; user-select: none;",
+            "{number}"
+        }
+    }
+}
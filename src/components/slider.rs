@@ -8,11 +8,19 @@ pub fn Slider(
     max: f32,
     step: f32,
     on_change: EventHandler<f32>,
+    /// Decimal places shown in the label.
+    #[props(default = 2)]
+    precision: usize,
+    /// Named values offered as chips under the slider.
+    #[props(default)]
+    presets: Vec<(String, f32)>,
 ) -> Element {
+    let shown = format!("{:.*}", precision, value);
+
     rsx! {
         div {
             class: "slider-container",
-            label { "{label}: {value:.2}" }
+            label { "{label}: {shown}" }
             input {
                 r#type: "range",
                 min: "{min}",
@@ -25,6 +33,21 @@ pub fn Slider(
                     }
                 }
             }
+            if !presets.is_empty() {
+                div {
+                    class: "slider-presets",
+                    for (name, preset) in presets {
+                        button {
+                            key: "{name}",
+                            // Within half a step, as the slider can't land closer
+                            class: if (value - preset).abs() <= step / 2.0 { "slider-preset active" } else { "slider-preset" },
+                            title: "{preset}",
+                            onclick: move |_| on_change.call(preset),
+                            "{name}"
+                        }
+                    }
+                }
+            }
         }
     }
 }
@@ -0,0 +1,40 @@
+use crate::worker::ApplyResult;
+use dioxus::prelude::*;
+
+/// Outcome of the most recent hardware apply, or a notice about the monitors, as shown in the
+/// status bar.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApplyStatus {
+    Applied(String),
+    Failed(String),
+    Notice(String),
+}
+
+impl From<&ApplyResult> for ApplyStatus {
+    fn from(result: &ApplyResult) -> Self {
+        match &result.result {
+            Ok(_) if result.global => ApplyStatus::Notice(format!(
+                "Applied to every monitor: {} only accepts the generic display device",
+                result.monitor.name
+            )),
+            Ok(_) => ApplyStatus::Applied(format!("Applied to {}", result.monitor.name)),
+            Err(e) => ApplyStatus::Failed(format!("{}: {}", result.monitor.name, e)),
+        }
+    }
+}
+
+#[component]
+pub fn StatusBar(status: Signal<Option<ApplyStatus>>) -> Element {
+    match status() {
+        Some(ApplyStatus::Applied(message)) => rsx! {
+            div { class: "status-bar", "✓ {message}" }
+        },
+        Some(ApplyStatus::Failed(message)) => rsx! {
+            div { class: "status-bar failed", "⚠️ {message}" }
+        },
+        Some(ApplyStatus::Notice(message)) => rsx! {
+            div { class: "status-bar notice", "ℹ️ {message}" }
+        },
+        None => rsx! {},
+    }
+}
@@ -0,0 +1,131 @@
+use crate::windows::display::MonitorInfo;
+use dioxus::{
+    desktop::{
+        tao::dpi::{PhysicalPosition, PhysicalSize},
+        window, Config, WindowBuilder,
+    },
+    prelude::*,
+};
+
+/// Reference content for judging settings by eye.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TestPattern {
+    /// A smooth ramp from black to white, which shows banding and tint.
+    Gradient,
+    /// Patches from 0 to 5% grey, which disappear when shadows are crushed.
+    NearBlack,
+    /// Patches from 95 to 100% grey, which merge when highlights are blown out.
+    NearWhite,
+    /// Full red, green, blue and white fields.
+    Primaries,
+}
+
+impl TestPattern {
+    const ALL: [TestPattern; 4] = [
+        TestPattern::Gradient,
+        TestPattern::NearBlack,
+        TestPattern::NearWhite,
+        TestPattern::Primaries,
+    ];
+
+    fn index(&self) -> usize {
+        Self::ALL.iter().position(|p| p == self).unwrap_or_default()
+    }
+
+    fn next(&self) -> Self {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
+    }
+
+    fn previous(&self) -> Self {
+        Self::ALL[(self.index() + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            TestPattern::Gradient => "Grayscale gradient",
+            TestPattern::NearBlack => "Near-black steps: each patch should be distinct from 0%",
+            TestPattern::NearWhite => "Near-white steps: each patch should be distinct from 100%",
+            TestPattern::Primaries => "Primary colors",
+        }
+    }
+
+    /// Get the background of each field, left to right.
+    fn fields(&self) -> Vec<String> {
+        let grey = |percent: u32| {
+            let level = (percent * 255 + 50) / 100;
+            format!("rgb({0}, {0}, {0})", level)
+        };
+
+        match self {
+            TestPattern::Gradient => vec!["linear-gradient(to right, black, white)".to_string()],
+            TestPattern::NearBlack => (0..=5).map(grey).collect(),
+            TestPattern::NearWhite => (95..=100).map(grey).collect(),
+            TestPattern::Primaries => ["#ff0000", "#00ff00", "#0000ff", "#ffffff"]
+                .map(str::to_string)
+                .to_vec(),
+        }
+    }
+}
+
+/// Open a borderless window covering the monitor that cycles through test patterns. It stays on
+/// top, so the main window can keep adjusting settings from another monitor while it's open.
+pub fn open_test_patterns(monitor: &MonitorInfo) {
+    let bounds = monitor.bounds;
+
+    if bounds.width == 0 || bounds.height == 0 {
+        return;
+    }
+
+    let builder = WindowBuilder::new()
+        .with_title(format!("Gammar test patterns: {}", monitor.name))
+        .with_decorations(false)
+        .with_always_on_top(true)
+        .with_resizable(false)
+        .with_position(PhysicalPosition::new(bounds.x, bounds.y))
+        .with_inner_size(PhysicalSize::new(bounds.width, bounds.height));
+
+    let dom = VirtualDom::new(TestPatternWindow);
+
+    // The window lives on by itself and closes on Esc
+    let _ = window().new_window(dom, Config::new().with_window(builder).with_menu(None));
+}
+
+/// The pattern window's content. Arrow keys, Space and clicks switch patterns; Esc closes.
+#[component]
+fn TestPatternWindow() -> Element {
+    let mut pattern = use_signal(|| TestPattern::Gradient);
+    let current = pattern();
+
+    rsx! {
+        div {
+            tabindex: "0",
+            style: "display: flex; flex-direction: column; width: 100vw; height: 100vh; margin: 0; background: black; outline: none; cursor: none; user-select: none; font-family: 'Segoe UI', sans-serif;",
+            onmounted: move |evt| async move {
+                let _ = evt.set_focus(true).await;
+            },
+            onkeydown: move |evt| match evt.key() {
+                Key::Escape => window().close(),
+                Key::ArrowRight | Key::ArrowDown | Key::Enter => pattern.set(current.next()),
+                Key::ArrowLeft | Key::ArrowUp => pattern.set(current.previous()),
+                Key::Character(c) if c == " " => pattern.set(current.next()),
+                _ => {}
+            },
+            onclick: move |_| pattern.set(current.next()),
+
+            div {
+                style: "display: flex; flex: 1;",
+                for (i, background) in current.fields().into_iter().enumerate() {
+                    div {
+                        key: "{i}",
+                        style: "flex: 1; background: {background};",
+                    }
+                }
+            }
+
+            p {
+                style: "margin: 0; padding: 8px 16px; background: #1e1e2e; color: #a0a0a0; font-size: 14px;",
+                "{current.label()} ({current.index() + 1}/{TestPattern::ALL.len()}) · ← → to switch, Esc to close"
+            }
+        }
+    }
+}
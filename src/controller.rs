@@ -0,0 +1,2784 @@
+use crate::{
+    backend::{platform_backend, GammaBackend, PowerSource},
+    profiles::{self, ImportSummary, Profile, ProfileColor},
+    schedule::LocalTime,
+    windows::{
+        ddc::capabilities::{self, Capabilities},
+        display::{
+            compute_gamma_ramp, find_monitor, fit_display_settings, ramp_file, ColorFilter,
+            DisplaySettings, GammaError, GammaRamp, Lut, MonitorInfo, MAX_EXTRA_DIMMING,
+            MAX_TEMPERATURE, MIN_TEMPERATURE, RAMP_TOLERANCE,
+        },
+        hotkeys::{binding_using, HotkeyAction, KeybindConfig},
+        icm,
+    },
+    worker::{ApplyPriority, ApplyRequest, ApplyResult, ApplyWorker, HardwareControl, Reapplies},
+    AppConfig, HotkeyTarget,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime},
+};
+
+/// How long the config must go unchanged before a deferred save writes it.
+pub const SAVE_DELAY: Duration = Duration::from_millis(500);
+
+/// Extra dimming past which a manual change has to be kept, or it's undone.
+pub const DIMMING_CONFIRM_THRESHOLD: f32 = 0.5;
+
+/// How long heavy extra dimming waits to be kept before it's undone.
+pub const DIMMING_REVERT_DELAY: Duration = Duration::from_secs(15);
+
+/// Owns the configuration, the gamma backend and the per-monitor state, and implements the
+/// adjustment semantics shared by the GUI and external tools.
+///
+/// Hardware applies are queued on an [`ApplyWorker`]; their outcome is reported through
+/// [`GammaController::poll_results`].
+pub struct GammaController {
+    config: AppConfig,
+    backend: Arc<dyn GammaBackend>,
+    worker: ApplyWorker,
+    monitors: Vec<MonitorInfo>,
+    hardware_capable: HashSet<String>,
+    backlight_capable: HashSet<String>,
+    /// DDC/CI capabilities read so far, or why reading them failed, by monitor id.
+    capabilities: HashMap<String, Result<Capabilities, String>>,
+    /// The monitor the user selected, while it's disconnected and another one stands in.
+    displaced_monitor_id: Option<String>,
+    /// Whether ramps far from identity are accepted, checked once at startup.
+    gamma_range_unlocked: bool,
+    /// Whether enforcing is paused for this session, without turning it off in the config.
+    enforce_paused: bool,
+    /// Whether this was a remote desktop session at the last check. Automatic applies are
+    /// skipped meanwhile, as they'd only fail.
+    remote_session: bool,
+    /// The power source at the last check, `None` without a battery.
+    power_source: Option<PowerSource>,
+    /// Monitors already logged as not supporting gamma ramps, so hotkeys don't log every press.
+    gamma_unsupported_logged: HashSet<String>,
+    /// The settings last queued for each monitor, which the worker may not have applied yet.
+    queued: HashMap<String, DisplaySettings>,
+    /// When a deferred save is due, pushed back by every further change.
+    save_due: Option<Instant>,
+    /// The schedule event in effect at the last tick, identified by its start.
+    schedule_event: Option<u32>,
+    /// Whether a manual change paused the schedule until its next event.
+    schedule_paused: bool,
+    /// Something worth telling the user about the latest apply, e.g. that a profile's monitor
+    /// was missing.
+    notice: Option<String>,
+    /// What the last toggled profile hotkey returns to when pressed again.
+    return_point: Option<ReturnPoint>,
+    /// Heavy extra dimming waiting to be kept, which is undone once it's due.
+    dimming_revert: Option<DimmingRevert>,
+}
+
+/// A profile removed by [`GammaController::remove_profile`], kept to undo the removal.
+#[derive(Debug, Clone)]
+pub struct RemovedProfile {
+    /// Where the profile was in the list.
+    pub index: usize,
+    pub profile: Profile,
+    /// The hotkeys that loaded the profile, with every binding of each.
+    pub keybinds: Vec<(HotkeyAction, Vec<KeybindConfig>)>,
+}
+
+/// The settings from before a profile hotkey toggled its profile on.
+#[derive(Debug, Clone)]
+struct ReturnPoint {
+    profile_id: u64,
+    /// The monitor a bound profile was loaded on, or `None` where settings changes go.
+    monitor_id: Option<String>,
+    settings: DisplaySettings,
+}
+
+/// The extra dimming from before a manual change took it past [`DIMMING_CONFIRM_THRESHOLD`].
+#[derive(Debug, Clone)]
+struct DimmingRevert {
+    /// The monitor dimmed, or `None` where settings changes go.
+    monitor_id: Option<String>,
+    all_monitors: bool,
+    extra_dimming: f32,
+    due: Instant,
+}
+
+/// How the selection changed after re-enumerating monitors.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TopologyChange {
+    /// The selected monitor that disappeared; the primary monitor is selected in its place.
+    pub lost: Option<MonitorInfo>,
+    /// The previously selected monitor that came back and was reselected.
+    pub restored: Option<MonitorInfo>,
+    /// The profile applied by a monitor rule that started matching.
+    pub rule_profile: Option<Profile>,
+}
+
+impl GammaController {
+    /// Create a new GammaController around the given config and backend, enumerating monitors
+    /// and selecting the primary one if the configured monitor isn't attached.
+    pub fn new(config: AppConfig, backend: Box<dyn GammaBackend>) -> Self {
+        let backend: Arc<dyn GammaBackend> = Arc::from(backend);
+        backend.set_global_fallback(!config.disable_global_dc_fallback);
+
+        let mut controller = Self {
+            config,
+            worker: ApplyWorker::spawn(backend.clone()),
+            gamma_range_unlocked: backend.gamma_range_unlocked(),
+            remote_session: backend.is_remote_session(),
+            power_source: backend.power_source(),
+            backend,
+            monitors: Vec::new(),
+            hardware_capable: HashSet::new(),
+            backlight_capable: HashSet::new(),
+            capabilities: HashMap::new(),
+            displaced_monitor_id: None,
+            enforce_paused: false,
+            gamma_unsupported_logged: HashSet::new(),
+            queued: HashMap::new(),
+            save_due: None,
+            schedule_event: None,
+            schedule_paused: false,
+            notice: None,
+            return_point: None,
+            dimming_revert: None,
+        };
+
+        controller.refresh_monitors();
+        controller
+    }
+
+    /// Create a GammaController from the saved config and the platform backend.
+    pub fn load() -> Self {
+        Self::new(AppConfig::load(), platform_backend())
+    }
+
+    /// Get a reference to the configuration.
+    pub fn config(&self) -> &AppConfig {
+        &self.config
+    }
+
+    /// Get a mutable reference to the configuration.
+    pub fn config_mut(&mut self) -> &mut AppConfig {
+        &mut self.config
+    }
+
+    /// Get the monitors found by the last enumeration.
+    pub fn monitors(&self) -> &[MonitorInfo] {
+        &self.monitors
+    }
+
+    /// Check whether the attached monitors differ from the last enumeration.
+    pub fn topology_changed(&self) -> bool {
+        self.backend.enumerate_monitors() != self.monitors
+    }
+
+    /// Check whether this is a remote desktop session, where gamma ramps can't be set.
+    pub fn remote_session(&self) -> bool {
+        self.remote_session
+    }
+
+    /// Check whether the session moved between remote desktop and the console since the last
+    /// check.
+    pub fn session_changed(&self) -> bool {
+        self.backend.is_remote_session() != self.remote_session
+    }
+
+    /// Get the power source at the last check, `None` on machines without a battery.
+    pub fn power_source(&self) -> Option<PowerSource> {
+        self.power_source
+    }
+
+    /// Check whether the machine switched between battery and AC power since the last check.
+    pub fn power_changed(&self) -> bool {
+        self.backend.power_source() != self.power_source
+    }
+
+    /// Pick up a switch of power source, applying the profile set for the new one, if any.
+    /// Returns the profile applied. Manual changes made afterwards stand until the next switch.
+    pub fn refresh_power(&mut self) -> Result<Option<Profile>, GammaError> {
+        self.power_source = self.backend.power_source();
+
+        let Some(profile) = self
+            .power_source
+            .and_then(|source| self.config.power_profiles.profile_for(source))
+            .and_then(|id| self.config.profile_manager.get_profile_by_id(id))
+            .cloned()
+        else {
+            return Ok(None);
+        };
+
+        if self.remote_session {
+            return Ok(None);
+        }
+
+        let all_monitors = self.config.apply_to_all_monitors;
+        self.queue_profile(&profile, ApplyPriority::Normal, all_monitors)?;
+
+        Ok(Some(profile))
+    }
+
+    /// Pick up a move between remote desktop and the console, returning whether the session is
+    /// now remote. On returning to the console, monitors are re-enumerated and the current
+    /// settings reapplied.
+    pub fn refresh_session(&mut self) -> bool {
+        self.remote_session = self.backend.is_remote_session();
+
+        if !self.remote_session {
+            self.refresh_monitors();
+
+            if !self.gamma_controls_unavailable() {
+                let _ = self.apply_current();
+            }
+        }
+
+        self.remote_session
+    }
+
+    /// Re-enumerate monitors, falling back to the primary monitor if the selected one is gone.
+    ///
+    /// The original selection is remembered, and when that monitor comes back it's reselected
+    /// and the current settings are reapplied to it.
+    pub fn refresh_monitors(&mut self) -> TopologyChange {
+        // Let in-flight applies land before forgetting monitors that are gone
+        self.worker.flush();
+
+        let previous = std::mem::replace(&mut self.monitors, self.backend.enumerate_monitors());
+        let is_attached = |monitors: &[MonitorInfo], id: &str| monitors.iter().any(|m| m.id == id);
+
+        self.worker
+            .retain_applied(|id| is_attached(&self.monitors, id));
+
+        // DDC/CI and WMI probes are slow, so only new monitors are checked
+        self.hardware_capable
+            .retain(|id| is_attached(&self.monitors, id));
+        self.backlight_capable
+            .retain(|id| is_attached(&self.monitors, id));
+
+        for monitor in &self.monitors {
+            if is_attached(&previous, &monitor.id) {
+                continue;
+            }
+
+            if self.backend.supports_hardware_control(monitor) {
+                self.hardware_capable.insert(monitor.id.clone());
+            }
+
+            if self.backend.supports_backlight(monitor) {
+                self.backlight_capable.insert(monitor.id.clone());
+            }
+        }
+
+        self.migrate_legacy_ids();
+
+        let mut change = self.update_selection(&previous);
+
+        // The first enumeration isn't a change, so the saved settings stand at startup
+        if !previous.is_empty() {
+            change.rule_profile = self.apply_monitor_rules(&previous);
+        }
+
+        change
+    }
+
+    /// Reselect the previously selected monitor if it came back, or fall back to the primary
+    /// monitor if the selected one is gone.
+    fn update_selection(&mut self, previous: &[MonitorInfo]) -> TopologyChange {
+        let is_attached = |monitors: &[MonitorInfo], id: &str| monitors.iter().any(|m| m.id == id);
+        let mut change = TopologyChange::default();
+
+        if let Some(monitor) = self
+            .displaced_monitor_id
+            .as_deref()
+            .and_then(|id| find_monitor(&self.monitors, Some(id)))
+        {
+            self.displaced_monitor_id = None;
+            self.config.selected_monitor_id = monitor.id.clone();
+
+            if !self.remote_session {
+                let _ = self.apply_current();
+            }
+
+            change.restored = Some(monitor);
+            return change;
+        }
+
+        let selected_id = self.config.selected_monitor_id.clone();
+
+        if selected_id.is_empty() || !is_attached(&self.monitors, &selected_id) {
+            if let Some(primary) = find_monitor(&self.monitors, None) {
+                if !selected_id.is_empty() {
+                    self.displaced_monitor_id.get_or_insert(selected_id.clone());
+                    change.lost = find_monitor(previous, Some(&selected_id));
+                }
+
+                self.config.selected_monitor_id = primary.id;
+            }
+        }
+
+        change
+    }
+
+    /// Apply the profile of the first monitor rule that matches the attached monitors but didn't
+    /// match the previous ones. Rules that kept matching are left alone, so manual changes stand
+    /// while unrelated monitors come and go.
+    fn apply_monitor_rules(&mut self, previous: &[MonitorInfo]) -> Option<Profile> {
+        if self.remote_session {
+            return None;
+        }
+
+        let profile = self
+            .config
+            .monitor_rules
+            .iter()
+            .filter(|rule| rule.matches(&self.monitors) && !rule.matches(previous))
+            .find_map(|rule| {
+                self.config
+                    .profile_manager
+                    .get_profile_by_id(rule.profile_id)
+            })
+            .cloned()?;
+
+        let all_monitors = self.config.apply_to_all_monitors;
+        self.queue_profile(&profile, ApplyPriority::Normal, all_monitors)
+            .ok()?;
+
+        Some(profile)
+    }
+
+    /// Rewrite stored monitor ids that only match an attached monitor's legacy id to its
+    /// current id.
+    fn migrate_legacy_ids(&mut self) {
+        let monitors = &self.monitors;
+        let migrate = |id: &str| {
+            find_monitor(monitors, Some(id))
+                .map(|m| m.id)
+                .unwrap_or_else(|| id.to_string())
+        };
+
+        self.config.selected_monitor_id = migrate(&self.config.selected_monitor_id);
+        self.config.hardware_control = self
+            .config
+            .hardware_control
+            .iter()
+            .map(|id| migrate(id))
+            .collect();
+        self.config.backlight_disabled = self
+            .config
+            .backlight_disabled
+            .iter()
+            .map(|id| migrate(id))
+            .collect();
+        self.config.luts = std::mem::take(&mut self.config.luts)
+            .into_iter()
+            .map(|(id, lut)| (migrate(&id), lut))
+            .collect();
+        for rule in &mut self.config.monitor_rules {
+            rule.monitor_id = migrate(&rule.monitor_id);
+        }
+    }
+
+    /// Get the currently selected monitor.
+    pub fn selected_monitor(&self) -> Option<MonitorInfo> {
+        find_monitor(
+            &self.monitors,
+            Some(self.config.selected_monitor_id.as_str()),
+        )
+    }
+
+    /// Get the monitor adjustment hotkeys act on: the one under the cursor when hotkeys target it,
+    /// otherwise the selected one.
+    pub fn hotkey_monitor(&self) -> Option<MonitorInfo> {
+        if self.config.hotkey_target == HotkeyTarget::MonitorUnderCursor {
+            let under_cursor = self.backend.cursor_position().and_then(|(x, y)| {
+                self.monitors
+                    .iter()
+                    .find(|m| m.contains_point(x, y))
+                    .cloned()
+            });
+
+            if under_cursor.is_some() {
+                return under_cursor;
+            }
+        }
+
+        self.selected_monitor()
+    }
+
+    /// Get the settings last successfully applied to a monitor, if any.
+    pub fn applied_settings(&self, monitor_id: &str) -> Option<DisplaySettings> {
+        self.worker.applied_settings(monitor_id)
+    }
+
+    /// Take the results of applies completed since the last call.
+    pub fn poll_results(&self) -> Vec<ApplyResult> {
+        self.worker.poll_results()
+    }
+
+    /// Block until every queued apply has been performed.
+    pub fn flush(&self) {
+        self.worker.flush();
+    }
+
+    /// Check whether ramps overwritten by other applications are currently being reapplied.
+    pub fn enforcing(&self) -> bool {
+        self.config.enforce.enabled && !self.enforce_paused
+    }
+
+    /// Check whether enforcing is paused for this session.
+    pub fn enforce_paused(&self) -> bool {
+        self.enforce_paused
+    }
+
+    /// Pause or resume enforcing for this session.
+    pub fn set_enforce_paused(&mut self, paused: bool) {
+        self.enforce_paused = paused;
+    }
+
+    /// Reapply the ramps of monitors that another application overwrote, if enforcing.
+    pub fn enforce(&self) {
+        if self.enforcing() && !self.remote_session {
+            self.worker.enforce();
+        }
+    }
+
+    /// Get how often a monitor's ramp was found overwritten and reapplied.
+    pub fn reapplies(&self, monitor_id: &str) -> Reapplies {
+        self.worker.reapplies(monitor_id)
+    }
+
+    /// Check whether a monitor's brightness and contrast can be controlled in hardware.
+    pub fn supports_hardware_control(&self, monitor_id: &str) -> bool {
+        self.hardware_capable.contains(monitor_id)
+    }
+
+    /// Check whether a monitor's brightness and contrast are currently controlled in hardware.
+    pub fn hardware_control(&self, monitor_id: &str) -> bool {
+        self.supports_hardware_control(monitor_id)
+            && self.config.hardware_control.contains(monitor_id)
+    }
+
+    /// Switch a monitor between hardware and gamma ramp brightness and contrast, reapplying the
+    /// current settings if it's the selected monitor.
+    pub fn set_hardware_control(
+        &mut self,
+        monitor_id: &str,
+        enabled: bool,
+    ) -> Result<(), GammaError> {
+        if enabled && !self.supports_hardware_control(monitor_id) {
+            return Err(GammaError::Unsupported(format!(
+                "Monitor {} does not support DDC/CI",
+                monitor_id
+            )));
+        }
+
+        if enabled {
+            self.config.hardware_control.insert(monitor_id.to_string());
+        } else {
+            self.config.hardware_control.remove(monitor_id);
+        }
+
+        if monitor_id == self.config.selected_monitor_id {
+            return self.apply_current();
+        }
+
+        Ok(())
+    }
+
+    /// Allow or forbid applying through the generic display device, which changes every monitor
+    /// at once, when a monitor's own device can't be opened.
+    pub fn set_global_dc_fallback(&mut self, enabled: bool) {
+        self.config.disable_global_dc_fallback = !enabled;
+        self.backend.set_global_fallback(enabled);
+    }
+
+    /// Get a monitor's DDC/CI capabilities, if they have been queried.
+    pub fn capabilities(&self, monitor_id: &str) -> Option<&Result<Capabilities, String>> {
+        self.capabilities.get(monitor_id)
+    }
+
+    /// Query a monitor's DDC/CI capabilities. The query is slow, so the result, including a
+    /// failure, is cached until queried again.
+    pub fn query_capabilities(&mut self, monitor_id: &str) -> &Result<Capabilities, String> {
+        let result = match find_monitor(&self.monitors, Some(monitor_id)) {
+            Some(monitor) => self.backend.read_capabilities(&monitor).and_then(|text| {
+                capabilities::parse(&text).ok_or_else(|| GammaError::Ddc {
+                    monitor: monitor.name.clone(),
+                    action: "read capabilities".to_string(),
+                    detail: "the reply is not a valid capabilities string".to_string(),
+                })
+            }),
+            None => Err(GammaError::MonitorNotFound {
+                id: monitor_id.to_string(),
+            }),
+        };
+
+        self.capabilities
+            .entry(monitor_id.to_string())
+            .insert_entry(result.map_err(|e| e.to_string()))
+            .into_mut()
+    }
+
+    /// Check whether a monitor's backlight can be set directly, as on most laptop panels.
+    pub fn supports_backlight(&self, monitor_id: &str) -> bool {
+        self.backlight_capable.contains(monitor_id)
+    }
+
+    /// Check whether a monitor's brightness currently drives its backlight. It does wherever
+    /// supported, unless turned off or the monitor is under DDC/CI control.
+    pub fn backlight_control(&self, monitor_id: &str) -> bool {
+        self.supports_backlight(monitor_id)
+            && !self.config.backlight_disabled.contains(monitor_id)
+            && !self.hardware_control(monitor_id)
+    }
+
+    /// Switch a monitor between backlight and gamma ramp brightness, reapplying the current
+    /// settings if it's the selected monitor.
+    pub fn set_backlight_control(
+        &mut self,
+        monitor_id: &str,
+        enabled: bool,
+    ) -> Result<(), GammaError> {
+        if enabled && !self.supports_backlight(monitor_id) {
+            return Err(GammaError::Unsupported(format!(
+                "Monitor {} has no settable backlight",
+                monitor_id
+            )));
+        }
+
+        if enabled {
+            self.config.backlight_disabled.remove(monitor_id);
+        } else {
+            self.config
+                .backlight_disabled
+                .insert(monitor_id.to_string());
+        }
+
+        if monitor_id == self.config.selected_monitor_id {
+            return self.apply_current();
+        }
+
+        Ok(())
+    }
+
+    /// Get where a monitor's brightness and contrast are applied.
+    pub fn brightness_control(&self, monitor_id: &str) -> HardwareControl {
+        if self.hardware_control(monitor_id) {
+            HardwareControl::Ddc
+        } else if self.backlight_control(monitor_id) {
+            HardwareControl::Backlight
+        } else {
+            HardwareControl::None
+        }
+    }
+
+    /// Check whether ramps outside the default Windows range are accepted.
+    pub fn gamma_range_unlocked(&self) -> bool {
+        self.gamma_range_unlocked
+    }
+
+    /// Check whether the current settings produce a ramp Windows would reject without the full
+    /// range unlocked.
+    pub fn exceeds_gamma_range(&self) -> bool {
+        !self.gamma_range_unlocked
+            && icm::exceeds_default_range(&compute_gamma_ramp(&self.config.current_settings))
+    }
+
+    /// Ask for the full gamma range to be unlocked. It takes effect after a reboot.
+    pub fn unlock_gamma_range(&self) -> Result<(), GammaError> {
+        self.backend.unlock_gamma_range()
+    }
+
+    /// Get the calibration LUT loaded for a monitor.
+    pub fn lut(&self, monitor_id: &str) -> Option<&Lut> {
+        self.config.luts.get(monitor_id)
+    }
+
+    /// Load or clear a monitor's calibration LUT, reapplying the current settings if it's the
+    /// selected monitor.
+    pub fn set_lut(&mut self, monitor_id: &str, lut: Option<Lut>) -> Result<(), GammaError> {
+        match lut {
+            Some(lut) => self.config.luts.insert(monitor_id.to_string(), lut),
+            None => self.config.luts.remove(monitor_id),
+        };
+
+        if monitor_id == self.config.selected_monitor_id {
+            return self.apply_current();
+        }
+
+        Ok(())
+    }
+
+    /// Compare the ramp loaded on the selected monitor with the one the current settings
+    /// produce. When they differ, e.g. because another tool changed it, returns an approximation
+    /// of the settings behind the loaded ramp.
+    ///
+    /// Returns `None` when the ramps match or the ramp can't be read.
+    pub fn hardware_mismatch(&self) -> Option<DisplaySettings> {
+        let monitor = self.selected_monitor()?;
+        let loaded = self.backend.read_ramp(&monitor).ok()?;
+        let expected = self.ramp_for(&monitor.id, &self.config.current_settings);
+
+        if loaded.max_difference(&expected) <= RAMP_TOLERANCE {
+            return None;
+        }
+
+        Some(fit_display_settings(&loaded))
+    }
+
+    /// Compute the ramp written to a monitor for the given settings, after hardware control has
+    /// taken its part and beneath the monitor's LUT.
+    fn ramp_for(&self, monitor_id: &str, settings: &DisplaySettings) -> GammaRamp {
+        let mut ramp =
+            compute_gamma_ramp(&self.brightness_control(monitor_id).ramp_settings(settings));
+
+        if let Some(lut) = self.lut(monitor_id) {
+            ramp = lut.apply(&ramp);
+        }
+
+        ramp
+    }
+
+    /// Get the ramp for a monitor's effective settings: those last applied to it, or the
+    /// current settings if none were yet.
+    pub fn effective_ramp(&self, monitor_id: &str) -> GammaRamp {
+        let settings = self
+            .applied_settings(monitor_id)
+            .unwrap_or(self.config.current_settings);
+
+        self.ramp_for(monitor_id, &settings)
+    }
+
+    /// Write a monitor's effective ramp to a file, in the format its extension picks.
+    pub fn export_ramp(&self, monitor_id: &str, path: &Path) -> Result<(), GammaError> {
+        let monitor = find_monitor(&self.monitors, Some(monitor_id)).ok_or_else(|| {
+            GammaError::MonitorNotFound {
+                id: monitor_id.to_string(),
+            }
+        })?;
+
+        ramp_file::save(
+            &self.effective_ramp(&monitor.id),
+            path,
+            &format!("Gammar - {}", monitor.name),
+        )
+    }
+
+    /// Select a monitor and apply the current settings to it.
+    pub fn select_monitor(&mut self, monitor_id: &str) -> Result<(), GammaError> {
+        if !self.monitors.iter().any(|m| m.id == monitor_id) {
+            return Err(GammaError::MonitorNotFound {
+                id: monitor_id.to_string(),
+            });
+        }
+
+        self.config.selected_monitor_id = monitor_id.to_string();
+        self.displaced_monitor_id = None;
+
+        if self.gamma_controls_unavailable() {
+            return Ok(());
+        }
+
+        self.apply_current()
+    }
+
+    /// Select the monitor after the selected one in enumeration order, wrapping around. Monitors
+    /// detached since the last enumeration are skipped, and an empty list is enumerated again.
+    pub fn select_next_monitor(&mut self) -> Result<(), GammaError> {
+        if self.monitors.is_empty() {
+            self.refresh_monitors();
+        }
+
+        let attached = self.backend.enumerate_monitors();
+        let count = self.monitors.len();
+        let start = self
+            .monitors
+            .iter()
+            .position(|m| m.id == self.config.selected_monitor_id)
+            .map_or(0, |index| index + 1);
+
+        let next = (start..start + count)
+            .map(|index| &self.monitors[index % count])
+            .find(|monitor| attached.iter().any(|m| m.id == monitor.id))
+            .map(|monitor| monitor.id.clone());
+
+        match next {
+            Some(id) => self.select_monitor(&id),
+            None => Err(GammaError::MonitorNotFound {
+                id: self.config.selected_monitor_id.clone(),
+            }),
+        }
+    }
+
+    /// Check whether changes only target the selected monitor and it doesn't accept gamma
+    /// ramps, so the display controls can't do anything.
+    pub fn gamma_controls_unavailable(&self) -> bool {
+        !self.config.apply_to_all_monitors
+            && self.selected_monitor().is_some_and(|m| !m.supports_gamma)
+    }
+
+    /// Apply the current settings to the selected monitor, or every monitor in all-monitors
+    /// mode.
+    pub fn apply_current(&mut self) -> Result<(), GammaError> {
+        let settings = self.config.current_settings;
+        let all_monitors = self.config.apply_to_all_monitors;
+        let verify = self.config.verify_applies;
+
+        self.queue_current(settings, ApplyPriority::Normal, None, all_monitors, verify)
+    }
+
+    /// Re-apply the current settings and read the ramps back whatever the config says, as on
+    /// startup, where a driver silently ignoring them would otherwise go unnoticed.
+    pub fn apply_current_verified(&mut self) -> Result<(), GammaError> {
+        let settings = self.config.current_settings;
+        let all_monitors = self.config.apply_to_all_monitors;
+
+        self.queue_current(settings, ApplyPriority::Normal, None, all_monitors, true)
+    }
+
+    /// Check whether settings changes apply to every monitor rather than the selected one.
+    pub fn apply_to_all_monitors(&self) -> bool {
+        self.config.apply_to_all_monitors
+    }
+
+    /// Switch all-monitors mode, applying the current settings everywhere when it's enabled.
+    pub fn set_apply_to_all_monitors(&mut self, enabled: bool) -> Result<(), GammaError> {
+        self.config.apply_to_all_monitors = enabled;
+
+        if enabled {
+            return self.apply_current();
+        }
+
+        Ok(())
+    }
+
+    /// Queue settings to be applied to a monitor. When the monitor is the selected one the
+    /// settings also become the current settings.
+    pub fn set_settings(
+        &mut self,
+        monitor_id: &str,
+        settings: DisplaySettings,
+    ) -> Result<(), GammaError> {
+        let verify = self.config.verify_applies;
+        self.manual_change();
+
+        self.queue_settings(monitor_id, settings, ApplyPriority::Normal, None, verify)
+    }
+
+    /// Set the current settings and queue them for the selected monitor, or every monitor in
+    /// all-monitors mode.
+    pub fn set_current_settings(&mut self, settings: DisplaySettings) -> Result<(), GammaError> {
+        let all_monitors = self.config.apply_to_all_monitors;
+        let verify = self.config.verify_applies;
+        self.manual_change();
+        self.guard_dimming(None, all_monitors, settings);
+
+        self.queue_current(settings, ApplyPriority::Normal, None, all_monitors, verify)
+    }
+
+    /// Show settings for a moment, e.g. to preview a profile, without pausing the schedule or
+    /// ending a profile toggle. They're the current settings until the caller restores the
+    /// previous ones the same way.
+    pub fn preview_settings(&mut self, settings: DisplaySettings) -> Result<(), GammaError> {
+        let all_monitors = self.config.apply_to_all_monitors;
+
+        self.queue_current(settings, ApplyPriority::High, None, all_monitors, false)
+    }
+
+    /// Show settings for a moment on one monitor, like [`Self::preview_settings`] but leaving the
+    /// current settings alone. The caller restores the monitor's previous settings the same way.
+    pub fn preview_settings_on(
+        &mut self,
+        monitor_id: &str,
+        settings: DisplaySettings,
+    ) -> Result<(), GammaError> {
+        self.queue_settings(monitor_id, settings, ApplyPriority::High, None, false)
+    }
+
+    /// Get the connected monitor a profile is bound to, unless it's the selected monitor, which
+    /// the current settings stand for.
+    pub fn profile_monitor(&self, profile: &Profile) -> Option<MonitorInfo> {
+        let monitor = find_monitor(&self.monitors, Some(profile.monitor_id.as_deref()?))?;
+
+        let selected = self.selected_monitor().map(|m| m.id);
+
+        (selected.as_ref() != Some(&monitor.id)).then_some(monitor)
+    }
+
+    /// Set the current settings and fade to them over the configured transition duration.
+    pub fn fade_current_settings(&mut self, settings: DisplaySettings) -> Result<(), GammaError> {
+        let all_monitors = self.config.apply_to_all_monitors;
+        let transition = self.config.transition.duration();
+        let verify = self.config.verify_applies;
+        self.manual_change();
+        self.guard_dimming(None, all_monitors, settings);
+
+        self.queue_current(
+            settings,
+            ApplyPriority::Normal,
+            transition,
+            all_monitors,
+            verify,
+        )
+    }
+
+    /// Start the countdown to undo extra dimming a manual change is taking past
+    /// [`DIMMING_CONFIRM_THRESHOLD`], so a screen dimmed too dark to see comes back on its own.
+    /// Call it before queueing the change. Dimming back below the threshold ends the countdown.
+    fn guard_dimming(
+        &mut self,
+        monitor_id: Option<String>,
+        all_monitors: bool,
+        settings: DisplaySettings,
+    ) {
+        let before = match &monitor_id {
+            Some(id) => self.monitor_settings(id),
+            None => self.config.current_settings,
+        };
+
+        if settings.extra_dimming <= DIMMING_CONFIRM_THRESHOLD {
+            self.dimming_revert
+                .take_if(|revert| revert.monitor_id == monitor_id);
+            return;
+        }
+        if self.config.disable_dimming_revert
+            || before.extra_dimming > DIMMING_CONFIRM_THRESHOLD
+            || self.dimming_revert.is_some()
+        {
+            return;
+        }
+
+        self.dimming_revert = Some(DimmingRevert {
+            monitor_id,
+            all_monitors,
+            extra_dimming: before.extra_dimming,
+            due: Instant::now() + DIMMING_REVERT_DELAY,
+        });
+    }
+
+    /// Keep the heavy extra dimming waiting to be undone.
+    pub fn keep_dimming(&mut self) {
+        self.dimming_revert = None;
+    }
+
+    /// Get how long is left until heavy extra dimming is undone, while it waits to be kept.
+    pub fn dimming_revert_in(&self) -> Option<Duration> {
+        self.dimming_revert
+            .as_ref()
+            .map(|revert| revert.due.saturating_duration_since(Instant::now()))
+    }
+
+    /// Undo heavy extra dimming that wasn't kept in time, leaving the other settings as they are.
+    /// Returns whether it was undone.
+    pub fn revert_dimming_if_due(&mut self, now: Instant) -> Result<bool, GammaError> {
+        let Some(revert) = self.dimming_revert.take_if(|revert| now >= revert.due) else {
+            return Ok(false);
+        };
+        self.notice = Some("Extra dimming wasn't kept, so it was undone".to_string());
+
+        match revert.monitor_id {
+            Some(monitor_id) => {
+                let settings = DisplaySettings {
+                    extra_dimming: revert.extra_dimming,
+                    ..self.monitor_settings(&monitor_id)
+                };
+                self.queue_settings(&monitor_id, settings, ApplyPriority::High, None, false)?;
+            }
+            None => {
+                let settings = DisplaySettings {
+                    extra_dimming: revert.extra_dimming,
+                    ..self.config.current_settings
+                };
+                self.queue_current(
+                    settings,
+                    ApplyPriority::High,
+                    None,
+                    revert.all_monitors,
+                    false,
+                )?;
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Note a change made by the user, which pauses the schedule and ends a profile toggle.
+    fn manual_change(&mut self) {
+        self.schedule_paused = true;
+        self.return_point = None;
+    }
+
+    /// Remember the current settings as the ones to go back to, ahead of a profile load or
+    /// reset replacing them.
+    pub fn remember_previous(&mut self) {
+        self.config.previous_settings = Some(self.config.current_settings);
+    }
+
+    /// Go back to the settings from before the last profile load or reset. The settings being
+    /// replaced become the previous ones, so restoring again goes forward.
+    pub fn restore_previous(&mut self) -> Result<(), GammaError> {
+        let Some(settings) = self.config.previous_settings else {
+            return Ok(());
+        };
+        let transition = self.config.transition.duration();
+        let all_monitors = self.config.apply_to_all_monitors;
+        let verify = self.config.verify_applies;
+
+        self.manual_change();
+        self.remember_previous();
+        self.queue_current(
+            settings,
+            ApplyPriority::Normal,
+            transition,
+            all_monitors,
+            verify,
+        )
+    }
+
+    fn queue_current(
+        &mut self,
+        settings: DisplaySettings,
+        priority: ApplyPriority,
+        transition: Option<Duration>,
+        all_monitors: bool,
+        verify: bool,
+    ) -> Result<(), GammaError> {
+        if !all_monitors {
+            let monitor_id = self.config.selected_monitor_id.clone();
+
+            return self.queue_settings(&monitor_id, settings, priority, transition, verify);
+        }
+
+        self.config.current_settings = settings;
+
+        let monitor_ids: Vec<String> = self
+            .monitors
+            .iter()
+            .filter(|m| m.supports_gamma)
+            .map(|m| m.id.clone())
+            .collect();
+
+        for monitor_id in monitor_ids {
+            self.queue_settings(&monitor_id, settings, priority, transition, verify)?;
+        }
+
+        Ok(())
+    }
+
+    /// Build the error for a monitor without gamma ramp support, logging it the first time.
+    fn unsupported_gamma_error(&mut self, monitor: &MonitorInfo) -> GammaError {
+        if self.gamma_unsupported_logged.insert(monitor.id.clone()) {
+            println!(
+                "{} doesn't support gamma ramps, ignoring changes",
+                monitor.name
+            );
+        }
+
+        GammaError::Unsupported(format!("{} doesn't support gamma ramps", monitor.name))
+    }
+
+    fn queue_settings(
+        &mut self,
+        monitor_id: &str,
+        settings: DisplaySettings,
+        priority: ApplyPriority,
+        transition: Option<Duration>,
+        verify: bool,
+    ) -> Result<(), GammaError> {
+        let monitor = find_monitor(&self.monitors, Some(monitor_id));
+
+        if let Some(monitor) = monitor.as_ref().filter(|m| !m.supports_gamma) {
+            return Err(self.unsupported_gamma_error(monitor));
+        }
+
+        if monitor_id == self.config.selected_monitor_id {
+            self.config.current_settings = settings;
+        }
+
+        let monitor = monitor.ok_or_else(|| GammaError::MonitorNotFound {
+            id: monitor_id.to_string(),
+        })?;
+        self.queued.insert(monitor.id.clone(), settings);
+
+        self.worker.enqueue(ApplyRequest {
+            hardware_control: self.brightness_control(&monitor.id),
+            lut: self.lut(&monitor.id).cloned().map(Arc::new),
+            monitor,
+            settings,
+            priority,
+            transition,
+            verify,
+        });
+
+        Ok(())
+    }
+
+    /// Apply a saved profile to its monitor, or the selected one when it isn't bound to one.
+    pub fn apply_profile(&mut self, id: u64) -> Result<(), GammaError> {
+        let profile = self.profile(id)?.clone();
+        let all_monitors = self.config.apply_to_all_monitors;
+
+        self.queue_profile(&profile, ApplyPriority::Normal, all_monitors)
+    }
+
+    /// Apply a saved profile to a chosen monitor, whatever it's bound to. The current settings,
+    /// the schedule and the previous settings are only affected when that's the selected monitor.
+    pub fn apply_profile_to(&mut self, id: u64, monitor_id: &str) -> Result<(), GammaError> {
+        let profile = self.profile(id)?.clone();
+        let transition = self.config.transition.duration();
+        let verify = self.config.verify_applies;
+        let monitor = find_monitor(&self.monitors, Some(monitor_id));
+        let selected = self.selected_monitor().map(|m| m.id);
+
+        if monitor.is_some_and(|m| selected == Some(m.id)) {
+            self.manual_change();
+            self.mark_applied(profile.id);
+            self.remember_previous();
+        }
+        self.queue_settings(
+            monitor_id,
+            profile.settings,
+            ApplyPriority::Normal,
+            transition,
+            verify,
+        )
+    }
+
+    /// Look up a saved profile by its id.
+    fn profile(&self, id: u64) -> Result<&Profile, GammaError> {
+        self.config
+            .profile_manager
+            .get_profile_by_id(id)
+            .ok_or(GammaError::ProfileNotFound { id })
+    }
+
+    /// Look up a saved profile by its id, to change it.
+    fn profile_mut(&mut self, id: u64) -> Result<&mut Profile, GammaError> {
+        self.config
+            .profile_manager
+            .get_profile_by_id_mut(id)
+            .ok_or(GammaError::ProfileNotFound { id })
+    }
+
+    /// Record that a profile was just applied.
+    fn mark_applied(&mut self, id: u64) {
+        let profiles = self.config.profile_manager.profiles_mut();
+
+        if let Some(profile) = profiles.iter_mut().find(|p| p.id == id) {
+            profile.last_applied = Some(SystemTime::now());
+        }
+    }
+
+    /// Fade to a profile's settings on the monitor it's bound to. Unbound profiles, and those
+    /// whose monitor isn't connected, go to the selected monitor or every monitor like other
+    /// settings changes; the latter leaves a notice.
+    fn queue_profile(
+        &mut self,
+        profile: &Profile,
+        priority: ApplyPriority,
+        all_monitors: bool,
+    ) -> Result<(), GammaError> {
+        let transition = self.config.transition.duration();
+        let verify = self.config.verify_applies;
+        self.manual_change();
+        self.mark_applied(profile.id);
+        // The previous settings are the selected monitor's, so only a change to it replaces them
+        if self.profile_monitor(profile).is_none() {
+            self.remember_previous();
+        }
+
+        if let Some(monitor_id) = &profile.monitor_id {
+            if let Some(monitor) = find_monitor(&self.monitors, Some(monitor_id)) {
+                return self.queue_settings(
+                    &monitor.id,
+                    profile.settings,
+                    priority,
+                    transition,
+                    verify,
+                );
+            }
+
+            let target = if all_monitors {
+                "every monitor".to_string()
+            } else {
+                self.selected_monitor()
+                    .map(|m| m.name)
+                    .unwrap_or_else(|| "the selected monitor".to_string())
+            };
+            self.notice = Some(format!(
+                "The monitor for {} isn't connected; applied it to {} instead",
+                profile.name, target
+            ));
+        }
+
+        self.queue_current(profile.settings, priority, transition, all_monitors, verify)
+    }
+
+    /// Bind a saved profile to a monitor, or unbind it with `None`.
+    pub fn set_profile_monitor(
+        &mut self,
+        id: u64,
+        monitor_id: Option<String>,
+    ) -> Result<(), GammaError> {
+        let profile = self.profile_mut(id)?;
+        profile.monitor_id = monitor_id;
+
+        Ok(())
+    }
+
+    /// Replace a saved profile's description.
+    pub fn set_profile_description(
+        &mut self,
+        id: u64,
+        description: String,
+    ) -> Result<(), GammaError> {
+        let profile = self.profile_mut(id)?;
+        profile.description = description;
+
+        Ok(())
+    }
+
+    /// Mark a saved profile with an accent color and icon, or clear them with `None` and an
+    /// empty icon.
+    pub fn set_profile_marker(
+        &mut self,
+        id: u64,
+        color: Option<ProfileColor>,
+        icon: String,
+    ) -> Result<(), GammaError> {
+        let profile = self.profile_mut(id)?;
+        profile.color = color;
+        profile.icon = icon;
+
+        Ok(())
+    }
+
+    /// Pin a saved profile to the top of the list, or unpin it.
+    pub fn set_profile_pinned(&mut self, id: u64, pinned: bool) -> Result<(), GammaError> {
+        self.profile_mut(id)?.pinned = pinned;
+
+        Ok(())
+    }
+
+    /// Lock a saved profile against changes and deletion, or unlock it.
+    pub fn set_profile_locked(&mut self, id: u64, locked: bool) -> Result<(), GammaError> {
+        if !self.config.profile_manager.set_locked(id, locked) {
+            return Err(GammaError::ProfileNotFound { id });
+        }
+
+        Ok(())
+    }
+
+    /// Take the notice left by the latest apply, if any.
+    pub fn take_notice(&mut self) -> Option<String> {
+        self.notice.take()
+    }
+
+    /// Replace a saved profile's settings, leaving the live settings alone.
+    pub fn update_profile_settings(
+        &mut self,
+        id: u64,
+        settings: DisplaySettings,
+    ) -> Result<(), GammaError> {
+        settings.validate()?;
+
+        let mut profile = self.profile(id)?.clone();
+        profile.settings = settings;
+
+        self.config
+            .profile_manager
+            .update_profile(id, profile, false)
+    }
+
+    /// Move a saved profile to another position, or the end if `to` is past it. Profile hotkeys
+    /// follow the profile's id, so they keep loading the same profile.
+    pub fn move_profile(&mut self, id: u64, to: usize) -> Result<(), GammaError> {
+        let manager = &mut self.config.profile_manager;
+        let from = manager
+            .position(id)
+            .ok_or(GammaError::ProfileNotFound { id })?;
+        let to = to.min(manager.profile_count() - 1);
+        manager.move_profile(from, to);
+
+        Ok(())
+    }
+
+    /// Remove a saved profile, along with the hotkeys that loaded it. Returns what was removed,
+    /// which [`Self::restore_profile`] puts back.
+    pub fn remove_profile(&mut self, id: u64) -> Result<RemovedProfile, GammaError> {
+        let index = self
+            .config
+            .profile_manager
+            .position(id)
+            .ok_or(GammaError::ProfileNotFound { id })?;
+        let profile = self.config.profile_manager.remove_profile(id, false)?;
+
+        let actions: Vec<HotkeyAction> = self
+            .config
+            .keybinds
+            .keys()
+            .filter(|action| action.profile_id() == Some(profile.id))
+            .copied()
+            .collect();
+        let keybinds = actions
+            .into_iter()
+            .filter_map(|action| Some((action, self.config.keybinds.remove(&action)?)))
+            .collect();
+
+        Ok(RemovedProfile {
+            index,
+            profile,
+            keybinds,
+        })
+    }
+
+    /// Remove several profiles at once, along with their hotkeys. Locked profiles are kept.
+    /// Returns the removed profiles.
+    pub fn remove_profiles(&mut self, ids: &HashSet<u64>) -> Vec<Profile> {
+        let removed = self.config.profile_manager.remove_profiles(ids, false);
+        let removed_ids: HashSet<u64> = removed.iter().map(|p| p.id).collect();
+        self.config.keybinds.retain(|action, _| {
+            !action
+                .profile_id()
+                .is_some_and(|id| removed_ids.contains(&id))
+        });
+
+        removed
+    }
+
+    /// Undo a profile's removal, putting it back where it was with its hotkeys. Hotkeys whose
+    /// keys have been bound to something else since stay removed.
+    pub fn restore_profile(&mut self, removed: RemovedProfile) {
+        self.config
+            .profile_manager
+            .restore_profile(removed.index, removed.profile);
+
+        for (action, bindings) in removed.keybinds {
+            let free: Vec<KeybindConfig> = bindings
+                .into_iter()
+                .filter(|keybind| binding_using(&self.config.keybinds, keybind, action).is_none())
+                .collect();
+
+            if !free.is_empty() {
+                self.config.keybinds.insert(action, free);
+            }
+        }
+    }
+
+    /// Write every saved profile to a standalone file, for importing on another install.
+    pub fn export_profiles(&self, path: &Path) -> Result<(), GammaError> {
+        profiles::save(self.config.profile_manager.get_profiles(), path)
+    }
+
+    /// Add the profiles of a file written by [`Self::export_profiles`] to the saved ones.
+    pub fn import_profiles(&mut self, path: &Path) -> Result<ImportSummary, GammaError> {
+        let imported = profiles::load(path)?;
+
+        Ok(self.config.profile_manager.import(imported))
+    }
+
+    /// Add the profile shared as a code by [`profiles::to_code`], numbering its name if it's
+    /// taken. Returns the name it was added under.
+    pub fn import_profile_code(&mut self, code: &str) -> Result<String, GammaError> {
+        let profile = profiles::from_code(code)?;
+        let manager = &mut self.config.profile_manager;
+        let id = manager.add_numbered(profile);
+
+        Ok(manager
+            .get_profile_by_id(id)
+            .map(|p| p.name.clone())
+            .unwrap_or_default())
+    }
+
+    /// Perform a hotkey action on the [hotkey monitor](Self::hotkey_monitor), or every monitor
+    /// when hotkeys or all settings changes target all of them, returning the resulting settings.
+    ///
+    /// Resets, restores and profile loads fade; step adjustments apply at once so repeated presses
+    /// stay responsive. Profiles bound to a monitor load on that monitor instead.
+    pub fn adjust(&mut self, action: HotkeyAction) -> Result<DisplaySettings, GammaError> {
+        let all_monitors =
+            self.config.apply_to_all_monitors || self.config.hotkeys_apply_to_all_monitors;
+        // Another monitor than the selected one is adjusted from its own settings, leaving the
+        // current settings, and the Settings tab's sliders, to the selected one
+        let other_monitor = self
+            .hotkey_monitor()
+            .filter(|m| !all_monitors && m.id != self.config.selected_monitor_id)
+            .map(|m| m.id);
+        let settings = match &other_monitor {
+            Some(id) => self.adjusted_from(action, self.monitor_settings(id)),
+            None => self.adjusted_settings(action),
+        };
+
+        if let HotkeyAction::ToggleProfile(id) = action {
+            return self.toggle_profile(id, all_monitors);
+        }
+
+        if let HotkeyAction::SelectMonitor(index) = action {
+            let id = match self.monitors.get(index) {
+                Some(monitor) => monitor.id.clone(),
+                None => {
+                    return Err(GammaError::MonitorNotFound {
+                        id: format!("#{}", index + 1),
+                    })
+                }
+            };
+            self.select_monitor(&id)?;
+
+            return Ok(settings);
+        }
+
+        if action == HotkeyAction::NextMonitor {
+            self.select_next_monitor()?;
+
+            return Ok(settings);
+        }
+
+        // The window is the UI's to show, and no settings change
+        if action == HotkeyAction::ToggleWindow {
+            return Ok(settings);
+        }
+
+        if let HotkeyAction::LoadProfile(id) = action {
+            let profile = self.profile(id)?.clone();
+            self.queue_profile(&profile, ApplyPriority::High, all_monitors)?;
+
+            return Ok(settings);
+        }
+
+        let resets = matches!(
+            action,
+            HotkeyAction::Reset
+                | HotkeyAction::ResetGamma
+                | HotkeyAction::ResetBrightness
+                | HotkeyAction::ResetContrast
+        );
+        let fades = resets || action == HotkeyAction::RestorePrevious;
+        let transition = if fades {
+            self.config.transition.duration()
+        } else {
+            None
+        };
+
+        let verify = self.config.verify_applies;
+        self.manual_change();
+        self.guard_dimming(other_monitor.clone(), all_monitors, settings);
+
+        if let Some(monitor_id) = other_monitor {
+            self.queue_settings(
+                &monitor_id,
+                settings,
+                ApplyPriority::High,
+                transition,
+                verify,
+            )?;
+
+            return Ok(settings);
+        }
+
+        if resets || action == HotkeyAction::RestorePrevious {
+            self.remember_previous();
+        }
+
+        self.queue_current(
+            settings,
+            ApplyPriority::High,
+            transition,
+            all_monitors,
+            verify,
+        )?;
+
+        Ok(settings)
+    }
+
+    /// Load a profile, remembering the settings to return to, or return to them when the profile
+    /// is the one toggled on last. Toggling another profile on keeps the original return point.
+    fn toggle_profile(
+        &mut self,
+        id: u64,
+        all_monitors: bool,
+    ) -> Result<DisplaySettings, GammaError> {
+        let transition = self.config.transition.duration();
+        let verify = self.config.verify_applies;
+
+        if let Some(point) = self.return_point.take_if(|point| point.profile_id == id) {
+            self.manual_change();
+
+            match &point.monitor_id {
+                Some(monitor_id) => self.queue_settings(
+                    monitor_id,
+                    point.settings,
+                    ApplyPriority::High,
+                    transition,
+                    verify,
+                )?,
+                None => self.queue_current(
+                    point.settings,
+                    ApplyPriority::High,
+                    transition,
+                    all_monitors,
+                    verify,
+                )?,
+            }
+
+            return Ok(point.settings);
+        }
+
+        let profile = self.profile(id)?.clone();
+        let monitor_id = profile
+            .monitor_id
+            .as_deref()
+            .and_then(|id| find_monitor(&self.monitors, Some(id)))
+            .map(|m| m.id);
+        let settings = match self.return_point.take() {
+            Some(point) if point.monitor_id == monitor_id => point.settings,
+            _ => monitor_id
+                .as_deref()
+                .and_then(|id| self.applied_settings(id))
+                .unwrap_or(self.config.current_settings),
+        };
+
+        self.queue_profile(&profile, ApplyPriority::High, all_monitors)?;
+        self.return_point = Some(ReturnPoint {
+            profile_id: id,
+            monitor_id,
+            settings,
+        });
+
+        Ok(profile.settings)
+    }
+
+    /// Get the settings a monitor is heading for: those queued last, or else those applied.
+    /// A monitor never adjusted is assumed to be at the defaults.
+    pub fn monitor_settings(&self, monitor_id: &str) -> DisplaySettings {
+        self.queued
+            .get(monitor_id)
+            .copied()
+            .or_else(|| self.applied_settings(monitor_id))
+            .unwrap_or_default()
+    }
+
+    /// Compute the settings a hotkey action would produce from the current settings.
+    pub fn adjusted_settings(&self, action: HotkeyAction) -> DisplaySettings {
+        self.adjusted_from(action, self.config.current_settings)
+    }
+
+    fn adjusted_from(&self, action: HotkeyAction, settings: DisplaySettings) -> DisplaySettings {
+        let step = &self.config.step_size;
+        let mut settings = settings;
+
+        match action {
+            HotkeyAction::IncreaseGamma => {
+                settings.gamma = (settings.gamma + step.gamma).min(3.0);
+            }
+            HotkeyAction::DecreaseGamma => {
+                settings.gamma = (settings.gamma - step.gamma).max(0.1);
+            }
+            HotkeyAction::IncreaseBrightness => {
+                settings.brightness = (settings.brightness + step.brightness).min(1.0);
+            }
+            HotkeyAction::DecreaseBrightness => {
+                settings.brightness = (settings.brightness - step.brightness).max(-1.0);
+            }
+            HotkeyAction::IncreaseContrast => {
+                settings.contrast = (settings.contrast + step.contrast).min(3.0);
+            }
+            HotkeyAction::DecreaseContrast => {
+                settings.contrast = (settings.contrast - step.contrast).max(0.1);
+            }
+            HotkeyAction::IncreaseDimming => {
+                settings.extra_dimming =
+                    (settings.extra_dimming + step.extra_dimming).min(MAX_EXTRA_DIMMING);
+            }
+            HotkeyAction::DecreaseDimming => {
+                settings.extra_dimming = (settings.extra_dimming - step.extra_dimming).max(0.0);
+            }
+            HotkeyAction::Reset => {
+                settings = DisplaySettings::default();
+            }
+            HotkeyAction::ResetGamma => {
+                settings.gamma = DisplaySettings::default().gamma;
+            }
+            HotkeyAction::ResetBrightness => {
+                settings.brightness = DisplaySettings::default().brightness;
+            }
+            HotkeyAction::ResetContrast => {
+                settings.contrast = DisplaySettings::default().contrast;
+            }
+            HotkeyAction::RestorePrevious => {
+                settings = self.config.previous_settings.unwrap_or(settings);
+            }
+            HotkeyAction::LoadProfile(id) => {
+                if let Some(profile) = self.config.profile_manager.get_profile_by_id(id) {
+                    settings = profile.settings;
+                }
+            }
+            HotkeyAction::ToggleProfile(id) => match &self.return_point {
+                Some(point) if point.profile_id == id => settings = point.settings,
+                _ => {
+                    if let Some(profile) = self.config.profile_manager.get_profile_by_id(id) {
+                        settings = profile.settings;
+                    }
+                }
+            },
+            HotkeyAction::ToggleFilter => {
+                settings.color_filter = match (settings.color_filter, self.config.toggle_filter) {
+                    (ColorFilter::None, ColorFilter::None) => ColorFilter::Grayscale,
+                    (ColorFilter::None, filter) => filter,
+                    _ => ColorFilter::None,
+                };
+            }
+            HotkeyAction::ToggleInvert => {
+                settings.invert = !settings.invert;
+            }
+            HotkeyAction::ApplyTemperaturePreset(kelvin) => {
+                settings.temperature = kelvin.clamp(MIN_TEMPERATURE, MAX_TEMPERATURE);
+            }
+            HotkeyAction::SelectMonitor(_)
+            | HotkeyAction::NextMonitor
+            | HotkeyAction::ToggleWindow => {}
+        }
+
+        settings
+    }
+
+    /// Save the configuration to disk.
+    pub fn save(&self) -> Result<(), GammaError> {
+        self.config.save().map_err(|source| GammaError::Io {
+            context: "Failed to save config".to_string(),
+            source,
+        })
+    }
+
+    /// Save the config once it has gone unchanged for [`SAVE_DELAY`], so dragging a slider or
+    /// holding a hotkey doesn't write the file on every step. The save happens in
+    /// [`Self::save_if_due`], which should be called periodically.
+    pub fn save_soon(&mut self) {
+        self.save_due = Some(Instant::now() + SAVE_DELAY);
+    }
+
+    /// Check whether a deferred save is waiting.
+    pub fn save_pending(&self) -> bool {
+        self.save_due.is_some()
+    }
+
+    /// Apply what the schedule calls for at a local time, returning whether anything was queued.
+    /// Call it periodically; as the settings follow from the time alone, starting mid-transition
+    /// or waking from sleep simply picks up where the schedule stands.
+    ///
+    /// A manual change pauses the schedule until the next event takes effect.
+    pub fn tick_schedule(&mut self, time: &LocalTime) -> Result<bool, GammaError> {
+        if !self.config.schedule.enabled {
+            return Ok(false);
+        }
+
+        let Some(state) = self
+            .config
+            .schedule
+            .state_at(&self.config.profile_manager, time)
+        else {
+            return Ok(false);
+        };
+
+        let event_changed =
+            self.schedule_event.replace(state.event_start) != Some(state.event_start);
+
+        if self.schedule_paused && !event_changed {
+            return Ok(false);
+        }
+
+        self.schedule_paused = false;
+
+        if state.settings == self.config.current_settings {
+            return Ok(false);
+        }
+
+        let all_monitors = self.config.apply_to_all_monitors;
+        let verify = self.config.verify_applies;
+
+        self.queue_current(
+            state.settings,
+            ApplyPriority::Low,
+            None,
+            all_monitors,
+            verify,
+        )?;
+
+        Ok(true)
+    }
+
+    /// Check whether a manual change is holding the schedule off until its next event.
+    pub fn schedule_paused(&self) -> bool {
+        self.config.schedule.enabled && self.schedule_paused
+    }
+
+    /// Perform the deferred save once it's due, returning whether it was written.
+    pub fn save_if_due(&mut self) -> Result<bool, GammaError> {
+        match self.save_due {
+            Some(due) if Instant::now() >= due => self.flush_save().map(|_| true),
+            _ => Ok(false),
+        }
+    }
+
+    /// Perform any deferred save right away, e.g. before exiting.
+    pub fn flush_save(&mut self) -> Result<(), GammaError> {
+        if self.save_due.take().is_some() {
+            return self.save();
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        backend::mock::{monitor, MockBackend},
+        schedule::{ScheduleConfig, ScheduleEvent},
+        windows::display::MonitorRect,
+        MonitorRule,
+    };
+
+    fn controller(config: AppConfig) -> GammaController {
+        let backend = MockBackend::new(vec![
+            monitor("primary", "\\\\.\\DISPLAY1", true),
+            monitor("secondary", "\\\\.\\DISPLAY2", false),
+        ]);
+
+        GammaController::new(config, Box::new(backend))
+    }
+
+    #[test]
+    fn selects_primary_when_unset() {
+        let controller = controller(AppConfig::default());
+
+        assert_eq!(controller.config().selected_monitor_id, "primary");
+    }
+
+    #[test]
+    fn keeps_configured_monitor_when_present() {
+        let config = AppConfig {
+            selected_monitor_id: "secondary".to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(controller(config).config().selected_monitor_id, "secondary");
+    }
+
+    #[test]
+    fn increase_gamma_steps_and_clamps() {
+        let mut controller = controller(AppConfig::default());
+
+        let settings = controller.adjust(HotkeyAction::IncreaseGamma).unwrap();
+        assert!((settings.gamma - 1.1).abs() < 1e-6);
+
+        controller.config_mut().current_settings.gamma = 2.95;
+        let settings = controller.adjust(HotkeyAction::IncreaseGamma).unwrap();
+        assert_eq!(settings.gamma, 3.0);
+    }
+
+    #[test]
+    fn decrease_actions_clamp_at_minimum() {
+        let mut controller = controller(AppConfig::default());
+        controller.config_mut().current_settings = DisplaySettings::new(0.15, -0.98, 0.12);
+
+        let settings = controller.adjusted_settings(HotkeyAction::DecreaseGamma);
+        assert_eq!(settings.gamma, 0.1);
+
+        let settings = controller.adjusted_settings(HotkeyAction::DecreaseBrightness);
+        assert_eq!(settings.brightness, -1.0);
+
+        let settings = controller.adjusted_settings(HotkeyAction::DecreaseContrast);
+        assert_eq!(settings.contrast, 0.1);
+
+        let settings = controller.adjusted_settings(HotkeyAction::DecreaseDimming);
+        assert_eq!(settings.extra_dimming, 0.0);
+    }
+
+    #[test]
+    fn loads_and_resets_remember_the_previous_settings() {
+        let mut controller = controller(AppConfig::default());
+        let start = DisplaySettings::new(1.4, 0.1, 1.0);
+        let night = DisplaySettings::new(0.8, -0.2, 1.0);
+        controller.config_mut().current_settings = start;
+        let id = controller
+            .config_mut()
+            .profile_manager
+            .add_profile(Profile::new("Night".to_string(), night));
+
+        // Step adjustments aren't remembered
+        controller.adjust(HotkeyAction::IncreaseGamma).unwrap();
+        assert_eq!(controller.config().previous_settings, None);
+        controller.config_mut().current_settings = start;
+
+        controller.adjust(HotkeyAction::LoadProfile(id)).unwrap();
+        assert_eq!(controller.config().previous_settings, Some(start));
+
+        let settings = controller.adjust(HotkeyAction::RestorePrevious).unwrap();
+        assert_eq!(settings, start);
+        assert_eq!(controller.config().previous_settings, Some(night));
+
+        controller.adjust(HotkeyAction::Reset).unwrap();
+        assert_eq!(controller.config().previous_settings, Some(start));
+
+        controller.restore_previous().unwrap();
+        assert_eq!(controller.config().current_settings, start);
+    }
+
+    #[test]
+    fn dimming_stops_at_its_cap() {
+        let mut controller = controller(AppConfig::default());
+        controller.config_mut().current_settings.extra_dimming = 0.85;
+
+        let settings = controller.adjusted_settings(HotkeyAction::IncreaseDimming);
+        assert_eq!(settings.extra_dimming, MAX_EXTRA_DIMMING);
+    }
+
+    #[test]
+    fn heavy_dimming_is_undone_unless_kept() {
+        let mut controller = controller(AppConfig::default());
+        let dim = |extra_dimming| DisplaySettings {
+            extra_dimming,
+            ..DisplaySettings::default()
+        };
+        let later = || Instant::now() + DIMMING_REVERT_DELAY;
+
+        // Moderate dimming needs no keeping
+        controller.set_current_settings(dim(0.4)).unwrap();
+        assert_eq!(controller.dimming_revert_in(), None);
+
+        controller.set_current_settings(dim(0.8)).unwrap();
+        assert!(controller.dimming_revert_in().is_some());
+        assert!(!controller.revert_dimming_if_due(Instant::now()).unwrap());
+
+        // Only the dimming is undone, back to where it was before going past the threshold
+        let brighter = DisplaySettings {
+            gamma: 1.3,
+            ..dim(0.9)
+        };
+        controller.set_current_settings(brighter).unwrap();
+        assert!(controller.revert_dimming_if_due(later()).unwrap());
+        assert_eq!(
+            controller.config().current_settings,
+            DisplaySettings {
+                gamma: 1.3,
+                ..dim(0.4)
+            }
+        );
+        assert!(controller.take_notice().is_some());
+        assert_eq!(controller.dimming_revert_in(), None);
+
+        // Kept dimming, dimming back below the threshold, and hotkeys
+        controller.set_current_settings(dim(0.8)).unwrap();
+        controller.keep_dimming();
+        assert!(!controller.revert_dimming_if_due(later()).unwrap());
+        controller.set_current_settings(dim(0.0)).unwrap();
+        controller.set_current_settings(dim(0.8)).unwrap();
+        controller.set_current_settings(dim(0.2)).unwrap();
+        assert_eq!(controller.dimming_revert_in(), None);
+        controller.config_mut().step_size.extra_dimming = 0.4;
+        controller.adjust(HotkeyAction::IncreaseDimming).unwrap();
+        assert!(controller.dimming_revert_in().is_some());
+        controller.keep_dimming();
+
+        controller.set_current_settings(dim(0.0)).unwrap();
+        controller.config_mut().disable_dimming_revert = true;
+        controller.set_current_settings(dim(0.8)).unwrap();
+        assert_eq!(controller.dimming_revert_in(), None);
+    }
+
+    #[test]
+    fn increase_actions_use_configured_step() {
+        let mut controller = controller(AppConfig::default());
+        controller.config_mut().step_size.brightness = 0.2;
+        controller.config_mut().step_size.contrast = 0.5;
+
+        let settings = controller.adjusted_settings(HotkeyAction::IncreaseBrightness);
+        assert!((settings.brightness - 0.2).abs() < 1e-6);
+
+        let settings = controller.adjusted_settings(HotkeyAction::IncreaseContrast);
+        assert!((settings.contrast - 1.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn reset_restores_defaults() {
+        let mut controller = controller(AppConfig::default());
+        controller.config_mut().current_settings = DisplaySettings::new(2.0, 0.5, 2.0);
+
+        let settings = controller.adjust(HotkeyAction::Reset).unwrap();
+
+        assert_eq!(settings, DisplaySettings::default());
+        assert_eq!(
+            controller.config().current_settings,
+            DisplaySettings::default()
+        );
+    }
+
+    #[test]
+    fn toggle_filter_switches_remembered_filter() {
+        let mut controller = controller(AppConfig::default());
+
+        let settings = controller.adjust(HotkeyAction::ToggleFilter).unwrap();
+        assert_eq!(settings.color_filter, ColorFilter::Grayscale);
+
+        let settings = controller.adjust(HotkeyAction::ToggleFilter).unwrap();
+        assert_eq!(settings.color_filter, ColorFilter::None);
+
+        controller.config_mut().toggle_filter = ColorFilter::Protanopia;
+        let settings = controller.adjust(HotkeyAction::ToggleFilter).unwrap();
+        assert_eq!(settings.color_filter, ColorFilter::Protanopia);
+    }
+
+    #[test]
+    fn toggle_invert_flips_and_reset_clears() {
+        let mut controller = controller(AppConfig::default());
+
+        let settings = controller.adjust(HotkeyAction::ToggleInvert).unwrap();
+        assert!(settings.invert);
+
+        let settings = controller.adjust(HotkeyAction::Reset).unwrap();
+        assert!(!settings.invert);
+    }
+
+    #[test]
+    fn temperature_preset_sets_and_clamps_temperature() {
+        let mut controller = controller(AppConfig::default());
+
+        let settings = controller
+            .adjust(HotkeyAction::ApplyTemperaturePreset(2700))
+            .unwrap();
+        assert_eq!(settings.temperature, 2700);
+
+        let settings = controller.adjusted_settings(HotkeyAction::ApplyTemperaturePreset(200));
+        assert_eq!(settings.temperature, MIN_TEMPERATURE);
+    }
+
+    #[test]
+    fn granular_resets_keep_other_settings() {
+        let mut controller = controller(AppConfig::default());
+        let dimmed = DisplaySettings::new(1.6, -0.4, 1.3);
+        controller.config_mut().current_settings = dimmed;
+
+        let settings = controller.adjust(HotkeyAction::ResetGamma).unwrap();
+        assert_eq!(settings, DisplaySettings::new(1.0, -0.4, 1.3));
+        assert_eq!(controller.config().previous_settings, Some(dimmed));
+
+        let settings = controller.adjusted_settings(HotkeyAction::ResetContrast);
+        assert_eq!(settings, DisplaySettings::new(1.0, -0.4, 1.0));
+
+        let settings = controller.adjusted_settings(HotkeyAction::ResetBrightness);
+        assert_eq!(settings, DisplaySettings::new(1.0, 0.0, 1.3));
+    }
+
+    #[test]
+    fn select_monitor_action_follows_enumeration_order() {
+        let mut controller = controller(AppConfig::default());
+        let settings = controller.config().current_settings;
+
+        assert_eq!(
+            controller.adjust(HotkeyAction::SelectMonitor(1)).unwrap(),
+            settings
+        );
+        assert_eq!(controller.config().selected_monitor_id, "secondary");
+
+        assert!(matches!(
+            controller.adjust(HotkeyAction::SelectMonitor(2)),
+            Err(GammaError::MonitorNotFound { .. })
+        ));
+        assert_eq!(controller.config().selected_monitor_id, "secondary");
+    }
+
+    #[test]
+    fn next_monitor_wraps_and_skips_detached_monitors() {
+        let primary = monitor("primary", "\\\\.\\DISPLAY1", true);
+        let secondary = monitor("secondary", "\\\\.\\DISPLAY2", false);
+        let third = monitor("third", "\\\\.\\DISPLAY3", false);
+        let backend = MockBackend::new(vec![primary.clone(), secondary.clone(), third.clone()]);
+        let mut controller = GammaController::new(AppConfig::default(), Box::new(backend.clone()));
+
+        let mut next = || {
+            controller.adjust(HotkeyAction::NextMonitor).unwrap();
+            controller.config().selected_monitor_id.clone()
+        };
+        assert_eq!(next(), "secondary");
+        assert_eq!(next(), "third");
+        assert_eq!(next(), "primary");
+
+        backend.set_monitors(vec![primary.clone(), third.clone()]);
+        assert_eq!(next(), "third");
+    }
+
+    #[test]
+    fn hotkeys_can_target_the_monitor_under_the_cursor() {
+        let mut primary = monitor("primary", "\\\\.\\DISPLAY1", true);
+        primary.bounds = MonitorRect {
+            x: 0,
+            y: 0,
+            width: 1920,
+            height: 1080,
+        };
+        let mut secondary = monitor("secondary", "\\\\.\\DISPLAY2", false);
+        secondary.bounds = MonitorRect {
+            x: 1920,
+            ..primary.bounds
+        };
+        let backend = MockBackend::new(vec![primary, secondary]);
+        let config = AppConfig {
+            selected_monitor_id: "primary".to_string(),
+            hotkey_target: HotkeyTarget::MonitorUnderCursor,
+            ..AppConfig::default()
+        };
+        let mut controller = GammaController::new(config, Box::new(backend.clone()));
+        let step = controller.config().step_size.gamma;
+
+        // Held hotkeys step from the last queued settings, before they're applied
+        backend.set_cursor_position(2500, 300);
+        assert_eq!(controller.hotkey_monitor().unwrap().id, "secondary");
+        controller.adjust(HotkeyAction::IncreaseGamma).unwrap();
+        let settings = controller.adjust(HotkeyAction::IncreaseGamma).unwrap();
+        controller.flush();
+
+        assert!((settings.gamma - (1.0 + 2.0 * step)).abs() < 1e-6);
+        assert_eq!(
+            controller.applied_settings("secondary").as_ref(),
+            Some(&settings)
+        );
+        // The selected monitor and its sliders are left alone
+        assert_eq!(
+            controller.config().current_settings,
+            DisplaySettings::default()
+        );
+        assert_eq!(controller.config().selected_monitor_id, "primary");
+
+        backend.set_cursor_position(100, 300);
+        let settings = controller.adjust(HotkeyAction::IncreaseGamma).unwrap();
+        assert_eq!(controller.config().current_settings, settings);
+
+        // Off every monitor, the selected one is adjusted
+        backend.set_cursor_position(-500, -500);
+        assert_eq!(controller.hotkey_monitor().unwrap().id, "primary");
+    }
+
+    #[test]
+    fn load_profile_applies_profile_settings() {
+        let mut config = AppConfig::default();
+        config.schedule.enabled = true;
+        let mut controller = controller(config);
+        let night = DisplaySettings::new(0.8, -0.3, 0.9);
+        let id = controller
+            .config_mut()
+            .profile_manager
+            .add_profile(Profile::new("Night".to_string(), night));
+
+        // Missing profiles are an error, and change nothing
+        assert!(matches!(
+            controller.adjust(HotkeyAction::LoadProfile(id + 5)),
+            Err(GammaError::ProfileNotFound { .. })
+        ));
+        assert!(!controller.schedule_paused());
+        assert_eq!(
+            controller.config().current_settings,
+            DisplaySettings::default()
+        );
+
+        let settings = controller.adjust(HotkeyAction::LoadProfile(id)).unwrap();
+        assert_eq!(settings, night);
+        let profile = controller.config().profile_manager.get_profile_by_id(id);
+        assert!(profile.unwrap().last_applied.is_some());
+    }
+
+    #[test]
+    fn bound_profiles_load_on_their_monitor() {
+        let mut controller = controller(AppConfig::default());
+        let movie = DisplaySettings::new(1.2, 0.1, 1.1);
+        let id = controller
+            .config_mut()
+            .profile_manager
+            .add_profile(Profile::new("Movie".to_string(), movie));
+        controller
+            .set_profile_monitor(id, Some("secondary".to_string()))
+            .unwrap();
+
+        controller.adjust(HotkeyAction::LoadProfile(id)).unwrap();
+        controller.flush();
+        assert_eq!(controller.applied_settings("secondary"), Some(movie));
+        assert_eq!(
+            controller.config().current_settings,
+            DisplaySettings::default()
+        );
+        assert_eq!(controller.take_notice(), None);
+        // The selected monitor didn't change, so there's nothing for it to restore
+        assert_eq!(controller.config().previous_settings, None);
+
+        // A missing monitor falls back to the selected one, with a notice
+        controller
+            .set_profile_monitor(id, Some("gone".to_string()))
+            .unwrap();
+        controller.apply_profile(id).unwrap();
+        assert_eq!(controller.config().current_settings, movie);
+        assert!(controller.take_notice().unwrap().contains("Movie"));
+        assert_eq!(
+            controller.config().previous_settings,
+            Some(DisplaySettings::default())
+        );
+    }
+
+    #[test]
+    fn profiles_apply_to_a_chosen_monitor() {
+        let mut controller = controller(AppConfig::default());
+        let movie = DisplaySettings::new(1.2, 0.1, 1.1);
+        let id = controller
+            .config_mut()
+            .profile_manager
+            .add_profile(Profile::new("Movie".to_string(), movie));
+
+        controller.apply_profile_to(id, "secondary").unwrap();
+        controller.flush();
+        assert_eq!(controller.applied_settings("secondary"), Some(movie));
+        assert_eq!(
+            controller.config().current_settings,
+            DisplaySettings::default()
+        );
+
+        let selected = controller.config().selected_monitor_id.clone();
+        controller.apply_profile_to(id, &selected).unwrap();
+        assert_eq!(controller.config().current_settings, movie);
+
+        assert!(matches!(
+            controller.apply_profile_to(id, "gone"),
+            Err(GammaError::MonitorNotFound { .. })
+        ));
+        assert!(controller.apply_profile_to(id + 1, &selected).is_err());
+    }
+
+    #[test]
+    fn applying_to_another_monitor_leaves_the_selected_one_alone() {
+        let mut config = AppConfig::default();
+        let id = config.profile_manager.add_profile(Profile::new(
+            "Movie".to_string(),
+            DisplaySettings::new(1.2, 0.1, 1.1),
+        ));
+        config.schedule.enabled = true;
+        let mut controller = controller(config);
+
+        controller.apply_profile_to(id, "secondary").unwrap();
+        assert!(!controller.schedule_paused());
+        assert_eq!(controller.config().previous_settings, None);
+        let profile = controller.config().profile_manager.get_profile_by_id(id);
+        assert_eq!(profile.unwrap().last_applied, None);
+
+        let selected = controller.config().selected_monitor_id.clone();
+        controller.apply_profile_to(id, &selected).unwrap();
+        assert!(controller.schedule_paused());
+        assert_eq!(
+            controller.config().previous_settings,
+            Some(DisplaySettings::default())
+        );
+        let profile = controller.config().profile_manager.get_profile_by_id(id);
+        assert!(profile.unwrap().last_applied.is_some());
+    }
+
+    #[test]
+    fn bound_profiles_preview_on_their_monitor() {
+        let mut controller = controller(AppConfig::default());
+        let movie = DisplaySettings::new(1.2, 0.1, 1.1);
+        let mut profile = Profile::new("Movie".to_string(), movie);
+        assert_eq!(controller.profile_monitor(&profile), None);
+
+        profile.monitor_id = Some("secondary".to_string());
+        let monitor = controller.profile_monitor(&profile).unwrap();
+        controller.preview_settings_on(&monitor.id, movie).unwrap();
+        controller.flush();
+        assert_eq!(controller.monitor_settings("secondary"), movie);
+        assert_eq!(
+            controller.config().current_settings,
+            DisplaySettings::default()
+        );
+
+        // The selected monitor, or a disconnected one, stands for the current settings
+        profile.monitor_id = Some(controller.config().selected_monitor_id.clone());
+        assert_eq!(controller.profile_monitor(&profile), None);
+        profile.monitor_id = Some("gone".to_string());
+        assert_eq!(controller.profile_monitor(&profile), None);
+    }
+
+    #[test]
+    fn power_switches_apply_their_profiles() {
+        let backend = MockBackend::new(vec![monitor("primary", "\\\\.\\DISPLAY1", true)]);
+        backend.set_power_source(Some(PowerSource::Ac));
+        let mut controller = GammaController::new(AppConfig::default(), Box::new(backend.clone()));
+        let saver = DisplaySettings::new(1.0, -0.3, 1.0);
+        let battery = controller
+            .config_mut()
+            .profile_manager
+            .add_profile(Profile::new("Saver".to_string(), saver));
+        controller.config_mut().power_profiles.on_battery = Some(battery);
+
+        // Starting up doesn't count as a switch
+        assert!(!controller.power_changed());
+
+        backend.set_power_source(Some(PowerSource::Battery));
+        assert!(controller.power_changed());
+        let applied = controller.refresh_power().unwrap();
+        assert_eq!(applied.map(|p| p.id), Some(battery));
+        assert_eq!(controller.config().current_settings, saver);
+        assert!(!controller.power_changed());
+
+        // Without a profile for AC, plugging in leaves the settings alone
+        backend.set_power_source(Some(PowerSource::Ac));
+        assert_eq!(controller.refresh_power().unwrap(), None);
+        assert_eq!(controller.config().current_settings, saver);
+
+        // Machines without a battery never switch
+        backend.set_power_source(None);
+        controller.refresh_power().unwrap();
+        assert!(!controller.power_changed());
+        assert_eq!(controller.power_source(), None);
+    }
+
+    #[test]
+    fn monitor_rules_apply_when_they_start_matching() {
+        let laptop = monitor("laptop", "\\\\.\\DISPLAY1", true);
+        let external = monitor("external", "\\\\.\\DISPLAY2", false);
+        let backend = MockBackend::new(vec![laptop.clone()]);
+        let mut controller = GammaController::new(AppConfig::default(), Box::new(backend.clone()));
+        let docked = DisplaySettings::new(1.2, 0.0, 1.0);
+        let undocked = DisplaySettings::new(1.0, -0.2, 1.0);
+        let mut add = |name: &str, settings| {
+            controller
+                .config_mut()
+                .profile_manager
+                .add_profile(Profile::new(name.to_string(), settings))
+        };
+        let (docked_id, undocked_id, other_id) = (
+            add("Docked", docked),
+            add("Undocked", undocked),
+            add("Other", DisplaySettings::default()),
+        );
+        let rule = |connected, profile_id| MonitorRule {
+            monitor_id: external.id.clone(),
+            monitor_name: external.name.clone(),
+            connected,
+            profile_id,
+        };
+        controller.config_mut().monitor_rules = vec![
+            rule(true, docked_id),
+            rule(false, undocked_id),
+            // Ties go to the earlier rule
+            rule(true, other_id),
+        ];
+
+        backend.set_monitors(vec![laptop.clone(), external.clone()]);
+        let change = controller.refresh_monitors();
+        assert_eq!(change.rule_profile.map(|p| p.id), Some(docked_id));
+        assert_eq!(controller.config().current_settings, docked);
+
+        // Rules that kept matching don't override manual changes
+        let manual = DisplaySettings::new(0.9, 0.0, 1.0);
+        controller.set_current_settings(manual).unwrap();
+        backend.set_monitors(vec![
+            laptop.clone(),
+            external.clone(),
+            monitor("tv", "\\\\.\\DISPLAY3", false),
+        ]);
+        assert_eq!(controller.refresh_monitors().rule_profile, None);
+        assert_eq!(controller.config().current_settings, manual);
+
+        backend.set_monitors(vec![laptop]);
+        let change = controller.refresh_monitors();
+        assert_eq!(change.rule_profile.map(|p| p.id), Some(undocked_id));
+        assert_eq!(controller.config().current_settings, undocked);
+    }
+
+    #[test]
+    fn profile_toggles_return_to_earlier_settings() {
+        let mut controller = controller(AppConfig::default());
+        let dark = DisplaySettings::new(0.8, -0.3, 0.9);
+        let id = controller
+            .config_mut()
+            .profile_manager
+            .add_profile(Profile::new("Dark maps".to_string(), dark));
+        let before = DisplaySettings::new(1.1, 0.0, 1.0);
+        controller.set_current_settings(before).unwrap();
+
+        let toggle = HotkeyAction::ToggleProfile(id);
+        assert_eq!(controller.adjust(toggle).unwrap(), dark);
+        assert_eq!(controller.adjusted_settings(toggle), before);
+        assert_eq!(controller.adjust(toggle).unwrap(), before);
+        assert_eq!(controller.config().current_settings, before);
+
+        // A manual change in between ends the toggle, so the next press loads the profile again
+        controller.adjust(toggle).unwrap();
+        controller.adjust(HotkeyAction::IncreaseGamma).unwrap();
+        assert_eq!(controller.adjust(toggle).unwrap(), dark);
+    }
+
+    #[test]
+    fn profile_edits_leave_live_settings_alone() {
+        let mut controller = controller(AppConfig::default());
+        let night = DisplaySettings::new(0.8, -0.3, 0.9);
+        let id = controller
+            .config_mut()
+            .profile_manager
+            .add_profile(Profile::new("Night".to_string(), night));
+
+        let edited = DisplaySettings {
+            gamma: 0.85,
+            ..night
+        };
+        controller.update_profile_settings(id, edited).unwrap();
+
+        let profile = controller.config().profile_manager.get_profile(0).unwrap();
+        assert_eq!((profile.name.as_str(), profile.settings), ("Night", edited));
+        assert_eq!(
+            controller.config().current_settings,
+            DisplaySettings::default()
+        );
+
+        let invalid = DisplaySettings {
+            gamma: 3.5,
+            ..night
+        };
+        assert!(matches!(
+            controller.update_profile_settings(id, invalid),
+            Err(GammaError::OutOfRange { .. })
+        ));
+        assert!(matches!(
+            controller.update_profile_settings(id + 1, edited),
+            Err(GammaError::ProfileNotFound { .. })
+        ));
+    }
+
+    fn bind_profiles(controller: &mut GammaController, names: &[&str]) -> Vec<u64> {
+        names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| {
+                let id = controller
+                    .config_mut()
+                    .profile_manager
+                    .add_profile(Profile::new(name.to_string(), DisplaySettings::default()));
+                controller.config_mut().keybinds.insert(
+                    HotkeyAction::LoadProfile(id),
+                    vec![KeybindConfig::new(
+                        vec!["Ctrl".to_string()],
+                        (index + 1).to_string(),
+                    )],
+                );
+                id
+            })
+            .collect()
+    }
+
+    #[test]
+    fn profile_hotkeys_follow_moves_and_removals() {
+        let mut controller = controller(AppConfig::default());
+        let ids = bind_profiles(&mut controller, &["A", "B", "C"]);
+
+        controller.move_profile(ids[2], 0).unwrap();
+
+        let names: Vec<&str> = controller
+            .config()
+            .profile_manager
+            .get_profiles()
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(names, ["C", "A", "B"]);
+
+        let key = |id| &controller.config().keybinds[&HotkeyAction::LoadProfile(id)][0].key;
+        assert_eq!([key(ids[0]), key(ids[1]), key(ids[2])], ["1", "2", "3"]);
+        assert!(controller.move_profile(99, 0).is_err());
+
+        let removed = controller.remove_profile(ids[0]).unwrap();
+        assert_eq!(removed.profile.id, ids[0]);
+        assert!(!controller
+            .config()
+            .keybinds
+            .contains_key(&HotkeyAction::LoadProfile(ids[0])));
+        assert_eq!(controller.config().keybinds.len(), 2);
+
+        // Undoing the removal brings back the profile in its place, with its hotkey
+        controller.restore_profile(removed);
+        assert_eq!(
+            controller.config().profile_manager.get_profiles()[1].id,
+            ids[0]
+        );
+        assert_eq!(
+            controller.config().keybinds[&HotkeyAction::LoadProfile(ids[0])][0].key,
+            "1"
+        );
+        controller.remove_profile(ids[0]).unwrap();
+
+        // Ids aren't reused after a removal
+        let id = controller
+            .config_mut()
+            .profile_manager
+            .add_profile(Profile::new("D".to_string(), DisplaySettings::default()));
+        assert!(!ids.contains(&id));
+    }
+
+    #[test]
+    fn removing_several_profiles_drops_their_hotkeys() {
+        let mut controller = controller(AppConfig::default());
+        let ids = bind_profiles(&mut controller, &["A", "B", "C", "D"]);
+        controller.set_profile_locked(ids[2], true).unwrap();
+
+        let removed = controller.remove_profiles(&HashSet::from([ids[3], ids[2], ids[1], 99]));
+        let names: Vec<&str> = removed.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["B", "D"]);
+        assert!(controller.remove_profile(ids[2]).is_err());
+
+        let remaining: Vec<u64> = controller
+            .config()
+            .profile_manager
+            .get_profiles()
+            .iter()
+            .map(|p| p.id)
+            .collect();
+        assert_eq!(remaining, [ids[0], ids[2]]);
+
+        let mut bound: Vec<Option<u64>> = controller
+            .config()
+            .keybinds
+            .keys()
+            .map(|action| action.profile_id())
+            .collect();
+        bound.sort();
+        assert_eq!(bound, [Some(ids[0]), Some(ids[2])]);
+    }
+
+    #[test]
+    fn default_keybinds_are_seeded_once() {
+        // Upgrading from a version that left the keybinds empty gives the defaults
+        let mut config: AppConfig = serde_json::from_value(serde_json::json!({
+            "current_settings": DisplaySettings::default(),
+            "step_size": crate::StepSize::default(),
+            "keybinds": {},
+            "profile_manager": { "profiles": [] },
+            "selected_monitor_id": "primary",
+        }))
+        .unwrap();
+        config.seed_keybinds();
+        assert_eq!(
+            config.keybinds.len(),
+            crate::windows::hotkeys::default_keybinds().len()
+        );
+
+        // Clearing them afterwards sticks
+        config.keybinds.clear();
+        let mut reloaded: AppConfig =
+            serde_json::from_value(serde_json::to_value(&config).unwrap()).unwrap();
+        reloaded.seed_keybinds();
+        assert!(reloaded.keybinds.is_empty());
+    }
+
+    #[test]
+    fn configs_from_before_profile_ids_keep_their_hotkeys() {
+        let mut config: AppConfig = serde_json::from_value(serde_json::json!({
+            "current_settings": DisplaySettings::default(),
+            "step_size": crate::StepSize::default(),
+            "keybinds": {
+                "LoadProfile(0)": KeybindConfig::new(vec!["Ctrl".to_string()], "1".to_string()),
+                "LoadProfile(1)": KeybindConfig::new(vec!["Ctrl".to_string()], "2".to_string()),
+                "LoadProfile(7)": KeybindConfig::new(vec!["Ctrl".to_string()], "8".to_string()),
+            },
+            "profile_manager": {
+                "profiles": [
+                    { "name": "Day", "settings": DisplaySettings::default() },
+                    { "name": "Night", "settings": DisplaySettings::new(1.0, -0.4, 1.0) },
+                ],
+            },
+            "selected_monitor_id": "",
+            "schedule": {
+                "enabled": true,
+                "events": [{ "start_minute": 1260, "duration_minutes": 30, "profile": "Night" }],
+            },
+        }))
+        .unwrap();
+        config.migrate_profile_ids();
+
+        let profiles = config.profile_manager.get_profiles();
+        assert!(profiles[0].id != 0 && profiles[0].id != profiles[1].id);
+        assert_eq!(config.schedule.events[0].profile_id, profiles[1].id);
+        assert_eq!(config.keybinds.len(), 2);
+        assert_eq!(
+            config.keybinds[&HotkeyAction::LoadProfile(profiles[1].id)][0].key,
+            "2"
+        );
+
+        // Once ids exist, hotkeys are read as ids
+        let mut reloaded: AppConfig =
+            serde_json::from_str(&serde_json::to_string(&config).unwrap()).unwrap();
+        reloaded.migrate_profile_ids();
+        let mut actions: Vec<HotkeyAction> = reloaded.keybinds.into_keys().collect();
+        actions.sort_by_key(|action| format!("{:?}", action));
+        assert_eq!(
+            actions,
+            [
+                HotkeyAction::LoadProfile(profiles[0].id),
+                HotkeyAction::LoadProfile(profiles[1].id)
+            ]
+        );
+    }
+
+    #[test]
+    fn step_adjustments_bypass_fade() {
+        let config = AppConfig {
+            transition: crate::TransitionConfig { duration_ms: 5000 },
+            ..Default::default()
+        };
+        let backend = MockBackend::single();
+        let mut controller = GammaController::new(config, Box::new(backend.clone()));
+
+        controller.apply_current().unwrap();
+        controller.adjust(HotkeyAction::IncreaseGamma).unwrap();
+
+        // A 5 second fade would make this flush hang
+        controller.flush();
+
+        assert_eq!(backend.apply_count(), 2);
+    }
+
+    #[test]
+    fn reset_fades_to_defaults() {
+        let config = AppConfig {
+            current_settings: DisplaySettings::new(2.0, 0.2, 1.5),
+            transition: crate::TransitionConfig { duration_ms: 60 },
+            ..Default::default()
+        };
+        let backend = MockBackend::single();
+        let mut controller = GammaController::new(config, Box::new(backend.clone()));
+
+        controller.apply_current().unwrap();
+        controller.flush();
+        controller.adjust(HotkeyAction::Reset).unwrap();
+        controller.flush();
+
+        assert!(backend.apply_count() > 2);
+        assert_eq!(
+            controller.applied_settings("1"),
+            Some(DisplaySettings::default())
+        );
+    }
+
+    #[test]
+    fn set_settings_on_other_monitor_keeps_current_settings() {
+        let mut controller = controller(AppConfig::default());
+        let dim = DisplaySettings::new(1.0, -0.5, 1.0);
+
+        controller.set_settings("secondary", dim).unwrap();
+        controller.flush();
+
+        assert_eq!(
+            controller.config().current_settings,
+            DisplaySettings::default()
+        );
+        assert_eq!(controller.applied_settings("secondary"), Some(dim));
+    }
+
+    #[test]
+    fn set_settings_on_missing_monitor_fails() {
+        let mut controller = controller(AppConfig::default());
+
+        assert!(controller
+            .set_settings("gone", DisplaySettings::default())
+            .is_err());
+    }
+
+    #[test]
+    fn hardware_mismatch_detects_foreign_ramp() {
+        let backend = MockBackend::single();
+        let controller = GammaController::new(AppConfig::default(), Box::new(backend.clone()));
+
+        assert_eq!(controller.hardware_mismatch(), None);
+
+        let foreign = DisplaySettings::new(1.8, -0.2, 1.1);
+        backend.set_hardware_ramp("1", compute_gamma_ramp(&foreign));
+
+        let fitted = controller.hardware_mismatch().unwrap();
+        assert!((fitted.gamma - foreign.gamma).abs() < 0.05);
+        assert!((fitted.brightness - foreign.brightness).abs() < 0.02);
+        assert!((fitted.contrast - foreign.contrast).abs() < 0.05);
+    }
+
+    #[test]
+    fn hardware_mismatch_ignores_own_ramp() {
+        let backend = MockBackend::single();
+        let mut controller = GammaController::new(AppConfig::default(), Box::new(backend));
+
+        controller
+            .set_current_settings(DisplaySettings::new(2.0, 0.1, 1.3))
+            .unwrap();
+        controller.flush();
+
+        assert_eq!(controller.hardware_mismatch(), None);
+    }
+
+    #[test]
+    fn hardware_control_routes_brightness_and_contrast() {
+        let backend = MockBackend::single();
+        backend.enable_ddc("1");
+        let mut controller = GammaController::new(AppConfig::default(), Box::new(backend.clone()));
+        let settings = DisplaySettings::new(1.5, -0.3, 1.2);
+
+        controller.set_hardware_control("1", true).unwrap();
+        controller.set_current_settings(settings).unwrap();
+        controller.flush();
+
+        assert_eq!(backend.ddc_applied().last().unwrap().1, settings);
+        assert_eq!(
+            backend.applied().last().unwrap().1,
+            compute_gamma_ramp(&DisplaySettings::new(1.5, 0.0, 1.0))
+        );
+    }
+
+    #[test]
+    fn hotkeys_respect_hardware_control() {
+        let backend = MockBackend::single();
+        backend.enable_ddc("1");
+        let mut controller = GammaController::new(AppConfig::default(), Box::new(backend.clone()));
+
+        controller.set_hardware_control("1", true).unwrap();
+        controller.flush();
+        controller.adjust(HotkeyAction::DecreaseBrightness).unwrap();
+        controller.flush();
+
+        let (_, last) = backend.ddc_applied().last().cloned().unwrap();
+        assert!((last.brightness + 0.05).abs() < 1e-6);
+
+        // Gamma-only steps leave the hardware alone
+        let writes = backend.ddc_applied().len();
+        controller.adjust(HotkeyAction::IncreaseGamma).unwrap();
+        controller.flush();
+        assert_eq!(backend.ddc_applied().len(), writes);
+    }
+
+    #[test]
+    fn capabilities_are_cached_and_failures_reported() {
+        let backend = MockBackend::multi();
+        backend.set_capabilities("1", "(vcp(10 12 60(0F 11)))");
+        backend.set_capabilities("2", "\u{fffd}garbage");
+        let mut controller = GammaController::new(AppConfig::default(), Box::new(backend.clone()));
+
+        assert!(controller.capabilities("1").is_none());
+        assert_eq!(
+            controller
+                .query_capabilities("1")
+                .as_ref()
+                .unwrap()
+                .vcp
+                .len(),
+            3
+        );
+        assert!(controller.query_capabilities("2").is_err());
+        assert!(controller.query_capabilities("3").is_err());
+        assert_eq!(backend.capability_reads(), 3);
+
+        assert!(controller.capabilities("1").unwrap().is_ok());
+        assert!(controller.capabilities("3").is_some());
+        assert_eq!(backend.capability_reads(), 3);
+    }
+
+    #[test]
+    fn hardware_control_requires_support() {
+        let mut controller = controller(AppConfig::default());
+
+        assert!(!controller.supports_hardware_control("primary"));
+        assert!(controller.set_hardware_control("primary", true).is_err());
+        assert!(!controller.hardware_control("primary"));
+    }
+
+    #[test]
+    fn backlight_takes_brightness_when_supported() {
+        let backend = MockBackend::single();
+        backend.enable_backlight("1");
+        let mut controller = GammaController::new(AppConfig::default(), Box::new(backend.clone()));
+
+        assert_eq!(
+            controller.brightness_control("1"),
+            HardwareControl::Backlight
+        );
+
+        controller
+            .set_current_settings(DisplaySettings::new(1.5, -0.3, 1.2))
+            .unwrap();
+        controller.flush();
+
+        assert_eq!(
+            backend.backlight_applied().last().unwrap(),
+            &("1".to_string(), 35)
+        );
+        assert_eq!(
+            backend.applied().last().unwrap().1,
+            compute_gamma_ramp(&DisplaySettings::new(1.5, 0.0, 1.2))
+        );
+    }
+
+    #[test]
+    fn backlight_can_be_turned_off() {
+        let backend = MockBackend::single();
+        backend.enable_backlight("1");
+        let mut controller = GammaController::new(AppConfig::default(), Box::new(backend.clone()));
+        let settings = DisplaySettings::new(1.5, -0.3, 1.2);
+
+        controller.set_backlight_control("1", false).unwrap();
+        controller.set_current_settings(settings).unwrap();
+        controller.flush();
+
+        assert_eq!(controller.brightness_control("1"), HardwareControl::None);
+        assert!(backend.backlight_applied().is_empty());
+        assert_eq!(
+            backend.applied().last().unwrap().1,
+            compute_gamma_ramp(&settings)
+        );
+    }
+
+    #[test]
+    fn ddc_takes_precedence_over_backlight() {
+        let backend = MockBackend::single();
+        backend.enable_ddc("1");
+        backend.enable_backlight("1");
+        let mut controller = GammaController::new(AppConfig::default(), Box::new(backend.clone()));
+
+        controller.set_hardware_control("1", true).unwrap();
+
+        assert_eq!(controller.brightness_control("1"), HardwareControl::Ddc);
+    }
+
+    #[test]
+    fn hardware_failures_are_reported() {
+        let backend = MockBackend::single();
+        backend.enable_ddc("1");
+        let mut controller = GammaController::new(AppConfig::default(), Box::new(backend.clone()));
+
+        controller.set_hardware_control("1", true).unwrap();
+        controller.flush();
+        controller.poll_results();
+
+        backend.fail_monitor("1");
+        controller
+            .set_current_settings(DisplaySettings::new(1.0, 0.4, 1.0))
+            .unwrap();
+        controller.flush();
+
+        let results = controller.poll_results();
+        assert!(results.last().unwrap().result.is_err());
+    }
+
+    #[test]
+    fn all_monitors_mode_applies_everywhere() {
+        let mut controller = controller(AppConfig::default());
+        let dim = DisplaySettings::new(1.0, -0.3, 1.0);
+
+        controller.set_apply_to_all_monitors(true).unwrap();
+        controller.set_current_settings(dim).unwrap();
+        controller.flush();
+
+        assert_eq!(controller.applied_settings("primary"), Some(dim));
+        assert_eq!(controller.applied_settings("secondary"), Some(dim));
+    }
+
+    #[test]
+    fn hotkeys_can_target_all_monitors_alone() {
+        let config = AppConfig {
+            hotkeys_apply_to_all_monitors: true,
+            ..Default::default()
+        };
+        let mut controller = controller(config);
+
+        controller
+            .set_current_settings(DisplaySettings::new(1.2, 0.0, 1.0))
+            .unwrap();
+        controller.flush();
+        assert_eq!(controller.applied_settings("secondary"), None);
+
+        let settings = controller.adjust(HotkeyAction::IncreaseGamma).unwrap();
+        controller.flush();
+        assert_eq!(controller.applied_settings("secondary"), Some(settings));
+        assert_eq!(controller.applied_settings("primary"), Some(settings));
+    }
+
+    #[test]
+    fn apply_profile_rejects_unknown_id() {
+        let mut controller = controller(AppConfig::default());
+
+        assert!(matches!(
+            controller.apply_profile(1),
+            Err(GammaError::ProfileNotFound { id: 1 })
+        ));
+    }
+
+    #[test]
+    fn schedule_pauses_after_manual_changes_until_next_event() {
+        let mut config = AppConfig::default();
+        let night = DisplaySettings::new(1.0, -0.4, 1.0);
+        let day = config
+            .profile_manager
+            .add_profile(Profile::new("Day".to_string(), DisplaySettings::default()));
+        let night_id = config
+            .profile_manager
+            .add_profile(Profile::new("Night".to_string(), night));
+        config.schedule = ScheduleConfig {
+            enabled: true,
+            events: vec![
+                ScheduleEvent::new(21 * 60, 30, night_id),
+                ScheduleEvent::new(7 * 60, 0, day),
+            ],
+            location: None,
+        };
+        let mut controller = controller(config);
+        let at = |minute| LocalTime {
+            day_of_year: 0,
+            weekday: 0,
+            minute,
+            utc_offset_minutes: 0,
+        };
+
+        // Starting mid-transition picks up halfway
+        assert!(controller.tick_schedule(&at(21.0 * 60.0 + 15.0)).unwrap());
+        assert!((controller.config().current_settings.brightness + 0.2).abs() < 1e-6);
+
+        controller
+            .set_current_settings(DisplaySettings::new(1.5, 0.0, 1.0))
+            .unwrap();
+        assert!(controller.schedule_paused());
+        assert!(!controller.tick_schedule(&at(22.0 * 60.0)).unwrap());
+        assert_eq!(controller.config().current_settings.gamma, 1.5);
+
+        // The next event resumes the schedule
+        assert!(controller.tick_schedule(&at(8.0 * 60.0)).unwrap());
+        assert!(!controller.schedule_paused());
+        assert_eq!(
+            controller.config().current_settings,
+            DisplaySettings::default()
+        );
+    }
+
+    #[test]
+    fn previews_leave_the_schedule_running() {
+        let mut config = AppConfig::default();
+        config.schedule.enabled = true;
+        let mut controller = controller(config);
+        let preview = DisplaySettings::new(0.8, -0.3, 0.9);
+
+        controller.preview_settings(preview).unwrap();
+        controller.flush();
+
+        assert_eq!(controller.applied_settings("primary"), Some(preview));
+        assert!(!controller.schedule_paused());
+    }
+
+    #[test]
+    fn deferred_save_waits_for_changes_to_settle() {
+        let mut controller = controller(AppConfig::default());
+        assert!(!controller.save_pending());
+
+        controller.save_soon();
+
+        assert!(controller.save_pending());
+        assert!(!controller.save_if_due().unwrap());
+        assert!(controller.save_pending());
+    }
+}
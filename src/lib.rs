@@ -1,17 +1,34 @@
 use crate::{
-    profiles::ProfileManager,
+    backend::PowerSource,
+    profiles::{ProfileManager, ProfileSort},
+    schedule::ScheduleConfig,
     windows::{
-        display::DisplaySettings,
-        hotkeys::{HotkeyAction, KeybindConfig},
+        display::{ColorFilter, DisplaySettings, Lut, MonitorInfo},
+        hotkeys::{default_keybinds, HotkeyAction, KeybindConfig},
     },
 };
 use serde::{ser::SerializeMap, Deserialize, Deserializer, Serialize, Serializer};
-use std::{collections::HashMap, fs, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::PathBuf,
+    time::Duration,
+};
 
+pub mod backend;
+pub mod calibration;
+#[cfg(feature = "gui")]
 pub mod components;
+pub mod controller;
+#[cfg(all(target_os = "linux", feature = "x11"))]
+pub mod linux;
+pub mod presets;
 pub mod profiles;
+pub mod schedule;
+#[cfg(feature = "gui")]
 pub mod tabs;
 pub mod windows;
+pub mod worker;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AppConfig {
@@ -21,9 +38,136 @@ pub struct AppConfig {
         serialize_with = "serialize_keybinds",
         deserialize_with = "deserialize_keybinds"
     )]
-    pub keybinds: HashMap<HotkeyAction, KeybindConfig>,
+    pub keybinds: HashMap<HotkeyAction, Vec<KeybindConfig>>,
     pub profile_manager: ProfileManager,
     pub selected_monitor_id: String,
+    #[serde(default)]
+    pub transition: TransitionConfig,
+    #[serde(default)]
+    pub hotkey_repeat: RepeatConfig,
+    /// Skip mouse bindings, so the low-level mouse hook is never installed.
+    #[serde(default)]
+    pub disable_mouse_bindings: bool,
+    /// Bind letter, digit and other typing keys without modifiers without asking first.
+    #[serde(default)]
+    pub allow_bare_keybinds: bool,
+    /// Pause hotkeys while a fullscreen app, like a game, is focused.
+    #[serde(default)]
+    pub pause_hotkeys_in_fullscreen: bool,
+    /// Count maximized windows as fullscreen when pausing hotkeys.
+    #[serde(default)]
+    pub fullscreen_includes_maximized: bool,
+    /// Ids of monitors whose brightness and contrast are driven over DDC/CI.
+    #[serde(default)]
+    pub hardware_control: HashSet<String>,
+    /// Ids of monitors with a settable backlight that should still dim through the gamma ramp.
+    #[serde(default)]
+    pub backlight_disabled: HashSet<String>,
+    /// Apply settings changes to every monitor instead of only the selected one.
+    #[serde(default)]
+    pub apply_to_all_monitors: bool,
+    /// Apply hotkey actions to every monitor, even when the settings tab targets one.
+    #[serde(default)]
+    pub hotkeys_apply_to_all_monitors: bool,
+    /// Which monitor adjustment hotkeys act on, when they don't apply to all of them.
+    #[serde(default)]
+    pub hotkey_target: HotkeyTarget,
+    /// The filter the toggle hotkey switches on, remembered from the last one picked. `None`
+    /// means none has been picked yet, and grayscale is used.
+    #[serde(default)]
+    pub toggle_filter: ColorFilter,
+    #[serde(default)]
+    pub temperature_presets: TemperaturePresets,
+    #[serde(default)]
+    pub slider_presets: SliderPresets,
+    /// Calibration LUTs by monitor id, applied beneath Gammar's own adjustments.
+    #[serde(default)]
+    pub luts: HashMap<String, Lut>,
+    #[serde(default)]
+    pub enforce: EnforceConfig,
+    /// Read ramps back after every apply to catch drivers that silently ignore them.
+    #[serde(default)]
+    pub verify_applies: bool,
+    /// Fail applies to monitors whose own device can't be opened, instead of falling back to the
+    /// generic display device, which changes every monitor at once.
+    #[serde(default)]
+    pub disable_global_dc_fallback: bool,
+    /// Profiles to ease between at set times of day.
+    #[serde(default)]
+    pub schedule: ScheduleConfig,
+    /// Don't preview profiles on the screen while the pointer rests on them in the Profiles tab.
+    #[serde(default)]
+    pub disable_hover_preview: bool,
+    /// Keep manual changes that take extra dimming past
+    /// [`DIMMING_CONFIRM_THRESHOLD`](controller::DIMMING_CONFIRM_THRESHOLD) without asking,
+    /// instead of undoing them unless they're kept.
+    #[serde(default)]
+    pub disable_dimming_revert: bool,
+    /// How the Profiles tab orders the list.
+    #[serde(default)]
+    pub profile_sort: ProfileSort,
+    /// The newest preset list version shown in the Profiles tab, so later presets can be
+    /// pointed out.
+    #[serde(default)]
+    pub presets_seen: u32,
+    /// Whether the config has had keybinds, given by default or otherwise, so clearing them all
+    /// doesn't bring the defaults back.
+    #[serde(default)]
+    pub keybinds_seeded: bool,
+    /// The settings from before the last profile load or reset, to go back to.
+    #[serde(default)]
+    pub previous_settings: Option<DisplaySettings>,
+    #[serde(default)]
+    pub power_profiles: PowerProfiles,
+    /// Profiles to apply when monitors come and go, the first rule starting to match winning.
+    #[serde(default)]
+    pub monitor_rules: Vec<MonitorRule>,
+}
+
+/// A profile to apply when a monitor is connected or disconnected, e.g. when docking.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitorRule {
+    pub monitor_id: String,
+    /// The monitor's name when the rule was made, to show while it's disconnected.
+    #[serde(default)]
+    pub monitor_name: String,
+    /// Whether the rule matches the monitor being connected, rather than disconnected.
+    pub connected: bool,
+    pub profile_id: u64,
+}
+
+impl MonitorRule {
+    /// Check whether the rule matches a set of attached monitors.
+    pub fn matches(&self, monitors: &[MonitorInfo]) -> bool {
+        monitors.iter().any(|m| m.id == self.monitor_id) == self.connected
+    }
+}
+
+/// Profiles to apply when the machine switches between battery and AC power, by id.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PowerProfiles {
+    #[serde(default)]
+    pub on_battery: Option<u64>,
+    #[serde(default)]
+    pub on_ac: Option<u64>,
+}
+
+impl PowerProfiles {
+    /// Get the id of the profile for a power source, if one is set.
+    pub fn profile_for(&self, source: PowerSource) -> Option<u64> {
+        match source {
+            PowerSource::Ac => self.on_ac,
+            PowerSource::Battery => self.on_battery,
+        }
+    }
+
+    /// Set the profile for a power source, or clear it with `None`.
+    pub fn set_profile(&mut self, source: PowerSource, id: Option<u64>) {
+        match source {
+            PowerSource::Ac => self.on_ac = id,
+            PowerSource::Battery => self.on_battery = id,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,6 +175,12 @@ pub struct StepSize {
     pub gamma: f32,
     pub brightness: f32,
     pub contrast: f32,
+    #[serde(default = "default_dimming_step")]
+    pub extra_dimming: f32,
+}
+
+fn default_dimming_step() -> f32 {
+    0.1
 }
 
 impl Default for StepSize {
@@ -39,6 +189,204 @@ impl Default for StepSize {
             gamma: 0.1,
             brightness: 0.05,
             contrast: 0.1,
+            extra_dimming: default_dimming_step(),
+        }
+    }
+}
+
+/// How profile loads and resets fade between settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransitionConfig {
+    pub duration_ms: u64,
+}
+
+impl Default for TransitionConfig {
+    fn default() -> Self {
+        Self { duration_ms: 300 }
+    }
+}
+
+impl TransitionConfig {
+    /// Longest supported fade.
+    pub const MAX_DURATION_MS: u64 = 5000;
+
+    /// Get the fade duration, or `None` when fades are disabled.
+    pub fn duration(&self) -> Option<Duration> {
+        let duration_ms = self.duration_ms.min(Self::MAX_DURATION_MS);
+
+        (duration_ms > 0).then(|| Duration::from_millis(duration_ms))
+    }
+}
+
+/// The monitor adjustment hotkeys act on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HotkeyTarget {
+    /// The monitor selected in the Settings tab.
+    #[default]
+    SelectedMonitor,
+    /// Whichever monitor the mouse cursor is on, falling back to the selected one.
+    MonitorUnderCursor,
+}
+
+impl HotkeyTarget {
+    pub const ALL: [HotkeyTarget; 2] = [
+        HotkeyTarget::SelectedMonitor,
+        HotkeyTarget::MonitorUnderCursor,
+    ];
+
+    pub fn format(&self) -> &'static str {
+        match self {
+            HotkeyTarget::SelectedMonitor => "Selected monitor",
+            HotkeyTarget::MonitorUnderCursor => "Monitor under the cursor",
+        }
+    }
+}
+
+/// How step hotkeys repeat while held.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepeatConfig {
+    /// How long keys must be held before the step starts repeating.
+    pub delay_ms: u64,
+    pub interval_ms: u64,
+}
+
+impl Default for RepeatConfig {
+    fn default() -> Self {
+        Self {
+            delay_ms: 400,
+            interval_ms: 80,
+        }
+    }
+}
+
+impl RepeatConfig {
+    /// Shortest supported repeat interval.
+    pub const MIN_INTERVAL_MS: u64 = 20;
+
+    pub fn delay(&self) -> Duration {
+        Duration::from_millis(self.delay_ms)
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms.max(Self::MIN_INTERVAL_MS))
+    }
+}
+
+/// Whether, and how often, ramps overwritten by other applications are reapplied.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnforceConfig {
+    pub enabled: bool,
+    pub interval_ms: u64,
+}
+
+impl Default for EnforceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_ms: 3000,
+        }
+    }
+}
+
+impl EnforceConfig {
+    /// Shortest supported check interval.
+    pub const MIN_INTERVAL_MS: u64 = 500;
+
+    /// Longest supported check interval.
+    pub const MAX_INTERVAL_MS: u64 = 60_000;
+
+    /// Get the time between checks.
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(
+            self.interval_ms
+                .clamp(Self::MIN_INTERVAL_MS, Self::MAX_INTERVAL_MS),
+        )
+    }
+}
+
+/// A named one-click color temperature.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemperaturePreset {
+    pub name: String,
+    pub kelvin: u32,
+}
+
+impl TemperaturePreset {
+    pub fn new(name: &str, kelvin: u32) -> Self {
+        Self {
+            name: name.to_string(),
+            kelvin,
+        }
+    }
+}
+
+/// Color temperature presets offered in the settings tab. Edit the config file to define your own.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TemperaturePresets(pub Vec<TemperaturePreset>);
+
+impl Default for TemperaturePresets {
+    fn default() -> Self {
+        Self(vec![
+            TemperaturePreset::new("Neutral", 6500),
+            TemperaturePreset::new("Daylight", 5000),
+            TemperaturePreset::new("Warm", 3400),
+            TemperaturePreset::new("Candle", 2700),
+        ])
+    }
+}
+
+impl TemperaturePresets {
+    /// Find the preset for a temperature.
+    pub fn find(&self, kelvin: u32) -> Option<&TemperaturePreset> {
+        self.0.iter().find(|preset| preset.kelvin == kelvin)
+    }
+}
+
+/// A named one-click value for a slider.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SliderPreset {
+    pub name: String,
+    pub value: f32,
+}
+
+impl SliderPreset {
+    pub fn new(name: &str, value: f32) -> Self {
+        Self {
+            name: name.to_string(),
+            value,
+        }
+    }
+
+    /// Get the presets as the `(name, value)` pairs the slider shows.
+    pub fn pairs(presets: &[Self]) -> Vec<(String, f32)> {
+        presets
+            .iter()
+            .map(|preset| (preset.name.clone(), preset.value))
+            .collect()
+    }
+}
+
+/// Presets offered under the display sliders. Edit the config file to define your own.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SliderPresets {
+    pub gamma: Vec<SliderPreset>,
+    pub brightness: Vec<SliderPreset>,
+    pub contrast: Vec<SliderPreset>,
+}
+
+impl Default for SliderPresets {
+    fn default() -> Self {
+        Self {
+            gamma: vec![
+                SliderPreset::new("Neutral", 1.0),
+                SliderPreset::new("1.8", 1.8),
+                SliderPreset::new("sRGB · 2.2", 2.2),
+                SliderPreset::new("2.4", 2.4),
+            ],
+            brightness: vec![SliderPreset::new("Neutral", 0.0)],
+            contrast: vec![SliderPreset::new("Neutral", 1.0)],
         }
     }
 }
@@ -54,22 +402,78 @@ impl AppConfig {
         path
     }
 
+    /// Load the saved config, falling back to the defaults.
     pub fn load() -> Self {
-        let path = Self::config_path();
+        let mut config = Self::load_saved().unwrap_or_default();
+        config.seed_keybinds();
+
+        config
+    }
 
-        if !path.exists() {
-            return Self::default();
+    /// Give the default keybinds to a config that has never had any, as on first run or from a
+    /// version that left them empty. Once it has had keybinds, clearing every one sticks.
+    pub fn seed_keybinds(&mut self) {
+        if self.keybinds_seeded {
+            return;
         }
 
-        let Ok(contents) = fs::read_to_string(&path) else {
-            return Self::default();
-        };
+        if self.keybinds.is_empty() {
+            self.keybinds = default_keybinds();
+        }
+        self.keybinds_seeded = true;
+    }
 
-        let Ok(config) = serde_json::from_str(&contents) else {
-            return Self::default();
-        };
+    fn load_saved() -> Option<Self> {
+        let contents = fs::read_to_string(Self::config_path()).ok()?;
+        let mut config = serde_json::from_str::<Self>(&contents).ok()?;
 
-        config
+        config.migrate_profile_ids();
+        config.profile_manager.dedupe_names();
+        for keybind in config.keybinds.values_mut().flatten() {
+            keybind.repair();
+        }
+        Some(config)
+    }
+
+    /// Give profiles from configs saved before profile ids existed their ids. Those configs keyed
+    /// profile hotkeys by index and schedule events by name, so both are resolved to ids.
+    /// Hotkeys for profiles that don't exist are dropped, logging each of their keybinds.
+    pub fn migrate_profile_ids(&mut self) {
+        let by_index = self.profile_manager.assign_missing_ids();
+        let profiles = &self.profile_manager;
+
+        for event in &mut self.schedule.events {
+            if let Some(name) = event.legacy_profile.take() {
+                let profile = profiles.get_profiles().iter().find(|p| p.name == name);
+                event.profile_id = profile.map(|p| p.id).unwrap_or_default();
+            }
+        }
+
+        self.keybinds = std::mem::take(&mut self.keybinds)
+            .into_iter()
+            .filter_map(|(action, bindings)| {
+                let migrated = match action {
+                    HotkeyAction::LoadProfile(key) if by_index => profiles
+                        .get_profile(key as usize)
+                        .map(|p| HotkeyAction::LoadProfile(p.id)),
+                    action => match action.profile_id() {
+                        Some(id) => profiles.get_profile_by_id(id).map(|_| action),
+                        None => Some(action),
+                    },
+                };
+
+                if migrated.is_none() {
+                    for keybind in &bindings {
+                        println!(
+                            "Dropped keybind {} for {}, as its profile doesn't exist",
+                            keybind.format(),
+                            action.format()
+                        );
+                    }
+                }
+                Some((migrated?, bindings))
+            })
+            .collect();
     }
 
     pub fn save(&self) -> Result<(), std::io::Error> {
@@ -80,9 +484,19 @@ impl AppConfig {
     }
 }
 
-/// Serialize keybinds HashMap with HotkeyAction keys as string keys in JSON
+/// An action's bindings as saved. Configs from before alternate bindings hold one keybind per
+/// action, which is still how a single binding is saved, so older versions keep loading it.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum SavedBindings {
+    One(KeybindConfig),
+    Many(Vec<KeybindConfig>),
+}
+
+/// Serialize keybinds HashMap with HotkeyAction keys as string keys in JSON, see
+/// [`HotkeyAction::to_key`]
 fn serialize_keybinds<S>(
-    keybinds: &HashMap<HotkeyAction, KeybindConfig>,
+    keybinds: &HashMap<HotkeyAction, Vec<KeybindConfig>>,
     serializer: S,
 ) -> Result<S::Ok, S::Error>
 where
@@ -90,64 +504,37 @@ where
 {
     let mut map = serializer.serialize_map(Some(keybinds.len()))?;
 
-    for (action, config) in keybinds {
-        let key = action_to_string(action);
-        map.serialize_entry(&key, config)?;
+    for (action, bindings) in keybinds {
+        match bindings.as_slice() {
+            [binding] => map.serialize_entry(&action.to_key(), binding)?,
+            _ => map.serialize_entry(&action.to_key(), bindings)?,
+        }
     }
 
     map.end()
 }
 
-/// Deserialize keybinds from JSON with string keys back to HotkeyAction keys
+/// Deserialize keybinds from JSON with string keys back to HotkeyAction keys. Actions without
+/// bindings are left out.
 fn deserialize_keybinds<'de, D>(
     deserializer: D,
-) -> Result<HashMap<HotkeyAction, KeybindConfig>, D::Error>
+) -> Result<HashMap<HotkeyAction, Vec<KeybindConfig>>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let string_map: HashMap<String, KeybindConfig> = HashMap::deserialize(deserializer)?;
+    let string_map: HashMap<String, SavedBindings> = HashMap::deserialize(deserializer)?;
     let mut keybinds = HashMap::new();
 
-    for (key_str, config) in string_map {
-        if let Some(action) = string_to_action(&key_str) {
-            keybinds.insert(action, config);
+    for (key_str, saved) in string_map {
+        let bindings = match saved {
+            SavedBindings::One(binding) => vec![binding],
+            SavedBindings::Many(bindings) => bindings,
+        };
+
+        if let Some(action) = HotkeyAction::from_key(&key_str).filter(|_| !bindings.is_empty()) {
+            keybinds.insert(action, bindings);
         }
     }
 
     Ok(keybinds)
 }
-
-/// Convert HotkeyAction to a string representation
-fn action_to_string(action: &HotkeyAction) -> String {
-    match action {
-        HotkeyAction::IncreaseGamma => "IncreaseGamma".to_string(),
-        HotkeyAction::DecreaseGamma => "DecreaseGamma".to_string(),
-        HotkeyAction::IncreaseBrightness => "IncreaseBrightness".to_string(),
-        HotkeyAction::DecreaseBrightness => "DecreaseBrightness".to_string(),
-        HotkeyAction::IncreaseContrast => "IncreaseContrast".to_string(),
-        HotkeyAction::DecreaseContrast => "DecreaseContrast".to_string(),
-        HotkeyAction::Reset => "Reset".to_string(),
-        HotkeyAction::LoadProfile(index) => format!("LoadProfile({})", index),
-    }
-}
-
-/// Convert string back to HotkeyAction
-fn string_to_action(s: &str) -> Option<HotkeyAction> {
-    match s {
-        "IncreaseGamma" => Some(HotkeyAction::IncreaseGamma),
-        "DecreaseGamma" => Some(HotkeyAction::DecreaseGamma),
-        "IncreaseBrightness" => Some(HotkeyAction::IncreaseBrightness),
-        "DecreaseBrightness" => Some(HotkeyAction::DecreaseBrightness),
-        "IncreaseContrast" => Some(HotkeyAction::IncreaseContrast),
-        "DecreaseContrast" => Some(HotkeyAction::DecreaseContrast),
-        "Reset" => Some(HotkeyAction::Reset),
-        s if s.starts_with("LoadProfile(") && s.ends_with(')') => {
-            let index_str = &s[12..s.len() - 1];
-            index_str
-                .parse::<usize>()
-                .ok()
-                .map(HotkeyAction::LoadProfile)
-        }
-        _ => None,
-    }
-}
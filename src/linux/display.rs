@@ -0,0 +1,354 @@
+use crate::{
+    backend::{GammaBackend, PowerSource},
+    windows::display::{
+        DisplayMode, GammaError, GammaRamp, MonitorInfo, MonitorRect, Orientation, RAMP_SIZE,
+    },
+};
+use std::{env, fs};
+use x11rb::{
+    connection::Connection,
+    protocol::randr::{self, ConnectionExt as _},
+    rust_connection::RustConnection,
+};
+
+/// Refresh rate of a RandR mode, rounded to whole Hz.
+fn refresh_rate(mode: &randr::ModeInfo) -> u32 {
+    let total = mode.htotal as f64 * mode.vtotal as f64;
+
+    if total == 0.0 {
+        return 0;
+    }
+
+    (mode.dot_clock as f64 / total).round() as u32
+}
+
+/// Orientation of a CRTC from its RandR rotation, ignoring reflections.
+fn orientation(rotation: randr::Rotation) -> Orientation {
+    if rotation.contains(randr::Rotation::ROTATE90) {
+        Orientation::Portrait
+    } else if rotation.contains(randr::Rotation::ROTATE180) {
+        Orientation::LandscapeFlipped
+    } else if rotation.contains(randr::Rotation::ROTATE270) {
+        Orientation::PortraitFlipped
+    } else {
+        Orientation::Landscape
+    }
+}
+
+/// DPI from an output's width in pixels and its physical width, or 0 when the monitor doesn't
+/// report a size, as projectors and virtual outputs often don't.
+fn dpi(width: u32, width_mm: u32) -> u32 {
+    if width_mm == 0 {
+        return 0;
+    }
+
+    (width as f32 * 25.4 / width_mm as f32).round() as u32
+}
+
+/// Gamma backend using the X11 RandR extension.
+pub struct XRandrBackend {
+    conn: RustConnection,
+    root: u32,
+}
+
+impl XRandrBackend {
+    /// Connect to the X server named by `DISPLAY`.
+    ///
+    /// Fails under Wayland, where XWayland accepts RandR gamma requests but they never reach the
+    /// compositor.
+    pub fn connect() -> Result<Self, GammaError> {
+        if env::var_os("WAYLAND_DISPLAY").is_some() {
+            return Err(GammaError::Unsupported(
+                "Gamma control is not supported under Wayland".to_string(),
+            ));
+        }
+
+        let (conn, screen_num) = x11rb::connect(None).map_err(|e| {
+            GammaError::Platform(format!("Failed to connect to the X server: {}", e))
+        })?;
+        let root = conn.setup().roots[screen_num].root;
+
+        conn.randr_query_version(1, 3)
+            .map_err(x11_error)?
+            .reply()
+            .map_err(|_| {
+                GammaError::Unsupported("The X server does not support RandR 1.3".to_string())
+            })?;
+
+        Ok(Self { conn, root })
+    }
+
+    /// Find the CRTC currently driving the output with the given name.
+    fn find_crtc(&self, output_name: &str) -> Result<randr::Crtc, GammaError> {
+        let resources = self
+            .conn
+            .randr_get_screen_resources_current(self.root)
+            .map_err(x11_error)?
+            .reply()
+            .map_err(x11_error)?;
+
+        for &output in &resources.outputs {
+            let info = self
+                .conn
+                .randr_get_output_info(output, resources.config_timestamp)
+                .map_err(x11_error)?
+                .reply()
+                .map_err(x11_error)?;
+
+            if info.crtc != 0 && info.name == output_name.as_bytes() {
+                return Ok(info.crtc);
+            }
+        }
+
+        // The output was unplugged or disabled since enumeration
+        Err(GammaError::MonitorNotFound {
+            id: output_name.to_string(),
+        })
+    }
+}
+
+impl GammaBackend for XRandrBackend {
+    fn enumerate_monitors(&self) -> Vec<MonitorInfo> {
+        let Some(resources) = self
+            .conn
+            .randr_get_screen_resources_current(self.root)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+        else {
+            return Vec::new();
+        };
+
+        let primary = self
+            .conn
+            .randr_get_output_primary(self.root)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .map(|reply| reply.output)
+            .unwrap_or(0);
+
+        let mut monitors = Vec::new();
+
+        for &output in &resources.outputs {
+            let Some(info) = self
+                .conn
+                .randr_get_output_info(output, resources.config_timestamp)
+                .ok()
+                .and_then(|cookie| cookie.reply().ok())
+            else {
+                continue;
+            };
+
+            if info.connection != randr::Connection::CONNECTED || info.crtc == 0 {
+                continue;
+            }
+
+            let device_name = String::from_utf8_lossy(&info.name).into_owned();
+            let is_primary = output == primary;
+
+            let crtc = self
+                .conn
+                .randr_get_crtc_info(info.crtc, resources.config_timestamp)
+                .ok()
+                .and_then(|cookie| cookie.reply().ok());
+
+            let (bounds, mode) = crtc
+                .map(|crtc| {
+                    let bounds = MonitorRect {
+                        x: crtc.x as i32,
+                        y: crtc.y as i32,
+                        width: crtc.width as u32,
+                        height: crtc.height as u32,
+                    };
+
+                    let refresh_rate = resources
+                        .modes
+                        .iter()
+                        .find(|mode| mode.id == crtc.mode)
+                        .map(refresh_rate)
+                        .unwrap_or(0);
+
+                    let mode = DisplayMode {
+                        width: bounds.width,
+                        height: bounds.height,
+                        refresh_rate,
+                        orientation: orientation(crtc.rotation),
+                    };
+
+                    (bounds, mode)
+                })
+                .unwrap_or_default();
+
+            // Virtual outputs, like some VNC and headless ones, have no gamma ramp
+            let supports_gamma = self
+                .conn
+                .randr_get_crtc_gamma_size(info.crtc)
+                .ok()
+                .and_then(|cookie| cookie.reply().ok())
+                .is_none_or(|reply| reply.size > 0);
+
+            monitors.push(MonitorInfo {
+                id: device_name.clone(),
+                name: if is_primary {
+                    format!("{} (Primary)", device_name)
+                } else {
+                    device_name.clone()
+                },
+                device_name,
+                is_primary,
+                legacy_id: None,
+                bounds,
+                // X11 has no notion of a work area per output
+                work_area: bounds,
+                // The physical size is that of the unrotated panel
+                dpi: match mode.orientation {
+                    Orientation::Portrait | Orientation::PortraitFlipped => {
+                        dpi(bounds.width, info.mm_height)
+                    }
+                    _ => dpi(bounds.width, info.mm_width),
+                },
+                mode,
+                is_hdr: false,
+                supports_gamma,
+                adapter: String::new(),
+            });
+        }
+
+        monitors.sort_by_key(|m| std::cmp::Reverse(m.is_primary));
+
+        monitors
+    }
+
+    fn apply_ramp(&self, monitor: &MonitorInfo, ramp: &GammaRamp) -> Result<(), GammaError> {
+        let crtc = self.find_crtc(&monitor.device_name)?;
+        let size = self
+            .conn
+            .randr_get_crtc_gamma_size(crtc)
+            .map_err(x11_error)?
+            .reply()
+            .map_err(x11_error)?
+            .size as usize;
+
+        let red = resample(ramp.red(), size);
+        let green = resample(ramp.green(), size);
+        let blue = resample(ramp.blue(), size);
+
+        self.conn
+            .randr_set_crtc_gamma(crtc, &red, &green, &blue)
+            .map_err(x11_error)?
+            .check()
+            .map_err(|e| {
+                GammaError::Platform(format!(
+                    "Failed to set gamma ramp for {}: {}",
+                    monitor.name, e
+                ))
+            })
+    }
+
+    fn read_ramp(&self, monitor: &MonitorInfo) -> Result<GammaRamp, GammaError> {
+        let crtc = self.find_crtc(&monitor.device_name)?;
+        let gamma = self
+            .conn
+            .randr_get_crtc_gamma(crtc)
+            .map_err(x11_error)?
+            .reply()
+            .map_err(x11_error)?;
+
+        let mut values = [0u16; RAMP_SIZE * 3];
+
+        for (channel, entries) in [gamma.red, gamma.green, gamma.blue].iter().enumerate() {
+            let start = channel * RAMP_SIZE;
+            values[start..start + RAMP_SIZE].copy_from_slice(&resample(entries, RAMP_SIZE));
+        }
+
+        Ok(GammaRamp::from_raw(values))
+    }
+
+    fn power_source(&self) -> Option<PowerSource> {
+        let supplies: Vec<(String, bool)> = fs::read_dir("/sys/class/power_supply")
+            .ok()?
+            .flatten()
+            .map(|entry| {
+                let read = |name: &str| fs::read_to_string(entry.path().join(name));
+                let kind = read("type").unwrap_or_default().trim().to_string();
+                let online = read("online").is_ok_and(|online| online.trim() == "1");
+                (kind, online)
+            })
+            .collect();
+
+        if !supplies.iter().any(|(kind, _)| kind == "Battery") {
+            return None;
+        }
+
+        match supplies
+            .iter()
+            .any(|(kind, online)| kind == "Mains" && *online)
+        {
+            true => Some(PowerSource::Ac),
+            false => Some(PowerSource::Battery),
+        }
+    }
+}
+
+/// Linearly resample a ramp channel to the gamma size of a CRTC.
+fn resample(channel: &[u16], size: usize) -> Vec<u16> {
+    if size == channel.len() {
+        return channel.to_vec();
+    }
+
+    if size <= 1 {
+        return channel.iter().take(size).copied().collect();
+    }
+
+    let last = (channel.len() - 1) as f32;
+
+    (0..size)
+        .map(|i| {
+            let position = i as f32 * last / (size - 1) as f32;
+            let lower = position.floor() as usize;
+            let upper = (lower + 1).min(channel.len() - 1);
+            let fraction = position - lower as f32;
+
+            (channel[lower] as f32 + (channel[upper] as f32 - channel[lower] as f32) * fraction)
+                .round() as u16
+        })
+        .collect()
+}
+
+fn x11_error(e: impl std::fmt::Display) -> GammaError {
+    GammaError::Platform(format!("X11 request failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn linear_channel() -> Vec<u16> {
+        (0..256).map(|i| i * 257).collect()
+    }
+
+    #[test]
+    fn resample_keeps_matching_size() {
+        let channel = linear_channel();
+
+        assert_eq!(resample(&channel, 256), channel);
+    }
+
+    #[test]
+    fn resample_upscales_linearly() {
+        let resampled = resample(&linear_channel(), 1024);
+
+        assert_eq!(resampled.len(), 1024);
+        assert_eq!(resampled[0], 0);
+        assert_eq!(resampled[1023], 65535);
+        assert!(resampled.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn resample_downscales_endpoints() {
+        let resampled = resample(&linear_channel(), 16);
+
+        assert_eq!(resampled.len(), 16);
+        assert_eq!(resampled[0], 0);
+        assert_eq!(resampled[15], 65535);
+    }
+}
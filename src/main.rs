@@ -5,20 +5,37 @@ use dioxus::{
     prelude::*,
 };
 use gammar::{
-    components::header::{Header, Tab},
+    backend::PowerSource,
+    components::{
+        header::{Header, Tab},
+        status_bar::{ApplyStatus, StatusBar},
+    },
+    controller::{GammaController, DIMMING_CONFIRM_THRESHOLD},
+    schedule::local_time,
     tabs::{
-        keybinds::KeybindsTab,
+        calibrate::CalibrateTab,
+        keybinds::{clear_shortcuts, shortcuts_cleared, KeybindsTab},
         profiles::ProfilesTab,
-        settings::{find_monitor, SettingsTab},
+        settings::SettingsTab,
     },
     windows::{
-        display::{apply_display_settings_to_monitor, enumerate_monitors, DisplaySettings},
-        hotkeys::HotkeyAction,
+        display::{DisplaySettings, GammaError},
+        fullscreen,
+        hotkeys::{HotkeyAction, CHORD_TIMEOUT},
+        mouse::{self, MouseBinding},
     },
-    AppConfig,
+    HotkeyTarget,
 };
 use global_hotkey::hotkey::HotKey;
-use std::str::FromStr;
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+    time::Duration,
+};
+use tokio::{
+    sync::mpsc::unbounded_channel,
+    time::{timeout_at, Instant},
+};
 
 const MAIN_CSS: &str = include_str!("../assets/main.css");
 const ICON_BYTES: &[u8] = include_bytes!("../assets/icon.png");
@@ -54,57 +71,513 @@ fn main() {
         .launch(App);
 }
 
+/// What the chord loop hears from shortcut callbacks.
+enum ChordEvent {
+    /// A chord's first stroke was pressed; these second keys finish one of its chords.
+    Started(Vec<(HotKey, HotkeyAction)>),
+    /// A second key was pressed in time.
+    Completed(HotkeyAction),
+}
+
+/// Name the monitor a select monitor hotkey picked, or the one a hotkey adjusted while hotkeys
+/// target the monitor under the cursor, which is otherwise easy to miss with the window hidden.
+fn hotkey_status<T>(
+    ctrl: &GammaController,
+    action: HotkeyAction,
+    result: &Result<T, GammaError>,
+) -> Option<ApplyStatus> {
+    if !matches!(
+        action,
+        HotkeyAction::SelectMonitor(_) | HotkeyAction::NextMonitor
+    ) {
+        return adjusted_status(ctrl, result);
+    }
+
+    match (result, ctrl.selected_monitor()) {
+        (Ok(_), Some(monitor)) => {
+            println!("Selected monitor: {}", monitor.name);
+            Some(ApplyStatus::Notice(format!("Selected {}", monitor.name)))
+        }
+        (Ok(_), None) => None,
+        (Err(e), _) => {
+            println!("Failed to select monitor: {}", e);
+            Some(ApplyStatus::Failed(e.to_string()))
+        }
+    }
+}
+
+fn adjusted_status<T>(
+    ctrl: &GammaController,
+    result: &Result<T, GammaError>,
+) -> Option<ApplyStatus> {
+    let config = ctrl.config();
+    if config.hotkey_target != HotkeyTarget::MonitorUnderCursor
+        || config.apply_to_all_monitors
+        || config.hotkeys_apply_to_all_monitors
+        || result.is_err()
+    {
+        return None;
+    }
+
+    let monitor = ctrl.hotkey_monitor()?;
+    println!("Adjusted monitor: {}", monitor.name);
+
+    Some(ApplyStatus::Notice(format!("Adjusted {}", monitor.name)))
+}
+
+/// Bring the window to the front, restoring it when it's minimized or hidden, or hide it when
+/// it's already there.
+fn toggle_window() {
+    let desktop = window();
+
+    if desktop.is_visible() && !desktop.is_minimized() && desktop.is_focused() {
+        desktop.set_visible(false);
+    } else {
+        desktop.set_visible(true);
+        desktop.set_minimized(false);
+        desktop.set_focus();
+    }
+}
+
 #[component]
 fn App() -> Element {
-    // Load configuration
-    let mut config = use_signal(AppConfig::load);
+    // Load configuration, enumerate monitors and select the configured (or primary) one
+    let mut controller = use_signal(GammaController::load);
 
     // Track keybind version for re-registration when keybinds change
     let keybind_version = use_signal(|| 0);
 
-    // Enumerate monitors
-    let monitors = use_signal(enumerate_monitors);
+    // Whether hotkeys are paused for a focused fullscreen app
+    let mut hotkeys_paused = use_signal(|| false);
 
     // Current tab
     let mut active_tab = use_signal(|| Tab::Settings);
 
-    // Initialize selected monitor to primary if not set
-    use_effect(move || {
-        let monitors_list = monitors();
-        let selected_id = config.read().selected_monitor_id.clone();
+    // Outcome of the latest hardware apply
+    let mut apply_status = use_signal(|| Option::<ApplyStatus>::None);
+
+    // Approximate settings behind a ramp loaded by something other than Gammar
+    let mut ramp_mismatch = use_signal(|| Option::<DisplaySettings>::None);
 
-        if selected_id.is_empty() || !monitors_list.iter().any(|m| m.id == selected_id) {
-            if let Some(primary_monitor) = find_monitor(&monitors_list, None) {
-                config.write().selected_monitor_id = primary_monitor.id;
-                let _ = config.read().save();
+    // Whether to explain that gamma control isn't available over remote desktop
+    let mut remote_notice = use_signal(|| controller.peek().remote_session());
+
+    // Seconds left until heavy extra dimming is undone, while it waits to be kept
+    let mut dimming_countdown = use_signal(|| Option::<u64>::None);
+
+    // Apply initial settings and confirm the driver loaded them, unless the loaded ramp doesn't
+    // match them; the settings tab then asks whether to reapply them or adopt the loaded state.
+    // HDR monitors are left alone until the user applies something, since gamma ramps misbehave
+    // there. Remote desktop sessions can't set ramps at all, so nothing is applied until the
+    // session returns to the console
+    use_hook(move || {
+        if controller.peek().remote_session()
+            || controller
+                .peek()
+                .selected_monitor()
+                .is_some_and(|m| m.is_hdr)
+        {
+            return;
+        }
+
+        let mismatch = controller.peek().hardware_mismatch();
+
+        match mismatch {
+            Some(fitted) => ramp_mismatch.set(Some(fitted)),
+            None => {
+                let _ = controller.write().apply_current_verified();
             }
         }
     });
 
-    // Apply initial settings
-    use_effect(move || {
-        let monitors_list = monitors();
-        let settings = config.read().current_settings;
-        let selected_id = config.read().selected_monitor_id.clone();
+    // Don't lose settings changed just before closing
+    use_drop(move || {
+        if let Ok(mut ctrl) = controller.try_write() {
+            let _ = ctrl.flush_save();
+        }
+    });
+
+    // Collect results from the apply worker, and write settings once they've settled
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+
+            if controller.peek().save_pending() {
+                let _ = controller.write().save_if_due();
+            }
+
+            let left = controller.peek().dimming_revert_in();
+            if left.is_some_and(|left| left.is_zero()) {
+                let mut ctrl = controller.write();
 
-        if let Some(monitor) = find_monitor(&monitors_list, Some(selected_id.as_str())) {
-            let _ = apply_display_settings_to_monitor(settings, &monitor);
+                if let Ok(true) = ctrl.revert_dimming_if_due(std::time::Instant::now()) {
+                    ctrl.save_soon();
+                }
+            }
+            let countdown = left
+                .filter(|left| !left.is_zero())
+                .map(|left| left.as_secs() + 1);
+            if *dimming_countdown.peek() != countdown {
+                dimming_countdown.set(countdown);
+            }
+
+            // Summarize failures from the whole batch over any successes
+            let results = controller.peek().poll_results();
+            let failures: Vec<String> = results
+                .iter()
+                .filter_map(|r| {
+                    let e = r.result.as_ref().err()?;
+                    Some(format!("{}: {}", r.monitor.name, e))
+                })
+                .collect();
+
+            let monitor_count = results
+                .iter()
+                .map(|r| &r.monitor.id)
+                .collect::<HashSet<_>>()
+                .len();
+
+            if !failures.is_empty() {
+                apply_status.set(Some(ApplyStatus::Failed(failures.join("; "))));
+            } else if let Some(result) = results.iter().rev().find(|r| r.global) {
+                apply_status.set(Some(ApplyStatus::from(result)));
+            } else if monitor_count > 1 {
+                apply_status.set(Some(ApplyStatus::Applied(format!(
+                    "Applied to {} monitors",
+                    monitor_count
+                ))));
+            } else if let Some(result) = results.last() {
+                apply_status.set(Some(ApplyStatus::from(result)));
+            }
+
+            // Notices about an apply are shown once it's through, unless it failed
+            if !results.is_empty() {
+                let notice = controller.write().take_notice();
+
+                if let Some(notice) = notice.filter(|_| failures.is_empty()) {
+                    apply_status.set(Some(ApplyStatus::Notice(notice)));
+                }
+            }
         }
     });
 
+    // Follow the time-of-day schedule. The clock is read afresh every tick, so starting
+    // mid-transition or waking from sleep catches up on its own
+    use_future(move || async move {
+        loop {
+            let due = {
+                let ctrl = controller.peek();
+                ctrl.config().schedule.enabled && !ctrl.remote_session()
+            };
+
+            if due {
+                let mut ctrl = controller.write();
+
+                if let Ok(true) = ctrl.tick_schedule(&local_time()) {
+                    ctrl.save_soon();
+                }
+            }
+
+            tokio::time::sleep(Duration::from_secs(30)).await;
+        }
+    });
+
+    // Watch for monitors being plugged in or removed
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(2)).await;
+
+            if !controller.peek().topology_changed() {
+                continue;
+            }
+
+            // A returning monitor is reselected and reapplied, which the status bar reports
+            let change = controller.write().refresh_monitors();
+
+            if let Some(lost) = change.lost {
+                let selected = controller.peek().selected_monitor();
+                let fallback = selected.map(|m| m.name).unwrap_or_default();
+
+                apply_status.set(Some(ApplyStatus::Notice(format!(
+                    "{} was disconnected; now controlling {}",
+                    lost.name, fallback
+                ))));
+            }
+
+            if let Some(profile) = change.rule_profile {
+                controller.write().save_soon();
+
+                apply_status.set(Some(ApplyStatus::Notice(format!(
+                    "Monitors changed; applied {}",
+                    profile.name
+                ))));
+            }
+        }
+    });
+
+    // Watch for the session moving between remote desktop and the console
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(2)).await;
+
+            if !controller.peek().session_changed() {
+                continue;
+            }
+
+            let remote = controller.write().refresh_session();
+            remote_notice.set(remote);
+
+            if !remote {
+                apply_status.set(Some(ApplyStatus::Notice(
+                    "Back on the local console; your settings were reapplied".to_string(),
+                )));
+            }
+        }
+    });
+
+    // Apply the profile set for battery or AC power when the machine switches between them
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+
+            if !controller.peek().power_changed() {
+                continue;
+            }
+
+            let mut ctrl = controller.write();
+
+            match ctrl.refresh_power() {
+                Ok(Some(profile)) => {
+                    ctrl.save_soon();
+
+                    let source = match ctrl.power_source() {
+                        Some(PowerSource::Battery) => "On battery power",
+                        _ => "Plugged in",
+                    };
+                    apply_status.set(Some(ApplyStatus::Notice(format!(
+                        "{}; applied {}",
+                        source, profile.name
+                    ))));
+                }
+                Ok(None) => {}
+                Err(e) => apply_status.set(Some(ApplyStatus::Failed(e.to_string()))),
+            }
+        }
+    });
+
+    // Pause hotkeys while a fullscreen app is focused, if asked to, so they don't clash with its
+    // own binds
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            let paused = {
+                let ctrl = controller.peek();
+                let config = ctrl.config();
+                config.pause_hotkeys_in_fullscreen
+                    && fullscreen::fullscreen_app_focused(config.fullscreen_includes_maximized)
+            };
+            if paused == *hotkeys_paused.peek() {
+                continue;
+            }
+
+            hotkeys_paused.set(paused);
+            if paused {
+                window().set_title("Gammar (hotkeys paused)");
+                apply_status.set(Some(ApplyStatus::Notice(
+                    "Hotkeys paused while a fullscreen app is focused".to_string(),
+                )));
+            } else {
+                window().set_title("Gammar");
+                apply_status.set(Some(ApplyStatus::Notice("Hotkeys resumed".to_string())));
+            }
+        }
+    });
+
+    // Reapply ramps that other applications overwrite, while enforcing is on
+    use_future(move || async move {
+        let mut reported = 0;
+
+        loop {
+            let interval = controller.peek().config().enforce.interval();
+            tokio::time::sleep(interval).await;
+
+            // Reapplies happen on the worker, so the previous check's are counted now
+            if let Some(monitor) = controller.peek().selected_monitor() {
+                let count = controller.peek().reapplies(&monitor.id).count;
+
+                if count > reported {
+                    apply_status.set(Some(ApplyStatus::Notice(format!(
+                        "Reapplied settings to {}: another application changed its gamma ramp \
+                         ({} times so far)",
+                        monitor.name, count
+                    ))));
+                }
+
+                reported = count;
+            }
+
+            controller.peek().enforce();
+        }
+    });
+
+    // Repeat steps while their hotkeys are held. Shortcut callbacks and the mouse hook run outside
+    // the runtime, so they report presses and releases here, as well as window toggles, which need
+    // it. The config is only saved on release.
+    let hold_events = use_hook(|| {
+        let (sender, mut events) = unbounded_channel::<(HotkeyAction, bool)>();
+
+        spawn(async move {
+            let mut held = None;
+            let mut next_repeat = Instant::now();
+
+            loop {
+                let event = match held {
+                    Some(action) => match timeout_at(next_repeat, events.recv()).await {
+                        Ok(event) => event,
+                        Err(_) => {
+                            let mut ctrl = controller.write();
+                            let _ = ctrl.adjust(action);
+                            next_repeat = Instant::now() + ctrl.config().hotkey_repeat.interval();
+                            continue;
+                        }
+                    },
+                    None => events.recv().await,
+                };
+                let Some((action, pressed)) = event else {
+                    break;
+                };
+
+                match (pressed, held) {
+                    // Keys repeated by the OS while held
+                    (true, Some(held_action)) if held_action == action => {}
+                    (true, _) if action == HotkeyAction::ToggleWindow => toggle_window(),
+                    (true, _) => {
+                        let mut ctrl = controller.write();
+                        let result = ctrl.adjust(action);
+                        if let Some(status) = hotkey_status(&ctrl, action, &result) {
+                            apply_status.set(Some(status));
+                        }
+                        next_repeat = Instant::now() + ctrl.config().hotkey_repeat.delay();
+                        held = Some(action);
+                    }
+                    (false, Some(held_action)) if held_action == action => {
+                        held = None;
+                        controller.write().save_soon();
+                    }
+                    (false, _) => {}
+                }
+            }
+        });
+
+        sender
+    });
+
+    // Chords register their second keys only for a moment after their first stroke. Shortcuts
+    // can't be registered from a shortcut callback, so strokes are reported here.
+    let chord_events = use_hook(|| {
+        let (sender, mut events) = unbounded_channel::<ChordEvent>();
+        let hold_events = hold_events.clone();
+        let completions = sender.clone();
+
+        spawn(async move {
+            let mut armed = Vec::new();
+            let mut armed_at = 0;
+            let mut deadline = Instant::now();
+
+            loop {
+                // The task keeps a sender, so nothing arrives only when the wait times out
+                let event = if armed.is_empty() {
+                    events.recv().await
+                } else {
+                    timeout_at(deadline, events.recv()).await.ok().flatten()
+                };
+
+                // Any event ends the wait. Re-registering the keybinds already removed the
+                // second keys if it happened meanwhile
+                if shortcuts_cleared() == armed_at {
+                    for handle in armed.drain(..) {
+                        window().remove_shortcut(handle);
+                    }
+                }
+                armed.clear();
+
+                match event {
+                    Some(ChordEvent::Started(second_keys)) => {
+                        for (hotkey, action) in second_keys {
+                            let completions = completions.clone();
+                            let result = window().create_shortcut(hotkey, move |state| {
+                                if state == HotKeyState::Pressed {
+                                    let _ = completions.send(ChordEvent::Completed(action));
+                                }
+                            });
+
+                            match result {
+                                Ok(handle) => armed.push(handle),
+                                Err(e) => println!("Failed to wait for chord key: {:?}", e),
+                            }
+                        }
+                        armed_at = shortcuts_cleared();
+                        deadline = Instant::now() + CHORD_TIMEOUT;
+                    }
+                    // Chords fire once, like mouse bindings
+                    Some(ChordEvent::Completed(action)) => {
+                        let _ = hold_events.send((action, true));
+                        let _ = hold_events.send((action, false));
+                    }
+                    None => {}
+                }
+            }
+        });
+
+        sender
+    });
+
     // Register all keybinds - re-register when keybind_version changes
     use_effect(move || {
         let version = keybind_version();
-        let keybinds = config.peek().keybinds.clone();
+        let paused = hotkeys_paused();
+        let keybinds = controller.peek().config().keybinds.clone();
 
         println!("Registering keybinds (version {})", version);
 
         // Remove all existing shortcuts
-        window().remove_all_shortcuts();
+        clear_shortcuts();
+
+        if paused {
+            println!("Hotkeys paused for a fullscreen app");
+            if let Err(e) = mouse::set_bindings(Vec::new(), |_| {}) {
+                println!("Failed to remove mouse bindings: {}", e);
+            }
+            return;
+        }
+
+        let mut mouse_bindings = Vec::new();
+        let mut chords: HashMap<HotKey, Vec<(HotKey, HotkeyAction)>> = HashMap::new();
+
+        // Register every binding of every action, skipping those for profiles that no longer
+        // exist
+        for (action, keybind) in keybinds
+            .iter()
+            .flat_map(|(action, bindings)| bindings.iter().map(|kb| (*action, kb)))
+        {
+            if let Some(id) = action.profile_id() {
+                if controller
+                    .peek()
+                    .config()
+                    .profile_manager
+                    .get_profile_by_id(id)
+                    .is_none()
+                {
+                    continue;
+                }
+            }
+
+            if let Some(binding) = MouseBinding::from_keybind(action, keybind) {
+                mouse_bindings.push(binding);
+                continue;
+            }
 
-        // Register all current keybinds
-        for (action, keybind) in keybinds.iter() {
-            let action = *action;
             let shortcut = keybind.to_shortcut_string();
 
             // Parse the shortcut string into a HotKey
@@ -116,54 +589,32 @@ fn App() -> Element {
                 }
             };
 
+            // Chords sharing a first stroke register it once, below
+            if let Some(then) = &keybind.then {
+                match HotKey::from_str(then) {
+                    Ok(second) => chords.entry(hotkey).or_default().push((second, action)),
+                    Err(e) => println!("Failed to parse chord key '{}': {:?}", then, e),
+                }
+                continue;
+            }
+
+            let hold_events = hold_events.clone();
             let result = window().create_shortcut(hotkey, move |state| {
-                if state != HotKeyState::Pressed {
+                if action.repeats() || action == HotkeyAction::ToggleWindow {
+                    let _ = hold_events.send((action, state == HotKeyState::Pressed));
                     return;
                 }
 
-                let mut cfg = config.write();
-                let step = cfg.step_size.clone();
-                let mut settings = cfg.current_settings;
-
-                match action {
-                    HotkeyAction::IncreaseGamma => {
-                        settings.gamma = (settings.gamma + step.gamma).min(3.0);
-                    }
-                    HotkeyAction::DecreaseGamma => {
-                        settings.gamma = (settings.gamma - step.gamma).max(0.1);
-                    }
-                    HotkeyAction::IncreaseBrightness => {
-                        settings.brightness = (settings.brightness + step.brightness).min(1.0);
-                    }
-                    HotkeyAction::DecreaseBrightness => {
-                        settings.brightness = (settings.brightness - step.brightness).max(-1.0);
-                    }
-                    HotkeyAction::IncreaseContrast => {
-                        settings.contrast = (settings.contrast + step.contrast).min(3.0);
-                    }
-                    HotkeyAction::DecreaseContrast => {
-                        settings.contrast = (settings.contrast - step.contrast).max(0.1);
-                    }
-                    HotkeyAction::Reset => {
-                        settings = DisplaySettings::default();
-                    }
-                    HotkeyAction::LoadProfile(index) => {
-                        if let Some(profile) = cfg.profile_manager.get_profile(index) {
-                            settings = profile.settings;
-                        }
-                    }
+                if state != HotKeyState::Pressed {
+                    return;
                 }
 
-                cfg.current_settings = settings;
-
-                let monitors_list = monitors();
-                let selected_id = cfg.selected_monitor_id.clone();
-
-                if let Some(monitor) = find_monitor(&monitors_list, Some(selected_id.as_str())) {
-                    let _ = apply_display_settings_to_monitor(settings, &monitor);
+                let mut ctrl = controller.write();
+                let result = ctrl.adjust(action);
+                if let Some(status) = hotkey_status(&ctrl, action, &result) {
+                    apply_status.set(Some(status));
                 }
-
-                let _ = cfg.save();
+                ctrl.save_soon();
             });
 
             match result {
@@ -171,6 +622,33 @@ fn App() -> Element {
                 Err(e) => println!("Failed to register shortcut {}: {:?}", shortcut, e),
             }
         }
+
+        for (hotkey, second_keys) in chords {
+            let chord_events = chord_events.clone();
+            let result = window().create_shortcut(hotkey, move |state| {
+                if state == HotKeyState::Pressed {
+                    let _ = chord_events.send(ChordEvent::Started(second_keys.clone()));
+                }
+            });
+
+            if let Err(e) = result {
+                println!("Failed to register chord {:?}: {:?}", hotkey, e);
+            }
+        }
+
+        // Mouse bindings are used as a press and release at once
+        if controller.peek().config().disable_mouse_bindings {
+            mouse_bindings.clear();
+        }
+        let hold_events = hold_events.clone();
+        let result = mouse::set_bindings(mouse_bindings, move |action| {
+            let _ = hold_events.send((action, true));
+            let _ = hold_events.send((action, false));
+        });
+
+        if let Err(e) = result {
+            println!("Failed to set mouse bindings: {}", e);
+        }
     });
 
     rsx! {
@@ -181,12 +659,51 @@ fn App() -> Element {
             Header { active_tab, on_tab_change: move |tab| active_tab.set(tab) }
 
             div { class: "content",
+                if remote_notice() {
+                    div {
+                        class: "session-banner",
+                        p {
+                            "This is a remote desktop session, where Windows doesn't allow changing gamma ramps. Your settings will be reapplied automatically when you're back on the local console."
+                        }
+                        div {
+                            class: "mismatch-actions",
+                            button {
+                                class: "secondary",
+                                onclick: move |_| remote_notice.set(false),
+                                "Dismiss"
+                            }
+                        }
+                    }
+                }
+
+                if let Some(seconds) = dimming_countdown() {
+                    div {
+                        class: "session-banner",
+                        p {
+                            "Extra dimming above {DIMMING_CONFIRM_THRESHOLD * 100.0:.0}% is undone in {seconds} s unless you keep it, in case the screen is too dark to see."
+                        }
+                        div {
+                            class: "mismatch-actions",
+                            button {
+                                onclick: move |_| {
+                                    controller.write().keep_dimming();
+                                    dimming_countdown.set(None);
+                                },
+                                "Keep"
+                            }
+                        }
+                    }
+                }
+
                 match active_tab() {
-                    Tab::Settings => rsx! { SettingsTab { config, monitors } },
-                    Tab::Keybinds => rsx! { KeybindsTab { config, keybind_version } },
-                    Tab::Profiles => rsx! { ProfilesTab { config, monitors } },
+                    Tab::Settings => rsx! { SettingsTab { controller, ramp_mismatch } },
+                    Tab::Keybinds => rsx! { KeybindsTab { controller, keybind_version } },
+                    Tab::Profiles => rsx! { ProfilesTab { controller, keybind_version } },
+                    Tab::Calibrate => rsx! { CalibrateTab { controller } },
                 }
             }
+
+            StatusBar { status: apply_status }
         }
     }
 }
@@ -0,0 +1,101 @@
+use crate::{profiles::Profile, windows::display::DisplaySettings};
+
+/// Version of the preset list. Bump it when adding presets, giving them this as their
+/// `added_in`, so they're pointed out to people who've seen the older list.
+pub const PRESETS_VERSION: u32 = 1;
+
+/// A built-in starting point for a profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+    Neutral,
+    DarkGameMaps,
+    MovieNight,
+    Reading,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 4] = [
+        Preset::Neutral,
+        Preset::DarkGameMaps,
+        Preset::MovieNight,
+        Preset::Reading,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Preset::Neutral => "Neutral",
+            Preset::DarkGameMaps => "Dark game maps",
+            Preset::MovieNight => "Movie night",
+            Preset::Reading => "Reading",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Preset::Neutral => "The display's own response, with nothing adjusted",
+            Preset::DarkGameMaps => "Lifts shadows so enemies in dark corners stand out",
+            Preset::MovieNight => "Warm and dim, for watching in a dark room",
+            Preset::Reading => "Softer contrast and a slightly warm white for long reads",
+        }
+    }
+
+    /// The preset list version the preset first shipped in.
+    pub fn added_in(&self) -> u32 {
+        1
+    }
+
+    pub fn settings(&self) -> DisplaySettings {
+        match self {
+            Preset::Neutral => DisplaySettings::default(),
+            Preset::DarkGameMaps => DisplaySettings::new(1.6, 0.05, 1.0),
+            Preset::MovieNight => DisplaySettings {
+                temperature: 4200,
+                ..DisplaySettings::new(1.0, -0.1, 1.05)
+            },
+            Preset::Reading => DisplaySettings {
+                temperature: 5500,
+                ..DisplaySettings::new(1.0, 0.0, 0.85)
+            },
+        }
+    }
+
+    /// Make a regular profile from the preset.
+    pub fn profile(&self) -> Profile {
+        Profile {
+            description: self.description().to_string(),
+            ..Profile::new(self.name().to_string(), self.settings())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profiles::ProfileManager;
+
+    #[test]
+    fn presets_are_valid_and_versioned() {
+        for preset in Preset::ALL {
+            assert!(preset.settings().validate().is_ok(), "{:?}", preset);
+            assert!((1..=PRESETS_VERSION).contains(&preset.added_in()));
+        }
+    }
+
+    #[test]
+    fn adding_a_preset_twice_numbers_the_copy() {
+        let mut manager = ProfileManager::new();
+        manager.add_numbered(Preset::Reading.profile());
+        manager.add_numbered(Preset::Reading.profile());
+
+        let names: Vec<&str> = manager
+            .get_profiles()
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(names, ["Reading", "Reading (2)"]);
+        assert_eq!(
+            manager.get_profiles()[1].settings,
+            Preset::Reading.settings()
+        );
+    }
+}
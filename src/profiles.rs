@@ -1,22 +1,202 @@
-use crate::windows::display::DisplaySettings;
+use crate::windows::display::{DisplaySettings, GammaError};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, fs, path::Path, time::SystemTime};
+
+/// Version of the profiles file format written by [`save`].
+pub const PROFILES_FILE_VERSION: u32 = 1;
+
+/// Start of a profile code from [`to_code`], which tells codes apart from other pasted text.
+const CODE_PREFIX: &str = "gammar1:";
+
+/// A standalone file of profiles, for carrying them between installs. Fields added in later
+/// versions must default, so older files keep loading.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ProfilesFile {
+    version: u32,
+    profiles: Vec<Profile>,
+}
+
+/// What importing profiles did.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    /// Profiles added, including renamed ones.
+    pub added: usize,
+    /// Profiles added under a new name, as one with the same name but other settings existed.
+    pub renamed: usize,
+    /// Profiles skipped as exact duplicates of existing ones.
+    pub skipped: usize,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Profile {
+    /// Identifies the profile across renames and reorders. Assigned when the profile is added.
+    #[serde(default)]
+    pub id: u64,
     pub name: String,
+    /// Notes on what the profile is for.
+    #[serde(default)]
+    pub description: String,
     pub settings: DisplaySettings,
+    /// The monitor the profile is always applied to. `None` applies it wherever settings
+    /// changes go.
+    #[serde(default)]
+    pub monitor_id: Option<String>,
+    /// When the profile was last applied, from the Profiles tab or a hotkey.
+    #[serde(default, with = "timestamp")]
+    pub last_applied: Option<SystemTime>,
+    /// Accent color marking the profile in lists.
+    #[serde(default)]
+    pub color: Option<ProfileColor>,
+    /// A short emoji or symbol shown before the name.
+    #[serde(default)]
+    pub icon: String,
+    /// Pinned profiles are listed first, whatever the sort order.
+    #[serde(default)]
+    pub pinned: bool,
+    /// Locked profiles can't be updated or removed without forcing it.
+    #[serde(default)]
+    pub locked: bool,
+}
+
+/// The accent colors a profile can be marked with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProfileColor {
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Teal,
+    Blue,
+    Purple,
+    Pink,
+}
+
+impl ProfileColor {
+    pub const ALL: [ProfileColor; 8] = [
+        ProfileColor::Red,
+        ProfileColor::Orange,
+        ProfileColor::Yellow,
+        ProfileColor::Green,
+        ProfileColor::Teal,
+        ProfileColor::Blue,
+        ProfileColor::Purple,
+        ProfileColor::Pink,
+    ];
+
+    pub fn format(&self) -> &'static str {
+        match self {
+            ProfileColor::Red => "Red",
+            ProfileColor::Orange => "Orange",
+            ProfileColor::Yellow => "Yellow",
+            ProfileColor::Green => "Green",
+            ProfileColor::Teal => "Teal",
+            ProfileColor::Blue => "Blue",
+            ProfileColor::Purple => "Purple",
+            ProfileColor::Pink => "Pink",
+        }
+    }
+
+    /// The color as CSS.
+    pub fn css(&self) -> &'static str {
+        match self {
+            ProfileColor::Red => "#e5484d",
+            ProfileColor::Orange => "#f76b15",
+            ProfileColor::Yellow => "#ffc53d",
+            ProfileColor::Green => "#30a46c",
+            ProfileColor::Teal => "#12a594",
+            ProfileColor::Blue => "#0090ff",
+            ProfileColor::Purple => "#8e4ec6",
+            ProfileColor::Pink => "#d6409f",
+        }
+    }
+}
+
+/// Serializes times as whole seconds since the Unix epoch.
+mod timestamp {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::time::{Duration, SystemTime};
+
+    pub fn serialize<S: Serializer>(
+        time: &Option<SystemTime>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        time.and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<SystemTime>, D::Error> {
+        let seconds = Option::<u64>::deserialize(deserializer)?;
+
+        Ok(seconds.map(|s| SystemTime::UNIX_EPOCH + Duration::from_secs(s)))
+    }
+}
+
+/// The order the Profiles tab lists profiles in. Only the display changes; the saved order
+/// stays the manual one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProfileSort {
+    #[default]
+    Manual,
+    Name,
+    RecentlyUsed,
+}
+
+impl ProfileSort {
+    pub const ALL: [ProfileSort; 3] = [
+        ProfileSort::Manual,
+        ProfileSort::Name,
+        ProfileSort::RecentlyUsed,
+    ];
+
+    pub fn format(&self) -> &'static str {
+        match self {
+            ProfileSort::Manual => "Manual order",
+            ProfileSort::Name => "Name",
+            ProfileSort::RecentlyUsed => "Recently used",
+        }
+    }
 }
 
 impl Profile {
     /// Create a new profile with the given name and display settings.
     pub fn new(name: String, settings: DisplaySettings) -> Self {
-        Self { name, settings }
+        Self {
+            id: 0,
+            name,
+            description: String::new(),
+            settings,
+            monitor_id: None,
+            last_applied: None,
+            color: None,
+            icon: String::new(),
+            pinned: false,
+            locked: false,
+        }
+    }
+
+    /// Copy the profile for another install: just its name, description, marker and settings,
+    /// which are clamped into range. Its monitor, pin, lock and use are this install's.
+    fn portable(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            description: self.description.clone(),
+            color: self.color,
+            icon: self.icon.clone(),
+            ..Self::new(String::new(), self.settings.clamped())
+        }
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProfileManager {
     profiles: Vec<Profile>,
+    /// The most recently assigned profile id. Ids start at 1, so 0 marks a profile without one.
+    #[serde(default)]
+    last_id: u64,
 }
 
 impl ProfileManager {
@@ -24,32 +204,175 @@ impl ProfileManager {
     pub fn new() -> Self {
         Self {
             profiles: Vec::new(),
+            last_id: 0,
         }
     }
 
-    /// Add a new profile to the manager.
-    pub fn add_profile(&mut self, profile: Profile) {
+    /// Add a new profile to the manager, giving it a fresh id. Returns the id.
+    pub fn add_profile(&mut self, mut profile: Profile) -> u64 {
+        let id = self.next_id();
+        profile.id = id;
         self.profiles.push(profile);
+
+        id
     }
 
-    /// Remove a profile by its index. Returns the removed profile if the index was valid.
-    pub fn remove_profile(&mut self, index: usize) -> Option<Profile> {
-        if index < self.profiles.len() {
-            return Some(self.profiles.remove(index));
+    /// Add a profile, numbering its name if another profile has it. Returns the id.
+    pub fn add_numbered(&mut self, mut profile: Profile) -> u64 {
+        profile.name = self.unique_name(&profile.name);
+
+        self.add_profile(profile)
+    }
+
+    /// Add a new profile like [`Self::add_profile`], unless another profile has its name.
+    pub fn add_profile_unique(&mut self, profile: Profile) -> Result<u64, GammaError> {
+        if self.find_by_name(&profile.name).is_some() {
+            return Err(GammaError::DuplicateProfileName { name: profile.name });
+        }
+
+        Ok(self.add_profile(profile))
+    }
+
+    /// Find a profile and its index by name.
+    pub fn find_by_name(&self, name: &str) -> Option<(usize, &Profile)> {
+        self.profiles
+            .iter()
+            .enumerate()
+            .find(|(_, p)| p.name == name)
+    }
+
+    /// Get a name no profile has yet, numbering `name` if it's taken, e.g. `Night (2)`.
+    pub fn unique_name(&self, name: &str) -> String {
+        if self.find_by_name(name).is_none() {
+            return name.to_string();
+        }
+
+        (2..)
+            .map(|n| format!("{} ({})", name, n))
+            .find(|candidate| self.find_by_name(candidate).is_none())
+            .unwrap_or_default()
+    }
+
+    /// Number the names of profiles that share one with an earlier profile, as configs from
+    /// before names had to be unique may have. Returns true if any were renamed.
+    pub fn dedupe_names(&mut self) -> bool {
+        let mut renamed = false;
+
+        for index in 1..self.profiles.len() {
+            let name = &self.profiles[index].name;
+            if self.profiles[..index].iter().any(|p| &p.name == name) {
+                let name = name.clone();
+                self.profiles[index].name = self.unique_name(&name);
+                renamed = true;
+            }
+        }
+
+        renamed
+    }
+
+    fn next_id(&mut self) -> u64 {
+        self.last_id += 1;
+        self.last_id
+    }
+
+    /// Give profiles saved before ids existed an id each. Returns true if any were missing.
+    pub fn assign_missing_ids(&mut self) -> bool {
+        let highest = self.profiles.iter().map(|p| p.id).max().unwrap_or(0);
+        self.last_id = self.last_id.max(highest);
+
+        let mut assigned = false;
+        for index in 0..self.profiles.len() {
+            if self.profiles[index].id == 0 {
+                self.profiles[index].id = self.next_id();
+                assigned = true;
+            }
+        }
+
+        assigned
+    }
+
+    /// Check that a profile may be changed, which locked ones only may when forced. Returns its
+    /// index.
+    fn check_unlocked(&self, id: u64, force: bool) -> Result<usize, GammaError> {
+        let index = self
+            .position(id)
+            .ok_or(GammaError::ProfileNotFound { id })?;
+        let profile = &self.profiles[index];
+
+        if profile.locked && !force {
+            return Err(GammaError::ProfileLocked {
+                name: profile.name.clone(),
+            });
         }
 
-        None
+        Ok(index)
+    }
+
+    /// Remove a profile by its id, returning it. Locked profiles are only removed when forced.
+    pub fn remove_profile(&mut self, id: u64, force: bool) -> Result<Profile, GammaError> {
+        let index = self.check_unlocked(id, force)?;
+
+        Ok(self.profiles.remove(index))
+    }
+
+    /// Remove every profile with one of the given ids, returning them in list order. Locked
+    /// profiles stay unless forced.
+    pub fn remove_profiles(&mut self, ids: &HashSet<u64>, force: bool) -> Vec<Profile> {
+        let (removed, kept) = std::mem::take(&mut self.profiles)
+            .into_iter()
+            .partition(|p| ids.contains(&p.id) && (force || !p.locked));
+        self.profiles = kept;
+
+        removed
     }
 
-    /// Update a profile at the given index. Returns true if successful.
-    pub fn update_profile(&mut self, index: usize, profile: Profile) -> bool {
-        if index < self.profiles.len() {
-            self.profiles[index] = profile;
+    /// Put a removed profile back at an index, or at the end if there are fewer profiles now,
+    /// keeping its id. It's renamed if another profile took its name in the meantime.
+    pub fn restore_profile(&mut self, index: usize, mut profile: Profile) {
+        self.last_id = self.last_id.max(profile.id);
+        profile.name = self.unique_name(&profile.name);
+        self.profiles
+            .insert(index.min(self.profiles.len()), profile);
+    }
+
+    /// Replace the profile with an id, which the replacement keeps. Locked profiles are only
+    /// replaced when forced.
+    pub fn update_profile(
+        &mut self,
+        id: u64,
+        mut profile: Profile,
+        force: bool,
+    ) -> Result<(), GammaError> {
+        let index = self.check_unlocked(id, force)?;
+        profile.id = id;
+        self.profiles[index] = profile;
+
+        Ok(())
+    }
+
+    /// Lock a profile against changes and removal, or unlock it. Returns false if no profile
+    /// has the id.
+    pub fn set_locked(&mut self, id: u64, locked: bool) -> bool {
+        match self.profiles.iter_mut().find(|p| p.id == id) {
+            Some(profile) => {
+                profile.locked = locked;
+                true
+            }
+            None => false,
+        }
+    }
 
-            return true;
+    /// Move a profile to another position, shifting the ones in between. Returns true if both
+    /// indices were valid.
+    pub fn move_profile(&mut self, from: usize, to: usize) -> bool {
+        if from >= self.profiles.len() || to >= self.profiles.len() {
+            return false;
         }
 
-        false
+        let profile = self.profiles.remove(from);
+        self.profiles.insert(to, profile);
+
+        true
     }
 
     /// Get a reference to a profile by its index.
@@ -57,6 +380,39 @@ impl ProfileManager {
         self.profiles.get(index)
     }
 
+    /// Get a reference to a profile by its id.
+    pub fn get_profile_by_id(&self, id: u64) -> Option<&Profile> {
+        self.profiles.iter().find(|p| p.id == id)
+    }
+
+    /// Get a mutable reference to a profile by its id.
+    pub fn get_profile_by_id_mut(&mut self, id: u64) -> Option<&mut Profile> {
+        self.profiles.iter_mut().find(|p| p.id == id)
+    }
+
+    /// Get the index of the profile with an id.
+    pub fn position(&self, id: u64) -> Option<usize> {
+        self.profiles.iter().position(|p| p.id == id)
+    }
+
+    /// Get the profiles in an order, with their indices. Pinned profiles come first, and
+    /// profiles never applied come after the rest when sorting by use.
+    pub fn sorted(&self, sort: ProfileSort) -> Vec<(usize, &Profile)> {
+        let mut profiles: Vec<(usize, &Profile)> = self.profiles.iter().enumerate().collect();
+
+        match sort {
+            ProfileSort::Manual => {}
+            ProfileSort::Name => profiles.sort_by_key(|(_, p)| p.name.to_lowercase()),
+            ProfileSort::RecentlyUsed => {
+                profiles.sort_by_key(|(_, p)| std::cmp::Reverse(p.last_applied))
+            }
+        }
+        // Sorts are stable, so each group keeps the order above
+        profiles.sort_by_key(|(_, p)| !p.pinned);
+
+        profiles
+    }
+
     /// Get a slice of all profiles.
     pub fn get_profiles(&self) -> &[Profile] {
         &self.profiles
@@ -67,6 +423,32 @@ impl ProfileManager {
         self.profiles.len()
     }
 
+    /// Add profiles from another install. Exact duplicates are skipped, and profiles whose name is
+    /// taken get a numbered one, e.g. `Night (2)`. Imported profiles get fresh ids and have their
+    /// settings clamped, keeping only what a share code carries.
+    pub fn import(&mut self, profiles: Vec<Profile>) -> ImportSummary {
+        let mut summary = ImportSummary::default();
+
+        for profile in profiles {
+            let mut profile = profile.portable();
+            let existing = self.profiles.iter().filter(|p| p.name == profile.name);
+            if existing.clone().any(|p| p.settings == profile.settings) {
+                summary.skipped += 1;
+                continue;
+            }
+
+            if existing.count() > 0 {
+                profile.name = self.unique_name(&profile.name);
+                summary.renamed += 1;
+            }
+
+            self.add_profile(profile);
+            summary.added += 1;
+        }
+
+        summary
+    }
+
     /// Get a mutable reference to the profiles vector.
     pub fn profiles_mut(&mut self) -> &mut Vec<Profile> {
         &mut self.profiles
@@ -78,3 +460,291 @@ impl Default for ProfileManager {
         Self::new()
     }
 }
+
+/// Write profiles to a standalone, versioned JSON file.
+pub fn save(profiles: &[Profile], path: &Path) -> Result<(), GammaError> {
+    let file = ProfilesFile {
+        version: PROFILES_FILE_VERSION,
+        profiles: profiles.to_vec(),
+    };
+    let json = serde_json::to_string_pretty(&file).unwrap_or_default();
+
+    fs::write(path, json).map_err(|source| GammaError::Io {
+        context: format!("Failed to write {}", path.display()),
+        source,
+    })
+}
+
+/// Read the profiles of a file written by [`save`].
+pub fn load(path: &Path) -> Result<Vec<Profile>, GammaError> {
+    let contents = fs::read_to_string(path).map_err(|source| GammaError::Io {
+        context: format!("Failed to read {}", path.display()),
+        source,
+    })?;
+
+    parse(&contents).map_err(|e| {
+        GammaError::InvalidProfiles(format!("{} isn't a profiles file: {}", path.display(), e))
+    })
+}
+
+fn parse(json: &str) -> Result<Vec<Profile>, serde_json::Error> {
+    serde_json::from_str::<ProfilesFile>(json).map(|file| file.profiles)
+}
+
+/// Encode a profile as a short text code to share, leaving out what only applies to this
+/// install: its id, monitor, pin and when it was last applied.
+pub fn to_code(profile: &Profile) -> String {
+    let json = serde_json::to_string(&profile.portable()).unwrap_or_default();
+
+    format!("{}{}", CODE_PREFIX, URL_SAFE_NO_PAD.encode(json))
+}
+
+/// Decode a profile from [`to_code`]. Whitespace is ignored, as chat apps often wrap long codes,
+/// and out-of-range settings are clamped.
+pub fn from_code(code: &str) -> Result<Profile, GammaError> {
+    let invalid =
+        |reason: &str| GammaError::InvalidProfiles(format!("Invalid profile code: {}", reason));
+
+    let code: String = code.split_whitespace().collect();
+    let data = code
+        .strip_prefix(CODE_PREFIX)
+        .ok_or_else(|| invalid("it should start with \"gammar1:\""))?;
+    let json = URL_SAFE_NO_PAD
+        .decode(data)
+        .map_err(|_| invalid("it's incomplete or damaged"))?;
+    let profile: Profile =
+        serde_json::from_slice(&json).map_err(|_| invalid("it's incomplete or damaged"))?;
+
+    if profile.name.trim().is_empty() {
+        return Err(invalid("the profile has no name"));
+    }
+
+    let mut profile = profile.portable();
+    profile.name = profile.name.trim().to_string();
+
+    Ok(profile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn manager(profiles: &[(&str, f32)]) -> ProfileManager {
+        let mut manager = ProfileManager::new();
+        for &(name, gamma) in profiles {
+            manager.add_profile(Profile::new(
+                name.to_string(),
+                DisplaySettings::new(gamma, 0.0, 1.0),
+            ));
+        }
+        manager
+    }
+
+    fn names(manager: &ProfileManager) -> Vec<&str> {
+        manager
+            .get_profiles()
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn profiles_survive_an_export_and_import() {
+        let mut original = manager(&[("Day", 1.0), ("Night", 0.8), ("Movie", 1.2)]);
+        original.profiles_mut()[2].description = "Only on the TV".to_string();
+        original.profiles_mut()[1].color = Some(ProfileColor::Purple);
+        original.profiles_mut()[1].icon = "🌙".to_string();
+        let path =
+            std::env::temp_dir().join(format!("gammar-profiles-{}.json", std::process::id()));
+
+        save(original.get_profiles(), &path).unwrap();
+        let mut imported = ProfileManager::new();
+        let summary = imported.import(load(&path).unwrap());
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(summary.added, 3);
+        assert_eq!(imported.get_profiles(), original.get_profiles());
+    }
+
+    #[test]
+    fn imports_skip_duplicates_and_rename_collisions() {
+        let mut manager = manager(&[("Day", 1.0), ("Night", 0.8), ("Night (2)", 0.7)]);
+        let incoming = self::manager(&[("Day", 1.0), ("Night", 0.9), ("Game", 1.1)]);
+
+        let summary = manager.import(incoming.get_profiles().to_vec());
+
+        assert_eq!(
+            summary,
+            ImportSummary {
+                added: 2,
+                renamed: 1,
+                skipped: 1
+            }
+        );
+        assert_eq!(
+            names(&manager),
+            ["Day", "Night", "Night (2)", "Night (3)", "Game"]
+        );
+    }
+
+    #[test]
+    fn imports_clamp_settings_and_drop_install_specific_fields() {
+        let mut profile = Profile::new("Wild".to_string(), DisplaySettings::new(9.0, -4.0, 0.0));
+        profile.monitor_id = Some("\\\\.\\DISPLAY2".to_string());
+        profile.last_applied = Some(SystemTime::now());
+        profile.pinned = true;
+        profile.locked = true;
+        profile.icon = "🎮".to_string();
+
+        let mut manager = ProfileManager::new();
+        manager.import(vec![profile]);
+
+        let imported = &manager.get_profiles()[0];
+        assert_eq!(
+            imported.settings,
+            DisplaySettings::new(9.0, -4.0, 0.0).clamped()
+        );
+        assert!(imported.settings.validate().is_ok());
+        assert_eq!(imported.icon, "🎮");
+        assert_eq!(imported.monitor_id, None);
+        assert_eq!(imported.last_applied, None);
+        assert!(!imported.pinned);
+        assert!(!imported.locked);
+    }
+
+    #[test]
+    fn names_stay_unique() {
+        let mut manager = manager(&[("Day", 1.0), ("Night", 0.8)]);
+
+        assert_eq!(manager.find_by_name("Night").map(|(i, _)| i), Some(1));
+        assert!(manager.find_by_name("night").is_none());
+        assert!(matches!(
+            manager.add_profile_unique(Profile::new("Day".to_string(), DisplaySettings::default())),
+            Err(GammaError::DuplicateProfileName { .. })
+        ));
+        assert!(manager
+            .add_profile_unique(Profile::new("Game".to_string(), DisplaySettings::default()))
+            .is_ok());
+
+        // Configs from before names were unique keep every profile
+        let mut duplicates = self::manager(&[("Night", 0.8), ("Night", 0.7), ("Night", 0.6)]);
+        assert!(duplicates.dedupe_names());
+        assert_eq!(names(&duplicates), ["Night", "Night (2)", "Night (3)"]);
+        assert!(!duplicates.dedupe_names());
+    }
+
+    #[test]
+    fn locked_profiles_resist_changes() {
+        let mut manager = manager(&[("Reference", 1.0), ("Scratch", 1.2)]);
+        let reference = manager.get_profiles()[0].id;
+        assert!(manager.set_locked(reference, true));
+        assert!(!manager.set_locked(99, true));
+
+        let edited = Profile::new("Reference".to_string(), DisplaySettings::new(2.0, 0.0, 1.0));
+        assert!(matches!(
+            manager.update_profile(reference, edited.clone(), false),
+            Err(GammaError::ProfileLocked { .. })
+        ));
+        assert!(manager.remove_profile(reference, false).is_err());
+        assert!(matches!(
+            manager.remove_profile(99, true),
+            Err(GammaError::ProfileNotFound { id: 99 })
+        ));
+
+        let ids: HashSet<u64> = manager.get_profiles().iter().map(|p| p.id).collect();
+        let removed = manager.remove_profiles(&ids, false);
+        assert_eq!(removed.len(), 1);
+        assert_eq!(names(&manager), ["Reference"]);
+
+        // Forcing goes through the lock, and the replacement keeps the id
+        manager.update_profile(reference, edited, true).unwrap();
+        assert_eq!(manager.get_profiles()[0].settings.gamma, 2.0);
+        assert_eq!(manager.get_profiles()[0].id, reference);
+        manager.remove_profile(reference, true).unwrap();
+        assert_eq!(manager.profile_count(), 0);
+    }
+
+    #[test]
+    fn sorting_keeps_indices() {
+        let mut manager = manager(&[("night", 0.8), ("Day", 1.0), ("Movie", 1.2)]);
+        let now = SystemTime::now();
+        manager.profiles_mut()[0].last_applied = Some(now - Duration::from_secs(60));
+        manager.profiles_mut()[2].last_applied = Some(now);
+
+        let order = |sort| -> Vec<usize> { manager.sorted(sort).iter().map(|(i, _)| *i).collect() };
+        assert_eq!(order(ProfileSort::Manual), [0, 1, 2]);
+        assert_eq!(order(ProfileSort::Name), [1, 2, 0]);
+        assert_eq!(order(ProfileSort::RecentlyUsed), [2, 0, 1]);
+
+        manager.profiles_mut()[1].pinned = true;
+        let order = |sort| -> Vec<usize> { manager.sorted(sort).iter().map(|(i, _)| *i).collect() };
+        assert_eq!(order(ProfileSort::Manual), [1, 0, 2]);
+        assert_eq!(order(ProfileSort::RecentlyUsed), [1, 2, 0]);
+    }
+
+    #[test]
+    fn last_applied_is_saved_as_seconds() {
+        let mut profile = Profile::new("Day".to_string(), DisplaySettings::default());
+        profile.last_applied = Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+
+        let json = serde_json::to_value(&profile).unwrap();
+        assert_eq!(json["last_applied"], 1_700_000_000);
+        assert_eq!(serde_json::from_value::<Profile>(json).unwrap(), profile);
+    }
+
+    #[test]
+    fn profiles_survive_a_code_round_trip() {
+        let mut profile = Profile::new("Night".to_string(), DisplaySettings::new(0.8, -0.1, 1.1));
+        profile.id = 4;
+        profile.pinned = true;
+        profile.description = "For late sessions".to_string();
+
+        let code = to_code(&profile);
+        assert!(code.starts_with(CODE_PREFIX));
+
+        // Wrapped codes still decode
+        let wrapped = format!("  {}\n{}  ", &code[..20], &code[20..]);
+        let decoded = from_code(&wrapped).unwrap();
+        assert_eq!(decoded.name, "Night");
+        assert_eq!(decoded.description, "For late sessions");
+        assert_eq!(decoded.settings, profile.settings);
+        assert_eq!((decoded.id, decoded.pinned), (0, false));
+    }
+
+    #[test]
+    fn bad_codes_are_rejected_and_settings_clamped() {
+        assert!(from_code("").is_err());
+        assert!(from_code("hello there").is_err());
+
+        let code = to_code(&Profile::new(
+            "Night".to_string(),
+            DisplaySettings::default(),
+        ));
+        assert!(from_code(&code[..code.len() - 6]).is_err());
+        assert!(from_code(&format!("{}!!", code)).is_err());
+
+        let json =
+            r#"{"name": "Wild", "settings": {"gamma": 9.0, "brightness": -4.0, "contrast": 1.0}}"#;
+        let code = format!("{}{}", CODE_PREFIX, URL_SAFE_NO_PAD.encode(json));
+        let settings = from_code(&code).unwrap().settings;
+        assert_eq!((settings.gamma, settings.brightness), (3.0, -1.0));
+    }
+
+    #[test]
+    fn files_from_other_versions_load() {
+        let profiles = parse(
+            r#"{"version": 2, "author": "me", "profiles": [
+                {"name": "Old", "settings": {"gamma": 1.0, "brightness": 0.0, "contrast": 1.0}}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(profiles[0].name, "Old");
+        assert_eq!(profiles[0].last_applied, None);
+        assert_eq!(profiles[0].color, None);
+        assert!(!profiles[0].locked);
+
+        assert!(parse(r#"{"profiles": []}"#).is_err());
+        assert!(parse("[]").is_err());
+    }
+}
@@ -0,0 +1,564 @@
+use crate::{profiles::ProfileManager, windows::display::DisplaySettings};
+use serde::{Deserialize, Serialize};
+
+/// Minutes in a day, the period the schedule repeats with.
+pub const MINUTES_PER_DAY: u32 = 24 * 60;
+
+/// Format minutes after midnight as `HH:MM`.
+pub fn format_time(minute: u32) -> String {
+    format!("{:02}:{:02}", minute / 60, minute % 60)
+}
+
+/// What an event's start follows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EventAnchor {
+    /// The event's own start time.
+    #[default]
+    Fixed,
+    /// Local sunrise at the schedule's location.
+    Sunrise,
+    /// Local sunset at the schedule's location.
+    Sunset,
+}
+
+impl EventAnchor {
+    pub const ALL: [EventAnchor; 3] = [
+        EventAnchor::Fixed,
+        EventAnchor::Sunrise,
+        EventAnchor::Sunset,
+    ];
+
+    pub fn format(&self) -> &'static str {
+        match self {
+            EventAnchor::Fixed => "At",
+            EventAnchor::Sunrise => "At sunrise",
+            EventAnchor::Sunset => "At sunset",
+        }
+    }
+}
+
+/// Days of the week an event happens on, as a bit per day from Monday.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Weekdays(pub u8);
+
+impl Weekdays {
+    pub const EVERY_DAY: Weekdays = Weekdays(0b111_1111);
+
+    /// Short day names, from Monday.
+    pub const NAMES: [&'static str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+    /// Check whether the days include one, counting from Monday as 0.
+    pub fn contains(&self, weekday: u32) -> bool {
+        weekday < 7 && self.0 & (1 << weekday) != 0
+    }
+
+    /// Add or remove a day, counting from Monday as 0.
+    pub fn set(&mut self, weekday: u32, on: bool) {
+        if weekday < 7 {
+            match on {
+                true => self.0 |= 1 << weekday,
+                false => self.0 &= !(1 << weekday),
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 & Self::EVERY_DAY.0 == 0
+    }
+}
+
+impl Default for Weekdays {
+    fn default() -> Self {
+        Self::EVERY_DAY
+    }
+}
+
+/// A change to a profile on some or all days, eased into from the previous event's profile.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduleEvent {
+    /// When the transition starts, in minutes after midnight. Events following the sun use it
+    /// on days the sun doesn't rise or set.
+    pub start_minute: u32,
+    #[serde(default)]
+    pub anchor: EventAnchor,
+    /// How long the transition takes. 0 switches at once.
+    pub duration_minutes: u32,
+    /// Id of the profile the transition ends at.
+    #[serde(default)]
+    pub profile_id: u64,
+    /// Name of the profile, which events saved before profile ids existed refer to it by. It's
+    /// resolved to the id when the config loads.
+    #[serde(default, rename = "profile", skip_serializing)]
+    pub legacy_profile: Option<String>,
+    /// Days the event happens on. Events saved before days could be picked happen every day.
+    #[serde(default)]
+    pub days: Weekdays,
+}
+
+impl ScheduleEvent {
+    /// Create an event easing into the profile with the given id.
+    pub fn new(start_minute: u32, duration_minutes: u32, profile_id: u64) -> Self {
+        Self {
+            start_minute,
+            duration_minutes,
+            anchor: EventAnchor::Fixed,
+            profile_id,
+            legacy_profile: None,
+            days: Weekdays::EVERY_DAY,
+        }
+    }
+
+    /// Format the start time as `HH:MM`.
+    pub fn start_time(&self) -> String {
+        format_time(self.start_minute)
+    }
+
+    /// Get when the event starts, in minutes after midnight, given the day's sun times.
+    pub fn start_at(&self, sun: Option<SunTimes>) -> u32 {
+        match (self.anchor, sun) {
+            (EventAnchor::Sunrise, Some(sun)) => sun.sunrise,
+            (EventAnchor::Sunset, Some(sun)) => sun.sunset,
+            _ => self.start_minute,
+        }
+    }
+
+    /// Parse an `HH:MM` time, as produced by time inputs, into minutes after midnight.
+    pub fn parse_time(time: &str) -> Option<u32> {
+        let (hours, minutes) = time.split_once(':')?;
+        let (hours, minutes): (u32, u32) = (hours.parse().ok()?, minutes.parse().ok()?);
+
+        (hours < 24 && minutes < 60).then_some(hours * 60 + minutes)
+    }
+}
+
+/// A place on Earth, in degrees. North and east are positive.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Location {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// Local sunrise and sunset, in minutes after midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SunTimes {
+    pub sunrise: u32,
+    pub sunset: u32,
+}
+
+impl Location {
+    /// Compute the day's sunrise and sunset with the NOAA solar position approximation, which
+    /// is good to a minute or two. Returns `None` during polar day or night, when the sun
+    /// doesn't cross the horizon.
+    pub fn sun_times(&self, time: &LocalTime) -> Option<SunTimes> {
+        // Fractional year at noon, in radians
+        let year = 2.0 * std::f64::consts::PI * time.day_of_year as f64 / 365.0;
+
+        let equation_of_time = 229.18
+            * (0.000075 + 0.001868 * year.cos()
+                - 0.032077 * year.sin()
+                - 0.014615 * (2.0 * year).cos()
+                - 0.040849 * (2.0 * year).sin());
+        let declination = 0.006918 - 0.399912 * year.cos() + 0.070257 * year.sin()
+            - 0.006758 * (2.0 * year).cos()
+            + 0.000907 * (2.0 * year).sin()
+            - 0.002697 * (3.0 * year).cos()
+            + 0.00148 * (3.0 * year).sin();
+
+        // The sun's upper edge touches the horizon, allowing for refraction, at 90.833°
+        let latitude = self.latitude.to_radians();
+        let cos_hour_angle = 90.833_f64.to_radians().cos() / (latitude.cos() * declination.cos())
+            - latitude.tan() * declination.tan();
+
+        if !(-1.0..=1.0).contains(&cos_hour_angle) {
+            return None;
+        }
+
+        let hour_angle = cos_hour_angle.acos().to_degrees();
+        let local = |utc: f64| {
+            let minute = (utc + time.utc_offset_minutes as f64).rem_euclid(MINUTES_PER_DAY as f64);
+            (minute.round() as u32) % MINUTES_PER_DAY
+        };
+
+        Some(SunTimes {
+            sunrise: local(720.0 - 4.0 * (self.longitude + hour_angle) - equation_of_time),
+            sunset: local(720.0 - 4.0 * (self.longitude - hour_angle) - equation_of_time),
+        })
+    }
+}
+
+/// Profiles to ease between at set times of day.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScheduleConfig {
+    pub enabled: bool,
+    pub events: Vec<ScheduleEvent>,
+    /// Where events following the sun compute sunrise and sunset for.
+    #[serde(default)]
+    pub location: Option<Location>,
+}
+
+/// The local date and time, as the schedule needs it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LocalTime {
+    /// Days since 1 January, which is 0.
+    pub day_of_year: u32,
+    /// Day of the week, from Monday as 0.
+    pub weekday: u32,
+    /// Minutes after midnight, with seconds as the fraction.
+    pub minute: f32,
+    /// How far local time is ahead of UTC, in minutes.
+    pub utc_offset_minutes: i32,
+}
+
+/// Where the schedule stands at a point in the day.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScheduledState {
+    /// Start of the event in effect, which identifies it.
+    pub event_start: u32,
+    pub settings: DisplaySettings,
+    /// Whether the event's transition is still running.
+    pub transitioning: bool,
+}
+
+impl ScheduleConfig {
+    /// Compute the day's sunrise and sunset at the configured location, if it has both.
+    pub fn sun_times(&self, time: &LocalTime) -> Option<SunTimes> {
+        self.location?.sun_times(time)
+    }
+
+    /// Compute the settings the schedule calls for at a time of day. The latest event that has
+    /// started, looking back over the past week for events on other days, is in effect; during
+    /// its transition the settings are interpolated from the previous event's profile. Events
+    /// whose profile no longer exists are skipped. Sun times are computed for each day, so they
+    /// follow the seasons.
+    pub fn state_at(&self, profiles: &ProfileManager, time: &LocalTime) -> Option<ScheduledState> {
+        let settings_of = |event: &ScheduleEvent| {
+            profiles
+                .get_profile_by_id(event.profile_id)
+                .map(|p| p.settings)
+        };
+
+        let day = MINUTES_PER_DAY as f32;
+        let minute = time.minute.rem_euclid(day);
+
+        // Each event's starts over the past week and a day, in minutes from today's midnight, so
+        // an event that happens once a week still has one before it
+        let mut starts: Vec<(f32, &ScheduleEvent, u32, DisplaySettings)> = Vec::new();
+        for days_ago in 0..=7 {
+            let date = LocalTime {
+                day_of_year: (time.day_of_year + 365 - days_ago) % 365,
+                weekday: (time.weekday + 7 - days_ago % 7) % 7,
+                ..*time
+            };
+            let sun = self.sun_times(&date);
+
+            for event in &self.events {
+                let Some(settings) =
+                    settings_of(event).filter(|_| event.days.contains(date.weekday))
+                else {
+                    continue;
+                };
+                let start = event.start_at(sun);
+
+                starts.push((start as f32 - days_ago as f32 * day, event, start, settings));
+            }
+        }
+        starts.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let index = starts.iter().rposition(|(at, ..)| *at <= minute)?;
+        let (at, event, start, to) = starts[index];
+        let from = index
+            .checked_sub(1)
+            .map(|previous| starts[previous].3)
+            .unwrap_or(to);
+
+        let elapsed = minute - at;
+        let t = match event.duration_minutes {
+            0 => 1.0,
+            duration => (elapsed / duration as f32).min(1.0),
+        };
+
+        Some(ScheduledState {
+            event_start: start,
+            settings: DisplaySettings::lerp(from, to, t),
+            transitioning: t < 1.0,
+        })
+    }
+}
+
+/// Read the local date and time.
+#[cfg(windows)]
+pub fn local_time() -> LocalTime {
+    use windows::Win32::System::SystemInformation::{GetLocalTime, GetSystemTime};
+
+    let (local, utc) = unsafe { (GetLocalTime(), GetSystemTime()) };
+
+    let leap = local.wYear % 4 == 0 && (local.wYear % 100 != 0 || local.wYear % 400 == 0);
+    let days_before_month = [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334];
+    let month = (local.wMonth as usize).clamp(1, 12);
+    let day_of_year = days_before_month[month - 1]
+        + (leap && month > 2) as u32
+        + (local.wDay as u32).saturating_sub(1);
+
+    // The offset is the difference between the clocks, a day apart when they're on either side
+    // of midnight. Offsets are whole quarter hours, which absorbs a minute ticking over between
+    // the two reads
+    let minutes = |hour: u16, minute: u16| hour as i32 * 60 + minute as i32;
+    let days = match (local.wYear, local.wMonth, local.wDay).cmp(&(utc.wYear, utc.wMonth, utc.wDay))
+    {
+        std::cmp::Ordering::Greater => 1,
+        std::cmp::Ordering::Less => -1,
+        std::cmp::Ordering::Equal => 0,
+    };
+    let offset = minutes(local.wHour, local.wMinute) - minutes(utc.wHour, utc.wMinute)
+        + days * MINUTES_PER_DAY as i32;
+
+    LocalTime {
+        day_of_year,
+        // Windows counts from Sunday
+        weekday: (local.wDayOfWeek as u32 + 6) % 7,
+        minute: local.wHour as f32 * 60.0 + local.wMinute as f32 + local.wSecond as f32 / 60.0,
+        utc_offset_minutes: (offset as f32 / 15.0).round() as i32 * 15,
+    }
+}
+
+/// Read the local date and time.
+#[cfg(unix)]
+pub fn local_time() -> LocalTime {
+    let now = unsafe { libc::time(std::ptr::null_mut()) };
+    let mut local = unsafe { std::mem::zeroed::<libc::tm>() };
+
+    if unsafe { libc::localtime_r(&now, &mut local) }.is_null() {
+        return LocalTime {
+            day_of_year: 0,
+            weekday: 0,
+            minute: 0.0,
+            utc_offset_minutes: 0,
+        };
+    }
+
+    LocalTime {
+        day_of_year: local.tm_yday as u32,
+        // tm_wday counts from Sunday
+        weekday: (local.tm_wday as u32 + 6) % 7,
+        minute: local.tm_hour as f32 * 60.0 + local.tm_min as f32 + local.tm_sec as f32 / 60.0,
+        utc_offset_minutes: (local.tm_gmtoff / 60) as i32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profiles::Profile;
+
+    fn profiles() -> ProfileManager {
+        let mut profiles = ProfileManager::new();
+        profiles.add_profile(Profile::new("Day".to_string(), DisplaySettings::default()));
+        profiles.add_profile(Profile::new(
+            "Night".to_string(),
+            DisplaySettings::new(1.0, -0.4, 1.0),
+        ));
+        profiles
+    }
+
+    const DAY: u64 = 1;
+    const NIGHT: u64 = 2;
+
+    fn event(start: &str, duration_minutes: u32, profile_id: u64) -> ScheduleEvent {
+        ScheduleEvent::new(
+            ScheduleEvent::parse_time(start).unwrap(),
+            duration_minutes,
+            profile_id,
+        )
+    }
+
+    fn schedule() -> ScheduleConfig {
+        ScheduleConfig {
+            enabled: true,
+            events: vec![event("21:00", 30, NIGHT), event("07:00", 0, DAY)],
+            location: None,
+        }
+    }
+
+    fn time(day_of_year: u32, minute: f32, utc_offset_minutes: i32) -> LocalTime {
+        LocalTime {
+            day_of_year,
+            weekday: 0,
+            minute,
+            utc_offset_minutes,
+        }
+    }
+
+    #[test]
+    fn transitions_ease_between_profiles() {
+        let profiles = profiles();
+        let at = |time: &str| {
+            let minute = ScheduleEvent::parse_time(time).unwrap() as f32;
+            schedule()
+                .state_at(&profiles, &self::time(0, minute, 0))
+                .unwrap()
+        };
+
+        assert_eq!(at("12:00").settings, DisplaySettings::default());
+        assert!(!at("12:00").transitioning);
+
+        let halfway = at("21:15");
+        assert!(halfway.transitioning);
+        assert!((halfway.settings.brightness + 0.2).abs() < 1e-6);
+
+        assert_eq!(at("21:30").settings.brightness, -0.4);
+        // Before the first event of the day, the last one of the previous day holds
+        assert_eq!(at("03:00").settings.brightness, -0.4);
+        assert_eq!(at("03:00").event_start, 21 * 60);
+        assert_eq!(at("07:00").settings, DisplaySettings::default());
+    }
+
+    #[test]
+    fn events_only_happen_on_their_days() {
+        let mut schedule = schedule();
+        // Night comes later on Friday and Saturday nights
+        let weekend = Weekdays(0b110_0000);
+        schedule.events[0].days = Weekdays(0b100_1111);
+        schedule.events.push(ScheduleEvent {
+            days: Weekdays(0b011_0000),
+            ..event("23:00", 0, NIGHT)
+        });
+        let at = |weekday: u32, time: &str| {
+            let minute = ScheduleEvent::parse_time(time).unwrap() as f32;
+            schedule
+                .state_at(
+                    &profiles(),
+                    &LocalTime {
+                        weekday,
+                        ..self::time(0, minute, 0)
+                    },
+                )
+                .unwrap()
+        };
+
+        assert_eq!(at(3, "22:00").event_start, 21 * 60);
+        assert_eq!(at(4, "22:00").event_start, 7 * 60);
+        assert_eq!(at(4, "23:30").event_start, 23 * 60);
+        // Early on Sunday, Saturday's late event still holds
+        assert_eq!(at(6, "02:00").event_start, 23 * 60);
+        // ...and early on Monday, Sunday's weekday event
+        assert_eq!(at(0, "02:00").event_start, 21 * 60);
+
+        // An event on one day a week holds until it comes round again
+        let weekly = ScheduleConfig {
+            events: vec![ScheduleEvent {
+                days: weekend,
+                ..event("10:00", 30, NIGHT)
+            }],
+            ..schedule.clone()
+        };
+        let state = weekly
+            .state_at(&profiles(), &time(0, 9.0 * 60.0, 0))
+            .unwrap();
+        assert_eq!(state.settings.brightness, -0.4);
+        assert!(!state.transitioning);
+
+        let mut days = Weekdays::default();
+        assert!(days.contains(6));
+        days.set(6, false);
+        assert!(!days.contains(6) && !days.is_empty());
+        assert!(Weekdays(0).is_empty());
+
+        // Events saved before days could be picked happen every day
+        let old: ScheduleEvent =
+            serde_json::from_str(r#"{"start_minute": 0, "duration_minutes": 0}"#).unwrap();
+        assert_eq!(old.days, Weekdays::EVERY_DAY);
+    }
+
+    #[test]
+    fn events_with_missing_profiles_are_skipped() {
+        let mut schedule = schedule();
+        schedule.events.push(event("12:00", 10, 99));
+
+        let state = schedule
+            .state_at(&profiles(), &time(0, 12.5 * 60.0, 0))
+            .unwrap();
+        assert_eq!(state.event_start, 7 * 60);
+
+        assert!(ScheduleConfig::default()
+            .state_at(&profiles(), &time(0, 0.0, 0))
+            .is_none());
+    }
+
+    #[test]
+    fn sun_times_follow_the_seasons() {
+        let london = Location {
+            latitude: 51.5,
+            longitude: -0.13,
+        };
+        let near = |minute: u32, expected: &str| {
+            minute.abs_diff(ScheduleEvent::parse_time(expected).unwrap()) <= 5
+        };
+
+        // Midsummer, on British Summer Time
+        let summer = london.sun_times(&time(171, 0.0, 60)).unwrap();
+        assert!(
+            near(summer.sunrise, "04:43"),
+            "{}",
+            format_time(summer.sunrise)
+        );
+        assert!(
+            near(summer.sunset, "21:21"),
+            "{}",
+            format_time(summer.sunset)
+        );
+
+        // Midwinter, on GMT
+        let winter = london.sun_times(&time(354, 0.0, 0)).unwrap();
+        assert!(
+            near(winter.sunrise, "08:04"),
+            "{}",
+            format_time(winter.sunrise)
+        );
+        assert!(
+            near(winter.sunset, "15:53"),
+            "{}",
+            format_time(winter.sunset)
+        );
+
+        let tromso = Location {
+            latitude: 69.65,
+            longitude: 18.96,
+        };
+        assert_eq!(tromso.sun_times(&time(171, 0.0, 120)), None);
+        assert_eq!(tromso.sun_times(&time(354, 0.0, 60)), None);
+    }
+
+    #[test]
+    fn sun_events_fall_back_to_fixed_times_without_a_sunset() {
+        let mut schedule = schedule();
+        schedule.events[0].anchor = EventAnchor::Sunset;
+        schedule.events[1].anchor = EventAnchor::Sunrise;
+
+        // In London in winter, night falls long before 21:00
+        schedule.location = Some(Location {
+            latitude: 51.5,
+            longitude: -0.13,
+        });
+        let state = schedule
+            .state_at(&profiles(), &time(354, 17.0 * 60.0, 0))
+            .unwrap();
+        assert_eq!(state.settings.brightness, -0.4);
+
+        // Above the Arctic circle the sun doesn't set, so the fixed times hold
+        schedule.location = Some(Location {
+            latitude: 69.65,
+            longitude: 18.96,
+        });
+        let state = schedule
+            .state_at(&profiles(), &time(171, 17.0 * 60.0, 120))
+            .unwrap();
+        assert_eq!(state.event_start, 7 * 60);
+    }
+
+    #[test]
+    fn times_parse_and_format() {
+        assert_eq!(ScheduleEvent::parse_time("07:05"), Some(425));
+        assert_eq!(ScheduleEvent::parse_time("24:00"), None);
+        assert_eq!(event("21:30", 0, NIGHT).start_time(), "21:30");
+    }
+}
@@ -1,3 +1,4 @@
+pub mod calibrate;
 pub mod keybinds;
 pub mod profiles;
 pub mod settings;
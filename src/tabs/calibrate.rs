@@ -0,0 +1,191 @@
+use crate::{
+    calibration::CalibrationStep, components::test_pattern::open_test_patterns,
+    controller::GammaController, profiles::Profile, windows::display::DisplaySettings,
+};
+use dioxus::prelude::*;
+
+/// Grey level as a CSS color, from a percentage.
+fn grey(percent: u32) -> String {
+    let level = (percent * 255 + 50) / 100;
+
+    format!("rgb({0}, {0}, {0})", level)
+}
+
+/// The reference pattern for a step, drawn inside the tab.
+#[component]
+fn StepPattern(step: CalibrationStep) -> Element {
+    let patches: Vec<u32> = match step {
+        CalibrationStep::BlackLevel => (0..=5).collect(),
+        CalibrationStep::WhiteLevel => (95..=100).collect(),
+        _ => Vec::new(),
+    };
+
+    rsx! {
+        match step {
+            CalibrationStep::BlackLevel | CalibrationStep::WhiteLevel => rsx! {
+                div {
+                    class: "calibration-pattern",
+                    for percent in patches {
+                        div {
+                            key: "{percent}",
+                            class: "calibration-patch",
+                            style: "background: {grey(percent)};",
+                        }
+                    }
+                }
+            },
+            // Alternating black and white lines average to 50% light, which a solid grey of
+            // 186 matches on a display following the sRGB curve
+            CalibrationStep::Gamma => rsx! {
+                div {
+                    class: "calibration-pattern gamma-match",
+                    div { class: "calibration-patch", style: "background: rgb(186, 186, 186);" }
+                }
+            },
+            CalibrationStep::Finish => rsx! {},
+        }
+    }
+}
+
+#[component]
+pub fn CalibrateTab(mut controller: Signal<GammaController>) -> Element {
+    let mut step = use_signal(|| CalibrationStep::BlackLevel);
+    let mut profile_name = use_signal(|| "Calibrated".to_string());
+    let mut error_msg = use_signal(|| Option::<String>::None);
+    let mut saved = use_signal(|| false);
+
+    let mut adjust = move |lighter: bool| {
+        let mut ctrl = controller.write();
+        let settings = step().adjust(ctrl.config().current_settings, lighter);
+
+        match ctrl.set_current_settings(settings) {
+            Ok(_) => {
+                error_msg.set(None);
+                ctrl.save_soon();
+            }
+            Err(e) => error_msg.set(Some(e.to_string())),
+        }
+    };
+
+    let current = step();
+    let settings = controller.read().config().current_settings;
+
+    rsx! {
+        div {
+            class: "settings-tab",
+
+            div {
+                class: "settings-card",
+                div {
+                    class: "card-header",
+                    h2 {
+                        "Step {current.index() + 1} of {CalibrationStep::ALL.len()}: {current.title()}"
+                    }
+                    p { class: "card-description", "{current.instructions()}" }
+                }
+
+                if let Some(error) = error_msg() {
+                    p { class: "range-warning", "{error}" }
+                }
+
+                StepPattern { step: current }
+
+                if current == CalibrationStep::Finish {
+                    p {
+                        class: "monitor-details",
+                        "Gamma {settings.gamma:.2}, brightness {settings.brightness:.2}, contrast {settings.contrast:.2}"
+                    }
+
+                    div {
+                        class: "new-profile",
+                        input {
+                            r#type: "text",
+                            placeholder: "Profile name",
+                            value: "{profile_name}",
+                            oninput: move |evt| {
+                                profile_name.set(evt.value());
+                                saved.set(false);
+                            },
+                        }
+                        button {
+                            disabled: profile_name().trim().is_empty(),
+                            onclick: move |_| {
+                                let mut ctrl = controller.write();
+                                let profile = Profile::new(
+                                    profile_name().trim().to_string(),
+                                    ctrl.config().current_settings,
+                                );
+                                match ctrl.config_mut().profile_manager.add_profile_unique(profile) {
+                                    Ok(_) => {
+                                        let _ = ctrl.save();
+                                        error_msg.set(None);
+                                        saved.set(true);
+                                    }
+                                    Err(e) => error_msg.set(Some(e.to_string())),
+                                }
+                            },
+                            if saved() { "Saved" } else { "Save as profile" }
+                        }
+                    }
+                } else {
+                    div {
+                        class: "calibration-actions",
+                        button { onclick: move |_| adjust(false), "Darker" }
+                        button { onclick: move |_| adjust(true), "Lighter" }
+                        button {
+                            class: "identify-button",
+                            title: "Check the result on full-screen patterns",
+                            onclick: move |_| {
+                                if let Some(monitor) = controller.read().selected_monitor() {
+                                    open_test_patterns(&monitor);
+                                }
+                            },
+                            "Full-screen patterns"
+                        }
+                    }
+                }
+
+                div {
+                    class: "calibration-nav",
+                    button {
+                        disabled: current.previous().is_none(),
+                        onclick: move |_| {
+                            if let Some(previous) = current.previous() {
+                                step.set(previous);
+                            }
+                        },
+                        "Back"
+                    }
+                    if let Some(next) = current.next() {
+                        button { onclick: move |_| step.set(next), "Next" }
+                    } else {
+                        button {
+                            onclick: move |_| {
+                                saved.set(false);
+                                step.set(CalibrationStep::BlackLevel);
+                            },
+                            "Start over"
+                        }
+                    }
+                    button {
+                        class: "reset-button",
+                        title: "Reset every setting to its default before calibrating",
+                        onclick: move |_| {
+                            let mut ctrl = controller.write();
+                            ctrl.remember_previous();
+
+                            match ctrl.set_current_settings(DisplaySettings::default()) {
+                                Ok(_) => ctrl.save_soon(),
+                                Err(e) => error_msg.set(Some(e.to_string())),
+                            }
+
+                            saved.set(false);
+                            step.set(CalibrationStep::BlackLevel);
+                        },
+                        "Reset to Default"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,25 +1,248 @@
 use crate::{
-    windows::hotkeys::{HotkeyAction, KeybindConfig},
-    AppConfig,
+    controller::GammaController,
+    windows::{
+        hotkeys::{
+            binding_using, default_keybinds, keybind_conflicts, load_keybinds, merge_keybinds,
+            remove_binding, save_keybinds, set_binding, HotkeyAction, KeybindConfig,
+        },
+        mouse::{self, Modifiers, MouseInput},
+    },
+    HotkeyTarget,
 };
-use dioxus::{desktop::window, prelude::*};
+use dioxus::{desktop::window, html::input_data::MouseButton, prelude::*};
 use global_hotkey::hotkey::HotKey;
-use std::str::FromStr;
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Get action display name with profile and monitor context
+fn get_action_name(action: HotkeyAction, controller: &GammaController) -> String {
+    let config = controller.config();
 
-/// Get action display name with profile context
-fn get_action_name(action: HotkeyAction, config: &AppConfig) -> String {
     match action {
-        HotkeyAction::LoadProfile(i) => {
-            if let Some(profile) = config.profile_manager.get_profile(i) {
+        HotkeyAction::LoadProfile(id) => {
+            if let Some(profile) = config.profile_manager.get_profile_by_id(id) {
                 format!("Load profile: {}", profile.name)
             } else {
                 action.format()
             }
         }
+        HotkeyAction::ToggleProfile(id) => {
+            if let Some(profile) = config.profile_manager.get_profile_by_id(id) {
+                format!("Toggle profile: {}", profile.name)
+            } else {
+                action.format()
+            }
+        }
+        HotkeyAction::ApplyTemperaturePreset(kelvin) => {
+            if let Some(preset) = config.temperature_presets.find(kelvin) {
+                format!("Temperature: {} ({}K)", preset.name, kelvin)
+            } else {
+                action.format()
+            }
+        }
+        HotkeyAction::SelectMonitor(index) => {
+            if let Some(monitor) = controller.monitors().get(index) {
+                format!("Select monitor: {}", monitor.name)
+            } else {
+                action.format()
+            }
+        }
         _ => action.format(),
     }
 }
 
+/// Explain why a keybind can't be registered, if it can't. Some recorded keys don't parse as
+/// global shortcuts, mouse bindings need a modifier and chords are keyboard only.
+fn unregistrable_reason(keybind: &KeybindConfig) -> Option<&'static str> {
+    if let Some(then) = &keybind.then {
+        if MouseInput::from_key_name(&keybind.key).is_some()
+            || MouseInput::from_key_name(then).is_some()
+        {
+            return Some("Mouse inputs can't be part of a chord");
+        }
+
+        if HotKey::from_str(then).is_err() {
+            return Some("This key can't be used as a chord's second key");
+        }
+    }
+
+    if MouseInput::from_key_name(&keybind.key).is_some() {
+        if !cfg!(windows) {
+            return Some("Mouse bindings are only supported on Windows");
+        }
+
+        return Modifiers::from_names(&keybind.modifiers)
+            .is_empty()
+            .then_some("Mouse bindings need Ctrl, Shift, Alt or Win held");
+    }
+
+    HotKey::from_str(&keybind.to_shortcut_string())
+        .is_err()
+        .then_some("This key can't be used as a global shortcut")
+}
+
+/// Bumped whenever every global shortcut is removed, so handles taken before are known to be
+/// stale. Removing a shortcut through a stale handle panics.
+static SHORTCUTS_CLEARED: AtomicUsize = AtomicUsize::new(0);
+
+/// Remove every global shortcut.
+pub fn clear_shortcuts() {
+    window().remove_all_shortcuts();
+    SHORTCUTS_CLEARED.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Count the times every shortcut was removed, see [`clear_shortcuts`].
+pub fn shortcuts_cleared() -> usize {
+    SHORTCUTS_CLEARED.load(Ordering::Relaxed)
+}
+
+/// Stop matching global shortcuts and mouse bindings, e.g. while recording one.
+fn suspend_shortcuts() {
+    clear_shortcuts();
+    let _ = mouse::set_bindings(Vec::new(), |_| {});
+}
+
+/// A recorded keybind waiting on a choice between cancelling and binding it anyway, as it's
+/// already assigned to another action or would block typing a key.
+#[derive(Debug, Clone)]
+struct PendingKeybind {
+    action: HotkeyAction,
+    /// Which of the action's bindings it sets, past the last one for an alternate.
+    index: usize,
+    keybind: KeybindConfig,
+    /// The binding of another action on the combination now, if any.
+    conflict: Option<(HotkeyAction, usize)>,
+    /// Whether the keybind types text, which is blocked in every app once bound.
+    blocks_typing: bool,
+}
+
+impl PendingKeybind {
+    fn describe(&self, ctrl: &GammaController) -> String {
+        let keybind = self.keybind.format();
+        let action = get_action_name(self.action, ctrl);
+
+        match self.conflict {
+            Some((other, _)) if self.blocks_typing => format!(
+                "{} is already used by {}, and binding it stops it typing in every app. Move it to {} anyway?",
+                keybind,
+                get_action_name(other, ctrl),
+                action
+            ),
+            Some((other, _)) => format!(
+                "{} is already used by {}. Move it to {}?",
+                keybind,
+                get_action_name(other, ctrl),
+                action
+            ),
+            None => format!(
+                "Binding {} stops it typing in every app. Bind it to {} anyway?",
+                keybind, action
+            ),
+        }
+    }
+}
+
+/// The strokes of a chord being recorded.
+#[derive(Debug, Clone, Default)]
+struct ChordCapture {
+    /// Whether the keybind being recorded is a chord.
+    enabled: bool,
+    /// The first stroke, once it's recorded.
+    first: Option<KeybindConfig>,
+}
+
+/// Record a captured stroke, which makes the keybind unless a chord is being recorded. A chord's
+/// first stroke is kept, returning `None`, and the second one completes it.
+fn record_stroke(
+    stroke: KeybindConfig,
+    mut chord: Signal<ChordCapture>,
+) -> Result<Option<KeybindConfig>, String> {
+    let capture = chord();
+
+    if !capture.enabled {
+        return Ok(Some(stroke));
+    }
+    if MouseInput::from_key_name(&stroke.key).is_some() {
+        return Err("Mouse inputs can't be part of a chord".to_string());
+    }
+
+    match capture.first {
+        None => {
+            if let Some(reason) = unregistrable_reason(&stroke) {
+                return Err(format!("{} ({})", reason, stroke.format()));
+            }
+
+            chord.write().first = Some(stroke);
+            Ok(None)
+        }
+        Some(_) if !stroke.modifiers.is_empty() => {
+            Err("Press the chord's second key on its own".to_string())
+        }
+        Some(first) => Ok(Some(KeybindConfig {
+            then: Some(stroke.key),
+            ..first
+        })),
+    }
+}
+
+/// Keybinds read from a file, waiting on a choice between replacing and merging.
+#[derive(Debug, Clone)]
+struct PendingImport {
+    keybinds: HashMap<HotkeyAction, Vec<KeybindConfig>>,
+    /// Actions skipped as none of their bindings can be registered here.
+    skipped: usize,
+    /// Actions skipped as they load a profile no profile here has the name of.
+    missing_profiles: usize,
+    /// Imported keybinds whose combination another action uses.
+    conflicts: usize,
+}
+
+impl PendingImport {
+    fn describe(&self) -> String {
+        let mut description = format!("Read {} keybinds", self.keybinds.len());
+
+        if self.skipped > 0 {
+            description.push_str(&format!(
+                ", skipping {} that can't be used here",
+                self.skipped
+            ));
+        }
+        if self.missing_profiles > 0 {
+            description.push_str(&format!(
+                ", skipping {} for profiles that don't exist here",
+                self.missing_profiles
+            ));
+        }
+        if self.conflicts > 0 {
+            description.push_str(&format!(
+                ". {} use keys your other keybinds do and are left out when merging",
+                self.conflicts
+            ));
+        }
+
+        description
+    }
+}
+
+/// Stop editing a keybind and register the shortcuts again.
+fn finish_editing(
+    mut editing_binding: Signal<Option<(HotkeyAction, usize)>>,
+    mut recording_keys: Signal<bool>,
+    mut captured_modifiers: Signal<Vec<String>>,
+    mut captured_key: Signal<Option<String>>,
+    mut keybind_version: Signal<usize>,
+) {
+    editing_binding.set(None);
+    recording_keys.set(false);
+    captured_modifiers.set(Vec::new());
+    captured_key.set(None);
+
+    keybind_version.set(keybind_version() + 1);
+}
+
 /// Handler for key capture events
 #[allow(clippy::too_many_arguments)]
 fn handle_key_capture(
@@ -27,159 +250,223 @@ fn handle_key_capture(
     code: String,
     mut captured_modifiers: Signal<Vec<String>>,
     mut captured_key: Signal<Option<String>>,
-    mut editing_action: Signal<Option<HotkeyAction>>,
+    editing_binding: Signal<Option<(HotkeyAction, usize)>>,
     mut recording_keys: Signal<bool>,
-    mut config: Signal<AppConfig>,
-    mut keybind_version: Signal<usize>,
+    mut controller: Signal<GammaController>,
+    keybind_version: Signal<usize>,
     mut error_msg: Signal<Option<String>>,
+    mut pending: Signal<Option<PendingKeybind>>,
+    chord: Signal<ChordCapture>,
 ) {
     // Handle ESC to cancel
     if key == "Escape" {
-        editing_action.set(None);
-        recording_keys.set(false);
-        captured_modifiers.set(Vec::new());
-        captured_key.set(None);
-
-        // Re-register all shortcuts after canceling
-        keybind_version.set(keybind_version() + 1);
+        finish_editing(
+            editing_binding,
+            recording_keys,
+            captured_modifiers,
+            captured_key,
+            keybind_version,
+        );
         return;
     }
 
     // Capture modifiers
     let mut mods = captured_modifiers();
-    match key.as_str() {
-        "Control" => {
-            if !mods.contains(&"Ctrl".to_string()) {
-                mods.push("Ctrl".to_string());
-            }
-        }
-        "Shift" => {
-            if !mods.contains(&"Shift".to_string()) {
-                mods.push("Shift".to_string());
-            }
-        }
-        "Alt" => {
-            if !mods.contains(&"Alt".to_string()) {
-                mods.push("Alt".to_string());
-            }
-        }
-        "Meta" => {
-            if !mods.contains(&"Win".to_string()) {
-                mods.push("Win".to_string());
-            }
-        }
-        _ => {
-            // Non-modifier key - this is the main key
-            let normalized_key = KeybindConfig::normalize_key_with_code(&key, &code);
-
-            if !normalized_key.is_empty() {
-                captured_key.set(Some(normalized_key.clone()));
-
-                // Save the keybind
-                if let Some(action) = editing_action() {
-                    let new_keybind = KeybindConfig::new(mods.clone(), normalized_key);
-                    let shortcut_string = new_keybind.to_shortcut_string();
-
-                    // Validate the keybind can be parsed
-                    match HotKey::from_str(&shortcut_string) {
-                        Ok(_) => {
-                            let cfg = config.read();
-
-                            // Check for duplicate keybinds
-                            let duplicate =
-                                cfg.keybinds.iter().find(|(other_action, other_keybind)| {
-                                    **other_action != action
-                                        && other_keybind.to_shortcut_string() == shortcut_string
-                                });
-
-                            if let Some((duplicate_action, _)) = duplicate {
-                                let duplicate_name = duplicate_action.format();
-                                drop(cfg);
-
-                                error_msg.set(Some(format!(
-                                    "Keybind '{}' is already used by: {}",
-                                    shortcut_string, duplicate_name
-                                )));
-
-                                captured_modifiers.set(Vec::new());
-                                captured_key.set(None);
-                            } else {
-                                drop(cfg);
+    if KeybindConfig::capture_modifier(&mut mods, &key) {
+        captured_modifiers.set(mods);
+        return;
+    }
 
-                                config.write().keybinds.insert(action, new_keybind);
-                                let _ = config.read().save();
+    // Non-modifier key - this is the main key. Mouse inputs come named already
+    let normalized_key = match MouseInput::from_key_name(&key) {
+        Some(input) => input.key_name().to_string(),
+        None => KeybindConfig::normalize_key_with_code(&key, &code),
+    };
 
-                                error_msg.set(None);
+    if !normalized_key.is_empty() {
+        captured_key.set(Some(normalized_key.clone()));
 
-                                // Re-register all shortcuts
-                                keybind_version.set(keybind_version() + 1);
+        // Save the keybind
+        if let Some((action, index)) = editing_binding() {
+            let stroke = KeybindConfig::new(mods.clone(), normalized_key);
+            let new_keybind = match record_stroke(stroke, chord) {
+                Ok(Some(keybind)) => keybind,
+                result => {
+                    error_msg.set(result.err());
+                    captured_modifiers.set(Vec::new());
+                    captured_key.set(None);
+                    return;
+                }
+            };
 
-                                // Reset state after successful save
-                                editing_action.set(None);
-                                recording_keys.set(false);
-                                captured_modifiers.set(Vec::new());
-                                captured_key.set(None);
-                            }
-                        }
-                        Err(e) => {
-                            error_msg.set(Some(format!(
-                                "Failed to register keybind '{}': {:?}",
-                                shortcut_string, e
-                            )));
+            // Validate the keybind can be parsed, keeping the overlay open if it can't
+            if let Some(reason) = unregistrable_reason(&new_keybind) {
+                error_msg.set(Some(format!("{} ({})", reason, new_keybind.format())));
 
-                            captured_modifiers.set(Vec::new());
-                            captured_key.set(None);
-                        }
-                    }
-                }
+                captured_modifiers.set(Vec::new());
+                captured_key.set(None);
+                return;
+            }
 
+            let ctrl = controller.read();
+
+            // The action's other bindings already fire on the combination
+            let bound_already = ctrl.config().keybinds.get(&action).is_some_and(|bindings| {
+                bindings
+                    .iter()
+                    .enumerate()
+                    .any(|(i, kb)| i != index && kb.same_combination(&new_keybind))
+            });
+            if bound_already {
+                drop(ctrl);
+                error_msg.set(Some(format!(
+                    "{} is already bound to this action",
+                    new_keybind.format()
+                )));
+                captured_modifiers.set(Vec::new());
+                captured_key.set(None);
                 return;
             }
+
+            // Check for the combination being assigned to another action
+            let other = binding_using(&ctrl.config().keybinds, &new_keybind, action);
+            let blocks_typing = new_keybind.blocks_typing() && !ctrl.config().allow_bare_keybinds;
+            drop(ctrl);
+
+            if other.is_some() || blocks_typing {
+                // Recording stops while the choice is made; the shortcuts stay
+                // unregistered until then
+                error_msg.set(None);
+                recording_keys.set(false);
+                captured_modifiers.set(Vec::new());
+                captured_key.set(None);
+                pending.set(Some(PendingKeybind {
+                    action,
+                    index,
+                    keybind: new_keybind,
+                    conflict: other,
+                    blocks_typing,
+                }));
+            } else {
+                let mut ctrl = controller.write();
+                set_binding(&mut ctrl.config_mut().keybinds, action, index, new_keybind);
+                let _ = ctrl.save();
+                drop(ctrl);
+
+                error_msg.set(None);
+
+                // Reset state after a successful save and re-register all shortcuts
+                finish_editing(
+                    editing_binding,
+                    recording_keys,
+                    captured_modifiers,
+                    captured_key,
+                    keybind_version,
+                );
+            }
         }
     }
-    captured_modifiers.set(mods);
 }
 
 /// Component for rendering a single keybind row
 #[component]
 fn KeybindRow(
     action: HotkeyAction,
-    mut config: Signal<AppConfig>,
-    mut editing_action: Signal<Option<HotkeyAction>>,
+    mut controller: Signal<GammaController>,
+    mut editing_binding: Signal<Option<(HotkeyAction, usize)>>,
     mut recording_keys: Signal<bool>,
     mut captured_modifiers: Signal<Vec<String>>,
     mut captured_key: Signal<Option<String>>,
     mut keybind_version: Signal<usize>,
     error_msg: Signal<Option<String>>,
+    mut pending: Signal<Option<PendingKeybind>>,
+    mut chord: Signal<ChordCapture>,
 ) -> Element {
-    let cfg = config.read();
-    let keybind = cfg.keybinds.get(&action).cloned();
-    let action_name = get_action_name(action, &cfg);
-    let keybind_str = keybind
+    let ctrl = controller.read();
+    let bindings = ctrl
+        .config()
+        .keybinds
+        .get(&action)
+        .cloned()
+        .unwrap_or_default();
+    let action_name = get_action_name(action, &ctrl);
+    let editing = editing_binding().filter(|(editing, _)| *editing == action);
+    let is_editing = editing.is_some();
+    let is_conflicting = pending
+        .read()
         .as_ref()
-        .map(|kb| kb.format())
-        .unwrap_or_else(|| "Not set".to_string());
-    let is_editing = editing_action() == Some(action);
+        .is_some_and(|p| p.conflict.is_some_and(|(other, _)| other == action));
 
-    drop(cfg);
+    drop(ctrl);
+
+    // Start recording the binding at `index`, past the last one for an alternate
+    let mut record = move |index: usize, as_chord: bool| {
+        suspend_shortcuts();
+        error_msg.set(None);
+        // Editing another keybind drops a pending choice
+        pending.set(None);
+        chord.set(ChordCapture {
+            enabled: as_chord,
+            first: None,
+        });
+
+        editing_binding.set(Some((action, index)));
+        recording_keys.set(true);
+        captured_modifiers.set(Vec::new());
+        captured_key.set(None);
+    };
+    let alternate = bindings.len();
 
     rsx! {
         tr {
             key: "{action_name}",
+            class: if is_conflicting { "conflict" } else { "" },
             td { "{action_name}" }
             td {
                 if is_editing && recording_keys() {
                     span { class: "recording", "Press keys... (ESC to cancel)" }
+                } else if bindings.is_empty() {
+                    code { "Not set" }
                 } else {
-                    code { "{keybind_str}" }
+                    for (index, keybind) in bindings.into_iter().enumerate() {
+                        span {
+                            key: "{index}",
+                            class: "keybind-binding",
+                            if let Some(reason) = unregistrable_reason(&keybind) {
+                                code {
+                                    class: "invalid",
+                                    title: "{reason}; record it again",
+                                    "{keybind.format()} ⚠"
+                                }
+                            } else {
+                                code { "{keybind.format()}" }
+                            }
+                            if !is_editing {
+                                button {
+                                    class: "delete-btn",
+                                    style: "margin-left: 5px; background: #e74c3c;",
+                                    title: "Clear this binding",
+                                    onclick: move |_| {
+                                        let mut ctrl = controller.write();
+                                        remove_binding(&mut ctrl.config_mut().keybinds, action, index);
+                                        let _ = ctrl.save();
+                                        keybind_version.set(keybind_version() + 1);
+                                    },
+                                    "✕"
+                                }
+                            }
+                        }
+                    }
                 }
             }
             td {
-                if is_editing {
+                if is_editing && pending.read().is_some() {
+                    span { class: "recording", "Waiting for a choice" }
+                } else if is_editing {
                     button {
                         class: "cancel-btn",
                         onclick: move |_| {
-                            editing_action.set(None);
+                            editing_binding.set(None);
                             recording_keys.set(false);
                             captured_modifiers.set(Vec::new());
                             captured_key.set(None);
@@ -189,29 +476,24 @@ fn KeybindRow(
                         "Cancel"
                     }
                 } else {
-                    button {
-                        class: "edit-btn",
-                        onclick: move |_| {
-                            window().remove_all_shortcuts();
-                            error_msg.set(None);
-
-                            editing_action.set(Some(action));
-                            recording_keys.set(true);
-                            captured_modifiers.set(Vec::new());
-                            captured_key.set(None);
-                        },
-                        "Edit"
+                    // Edit and Chord record the main binding
+                    for as_chord in [false, true] {
+                        button {
+                            key: "{as_chord}",
+                            class: "edit-btn",
+                            style: if as_chord { "margin-left: 5px;" } else { "" },
+                            title: if as_chord { "Record two strokes, like Ctrl + Alt + G, then B" } else { "" },
+                            onclick: move |_| record(0, as_chord),
+                            if as_chord { "Chord" } else { "Edit" }
+                        }
                     }
-                    if keybind.is_some() {
+                    if alternate > 0 {
                         button {
-                            class: "delete-btn",
-                            style: "margin-left: 5px; background: #e74c3c;",
-                            onclick: move |_| {
-                                config.write().keybinds.remove(&action);
-                                let _ = config.read().save();
-                                keybind_version.set(keybind_version() + 1);
-                            },
-                            "Clear"
+                            class: "edit-btn",
+                            style: "margin-left: 5px;",
+                            title: "Bind another key combination to this action, keeping the current ones",
+                            onclick: move |_| record(alternate, false),
+                            "Add alternate"
                         }
                     }
                 }
@@ -221,18 +503,231 @@ fn KeybindRow(
 }
 
 #[component]
-pub fn KeybindsTab(mut config: Signal<AppConfig>, mut keybind_version: Signal<usize>) -> Element {
-    let editing_action = use_signal(|| Option::<HotkeyAction>::None);
+pub fn KeybindsTab(
+    mut controller: Signal<GammaController>,
+    mut keybind_version: Signal<usize>,
+) -> Element {
+    let editing_binding = use_signal(|| Option::<(HotkeyAction, usize)>::None);
     let recording_keys = use_signal(|| false);
     let captured_modifiers = use_signal(Vec::<String>::new);
     let captured_key = use_signal(|| Option::<String>::None);
     let error_msg = use_signal(|| Option::<String>::None);
+    let mut pending = use_signal(|| Option::<PendingKeybind>::None);
+    let chord = use_signal(ChordCapture::default);
+    let mut confirm_defaults = use_signal(|| false);
+    let mut pending_import = use_signal(|| Option::<PendingImport>::None);
+    let mut transfer_msg = use_signal(|| Option::<Result<String, String>>::None);
+    let mouse_enabled = cfg!(windows) && !controller.read().config().disable_mouse_bindings;
+
+    let capture_mouse = move |input: MouseInput| {
+        handle_key_capture(
+            input.key_name().to_string(),
+            String::new(),
+            captured_modifiers,
+            captured_key,
+            editing_binding,
+            recording_keys,
+            controller,
+            keybind_version,
+            error_msg,
+            pending,
+            chord,
+        );
+    };
+
+    // Leaving the tab mid-choice cancels it, registering the shortcuts again
+    use_drop(move || {
+        if pending
+            .try_write()
+            .ok()
+            .and_then(|mut c| c.take())
+            .is_some()
+        {
+            if let Ok(mut version) = keybind_version.try_write() {
+                *version += 1;
+            }
+        }
+    });
 
     rsx! {
         div {
             class: "keybinds-tab",
             h2 { "Keyboard shortcuts" }
-            p { class: "info", "Click 'Edit' to change a keybind." }
+            p { class: "info", "Click 'Edit' to change a keybind, or 'Add alternate' to bind another combination to the same action." }
+
+            label {
+                class: "checkbox-label",
+                input {
+                    r#type: "checkbox",
+                    checked: controller.read().config().hotkeys_apply_to_all_monitors,
+                    onchange: move |evt| {
+                        let mut ctrl = controller.write();
+                        ctrl.config_mut().hotkeys_apply_to_all_monitors = evt.checked();
+                        let _ = ctrl.save();
+                    },
+                }
+                "Hotkeys apply to all monitors"
+            }
+            label {
+                class: "checkbox-label",
+                title: "Settings tab sliders always follow the selected monitor",
+                "Hotkeys adjust "
+                select {
+                    disabled: controller.read().config().hotkeys_apply_to_all_monitors,
+                    value: "{controller.read().config().hotkey_target:?}",
+                    onchange: move |evt| {
+                        let value = evt.value();
+                        let Some(target) = HotkeyTarget::ALL.into_iter().find(|t| format!("{t:?}") == value) else {
+                            return;
+                        };
+
+                        let mut ctrl = controller.write();
+                        ctrl.config_mut().hotkey_target = target;
+                        let _ = ctrl.save();
+                    },
+                    for target in HotkeyTarget::ALL {
+                        option { key: "{target:?}", value: "{target:?}", "{target.format()}" }
+                    }
+                }
+            }
+            label {
+                class: "checkbox-label",
+                title: "A letter or digit bound on its own, or with AltGr, can't be typed in any app while Gammar runs",
+                input {
+                    r#type: "checkbox",
+                    checked: !controller.read().config().allow_bare_keybinds,
+                    onchange: move |evt| {
+                        let mut ctrl = controller.write();
+                        ctrl.config_mut().allow_bare_keybinds = !evt.checked();
+                        let _ = ctrl.save();
+                    },
+                }
+                "Ask before binding keys that type text"
+            }
+            if cfg!(windows) {
+                label {
+                    class: "checkbox-label",
+                    title: "Checked every second. Games in exclusive fullscreen and borderless windows covering a monitor count",
+                    input {
+                        r#type: "checkbox",
+                        checked: controller.read().config().pause_hotkeys_in_fullscreen,
+                        onchange: move |evt| {
+                            let mut ctrl = controller.write();
+                            ctrl.config_mut().pause_hotkeys_in_fullscreen = evt.checked();
+                            let _ = ctrl.save();
+                        },
+                    }
+                    "Pause hotkeys while a fullscreen app is focused"
+                }
+                if controller.read().config().pause_hotkeys_in_fullscreen {
+                    label {
+                        class: "checkbox-label",
+                        style: "margin-left: 24px;",
+                        input {
+                            r#type: "checkbox",
+                            checked: controller.read().config().fullscreen_includes_maximized,
+                            onchange: move |evt| {
+                                let mut ctrl = controller.write();
+                                ctrl.config_mut().fullscreen_includes_maximized = evt.checked();
+                                let _ = ctrl.save();
+                            },
+                        }
+                        "Count maximized windows as fullscreen"
+                    }
+                }
+                label {
+                    class: "checkbox-label",
+                    title: "Mouse bindings install a low-level mouse hook, which every mouse event passes through. It's only installed while there are mouse bindings",
+                    input {
+                        r#type: "checkbox",
+                        checked: mouse_enabled,
+                        onchange: move |evt| {
+                            let mut ctrl = controller.write();
+                            ctrl.config_mut().disable_mouse_bindings = !evt.checked();
+                            let _ = ctrl.save();
+                            keybind_version += 1;
+                        },
+                    }
+                    "Allow mouse bindings, e.g. Ctrl + Alt + WheelUp"
+                }
+            }
+
+            if confirm_defaults() {
+                div {
+                    class: "undo-bar",
+                    span { "Replace every keybind, including profile and temperature shortcuts, with the defaults?" }
+                    div {
+                        class: "bulk-actions",
+                        button {
+                            onclick: move |_| {
+                                confirm_defaults.set(false);
+
+                                let mut ctrl = controller.write();
+                                ctrl.config_mut().keybinds = default_keybinds();
+                                let _ = ctrl.save();
+                                keybind_version += 1;
+                            },
+                            "Restore"
+                        }
+                        button { onclick: move |_| confirm_defaults.set(false), "Cancel" }
+                    }
+                }
+            } else {
+                button {
+                    class: "edit-btn",
+                    disabled: recording_keys() || pending.read().is_some(),
+                    onclick: move |_| confirm_defaults.set(true),
+                    "Restore default keybinds"
+                }
+            }
+
+            if let Some(choice) = pending() {
+                div {
+                    class: "undo-bar keybind-conflict",
+                    span { "{choice.describe(&controller.read())}" }
+                    div {
+                        class: "bulk-actions",
+                        button {
+                            onclick: move |_| {
+                                let Some(choice) = pending.take() else {
+                                    return;
+                                };
+
+                                let mut ctrl = controller.write();
+                                let keybinds = &mut ctrl.config_mut().keybinds;
+                                if let Some((other, index)) = choice.conflict {
+                                    remove_binding(keybinds, other, index);
+                                }
+                                set_binding(keybinds, choice.action, choice.index, choice.keybind);
+                                let _ = ctrl.save();
+                                drop(ctrl);
+
+                                finish_editing(
+                                    editing_binding,
+                                    recording_keys,
+                                    captured_modifiers,
+                                    captured_key,
+                                    keybind_version,
+                                );
+                            },
+                            if choice.conflict.is_some() { "Reassign" } else { "Bind anyway" }
+                        }
+                        button {
+                            onclick: move |_| {
+                                pending.set(None);
+                                finish_editing(
+                                    editing_binding,
+                                    recording_keys,
+                                    captured_modifiers,
+                                    captured_key,
+                                    keybind_version,
+                                );
+                            },
+                            "Cancel"
+                        }
+                    }
+                }
+            }
 
             table {
                 class: "keybinds-table",
@@ -253,25 +748,36 @@ pub fn KeybindsTab(mut config: Signal<AppConfig>, mut keybind_version: Signal<us
                             DecreaseBrightness,
                             IncreaseContrast,
                             DecreaseContrast,
+                            IncreaseDimming,
+                            DecreaseDimming,
                             Reset,
+                            ResetGamma,
+                            ResetBrightness,
+                            ResetContrast,
+                            RestorePrevious,
+                            ToggleFilter,
+                            ToggleInvert,
+                            ToggleWindow,
                         ]
                     } {
                         KeybindRow {
                             action,
-                            config,
-                            editing_action,
+                            controller,
+                            editing_binding,
                             recording_keys,
                             captured_modifiers,
                             captured_key,
                             keybind_version,
                             error_msg,
+                            pending,
+                            chord,
                         }
                     }
                 }
             }
 
             // Profile keybinds section
-            if config.read().profile_manager.profile_count() > 0 {
+            if controller.read().config().profile_manager.profile_count() > 0 {
                 h3 { style: "margin-top: 30px;", "Profile Shortcuts" }
                 table {
                     class: "keybinds-table",
@@ -283,16 +789,104 @@ pub fn KeybindsTab(mut config: Signal<AppConfig>, mut keybind_version: Signal<us
                         }
                     }
                     tbody {
-                        for i in 0..config.read().profile_manager.profile_count() {
+                        for action in controller
+                            .read()
+                            .config()
+                            .profile_manager
+                            .get_profiles()
+                            .iter()
+                            .flat_map(|p| [HotkeyAction::LoadProfile(p.id), HotkeyAction::ToggleProfile(p.id)])
+                            .collect::<Vec<_>>()
+                        {
+                            KeybindRow {
+                                key: "{action:?}",
+                                action,
+                                controller,
+                                editing_binding,
+                                recording_keys,
+                                captured_modifiers,
+                                captured_key,
+                                keybind_version,
+                                error_msg,
+                                pending,
+                                chord,
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Monitor keybinds section
+            if controller.read().monitors().len() > 1 {
+                h3 { style: "margin-top: 30px;", "Monitor Shortcuts" }
+                table {
+                    class: "keybinds-table",
+                    thead {
+                        tr {
+                            th { "Monitor" }
+                            th { "Current keybind" }
+                            th { "Actions" }
+                        }
+                    }
+                    tbody {
+                        KeybindRow {
+                            key: "next",
+                            action: HotkeyAction::NextMonitor,
+                            controller,
+                            editing_binding,
+                            recording_keys,
+                            captured_modifiers,
+                            captured_key,
+                            keybind_version,
+                            error_msg,
+                            pending,
+                            chord,
+                        }
+                        for index in 0..controller.read().monitors().len() {
                             KeybindRow {
-                                action: HotkeyAction::LoadProfile(i),
-                                config,
-                                editing_action,
+                                key: "{index}",
+                                action: HotkeyAction::SelectMonitor(index),
+                                controller,
+                                editing_binding,
                                 recording_keys,
                                 captured_modifiers,
                                 captured_key,
                                 keybind_version,
                                 error_msg,
+                                pending,
+                                chord,
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Temperature preset keybinds section
+            if !controller.read().config().temperature_presets.0.is_empty() {
+                h3 { style: "margin-top: 30px;", "Temperature Shortcuts" }
+                table {
+                    class: "keybinds-table",
+                    thead {
+                        tr {
+                            th { "Preset" }
+                            th { "Current keybind" }
+                            th { "Actions" }
+                        }
+                    }
+                    tbody {
+                        for preset in controller.read().config().temperature_presets.0.clone() {
+                            KeybindRow {
+                                key: "{preset.kelvin}",
+                                action: HotkeyAction::ApplyTemperaturePreset(preset.kelvin),
+                                controller,
+                                editing_binding,
+                                recording_keys,
+                                captured_modifiers,
+                                captured_key,
+                                keybind_version,
+                                error_msg,
+                                pending,
+                                chord,
                             }
                         }
                     }
@@ -314,6 +908,33 @@ pub fn KeybindsTab(mut config: Signal<AppConfig>, mut keybind_version: Signal<us
                     onclick: move |evt| {
                         evt.stop_propagation();
                     },
+                    onwheel: move |evt| {
+                        if !mouse_enabled {
+                            return;
+                        }
+
+                        // Negative deltas scroll up
+                        let delta = evt.delta().strip_units().y;
+                        if delta < 0.0 {
+                            capture_mouse(MouseInput::WheelUp);
+                        } else if delta > 0.0 {
+                            capture_mouse(MouseInput::WheelDown);
+                        }
+                    },
+                    onmousedown: move |evt| {
+                        if !mouse_enabled {
+                            return;
+                        }
+
+                        let input = match evt.trigger_button() {
+                            Some(MouseButton::Auxiliary) => MouseInput::MiddleClick,
+                            Some(MouseButton::Fourth) => MouseInput::Mouse4,
+                            Some(MouseButton::Fifth) => MouseInput::Mouse5,
+                            _ => return,
+                        };
+                        evt.prevent_default();
+                        capture_mouse(input);
+                    },
                     onkeydown: move |evt| {
                         evt.prevent_default();
                         let key = evt.key().to_string();
@@ -323,16 +944,30 @@ pub fn KeybindsTab(mut config: Signal<AppConfig>, mut keybind_version: Signal<us
                             code,
                             captured_modifiers,
                             captured_key,
-                            editing_action,
+                            editing_binding,
                             recording_keys,
-                            config,
+                            controller,
                             keybind_version,
                             error_msg,
+                            pending,
+                            chord,
                         );
                     },
 
                     div { class: "key-capture-box",
-                        h3 { "Press your key combination" }
+                        if chord().enabled {
+                            if let Some(first) = chord().first {
+                                h3 { "{first.format()}, then..." }
+                                p { class: "hint-text", "Press the second key on its own" }
+                            } else {
+                                h3 { "Press the chord's first combination" }
+                            }
+                        } else {
+                            h3 { "Press your key combination" }
+                        }
+                        if mouse_enabled && !chord().enabled {
+                            p { class: "hint-text", "Or hold modifiers and scroll, middle-click or use an extra mouse button" }
+                        }
 
                         if let Some(err) = error_msg() {
                             div {
@@ -354,6 +989,138 @@ pub fn KeybindsTab(mut config: Signal<AppConfig>, mut keybind_version: Signal<us
                 }
             }
 
+            if let Some(pending) = pending_import() {
+                div {
+                    class: "undo-bar",
+                    span { "{pending.describe()}. Replace your keybinds with them, or merge them in?" }
+                    div {
+                        class: "bulk-actions",
+                        for replace in [true, false] {
+                            button {
+                                key: "{replace}",
+                                onclick: move |_| {
+                                    let Some(pending) = pending_import.take() else {
+                                        return;
+                                    };
+
+                                    let mut ctrl = controller.write();
+                                    let keybinds = &mut ctrl.config_mut().keybinds;
+                                    let message = if replace {
+                                        let count = pending.keybinds.len();
+                                        *keybinds = pending.keybinds;
+                                        format!("Replaced your keybinds with {} imported ones", count)
+                                    } else {
+                                        let merged = merge_keybinds(keybinds, pending.keybinds);
+                                        format!("Merged {} imported keybinds", merged)
+                                    };
+                                    let _ = ctrl.save();
+                                    keybind_version += 1;
+
+                                    transfer_msg.set(Some(Ok(message)));
+                                },
+                                if replace { "Replace" } else { "Merge" }
+                            }
+                        }
+                        button { onclick: move |_| pending_import.set(None), "Cancel" }
+                    }
+                }
+            }
+
+            div {
+                class: "profile-transfer",
+                button {
+                    class: "identify-button",
+                    disabled: controller.read().config().keybinds.is_empty(),
+                    onclick: move |_| {
+                        spawn(async move {
+                            let Some(file) = rfd::AsyncFileDialog::new()
+                                .set_file_name("gammar-keybinds.json")
+                                .add_filter("Gammar keybinds", &["json"])
+                                .save_file()
+                                .await
+                            else {
+                                return;
+                            };
+
+                            let ctrl = controller.read();
+                            let profiles = &ctrl.config().profile_manager;
+                            let result = save_keybinds(
+                                &ctrl.config().keybinds,
+                                |id| profiles.get_profile_by_id(id).map(|p| p.name.clone()),
+                                file.path(),
+                            );
+                            drop(ctrl);
+                            transfer_msg.set(Some(
+                                result
+                                    .map(|_| format!("Exported to {}", file.path().display()))
+                                    .map_err(|e| e.to_string()),
+                            ));
+                        });
+                    },
+                    "Export keybinds…"
+                }
+                button {
+                    class: "identify-button",
+                    onclick: move |_| {
+                        spawn(async move {
+                            let Some(file) = rfd::AsyncFileDialog::new()
+                                .add_filter("Gammar keybinds", &["json"])
+                                .pick_file()
+                                .await
+                            else {
+                                return;
+                            };
+
+                            // Profile ids differ between installs, so profiles are found by name
+                            let ctrl = controller.read();
+                            let profiles = &ctrl.config().profile_manager;
+                            let loaded = load_keybinds(file.path(), |name| {
+                                profiles.find_by_name(name).map(|(_, p)| p.id)
+                            });
+                            let imported = match loaded {
+                                Ok(imported) => imported,
+                                Err(e) => {
+                                    drop(ctrl);
+                                    transfer_msg.set(Some(Err(e.to_string())));
+                                    return;
+                                }
+                            };
+
+                            let count = imported.keybinds.len();
+                            let keybinds: HashMap<_, _> = imported
+                                .keybinds
+                                .into_iter()
+                                .map(|(action, bindings)| {
+                                    let registrable: Vec<_> = bindings
+                                        .into_iter()
+                                        .filter(|kb| unregistrable_reason(kb).is_none())
+                                        .collect();
+                                    (action, registrable)
+                                })
+                                .filter(|(_, bindings)| !bindings.is_empty())
+                                .collect();
+                            let conflicts = keybind_conflicts(&ctrl.config().keybinds, &keybinds).len();
+                            drop(ctrl);
+
+                            transfer_msg.set(None);
+                            pending_import.set(Some(PendingImport {
+                                skipped: count - keybinds.len(),
+                                missing_profiles: imported.missing_profiles,
+                                keybinds,
+                                conflicts,
+                            }));
+                        });
+                    },
+                    "Import keybinds…"
+                }
+
+                match transfer_msg() {
+                    Some(Ok(message)) => rsx! { span { class: "transfer-status", "{message}" } },
+                    Some(Err(error)) => rsx! { span { class: "range-warning", "{error}" } },
+                    None => rsx! {},
+                }
+            }
+
             p { class: "hint", "Note: Keybinds are captured globally and not propagated to other applications. Avoid using shortcuts that may interfere with other software." }
         }
     }
@@ -1,14 +1,565 @@
 use crate::{
-    profiles::Profile,
-    tabs::settings::find_monitor,
-    windows::display::{apply_display_settings_to_monitor, MonitorInfo},
-    AppConfig,
+    components::slider::Slider,
+    controller::{GammaController, RemovedProfile},
+    presets::{Preset, PRESETS_VERSION},
+    profiles::{self, ImportSummary, Profile, ProfileColor, ProfileSort},
+    windows::display::{
+        find_monitor, DisplaySettings, GammaError, BRIGHTNESS_RANGE, CONTRAST_RANGE, GAMMA_RANGE,
+    },
 };
 use dioxus::prelude::*;
+use std::{
+    collections::{HashMap, HashSet},
+    ops::RangeInclusive,
+    time::Duration,
+};
+
+/// How long the pointer must rest on a profile before it's previewed, so skimming the list
+/// doesn't flash each one onto the screen.
+const HOVER_DELAY: Duration = Duration::from_millis(400);
+
+/// How long a hover preview lasts before the previous settings come back on their own.
+const HOVER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long a deleted profile can be brought back.
+const UNDO_WINDOW: Duration = Duration::from_secs(10);
+
+/// A numeric input for one setting of the profile editor.
+#[component]
+fn NumberField(
+    label: String,
+    value: f32,
+    range: RangeInclusive<f32>,
+    on_change: EventHandler<f32>,
+) -> Element {
+    rsx! {
+        label {
+            class: "profile-field",
+            "{label}"
+            input {
+                r#type: "number",
+                min: "{range.start()}",
+                max: "{range.end()}",
+                step: "0.01",
+                value: "{value}",
+                oninput: move |evt| {
+                    if let Ok(value) = evt.value().parse::<f32>() {
+                        on_change.call(value);
+                    }
+                },
+            }
+        }
+    }
+}
 
+/// Edits a saved profile's description, marker, gamma, brightness and contrast in place. With live
+/// preview on, the edited values are applied while the editor is open and the previous settings
+/// come back on cancel.
 #[component]
-pub fn ProfilesTab(mut config: Signal<AppConfig>, monitors: Signal<Vec<MonitorInfo>>) -> Element {
+fn ProfileEditor(
+    mut controller: Signal<GammaController>,
+    profile_id: u64,
+    settings: DisplaySettings,
+    description: String,
+    color: Option<ProfileColor>,
+    icon: String,
+    on_close: EventHandler<()>,
+) -> Element {
+    let mut draft = use_signal(|| settings);
+    let mut draft_description = use_signal(|| description);
+    let mut draft_color = use_signal(|| color);
+    let mut draft_icon = use_signal(|| icon);
+    // The live settings to restore, while previewing
+    let mut preview = use_signal(|| Option::<DisplaySettings>::None);
+    let mut error_msg = use_signal(|| Option::<String>::None);
+
+    let mut edit = move |settings: DisplaySettings| {
+        draft.set(settings);
+
+        if preview().is_some() && settings.validate().is_ok() {
+            let _ = controller.write().set_current_settings(settings);
+        }
+    };
+
+    // Also run on drop, e.g. when switching tabs mid-edit, so it mustn't panic on a dropped signal
+    let mut stop_preview = move || {
+        if let Some(original) = preview.try_write().ok().and_then(|mut p| p.take()) {
+            let _ = controller.write().set_current_settings(original);
+        }
+    };
+
+    use_drop(stop_preview);
+
+    let current = draft();
+    let invalid = current.validate().err().map(|e| e.to_string());
+
+    rsx! {
+        div {
+            class: "profile-editor",
+
+            textarea {
+                class: "profile-description-input",
+                rows: "2",
+                placeholder: "Description",
+                value: "{draft_description}",
+                oninput: move |evt| draft_description.set(evt.value()),
+            }
+
+            div {
+                class: "profile-marker",
+                input {
+                    r#type: "text",
+                    class: "profile-icon-input",
+                    placeholder: "Icon",
+                    title: "An emoji or symbol shown before the name",
+                    maxlength: "8",
+                    value: "{draft_icon}",
+                    oninput: move |evt| draft_icon.set(evt.value()),
+                }
+                button {
+                    class: if draft_color().is_none() { "color-swatch none selected" } else { "color-swatch none" },
+                    title: "No color",
+                    onclick: move |_| draft_color.set(None),
+                }
+                for color in ProfileColor::ALL {
+                    button {
+                        key: "{color:?}",
+                        class: if draft_color() == Some(color) { "color-swatch selected" } else { "color-swatch" },
+                        style: "background: {color.css()};",
+                        title: "{color.format()}",
+                        onclick: move |_| draft_color.set(Some(color)),
+                    }
+                }
+            }
+
+            div {
+                class: "profile-fields",
+                NumberField {
+                    label: "Gamma",
+                    value: current.gamma,
+                    range: GAMMA_RANGE,
+                    on_change: move |gamma| edit(DisplaySettings { gamma, ..draft() }),
+                }
+                NumberField {
+                    label: "Brightness",
+                    value: current.brightness,
+                    range: BRIGHTNESS_RANGE,
+                    on_change: move |brightness| edit(DisplaySettings { brightness, ..draft() }),
+                }
+                NumberField {
+                    label: "Contrast",
+                    value: current.contrast,
+                    range: CONTRAST_RANGE,
+                    on_change: move |contrast| edit(DisplaySettings { contrast, ..draft() }),
+                }
+            }
+
+            if let Some(error) = invalid.clone().or(error_msg()) {
+                p { class: "range-warning", "{error}" }
+            }
+
+            label {
+                class: "checkbox-label",
+                input {
+                    r#type: "checkbox",
+                    checked: preview().is_some(),
+                    onchange: move |evt| {
+                        if evt.checked() {
+                            preview.set(Some(controller.peek().config().current_settings));
+                            edit(draft());
+                        } else {
+                            stop_preview();
+                        }
+                    },
+                }
+                "Live preview"
+            }
+
+            div {
+                class: "profile-actions",
+                button {
+                    disabled: invalid.is_some(),
+                    onclick: move |_| {
+                        let mut ctrl = controller.write();
+
+                        let description = draft_description().trim().to_string();
+                        let result = ctrl
+                            .update_profile_settings(profile_id, draft())
+                            .and_then(|_| ctrl.set_profile_description(profile_id, description))
+                            .and_then(|_| {
+                                ctrl.set_profile_marker(profile_id, draft_color(), draft_icon().trim().to_string())
+                            });
+
+                        match result {
+                            Ok(_) => {
+                                let _ = ctrl.save();
+                                // The previewed values are the profile's now, so they stay
+                                preview.set(None);
+                                on_close.call(());
+                            }
+                            Err(e) => error_msg.set(Some(e.to_string())),
+                        }
+                    },
+                    "Save"
+                }
+                button {
+                    class: "delete",
+                    onclick: move |_| {
+                        stop_preview();
+                        on_close.call(());
+                    },
+                    "Cancel"
+                }
+            }
+        }
+    }
+}
+
+/// Mixes two saved profiles, applying the mix live as the slider moves so an in-between look can
+/// be dialed in and saved.
+#[component]
+fn ProfileBlend(mut controller: Signal<GammaController>) -> Element {
+    let from = use_signal(|| Option::<u64>::None);
+    let to = use_signal(|| Option::<u64>::None);
+    let mut percent = use_signal(|| 50.0_f32);
+    let mut blend_name = use_signal(String::new);
+    let mut error_msg = use_signal(|| Option::<String>::None);
+
+    let profiles: Vec<(u64, String, DisplaySettings)> = controller
+        .read()
+        .config()
+        .profile_manager
+        .get_profiles()
+        .iter()
+        .map(|p| (p.id, p.name.clone(), p.settings))
+        .collect();
+    let settings_of = |id: Option<u64>| {
+        profiles
+            .iter()
+            .find(|(p, _, _)| Some(*p) == id)
+            .map(|(_, _, settings)| *settings)
+    };
+    let ends = settings_of(from()).zip(settings_of(to()));
+    let blend = ends.map(|(a, b)| DisplaySettings::lerp(a, b, percent() / 100.0));
+
+    let mut apply = move |settings: DisplaySettings| {
+        let mut ctrl = controller.write();
+        match ctrl.set_current_settings(settings) {
+            Ok(_) => {
+                error_msg.set(None);
+                ctrl.save_soon();
+            }
+            Err(e) => error_msg.set(Some(e.to_string())),
+        }
+    };
+
+    rsx! {
+        div {
+            class: "new-profile profile-blend",
+            h3 { "Blend two profiles" }
+            div {
+                class: "blend-profiles",
+                for (label , mut side) in [("From", from), ("To", to)] {
+                    label {
+                        key: "{label}",
+                        "{label} "
+                        select {
+                            value: side().map(|id| id.to_string()).unwrap_or_default(),
+                            onchange: move |evt| side.set(evt.value().parse::<u64>().ok()),
+                            option { value: "", disabled: true, "Pick a profile" }
+                            for (id, name, _) in profiles.iter() {
+                                option { key: "{id}", value: "{id}", "{name}" }
+                            }
+                        }
+                    }
+                }
+            }
+            if let (Some((a, b)), Some(settings)) = (ends, blend) {
+                Slider {
+                    label: "Mix (%)".to_string(),
+                    value: percent(),
+                    min: 0.0,
+                    max: 100.0,
+                    step: 1.0,
+                    precision: 0,
+                    on_change: move |value: f32| {
+                        percent.set(value);
+                        apply(DisplaySettings::lerp(a, b, value / 100.0));
+                    },
+                }
+                p {
+                    class: "monitor-details",
+                    "Gamma {settings.gamma:.2}, brightness {settings.brightness:.2}, contrast {settings.contrast:.2}"
+                }
+                if let Some(error) = error_msg() {
+                    p { class: "range-warning", "{error}" }
+                }
+                input {
+                    r#type: "text",
+                    placeholder: "Blend name",
+                    value: "{blend_name}",
+                    oninput: move |evt| blend_name.set(evt.value()),
+                }
+                button {
+                    disabled: blend_name().trim().is_empty(),
+                    onclick: move |_| {
+                        let mut ctrl = controller.write();
+                        let profile = Profile::new(blend_name().trim().to_string(), settings);
+                        match ctrl.config_mut().profile_manager.add_profile_unique(profile) {
+                            Ok(_) => {
+                                let _ = ctrl.save();
+                                blend_name.set(String::new());
+                                error_msg.set(None);
+                            }
+                            Err(e) => error_msg.set(Some(e.to_string())),
+                        }
+                    },
+                    "Save blend as profile"
+                }
+            }
+        }
+    }
+}
+
+/// Move a profile, closing the editor as it refers to the profile by index.
+fn move_profile(
+    mut controller: Signal<GammaController>,
+    mut editing: Signal<Option<usize>>,
+    id: u64,
+    to: usize,
+) {
+    let mut ctrl = controller.write();
+
+    if ctrl.move_profile(id, to).is_ok() {
+        let _ = ctrl.save();
+        editing.set(None);
+    }
+}
+
+/// The monitor a profile is bound to, picked from the connected ones.
+#[component]
+fn ProfileMonitor(
+    mut controller: Signal<GammaController>,
+    profile_id: u64,
+    monitor_id: Option<String>,
+) -> Element {
+    let monitors = controller.read().monitors().to_vec();
+    let disconnected = monitor_id
+        .as_ref()
+        .filter(|id| find_monitor(&monitors, Some(id.as_str())).is_none());
+
+    rsx! {
+        select {
+            class: "monitor-select profile-monitor",
+            title: "The monitor this profile is applied to",
+            value: monitor_id.clone().unwrap_or_default(),
+            onchange: move |evt| {
+                let value = evt.value();
+                let mut ctrl = controller.write();
+
+                if ctrl.set_profile_monitor(profile_id, Some(value).filter(|id| !id.is_empty())).is_ok() {
+                    let _ = ctrl.save();
+                }
+            },
+
+            option { value: "", "Selected monitor" }
+            for monitor in monitors.iter() {
+                option { key: "{monitor.id}", value: "{monitor.id}", "{monitor.name}" }
+            }
+            if let Some(id) = disconnected {
+                option { value: "{id}", "Disconnected monitor" }
+            }
+        }
+    }
+}
+
+/// Describe how updating a profile changes its settings, e.g. `Gamma 1.00 → 0.90, ...`.
+fn describe_update(old: DisplaySettings, new: DisplaySettings) -> String {
+    let mut changes = vec![
+        format!("Gamma {:.2} → {:.2}", old.gamma, new.gamma),
+        format!("Brightness {:.2} → {:.2}", old.brightness, new.brightness),
+        format!("Contrast {:.2} → {:.2}", old.contrast, new.contrast),
+    ];
+
+    let others = DisplaySettings {
+        gamma: old.gamma,
+        brightness: old.brightness,
+        contrast: old.contrast,
+        ..new
+    };
+    if others != old {
+        changes.push("and other settings".to_string());
+    }
+
+    changes.join(", ")
+}
+
+/// Describe an import, e.g. `Imported 2 profiles (1 renamed, 1 duplicate skipped)`.
+fn describe_import(summary: ImportSummary) -> String {
+    let plural = |count: usize, word: &str| match count {
+        1 => format!("1 {}", word),
+        _ => format!("{} {}s", count, word),
+    };
+
+    let mut details = Vec::new();
+    if summary.renamed > 0 {
+        details.push(format!("{} renamed", summary.renamed));
+    }
+    if summary.skipped > 0 {
+        details.push(format!("{} skipped", plural(summary.skipped, "duplicate")));
+    }
+
+    let added = format!("Imported {}", plural(summary.added, "profile"));
+    if details.is_empty() {
+        added
+    } else {
+        format!("{} ({})", added, details.join(", "))
+    }
+}
+
+/// The settings from before a hover preview, and the monitor they were on. Previews on the
+/// selected monitor have no monitor, as they replace the current settings.
+#[derive(Clone, PartialEq)]
+struct HoverSnapshot {
+    monitor_id: Option<String>,
+    settings: DisplaySettings,
+}
+
+/// Restore the settings from before a hover preview, if one is showing. Also runs on drop, so it
+/// mustn't panic on a dropped signal.
+fn end_hover_preview(
+    mut controller: Signal<GammaController>,
+    mut snapshot: Signal<Option<HoverSnapshot>>,
+) {
+    let Some(original) = snapshot.try_write().ok().and_then(|mut s| s.take()) else {
+        return;
+    };
+    let mut ctrl = controller.write();
+
+    let _ = match original.monitor_id {
+        Some(monitor_id) => ctrl.preview_settings_on(&monitor_id, original.settings),
+        None => ctrl.preview_settings(original.settings),
+    };
+}
+
+#[component]
+pub fn ProfilesTab(
+    mut controller: Signal<GammaController>,
+    mut keybind_version: Signal<usize>,
+) -> Element {
     let mut new_profile_name = use_signal(String::new);
+    let mut editing = use_signal(|| Option::<usize>::None);
+    let mut transfer_msg = use_signal(|| Option::<Result<String, String>>::None);
+    let mut profile_code = use_signal(String::new);
+    // The monitor each profile's Apply button targets, by profile id, where one was picked
+    let mut apply_targets = use_signal(HashMap::<u64, String>::new);
+    let mut apply_error = use_signal(|| Option::<String>::None);
+    // The settings from before the hovered profile was previewed, while it is
+    let mut hover_snapshot = use_signal(|| Option::<HoverSnapshot>::None);
+    // Bumped whenever the pointer enters or leaves a profile, cancelling pending previews
+    let mut hover_generation = use_signal(|| 0_u64);
+
+    // The id of the profile whose Delete button asks for confirmation
+    let mut confirm_delete = use_signal(|| Option::<u64>::None);
+    // The id of the profile whose Update button asks for confirmation
+    let mut confirm_update = use_signal(|| Option::<u64>::None);
+    // The last deleted profile, while it can still be brought back
+    let mut deleted = use_signal(|| Option::<RemovedProfile>::None);
+    let mut deleted_generation = use_signal(|| 0_u64);
+    // The ids of the profiles ticked for deleting together
+    let mut selected = use_signal(HashSet::<u64>::new);
+    let mut confirm_bulk_delete = use_signal(|| false);
+    // The id of the locked profile whose unlock button asks for confirmation
+    let mut confirm_unlock = use_signal(|| Option::<u64>::None);
+
+    use_drop(move || {
+        end_hover_preview(controller, hover_snapshot);
+
+        // Presets listed on this visit aren't new any more
+        if let Ok(mut ctrl) = controller.try_write() {
+            if ctrl.config().presets_seen < PRESETS_VERSION {
+                ctrl.config_mut().presets_seen = PRESETS_VERSION;
+                ctrl.save_soon();
+            }
+        }
+    });
+    let presets_seen = controller.read().config().presets_seen;
+
+    let name_error = {
+        let name = new_profile_name();
+        let ctrl = controller.read();
+
+        ctrl.config()
+            .profile_manager
+            .find_by_name(name.trim())
+            .map(|(_, p)| {
+                GammaError::DuplicateProfileName {
+                    name: p.name.clone(),
+                }
+                .to_string()
+            })
+    };
+
+    let mut start_hover = move |id: u64| {
+        hover_generation += 1;
+        let generation = hover_generation();
+
+        if controller.read().config().disable_hover_preview || editing().is_some() {
+            return;
+        }
+
+        spawn(async move {
+            tokio::time::sleep(HOVER_DELAY).await;
+
+            let profile = controller
+                .peek()
+                .config()
+                .profile_manager
+                .get_profile_by_id(id)
+                .cloned();
+            let Some(profile) = profile.filter(|_| hover_generation() == generation) else {
+                return;
+            };
+            // Bound profiles preview on their own monitor, as they'd apply there
+            let monitor_id = controller.peek().profile_monitor(&profile).map(|m| m.id);
+
+            if hover_snapshot
+                .peek()
+                .as_ref()
+                .is_some_and(|s| s.monitor_id != monitor_id)
+            {
+                end_hover_preview(controller, hover_snapshot);
+            }
+            if hover_snapshot().is_none() {
+                let settings = match &monitor_id {
+                    Some(monitor_id) => controller.peek().monitor_settings(monitor_id),
+                    None => controller.peek().config().current_settings,
+                };
+                hover_snapshot.set(Some(HoverSnapshot {
+                    monitor_id: monitor_id.clone(),
+                    settings,
+                }));
+            }
+
+            let _ = match &monitor_id {
+                Some(monitor_id) => controller
+                    .write()
+                    .preview_settings_on(monitor_id, profile.settings),
+                None => controller.write().preview_settings(profile.settings),
+            };
+
+            tokio::time::sleep(HOVER_TIMEOUT).await;
+
+            if hover_generation() == generation {
+                end_hover_preview(controller, hover_snapshot);
+            }
+        });
+    };
+
+    let mut end_hover = move || {
+        hover_generation += 1;
+        end_hover_preview(controller, hover_snapshot);
+    };
 
     rsx! {
         div {
@@ -24,68 +575,492 @@ pub fn ProfilesTab(mut config: Signal<AppConfig>, monitors: Signal<Vec<MonitorIn
                     value: "{new_profile_name}",
                     oninput: move |evt| new_profile_name.set(evt.value())
                 }
+                if let Some(error) = name_error.clone() {
+                    p { class: "range-warning", "{error}" }
+                }
                 button {
+                    disabled: name_error.is_some(),
                     onclick: move |_| {
-                        let name = new_profile_name();
+                        let name = new_profile_name().trim().to_string();
                         if !name.is_empty() {
-                            let current_settings = config.read().current_settings;
-                            let profile = Profile::new(name, current_settings);
-                            config.write().profile_manager.add_profile(profile);
-                            let _ = config.read().save();
-                            new_profile_name.set(String::new());
+                            let mut ctrl = controller.write();
+                            let profile = Profile::new(name, ctrl.config().current_settings);
+                            if ctrl.config_mut().profile_manager.add_profile_unique(profile).is_ok() {
+                                let _ = ctrl.save();
+                                new_profile_name.set(String::new());
+                            }
                         }
                     },
                     "Save current settings as profile"
                 }
+                select {
+                    class: "preset-select",
+                    title: "Add a ready-made profile to start from",
+                    value: "",
+                    onchange: move |evt| {
+                        let value = evt.value();
+                        let Some(preset) = Preset::ALL.into_iter().find(|p| format!("{p:?}") == value) else {
+                            return;
+                        };
+
+                        let mut ctrl = controller.write();
+                        ctrl.config_mut().profile_manager.add_numbered(preset.profile());
+                        ctrl.config_mut().presets_seen = PRESETS_VERSION;
+                        let _ = ctrl.save();
+                    },
+                    option { value: "", disabled: true, "Add preset…" }
+                    for preset in Preset::ALL {
+                        option {
+                            key: "{preset:?}",
+                            value: "{preset:?}",
+                            title: "{preset.description()}",
+                            if preset.added_in() > presets_seen {
+                                "{preset.name()} (new)"
+                            } else {
+                                "{preset.name()}"
+                            }
+                        }
+                    }
+                }
+            }
+
+            if controller.read().config().profile_manager.get_profiles().len() >= 2 {
+                ProfileBlend { controller }
             }
 
             h3 { "Saved profiles" }
+            if let Some(error) = apply_error() {
+                p { class: "range-warning", "{error}" }
+            }
+            if let Some(removed) = deleted() {
+                div {
+                    class: "undo-bar",
+                    span { "Deleted {removed.profile.name}" }
+                    button {
+                        onclick: move |_| {
+                            let Some(removed) = deleted.take() else {
+                                return;
+                            };
+
+                            let mut ctrl = controller.write();
+                            ctrl.restore_profile(removed);
+                            let _ = ctrl.save();
+                            keybind_version += 1;
+                            editing.set(None);
+                        },
+                        "Undo"
+                    }
+                }
+            }
+            if !selected.read().is_empty() {
+                div {
+                    class: "undo-bar",
+                    if confirm_bulk_delete() {
+                        span { "Really delete {selected.read().len()} selected profiles?" }
+                        div {
+                            class: "bulk-actions",
+                            button {
+                                onclick: move |_| {
+                                    let ids = selected.take();
+                                    confirm_bulk_delete.set(false);
+                                    confirm_delete.set(None);
+                                    confirm_update.set(None);
+                                    editing.set(None);
+                                    // The undo bar goes too, as indices have moved under it
+                                    deleted.set(None);
+
+                                    let mut ctrl = controller.write();
+                                    ctrl.remove_profiles(&ids);
+                                    let _ = ctrl.save();
+                                    keybind_version += 1;
+                                },
+                                "Delete"
+                            }
+                            button { onclick: move |_| confirm_bulk_delete.set(false), "Cancel" }
+                        }
+                    } else {
+                        span { "{selected.read().len()} selected" }
+                        div {
+                            class: "bulk-actions",
+                            button { onclick: move |_| confirm_bulk_delete.set(true), "Delete selected" }
+                            button {
+                                onclick: move |_| {
+                                    selected.write().clear();
+                                },
+                                "Clear selection"
+                            }
+                        }
+                    }
+                }
+            }
+            label {
+                class: "checkbox-label",
+                input {
+                    r#type: "checkbox",
+                    checked: !controller.read().config().disable_hover_preview,
+                    onchange: move |evt| {
+                        end_hover();
+
+                        let mut ctrl = controller.write();
+                        ctrl.config_mut().disable_hover_preview = !evt.checked();
+                        let _ = ctrl.save();
+                    },
+                }
+                "Preview profiles while hovering over them"
+            }
+            label {
+                class: "profile-sort",
+                "Sort by "
+                select {
+                    value: "{controller.read().config().profile_sort:?}",
+                    onchange: move |evt| {
+                        let value = evt.value();
+                        let Some(sort) = ProfileSort::ALL.into_iter().find(|s| format!("{s:?}") == value) else {
+                            return;
+                        };
+
+                        let mut ctrl = controller.write();
+                        ctrl.config_mut().profile_sort = sort;
+                        let _ = ctrl.save();
+                    },
+                    for sort in ProfileSort::ALL {
+                        option { key: "{sort:?}", value: "{sort:?}", "{sort.format()}" }
+                    }
+                }
+            }
+            if let Some(previous) = controller.read().config().previous_settings {
+                div {
+                    class: "profile-item previous-settings",
+                    div {
+                        class: "profile-info",
+                        h4 { "Previous settings" }
+                        p { class: "profile-description", "The settings from before the last profile or reset was applied" }
+                        p { "Gamma: {previous.gamma:.2}, Brightness: {previous.brightness:.2}, Contrast: {previous.contrast:.2}" }
+                    }
+                    div {
+                        class: "profile-actions",
+                        button {
+                            onclick: move |_| {
+                                end_hover();
+
+                                let mut ctrl = controller.write();
+                                let _ = ctrl.restore_previous();
+                                ctrl.save_soon();
+                            },
+                            "Restore"
+                        }
+                    }
+                }
+            }
             div {
                 class: "profiles-list",
                 {
-                    let profiles = config.read().profile_manager.get_profiles().to_vec();
+                    let sort = controller.read().config().profile_sort;
+                    // Sorting only changes the order shown, so each row keeps its profile's index
+                    let profiles: Vec<(usize, Profile)> = controller
+                        .read()
+                        .config()
+                        .profile_manager
+                        .sorted(sort)
+                        .into_iter()
+                        .map(|(index, profile)| (index, profile.clone()))
+                        .collect();
+                    let count = profiles.len();
+                    let monitors = controller.read().monitors().to_vec();
+                    // A hover preview stands in for the live settings, which come back once it ends
+                    let live = hover_snapshot()
+                        .filter(|s| s.monitor_id.is_none())
+                        .map(|s| s.settings)
+                        .unwrap_or(controller.read().config().current_settings);
 
                     if profiles.is_empty() {
                         rsx! { p { class: "empty", "No profiles yet. Create one above!" } }
                     } else {
                         rsx! {
-                            for (index , profile) in profiles.iter().enumerate() {
-                                {
-                                    let profile_settings = profile.settings;
-                                    rsx! {
-                                        div {
-                                            key: "{index}",
-                                            class: "profile-item",
-                                            div {
-                                                class: "profile-info",
-                                                h4 { "{profile.name}" }
-                                                p { "Gamma: {profile.settings.gamma:.2}, Brightness: {profile.settings.brightness:.2}, Contrast: {profile.settings.contrast:.2}" }
+                            for (index , profile) in profiles {
+                                div {
+                                    key: "{profile.id}",
+                                    class: "profile-item",
+                                    onmouseenter: move |_| start_hover(profile.id),
+                                    onmouseleave: move |_| end_hover(),
+                                    input {
+                                        r#type: "checkbox",
+                                        class: "profile-select",
+                                        title: if profile.locked { "Locked profiles can't be deleted" } else { "Select for deleting together" },
+                                        disabled: profile.locked,
+                                        checked: selected.read().contains(&profile.id),
+                                        onchange: move |evt| {
+                                            confirm_bulk_delete.set(false);
+
+                                            if evt.checked() {
+                                                selected.write().insert(profile.id);
+                                            } else {
+                                                selected.write().remove(&profile.id);
+                                            }
+                                        },
+                                    }
+                                    div {
+                                        class: "profile-info",
+                                        h4 {
+                                            title: "{profile.description}",
+                                            if let Some(color) = profile.color {
+                                                span { class: "profile-dot", style: "background: {color.css()};" }
                                             }
+                                            if !profile.icon.is_empty() {
+                                                span { class: "profile-icon", "{profile.icon}" }
+                                            }
+                                            "{profile.name}"
+                                        }
+                                        if !profile.description.is_empty() && editing() != Some(index) {
+                                            p { class: "profile-description", "{profile.description}" }
+                                        }
+                                        p { "Gamma: {profile.settings.gamma:.2}, Brightness: {profile.settings.brightness:.2}, Contrast: {profile.settings.contrast:.2}" }
+
+                                        if confirm_update() == Some(profile.id) {
                                             div {
-                                                class: "profile-actions",
+                                                class: "profile-update",
+                                                span { "Replace with the current settings? {describe_update(profile.settings, live)}" }
                                                 button {
                                                     onclick: move |_| {
-                                                        config.write().current_settings = profile_settings;
+                                                        let Some(id) = confirm_update.take() else {
+                                                            return;
+                                                        };
 
-                                                        let monitors_list = monitors();
-                                                        let selected_id = config.read().selected_monitor_id.clone();
-
-                                                        if let Some(monitor) = find_monitor(&monitors_list, Some(selected_id.as_str())) {
-                                                            let _ = apply_display_settings_to_monitor(profile_settings, &monitor);
+                                                        let mut ctrl = controller.write();
+                                                        if ctrl.update_profile_settings(id, live).is_ok() {
+                                                            let _ = ctrl.save();
                                                         }
-
-                                                        let _ = config.read().save();
                                                     },
-                                                    "Apply"
+                                                    "Update"
                                                 }
-                                                button {
-                                                    class: "delete",
-                                                    onclick: move |_| {
-                                                        config.write().profile_manager.remove_profile(index);
-                                                        let _ = config.read().save();
-                                                    },
-                                                    "Delete"
+                                                button { onclick: move |_| confirm_update.set(None), "Cancel" }
+                                            }
+                                        }
+
+                                        ProfileMonitor {
+                                            controller,
+                                            profile_id: profile.id,
+                                            monitor_id: profile.monitor_id.clone(),
+                                        }
+
+                                        if editing() == Some(index) {
+                                            ProfileEditor {
+                                                controller,
+                                                profile_id: profile.id,
+                                                settings: profile.settings,
+                                                description: profile.description.clone(),
+                                                color: profile.color,
+                                                icon: profile.icon.clone(),
+                                                on_close: move |_| editing.set(None),
+                                            }
+                                        }
+                                    }
+                                    div {
+                                        class: "profile-actions",
+                                        button {
+                                            class: if profile.pinned { "pin pinned" } else { "pin" },
+                                            title: if profile.pinned { "Unpin" } else { "Pin to the top" },
+                                            onclick: move |_| {
+                                                let mut ctrl = controller.write();
+                                                if ctrl.set_profile_pinned(profile.id, !profile.pinned).is_ok() {
+                                                    let _ = ctrl.save();
+                                                }
+                                            },
+                                            if profile.pinned { "★" } else { "☆" }
+                                        }
+                                        if confirm_unlock() == Some(profile.id) {
+                                            span { class: "confirm-delete", "Unlock?" }
+                                            button {
+                                                onclick: move |_| {
+                                                    let Some(id) = confirm_unlock.take() else {
+                                                        return;
+                                                    };
+
+                                                    let mut ctrl = controller.write();
+                                                    if ctrl.set_profile_locked(id, false).is_ok() {
+                                                        let _ = ctrl.save();
+                                                    }
+                                                },
+                                                "Yes"
+                                            }
+                                            button { onclick: move |_| confirm_unlock.set(None), "No" }
+                                        } else if profile.locked {
+                                            button {
+                                                class: "pin",
+                                                title: "Locked against changes and deletion. Click to unlock",
+                                                onclick: move |_| confirm_unlock.set(Some(profile.id)),
+                                                "🔒"
+                                            }
+                                        } else {
+                                            button {
+                                                class: "pin",
+                                                title: "Lock against changes and deletion",
+                                                onclick: move |_| {
+                                                    let mut ctrl = controller.write();
+                                                    if ctrl.set_profile_locked(profile.id, true).is_ok() {
+                                                        let _ = ctrl.save();
+                                                    }
+
+                                                    selected.write().remove(&profile.id);
+                                                    confirm_delete.set(None);
+                                                    confirm_update.set(None);
+                                                    if editing() == Some(index) {
+                                                        editing.set(None);
+                                                    }
+                                                },
+                                                "🔓"
+                                            }
+                                        }
+                                        if sort == ProfileSort::Manual {
+                                            button {
+                                                class: "move",
+                                                title: "Move up",
+                                                disabled: index == 0,
+                                                onclick: move |_| {
+                                                    move_profile(controller, editing, profile.id, index.saturating_sub(1));
+                                                },
+                                                "↑"
+                                            }
+                                            button {
+                                                class: "move",
+                                                title: "Move down",
+                                                disabled: index + 1 == count,
+                                                onclick: move |_| {
+                                                    move_profile(controller, editing, profile.id, index + 1);
+                                                },
+                                                "↓"
+                                            }
+                                        }
+                                        if editing() != Some(index) && !profile.locked {
+                                            button {
+                                                onclick: move |_| {
+                                                    end_hover();
+                                                    editing.set(Some(index));
+                                                },
+                                                "Edit"
+                                            }
+                                        }
+                                        button {
+                                            title: "Copy a code others can import this profile from",
+                                            onclick: {
+                                                let code = profiles::to_code(&profile);
+                                                let name = profile.name.clone();
+                                                move |_| {
+                                                    let code = serde_json::to_string(&code).unwrap_or_default();
+                                                    document::eval(&format!("navigator.clipboard.writeText({});", code));
+                                                    transfer_msg.set(Some(Ok(format!("Copied the code for {}", name))));
                                                 }
+                                            },
+                                            "Copy code"
+                                        }
+                                        if !profile.locked {
+                                            button {
+                                                title: "Replace this profile's settings with the current ones",
+                                                onclick: move |_| confirm_update.set(Some(profile.id)),
+                                                "Update"
+                                            }
+                                        }
+                                        button {
+                                            onclick: move |_| {
+                                                hover_generation += 1;
+                                                let target = apply_targets.read().get(&profile.id).cloned();
+
+                                                // Applying keeps the previewed settings, so the
+                                                // snapshot is only restored when the profile goes
+                                                // to another monitor than the preview was on
+                                                let applied_on = match &target {
+                                                    Some(monitor_id) => {
+                                                        let selected = controller.peek().selected_monitor().map(|m| m.id);
+                                                        Some(monitor_id.clone()).filter(|id| Some(id) != selected.as_ref())
+                                                    }
+                                                    None => {
+                                                        let ctrl = controller.peek();
+                                                        let profile = ctrl.config().profile_manager.get_profile_by_id(profile.id);
+                                                        profile.and_then(|p| ctrl.profile_monitor(p)).map(|m| m.id)
+                                                    }
+                                                };
+                                                if hover_snapshot.peek().as_ref().is_some_and(|s| s.monitor_id != applied_on) {
+                                                    end_hover_preview(controller, hover_snapshot);
+                                                }
+                                                let snapshot = hover_snapshot.take();
+
+                                                let mut ctrl = controller.write();
+                                                let result = match target {
+                                                    Some(monitor_id) => ctrl.apply_profile_to(profile.id, &monitor_id),
+                                                    None => ctrl.apply_profile(profile.id),
+                                                };
+
+                                                match result {
+                                                    Ok(_) => {
+                                                        if let Some(snapshot) = snapshot.filter(|s| s.monitor_id.is_none()) {
+                                                            // The preview stood in for the settings before it
+                                                            ctrl.config_mut().previous_settings = Some(snapshot.settings);
+                                                        }
+                                                        apply_error.set(None);
+                                                        ctrl.save_soon();
+                                                    }
+                                                    Err(e) => apply_error.set(Some(e.to_string())),
+                                                }
+                                            },
+                                            "Apply"
+                                        }
+                                        select {
+                                            class: "monitor-select apply-target",
+                                            title: "The monitor Apply targets",
+                                            value: apply_targets.read().get(&profile.id).cloned().unwrap_or_default(),
+                                            onchange: move |evt| {
+                                                let value = evt.value();
+                                                if value.is_empty() {
+                                                    apply_targets.write().remove(&profile.id);
+                                                } else {
+                                                    apply_targets.write().insert(profile.id, value);
+                                                }
+                                            },
+                                            option {
+                                                value: "",
+                                                if profile.monitor_id.is_some() { "Its own monitor" } else { "Selected monitor" }
+                                            }
+                                            for monitor in monitors.iter() {
+                                                option { key: "{monitor.id}", value: "{monitor.id}", "{monitor.name}" }
+                                            }
+                                        }
+                                        if confirm_delete() == Some(profile.id) {
+                                            span { class: "confirm-delete", "Really delete?" }
+                                            button {
+                                                class: "delete",
+                                                onclick: move |_| {
+                                                    let Some(id) = confirm_delete.take() else {
+                                                        return;
+                                                    };
+                                                    editing.set(None);
+
+                                                    let Ok(removed) = controller.write().remove_profile(id) else {
+                                                        return;
+                                                    };
+                                                    let _ = controller.read().save();
+                                                    keybind_version += 1;
+                                                    selected.write().remove(&removed.profile.id);
+
+                                                    deleted.set(Some(removed));
+                                                    deleted_generation += 1;
+                                                    let generation = deleted_generation();
+
+                                                    spawn(async move {
+                                                        tokio::time::sleep(UNDO_WINDOW).await;
+
+                                                        if deleted_generation() == generation {
+                                                            deleted.set(None);
+                                                        }
+                                                    });
+                                                },
+                                                "Yes"
+                                            }
+                                            button { onclick: move |_| confirm_delete.set(None), "No" }
+                                        } else if !profile.locked {
+                                            button {
+                                                class: "delete",
+                                                onclick: move |_| confirm_delete.set(Some(profile.id)),
+                                                "Delete"
                                             }
                                         }
                                     }
@@ -95,6 +1070,85 @@ pub fn ProfilesTab(mut config: Signal<AppConfig>, monitors: Signal<Vec<MonitorIn
                     }
                 }
             }
+
+            div {
+                class: "profile-transfer",
+                button {
+                    class: "identify-button",
+                    disabled: controller.read().config().profile_manager.profile_count() == 0,
+                    onclick: move |_| {
+                        spawn(async move {
+                            let Some(file) = rfd::AsyncFileDialog::new()
+                                .set_file_name("gammar-profiles.json")
+                                .add_filter("Gammar profiles", &["json"])
+                                .save_file()
+                                .await
+                            else {
+                                return;
+                            };
+
+                            let result = controller.read().export_profiles(file.path());
+                            transfer_msg.set(Some(
+                                result
+                                    .map(|_| format!("Exported to {}", file.path().display()))
+                                    .map_err(|e| e.to_string()),
+                            ));
+                        });
+                    },
+                    "Export profiles…"
+                }
+                button {
+                    class: "identify-button",
+                    onclick: move |_| {
+                        spawn(async move {
+                            let Some(file) = rfd::AsyncFileDialog::new()
+                                .add_filter("Gammar profiles", &["json"])
+                                .pick_file()
+                                .await
+                            else {
+                                return;
+                            };
+
+                            let mut ctrl = controller.write();
+                            let result = ctrl.import_profiles(file.path());
+                            if result.is_ok() {
+                                let _ = ctrl.save();
+                            }
+                            transfer_msg.set(Some(result.map(describe_import).map_err(|e| e.to_string())));
+                        });
+                    },
+                    "Import profiles…"
+                }
+
+                input {
+                    r#type: "text",
+                    class: "profile-code-input",
+                    placeholder: "Paste a profile code",
+                    value: "{profile_code}",
+                    oninput: move |evt| profile_code.set(evt.value()),
+                }
+                button {
+                    class: "identify-button",
+                    disabled: profile_code().trim().is_empty(),
+                    onclick: move |_| {
+                        let mut ctrl = controller.write();
+                        let result = ctrl.import_profile_code(&profile_code());
+
+                        if result.is_ok() {
+                            let _ = ctrl.save();
+                            profile_code.set(String::new());
+                        }
+                        transfer_msg.set(Some(result.map(|name| format!("Added {}", name)).map_err(|e| e.to_string())));
+                    },
+                    "Import from code"
+                }
+
+                match transfer_msg() {
+                    Some(Ok(message)) => rsx! { span { class: "transfer-status", "{message}" } },
+                    Some(Err(error)) => rsx! { span { class: "range-warning", "{error}" } },
+                    None => rsx! {},
+                }
+            }
         }
     }
 }
@@ -1,82 +1,198 @@
 use crate::{
-    components::slider::Slider,
-    windows::display::{apply_display_settings_to_monitor, DisplaySettings, MonitorInfo},
-    AppConfig,
+    backend::PowerSource,
+    components::{identify::identify_monitors, slider::Slider, test_pattern::open_test_patterns},
+    controller::{GammaController, DIMMING_CONFIRM_THRESHOLD},
+    schedule::{format_time, local_time, EventAnchor, Location, ScheduleEvent, Weekdays},
+    windows::display::{
+        compute_gamma_ramp, describe_monitor, ramp_file, BrightnessMode, Clipping, ColorFilter,
+        DisplaySettings, GammaError, Lut, RampFormat, MAX_EXTRA_DIMMING, MAX_TEMPERATURE,
+        MIN_TEMPERATURE,
+    },
+    EnforceConfig, MonitorRule, RepeatConfig, SliderPreset, TransitionConfig,
 };
 use dioxus::prelude::*;
 
-pub fn find_monitor(monitors: &[MonitorInfo], id: Option<&str>) -> Option<MonitorInfo> {
-    if let Some(id) = id {
-        return monitors.iter().find(|m| m.id == id).cloned();
-    }
+/// The profile applied when the machine switches to a power source.
+#[component]
+fn PowerProfileSelect(
+    mut controller: Signal<GammaController>,
+    source: PowerSource,
+    profiles: Vec<(u64, String)>,
+) -> Element {
+    let selected = controller
+        .read()
+        .config()
+        .power_profiles
+        .profile_for(source);
 
-    monitors
-        .iter()
-        .find(|m| m.is_primary)
-        .cloned()
-        .or_else(|| monitors.first().cloned())
+    rsx! {
+        select {
+            value: selected.map(|id| id.to_string()).unwrap_or_default(),
+            onchange: move |evt| {
+                let id = evt.value().parse::<u64>().ok();
+                let mut ctrl = controller.write();
+                ctrl.config_mut().power_profiles.set_profile(source, id);
+                let _ = ctrl.save();
+            },
+            option { value: "", "Leave the settings alone" }
+            if let Some(id) = selected.filter(|id| !profiles.iter().any(|(p, _)| p == id)) {
+                option { value: "{id}", "Deleted profile" }
+            }
+            for (id, name) in profiles.iter() {
+                option { key: "{id}", value: "{id}", "{name}" }
+            }
+        }
+    }
 }
 
-/// Apply settings and handle errors
+/// Apply settings, optionally fading to them, and handle errors
 fn apply_settings_update(
     settings: DisplaySettings,
-    monitors: &[MonitorInfo],
-    selected_id: &str,
-    mut config: Signal<AppConfig>,
+    fade: bool,
+    mut controller: Signal<GammaController>,
     mut error_msg: Signal<Option<String>>,
 ) {
-    config.write().current_settings = settings;
+    let mut ctrl = controller.write();
 
-    if let Some(monitor) = find_monitor(monitors, Some(selected_id)) {
-        match apply_display_settings_to_monitor(settings, &monitor) {
-            Ok(_) => {
-                error_msg.set(None);
-                let _ = config.read().save();
-            }
-            Err(e) => error_msg.set(Some(e.to_string())),
+    let result = if fade {
+        ctrl.fade_current_settings(settings)
+    } else {
+        ctrl.set_current_settings(settings)
+    };
+
+    match result {
+        Ok(_) => {
+            error_msg.set(None);
+            ctrl.save_soon();
         }
+        Err(GammaError::MonitorNotFound { .. }) => {
+            // The selection went stale, e.g. after an unplug the hot-plug poll hasn't caught yet
+            ctrl.refresh_monitors();
+            error_msg.set(Some(
+                "The selected monitor is no longer attached. The monitor list has been \
+                 refreshed; pick a monitor and try again"
+                    .to_string(),
+            ));
+        }
+        Err(e) => error_msg.set(Some(e.to_string())),
     }
 }
 
 /// Update a display setting using a closure
 fn update_display_setting<F>(
-    config: Signal<AppConfig>,
-    monitors: Signal<Vec<MonitorInfo>>,
+    controller: Signal<GammaController>,
     error_msg: Signal<Option<String>>,
     update_fn: F,
 ) where
     F: FnOnce(&mut DisplaySettings),
 {
-    let mut settings = config.read().current_settings;
+    let mut settings = controller.read().config().current_settings;
 
     update_fn(&mut settings);
 
-    let monitors_list = monitors();
-    let selected_id = config.read().selected_monitor_id.clone();
-
-    apply_settings_update(settings, &monitors_list, &selected_id, config, error_msg);
+    apply_settings_update(settings, false, controller, error_msg);
 }
 
 /// Update a step size setting and save
-fn update_step_size<F>(mut config: Signal<AppConfig>, update_fn: F)
+fn update_step_size<F>(mut controller: Signal<GammaController>, update_fn: F)
 where
     F: FnOnce(&mut crate::StepSize),
 {
-    let mut step = config.read().step_size.clone();
+    let mut ctrl = controller.write();
 
-    update_fn(&mut step);
-    config.write().step_size = step;
+    update_fn(&mut ctrl.config_mut().step_size);
 
-    let _ = config.read().save();
+    let _ = ctrl.save();
 }
 
+/// Update the fade duration, in seconds, and save
+fn update_transition(mut controller: Signal<GammaController>, seconds: f32) {
+    let mut ctrl = controller.write();
+
+    ctrl.config_mut().transition.duration_ms = (seconds * 1000.0).round() as u64;
+
+    let _ = ctrl.save();
+}
+
+/// Update enforcing settings and save
+fn update_enforce<F>(mut controller: Signal<GammaController>, update_fn: F)
+where
+    F: FnOnce(&mut crate::EnforceConfig),
+{
+    let mut ctrl = controller.write();
+
+    update_fn(&mut ctrl.config_mut().enforce);
+
+    let _ = ctrl.save();
+}
+
+/// Update the schedule and save
+fn update_schedule<F>(mut controller: Signal<GammaController>, update_fn: F)
+where
+    F: FnOnce(&mut crate::schedule::ScheduleConfig),
+{
+    let mut ctrl = controller.write();
+
+    update_fn(&mut ctrl.config_mut().schedule);
+
+    let _ = ctrl.save();
+}
+
+/// Apply changes to the monitor rules and save them.
+fn update_monitor_rules<F>(mut controller: Signal<GammaController>, update_fn: F)
+where
+    F: FnOnce(&mut Vec<MonitorRule>),
+{
+    let mut ctrl = controller.write();
+
+    update_fn(&mut ctrl.config_mut().monitor_rules);
+
+    let _ = ctrl.save();
+}
+
+/// Value of the monitor selector entry that targets every monitor
+const ALL_MONITORS: &str = "all";
+
 #[component]
-pub fn SettingsTab(mut config: Signal<AppConfig>, monitors: Signal<Vec<MonitorInfo>>) -> Element {
-    let error_msg = use_signal(|| Option::<String>::None);
+pub fn SettingsTab(
+    mut controller: Signal<GammaController>,
+    mut ramp_mismatch: Signal<Option<DisplaySettings>>,
+) -> Element {
+    let mut error_msg = use_signal(|| Option::<String>::None);
+    let mut range_unlock_requested = use_signal(|| false);
+    let mut exported = use_signal(|| Option::<String>::None);
 
     rsx! {
         div {
             class: "settings-tab",
+            if let Some(fitted) = ramp_mismatch() {
+                div {
+                    class: "mismatch-banner",
+                    p {
+                        "The display's current gamma doesn't match your saved settings. It may have been changed by another application."
+                    }
+                    div {
+                        class: "mismatch-actions",
+                        button {
+                            onclick: move |_| {
+                                let saved = controller.peek().config().current_settings;
+                                apply_settings_update(saved, false, controller, error_msg);
+                                ramp_mismatch.set(None);
+                            },
+                            "Reapply my settings"
+                        }
+                        button {
+                            class: "secondary",
+                            onclick: move |_| {
+                                apply_settings_update(fitted, true, controller, error_msg);
+                                ramp_mismatch.set(None);
+                            },
+                            "Adopt current state (approximate)"
+                        }
+                    }
+                }
+            }
+
             div {
                 class: "settings-card",
                 div {
@@ -87,25 +203,209 @@ pub fn SettingsTab(mut config: Signal<AppConfig>, monitors: Signal<Vec<MonitorIn
                         "Choose which display to control"
                     }
                 }
-                select {
-                    class: "monitor-select",
-                    value: config.read().selected_monitor_id.clone(),
-                    onchange: move |evt| {
-                        let value = evt.value();
-                        config.write().selected_monitor_id = value;
-                        let _ = config.read().save();
-                    },
+                div {
+                    class: "monitor-row",
+                    select {
+                        class: "monitor-select",
+                        value: if controller.read().apply_to_all_monitors() {
+                            ALL_MONITORS.to_string()
+                        } else {
+                            controller.read().config().selected_monitor_id.clone()
+                        },
+                        onchange: move |evt| {
+                            let mut ctrl = controller.write();
+                            let value = evt.value();
+
+                            let result = if value == ALL_MONITORS {
+                                ctrl.set_apply_to_all_monitors(true)
+                            } else {
+                                let _ = ctrl.set_apply_to_all_monitors(false);
+                                ctrl.select_monitor(&value)
+                            };
+
+                            match result {
+                                Ok(_) => error_msg.set(None),
+                                Err(e) => error_msg.set(Some(e.to_string())),
+                            }
+
+                            let _ = ctrl.save();
+                        },
+
+                        if controller.read().monitors().len() > 1 {
+                            option { value: ALL_MONITORS, "All monitors" }
+                        }
+
+                        for monitor in controller.read().monitors().to_vec() {
+                            option {
+                                key: "{monitor.id}",
+                                value: "{monitor.id}",
+                                if monitor.adapter.is_empty() {
+                                    "{monitor.name}"
+                                } else {
+                                    "{monitor.name} · {monitor.adapter}"
+                                }
+                            }
+                        }
+                    }
+
+                    button {
+                        class: "identify-button",
+                        title: "Show each monitor's number on the monitor itself",
+                        onclick: move |_| identify_monitors(controller.read().monitors()),
+                        "Identify"
+                    }
+
+                    button {
+                        class: "identify-button",
+                        title: "Show reference patterns on the selected monitor to judge settings by eye",
+                        disabled: controller.read().apply_to_all_monitors(),
+                        onclick: move |_| {
+                            if let Some(monitor) = controller.read().selected_monitor() {
+                                open_test_patterns(&monitor);
+                            }
+                        },
+                        "Test patterns"
+                    }
+                }
+
+                {
+                    let ctrl = controller.read();
+                    let details = ctrl
+                        .selected_monitor()
+                        .filter(|_| !ctrl.apply_to_all_monitors())
+                        .map(|monitor| describe_monitor(&monitor, ctrl.monitors()))
+                        .unwrap_or_default();
+
+                    rsx! {
+                        if !details.is_empty() {
+                            p { class: "monitor-details", "{details}" }
+                        }
+                    }
+                }
+
+                {
+                    let ctrl = controller.read();
+                    let monitor_id = ctrl.config().selected_monitor_id.clone();
+                    let supported = ctrl.supports_hardware_control(&monitor_id)
+                        && !ctrl.apply_to_all_monitors();
+                    let enabled = ctrl.hardware_control(&monitor_id);
+                    let backlight_supported = ctrl.supports_backlight(&monitor_id)
+                        && !ctrl.apply_to_all_monitors();
+                    let backlight_enabled = !ctrl.config().backlight_disabled.contains(&monitor_id);
+                    let path = ctrl.brightness_control(&monitor_id).format();
+                    drop(ctrl);
+                    let backlight_monitor_id = monitor_id.clone();
+
+                    rsx! {
+                        p { class: "monitor-details", "Brightness: {path}" }
+                        if backlight_supported {
+                            label {
+                                class: "checkbox-label",
+                                input {
+                                    r#type: "checkbox",
+                                    checked: backlight_enabled,
+                                    disabled: enabled,
+                                    onchange: move |evt| {
+                                        let mut ctrl = controller.write();
+
+                                        match ctrl.set_backlight_control(&backlight_monitor_id, evt.checked()) {
+                                            Ok(_) => error_msg.set(None),
+                                            Err(e) => error_msg.set(Some(e.to_string())),
+                                        }
+
+                                        let _ = ctrl.save();
+                                    },
+                                }
+                                "Backlight: dim the laptop panel itself instead of the gamma ramp"
+                            }
+                        }
+                        if supported {
+                            label {
+                                class: "checkbox-label",
+                                input {
+                                    r#type: "checkbox",
+                                    checked: enabled,
+                                    onchange: move |evt| {
+                                        let mut ctrl = controller.write();
+
+                                        match ctrl.set_hardware_control(&monitor_id, evt.checked()) {
+                                            Ok(_) => error_msg.set(None),
+                                            Err(e) => error_msg.set(Some(e.to_string())),
+                                        }
+
+                                        let _ = ctrl.save();
+                                    },
+                                }
+                                "Hardware control (DDC/CI): adjust brightness and contrast on the monitor itself"
+                            }
+                        }
+                    }
+                }
+
+                if !controller.read().apply_to_all_monitors() {
+                    {
+                        let ctrl = controller.read();
+                        let monitor_id = ctrl.config().selected_monitor_id.clone();
+                        let report = ctrl.capabilities(&monitor_id).cloned();
+                        drop(ctrl);
+
+                        rsx! {
+                            details {
+                                class: "capabilities",
+                                summary { "Capabilities" }
+
+                                match &report {
+                                    None => rsx! {
+                                        p {
+                                            class: "monitor-details",
+                                            "Ask the monitor which DDC/CI controls it supports. This can take a few seconds"
+                                        }
+                                    },
+                                    Some(Err(error)) => rsx! {
+                                        p { class: "monitor-details", "Couldn't read the capabilities: {error}" }
+                                    },
+                                    Some(Ok(capabilities)) => rsx! {
+                                        if let Some(model) = &capabilities.model {
+                                            p {
+                                                class: "monitor-details",
+                                                "Model {model}"
+                                                if let Some(version) = &capabilities.mccs_version {
+                                                    ", MCCS {version}"
+                                                }
+                                            }
+                                        }
+                                        if capabilities.vcp.is_empty() {
+                                            p { class: "monitor-details", "No VCP codes reported" }
+                                        }
+                                        ul {
+                                            class: "capability-list",
+                                            for vcp in capabilities.vcp.iter() {
+                                                li { key: "{vcp.code}", "{vcp.describe()}" }
+                                            }
+                                        }
+                                    },
+                                }
 
-                    for monitor in monitors().iter() {
-                        option {
-                            key: "{monitor.id}",
-                            value: "{monitor.id}",
-                            "{monitor.name}"
+                                button {
+                                    class: "identify-button",
+                                    onclick: move |_| {
+                                        controller.write().query_capabilities(&monitor_id);
+                                    },
+                                    if report.is_some() { "Query again" } else { "Query capabilities" }
+                                }
+                            }
                         }
                     }
                 }
             }
 
+            if controller.read().selected_monitor().is_some_and(|m| m.is_hdr) {
+                div {
+                    class: "hdr-warning",
+                    "⚠️ HDR is enabled on this monitor. Windows ignores or distorts gamma ramps while HDR is on, so changes here may have no effect or look wrong. Turn HDR off in Windows display settings for reliable results."
+                }
+            }
+
             if let Some(err) = error_msg() {
                 div {
                     class: "error-message",
@@ -113,64 +413,856 @@ pub fn SettingsTab(mut config: Signal<AppConfig>, monitors: Signal<Vec<MonitorIn
                 }
             }
 
-            // Display controls card
-            div {
-                class: "settings-card",
+            if controller.read().gamma_controls_unavailable() {
                 div {
-                    class: "card-header",
-                    h2 { "Display controls" }
-                    p {
-                        class: "card-description",
-                        "Adjust gamma, brightness, and contrast for your display"
+                    class: "hdr-warning",
+                    "⚠️ This monitor doesn't support gamma ramps, which is common for virtual displays, some USB docks and remote desktop sessions. The display controls are disabled while it's selected."
+                }
+            }
+
+            fieldset {
+                class: "gamma-controls",
+                disabled: controller.read().gamma_controls_unavailable(),
+
+                // Display controls card
+                div {
+                    class: "settings-card",
+                    div {
+                        class: "card-header",
+                        h2 {
+                            "Display controls"
+                            if controller.read().gamma_range_unlocked() {
+                                span {
+                                    class: "badge",
+                                    title: "GdiIcmGammaRange is set, so extreme settings are accepted",
+                                    "Full range"
+                                }
+                            }
+                            {
+                                let settings = controller.read().config().current_settings;
+                                let clipping = Clipping::of(&compute_gamma_ramp(&settings));
+                                let percent = clipping.percent();
+
+                                rsx! {
+                                    if percent > 0.0 {
+                                        span {
+                                            class: if percent > Clipping::SEVERE_PERCENT { "badge clipping severe" } else { "badge clipping" },
+                                            title: clipping.describe(),
+                                            "{percent:.0}% of levels clipped"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        p {
+                            class: "card-description",
+                            "Adjust gamma, brightness, and contrast for your display"
+                        }
+                    }
+
+                    div {
+                        class: "sliders-grid",
+
+                        Slider {
+                            label: "Gamma",
+                            value: controller.read().config().current_settings.gamma,
+                            min: 0.1,
+                            max: 3.0,
+                            step: 0.01,
+                            presets: SliderPreset::pairs(&controller.read().config().slider_presets.gamma),
+                            on_change: move |value| {
+                                update_display_setting(controller, error_msg, |s| s.gamma = value);
+                            }
+                        }
+
+                        Slider {
+                            label: "Brightness",
+                            value: controller.read().config().current_settings.brightness,
+                            min: -1.0,
+                            max: 1.0,
+                            step: 0.01,
+                            presets: SliderPreset::pairs(&controller.read().config().slider_presets.brightness),
+                            on_change: move |value| {
+                                update_display_setting(controller, error_msg, |s| s.brightness = value);
+                            }
+                        }
+
+                        Slider {
+                            label: "Contrast",
+                            value: controller.read().config().current_settings.contrast,
+                            min: 0.1,
+                            max: 3.0,
+                            step: 0.01,
+                            presets: SliderPreset::pairs(&controller.read().config().slider_presets.contrast),
+                            on_change: move |value| {
+                                update_display_setting(controller, error_msg, |s| s.contrast = value);
+                            }
+                        }
+
+                        Slider {
+                            label: "Extra dimming (%)",
+                            value: controller.read().config().current_settings.extra_dimming * 100.0,
+                            min: 0.0,
+                            max: MAX_EXTRA_DIMMING * 100.0,
+                            step: 1.0,
+                            precision: 0,
+                            on_change: move |value: f32| {
+                                update_display_setting(controller, error_msg, |s| s.extra_dimming = value / 100.0);
+                            }
+                        }
+                    }
+
+                    label {
+                        class: "checkbox-label",
+                        title: "Normally brightness is added to every level, which also lifts black to grey. Preserving black scales the levels instead, dimming or boosting the white point while black stays black",
+                        input {
+                            r#type: "checkbox",
+                            checked: controller.read().config().current_settings.brightness_mode == BrightnessMode::PreserveBlack,
+                            onchange: move |evt| {
+                                let mode = if evt.checked() {
+                                    BrightnessMode::PreserveBlack
+                                } else {
+                                    BrightnessMode::Offset
+                                };
+
+                                update_display_setting(controller, error_msg, |s| s.brightness_mode = mode);
+                            },
+                        }
+                        "Preserve black level: brightness scales the white point instead of lifting black"
+                    }
+
+                    label {
+                        class: "checkbox-label",
+                        title: "Changes that take extra dimming past this are undone after a few seconds unless you keep them, so a screen dimmed too dark to see comes back on its own",
+                        input {
+                            r#type: "checkbox",
+                            checked: !controller.read().config().disable_dimming_revert,
+                            onchange: move |evt| {
+                                let mut ctrl = controller.write();
+                                ctrl.config_mut().disable_dimming_revert = !evt.checked();
+                                if !evt.checked() {
+                                    ctrl.keep_dimming();
+                                }
+                                let _ = ctrl.save();
+                            },
+                        }
+                        "Ask to keep extra dimming above {DIMMING_CONFIRM_THRESHOLD * 100.0:.0}%"
+                    }
+
+                    if controller.read().exceeds_gamma_range() {
+                        div {
+                            class: "range-warning",
+                            if range_unlock_requested() {
+                                p { "The full range has been unlocked. Restart Windows for it to take effect." }
+                            } else {
+                                p { "Windows rejects settings this extreme unless the full gamma range is unlocked. Unlocking sets the GdiIcmGammaRange registry value, needs administrator access and takes effect after a restart." }
+                                button {
+                                    onclick: move |_| match controller.read().unlock_gamma_range() {
+                                        Ok(_) => {
+                                            error_msg.set(None);
+                                            range_unlock_requested.set(true);
+                                        }
+                                        Err(e) => error_msg.set(Some(e.to_string())),
+                                    },
+                                    "Unlock full range"
+                                }
+                            }
+                        }
+                    }
+
+                    button {
+                        class: "reset-button",
+                        onclick: move |_| {
+                            controller.write().remember_previous();
+                            apply_settings_update(DisplaySettings::default(), true, controller, error_msg);
+                        },
+                        "Reset to Default"
                     }
                 }
 
+                // Calibration LUT card
+                {
+                    let ctrl = controller.read();
+                    let monitor_id = ctrl.config().selected_monitor_id.clone();
+                    let lut_name = ctrl.lut(&monitor_id).map(|lut| lut.name.clone());
+                    let load_id = monitor_id.clone();
+                    let export_id = monitor_id.clone();
+                    let export_name = ctrl
+                        .selected_monitor()
+                        .map(|m| ramp_file::file_name(&m.name, RampFormat::Icc))
+                        .unwrap_or_default();
+                    drop(ctrl);
+
+                    rsx! {
+                        div {
+                            class: "settings-card",
+                            div {
+                                class: "card-header",
+                                h2 { "Calibration LUT" }
+                                p {
+                                    class: "card-description",
+                                    "Load a 1D .cube or R,G,B CSV table, or the calibration curve of an .icc/.icm profile, e.g. from DisplayCAL, for this monitor. Gammar's adjustments are applied on top of it"
+                                }
+                            }
+
+                            div {
+                                class: "lut-row",
+                                span {
+                                    class: "lut-name",
+                                    if let Some(name) = &lut_name { "Loaded: {name}" } else { "No LUT loaded" }
+                                }
+
+                                label {
+                                    class: "identify-button lut-picker",
+                                    "Load LUT…"
+                                    input {
+                                        r#type: "file",
+                                        accept: ".cube,.csv,.icc,.icm",
+                                        onchange: move |evt: FormEvent| {
+                                            let Some(file) = evt.files().into_iter().next() else {
+                                                return;
+                                            };
+
+                                            let result = Lut::load(&file.path()).and_then(|lut| {
+                                                controller.write().set_lut(&load_id, Some(lut))
+                                            });
+
+                                            match result {
+                                                Ok(_) => {
+                                                    error_msg.set(None);
+                                                    let _ = controller.read().save();
+                                                }
+                                                Err(e) => error_msg.set(Some(e.to_string())),
+                                            }
+                                        },
+                                    }
+                                }
+
+                                if lut_name.is_some() {
+                                    button {
+                                        class: "identify-button",
+                                        onclick: move |_| {
+                                            let mut ctrl = controller.write();
+
+                                            match ctrl.set_lut(&monitor_id, None) {
+                                                Ok(_) => error_msg.set(None),
+                                                Err(e) => error_msg.set(Some(e.to_string())),
+                                            }
+
+                                            let _ = ctrl.save();
+                                        },
+                                        "Clear LUT"
+                                    }
+                                }
+                            }
+
+                            div {
+                                class: "lut-row",
+                                span {
+                                    class: "lut-name",
+                                    if let Some(path) = exported() { "Exported to {path}" } else { "Save the ramp Gammar writes to this monitor, for use in other calibration tools" }
+                                }
+
+                                button {
+                                    class: "identify-button",
+                                    onclick: move |_| {
+                                        let monitor_id = export_id.clone();
+                                        let file_name = export_name.clone();
+
+                                        spawn(async move {
+                                            let Some(file) = rfd::AsyncFileDialog::new()
+                                                .set_file_name(file_name)
+                                                .add_filter("ICC profile (vcgt)", &["icc", "icm"])
+                                                .add_filter("CSV table", &["csv"])
+                                                .add_filter("Raw 16-bit ramp", &["bin"])
+                                                .save_file()
+                                                .await
+                                            else {
+                                                return;
+                                            };
+
+                                            match controller.read().export_ramp(&monitor_id, file.path()) {
+                                                Ok(_) => {
+                                                    error_msg.set(None);
+                                                    exported.set(Some(file.path().display().to_string()));
+                                                }
+                                                Err(e) => error_msg.set(Some(e.to_string())),
+                                            }
+                                        });
+                                    },
+                                    "Export ramp…"
+                                }
+                            }
+                        }
+                    }
+                }
+
+                // Color temperature card
                 div {
-                    class: "sliders-grid",
+                    class: "settings-card",
+                    div {
+                        class: "card-header",
+                        h2 { "Color temperature" }
+                        p {
+                            class: "card-description",
+                            "Warm the white point to reduce blue light. Presets can be edited in the config file"
+                        }
+                    }
 
-                    Slider {
-                        label: "Gamma",
-                        value: config.read().current_settings.gamma,
-                        min: 0.1,
-                        max: 3.0,
-                        step: 0.01,
-                        on_change: move |value| {
-                            update_display_setting(config, monitors, error_msg, |s| s.gamma = value);
+                    div {
+                        class: "sliders-grid",
+
+                        Slider {
+                            label: "Temperature (K)",
+                            value: controller.read().config().current_settings.temperature as f32,
+                            min: MIN_TEMPERATURE as f32,
+                            max: MAX_TEMPERATURE as f32,
+                            step: 100.0,
+                            precision: 0,
+                            on_change: move |value: f32| {
+                                update_display_setting(controller, error_msg, |s| s.temperature = value.round() as u32);
+                            }
                         }
                     }
 
-                    Slider {
-                        label: "Brightness",
-                        value: config.read().current_settings.brightness,
-                        min: -1.0,
-                        max: 1.0,
-                        step: 0.01,
-                        on_change: move |value| {
-                            update_display_setting(config, monitors, error_msg, |s| s.brightness = value);
+                    div {
+                        class: "preset-buttons",
+                        for preset in controller.read().config().temperature_presets.0.clone() {
+                            button {
+                                key: "{preset.kelvin}",
+                                class: if controller.read().config().current_settings.temperature == preset.kelvin { "preset-button active" } else { "preset-button" },
+                                title: "{preset.kelvin}K",
+                                onclick: move |_| {
+                                    let mut settings = controller.peek().config().current_settings;
+                                    settings.temperature = preset.kelvin.clamp(MIN_TEMPERATURE, MAX_TEMPERATURE);
+                                    apply_settings_update(settings, false, controller, error_msg);
+                                },
+                                "{preset.name} · {preset.kelvin}K"
+                            }
                         }
                     }
+                }
 
-                    Slider {
-                        label: "Contrast",
-                        value: config.read().current_settings.contrast,
-                        min: 0.1,
-                        max: 3.0,
-                        step: 0.01,
-                        on_change: move |value| {
-                            update_display_setting(config, monitors, error_msg, |s| s.contrast = value);
+                // Color balance card
+                div {
+                    class: "settings-card",
+                    div {
+                        class: "card-header",
+                        h2 { "Color balance" }
+                        p {
+                            class: "card-description",
+                            "Correct a color cast by tinting the picture. Each axis dims the channels on the opposite side"
+                        }
+                    }
+
+                    div {
+                        class: "sliders-grid",
+
+                        Slider {
+                            label: "Green ↔ Magenta",
+                            value: controller.read().config().current_settings.green_magenta,
+                            min: -1.0,
+                            max: 1.0,
+                            step: 0.01,
+                            on_change: move |value| {
+                                update_display_setting(controller, error_msg, |s| s.green_magenta = value);
+                            }
+                        }
+
+                        Slider {
+                            label: "Blue ↔ Amber",
+                            value: controller.read().config().current_settings.blue_amber,
+                            min: -1.0,
+                            max: 1.0,
+                            step: 0.01,
+                            on_change: move |value| {
+                                update_display_setting(controller, error_msg, |s| s.blue_amber = value);
+                            }
+                        }
+                    }
+
+                    button {
+                        class: "reset-button",
+                        onclick: move |_| {
+                            update_display_setting(controller, error_msg, |s| {
+                                s.green_magenta = 0.0;
+                                s.blue_amber = 0.0;
+                            });
+                        },
+                        "Reset color balance"
+                    }
+                }
+
+                // Color effects card
+                div {
+                    class: "settings-card",
+                    div {
+                        class: "card-header",
+                        h2 { "Color effects" }
+                        p {
+                            class: "card-description",
+                            "Filters are applied through the gamma ramp, which adjusts each color channel on its own. They reweight the channels rather than truly mixing them, so grayscale mutes colors instead of removing them"
+                        }
+                    }
+
+                    select {
+                        class: "monitor-select",
+                        value: "{controller.read().config().current_settings.color_filter:?}",
+                        onchange: move |evt| {
+                            let Some(filter) = ColorFilter::ALL
+                                .into_iter()
+                                .find(|f| format!("{:?}", f) == evt.value())
+                            else {
+                                return;
+                            };
+
+                            if filter != ColorFilter::None {
+                                controller.write().config_mut().toggle_filter = filter;
+                            }
+
+                            update_display_setting(controller, error_msg, |s| s.color_filter = filter);
+                        },
+
+                        for filter in ColorFilter::ALL {
+                            option {
+                                value: "{filter:?}",
+                                "{filter.format()}"
+                            }
+                        }
+                    }
+
+                    label {
+                        class: "checkbox-label",
+                        input {
+                            r#type: "checkbox",
+                            checked: controller.read().config().current_settings.invert,
+                            onchange: move |evt| {
+                                update_display_setting(controller, error_msg, |s| s.invert = evt.checked());
+                            },
+                        }
+                        "Negative: invert colors"
+                    }
+                }
+
+                // Advanced card
+                div {
+                    class: "settings-card",
+                    div {
+                        class: "card-header",
+                        h2 { "Advanced" }
+                        p {
+                            class: "card-description",
+                            "Limit the output range, e.g. to 16–235 video levels for a projector. Black maps to the black point and white to the white point. The contrast pivot is the level contrast leaves unchanged"
+                        }
+                    }
+
+                    div {
+                        class: "sliders-grid",
+
+                        Slider {
+                            label: "Black point",
+                            value: controller.read().config().current_settings.output_min,
+                            min: 0.0,
+                            max: 1.0,
+                            step: 0.01,
+                            on_change: move |value| {
+                                update_display_setting(controller, error_msg, |s| s.output_min = value);
+                            }
+                        }
+
+                        Slider {
+                            label: "White point",
+                            value: controller.read().config().current_settings.output_max,
+                            min: 0.0,
+                            max: 1.0,
+                            step: 0.01,
+                            on_change: move |value| {
+                                update_display_setting(controller, error_msg, |s| s.output_max = value);
+                            }
+                        }
+
+                        Slider {
+                            label: "Contrast pivot",
+                            value: controller.read().config().current_settings.contrast_pivot,
+                            min: 0.0,
+                            max: 1.0,
+                            step: 0.01,
+                            on_change: move |value| {
+                                update_display_setting(controller, error_msg, |s| s.contrast_pivot = value);
+                            }
+                        }
+                    }
+
+                    if controller.read().config().current_settings.output_range_inverted() {
+                        div {
+                            class: "range-warning",
+                            "⚠️ The black point is at or above the white point, so the picture will be flat or inverted. Lower the black point or raise the white point."
                         }
                     }
                 }
+            }
+
+            // Schedule card
+            {
+                let ctrl = controller.read();
+                let schedule = ctrl.config().schedule.clone();
+                let profiles: Vec<(u64, String)> = ctrl
+                    .config()
+                    .profile_manager
+                    .get_profiles()
+                    .iter()
+                    .map(|p| (p.id, p.name.clone()))
+                    .collect();
+                let paused = ctrl.schedule_paused();
+                drop(ctrl);
+
+                let location = schedule.location;
+                let sun_summary = match (location, schedule.sun_times(&local_time())) {
+                    (None, _) => "Enter a location to follow sunrise and sunset".to_string(),
+                    (Some(_), Some(sun)) => format!(
+                        "Today: sunrise {}, sunset {}",
+                        format_time(sun.sunrise),
+                        format_time(sun.sunset)
+                    ),
+                    (Some(_), None) => {
+                        "The sun doesn't rise or set here today, so sun events use their fixed times"
+                            .to_string()
+                    }
+                };
+
+                rsx! {
+                    div {
+                        class: "settings-card",
+                        div {
+                            class: "card-header",
+                            h2 { "Schedule" }
+                            p {
+                                class: "card-description",
+                                "Ease into a profile at set times of day or at sunrise and sunset, on every day or only some, e.g. dimmer settings over 30 minutes from sunset. A manual change pauses the schedule until its next event"
+                            }
+                        }
+
+                        label {
+                            class: "checkbox-label",
+                            input {
+                                r#type: "checkbox",
+                                checked: schedule.enabled,
+                                onchange: move |evt| update_schedule(controller, |s| s.enabled = evt.checked()),
+                            }
+                            "Follow the schedule"
+                        }
+
+                        div {
+                            class: "schedule-event",
+                            label {
+                                "Latitude "
+                                input {
+                                    r#type: "number",
+                                    min: "-90",
+                                    max: "90",
+                                    step: "0.01",
+                                    value: location.map(|l| l.latitude.to_string()).unwrap_or_default(),
+                                    onchange: move |evt| {
+                                        let latitude = evt.value().parse::<f64>().ok();
+                                        update_schedule(controller, |s| {
+                                            s.location = latitude.map(|latitude| Location {
+                                                latitude: latitude.clamp(-90.0, 90.0),
+                                                ..s.location.unwrap_or_default()
+                                            });
+                                        });
+                                    },
+                                }
+                            }
+                            label {
+                                "Longitude "
+                                input {
+                                    r#type: "number",
+                                    min: "-180",
+                                    max: "180",
+                                    step: "0.01",
+                                    value: location.map(|l| l.longitude.to_string()).unwrap_or_default(),
+                                    onchange: move |evt| {
+                                        let longitude = evt.value().parse::<f64>().ok();
+                                        update_schedule(controller, |s| {
+                                            s.location = longitude.map(|longitude| Location {
+                                                longitude: longitude.clamp(-180.0, 180.0),
+                                                ..s.location.unwrap_or_default()
+                                            });
+                                        });
+                                    },
+                                }
+                            }
+                            span { "{sun_summary}" }
+                        }
 
-                button {
-                    class: "reset-button",
-                    onclick: move |_| {
-                        let settings = DisplaySettings::default();
-                        let monitors_list = monitors();
-                        let selected_id = config.read().selected_monitor_id.clone();
-                        apply_settings_update(settings, &monitors_list, &selected_id, config, error_msg);
-                    },
-                    "Reset to Default"
+                        if profiles.is_empty() {
+                            p { class: "monitor-details", "Save a profile first to schedule it" }
+                        }
+
+                        for (index, event) in schedule.events.iter().enumerate() {
+                            div {
+                                key: "{index}",
+                                class: "schedule-event",
+                                select {
+                                    value: "{event.anchor:?}",
+                                    onchange: move |evt| {
+                                        if let Some(anchor) = EventAnchor::ALL.into_iter().find(|a| format!("{:?}", a) == evt.value()) {
+                                            update_schedule(controller, |s| s.events[index].anchor = anchor);
+                                        }
+                                    },
+                                    for anchor in EventAnchor::ALL {
+                                        option { key: "{anchor:?}", value: "{anchor:?}", "{anchor.format()}" }
+                                    }
+                                }
+                                input {
+                                    r#type: "time",
+                                    title: if event.anchor == EventAnchor::Fixed { "" } else { "Used on days the sun doesn't rise or set" },
+                                    value: "{event.start_time()}",
+                                    onchange: move |evt| {
+                                        if let Some(minute) = ScheduleEvent::parse_time(&evt.value()) {
+                                            update_schedule(controller, |s| s.events[index].start_minute = minute);
+                                        }
+                                    },
+                                }
+                                label {
+                                    "over "
+                                    input {
+                                        r#type: "number",
+                                        min: "0",
+                                        max: "720",
+                                        value: "{event.duration_minutes}",
+                                        onchange: move |evt| {
+                                            if let Ok(minutes) = evt.value().parse::<u32>() {
+                                                update_schedule(controller, |s| s.events[index].duration_minutes = minutes.min(720));
+                                            }
+                                        },
+                                    }
+                                    " min to"
+                                }
+                                select {
+                                    value: "{event.profile_id}",
+                                    onchange: move |evt| {
+                                        if let Ok(id) = evt.value().parse::<u64>() {
+                                            update_schedule(controller, |s| s.events[index].profile_id = id);
+                                        }
+                                    },
+                                    if !profiles.iter().any(|(id, _)| *id == event.profile_id) {
+                                        option { value: "{event.profile_id}", "Deleted profile" }
+                                    }
+                                    for (id, name) in profiles.iter() {
+                                        option { key: "{id}", value: "{id}", "{name}" }
+                                    }
+                                }
+                                div {
+                                    class: "schedule-days",
+                                    for (weekday, name) in (0..).zip(Weekdays::NAMES) {
+                                        label {
+                                            key: "{name}",
+                                            // An event needs at least one day, so the last one can't be cleared
+                                            title: if event.days == Weekdays(1 << weekday) { "Events need at least one day" } else { "" },
+                                            input {
+                                                r#type: "checkbox",
+                                                checked: event.days.contains(weekday),
+                                                disabled: event.days == Weekdays(1 << weekday),
+                                                onchange: move |evt| {
+                                                    update_schedule(controller, |s| {
+                                                        let mut days = s.events[index].days;
+                                                        days.set(weekday, evt.checked());
+                                                        if !days.is_empty() {
+                                                            s.events[index].days = days;
+                                                        }
+                                                    });
+                                                },
+                                            }
+                                            "{name}"
+                                        }
+                                    }
+                                }
+                                button {
+                                    class: "delete",
+                                    onclick: move |_| update_schedule(controller, |s| {
+                                        s.events.remove(index);
+                                    }),
+                                    "Remove"
+                                }
+                            }
+                        }
+
+                        div {
+                            class: "enforce-status",
+                            button {
+                                disabled: profiles.is_empty(),
+                                onclick: move |_| {
+                                    let profile_id = profiles.first().map(|(id, _)| *id).unwrap_or_default();
+                                    update_schedule(controller, |s| s.events.push(ScheduleEvent::new(21 * 60, 30, profile_id)));
+                                },
+                                "Add event"
+                            }
+                            if paused {
+                                span { "Paused by a manual change until the next event" }
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Power source card
+            {
+                let ctrl = controller.read();
+                let profiles: Vec<(u64, String)> = ctrl
+                    .config()
+                    .profile_manager
+                    .get_profiles()
+                    .iter()
+                    .map(|p| (p.id, p.name.clone()))
+                    .collect();
+                let has_battery = ctrl.power_source().is_some();
+                drop(ctrl);
+
+                rsx! {
+                    div {
+                        class: "settings-card",
+                        div {
+                            class: "card-header",
+                            h2 { "Power source" }
+                            p {
+                                class: "card-description",
+                                "Apply a profile when the machine switches between battery and AC power, e.g. dimmer settings to save power. Manual changes stand until the next switch"
+                            }
+                        }
+                        if !has_battery {
+                            p { class: "monitor-details", "This machine doesn't report a battery, so these never apply" }
+                        }
+                        div {
+                            class: "schedule-event",
+                            label {
+                                "On battery "
+                                PowerProfileSelect { controller, source: PowerSource::Battery, profiles: profiles.clone() }
+                            }
+                            label {
+                                "On AC power "
+                                PowerProfileSelect { controller, source: PowerSource::Ac, profiles }
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Monitor rules card
+            {
+                let ctrl = controller.read();
+                let profiles: Vec<(u64, String)> = ctrl
+                    .config()
+                    .profile_manager
+                    .get_profiles()
+                    .iter()
+                    .map(|p| (p.id, p.name.clone()))
+                    .collect();
+                let monitors = ctrl.monitors().to_vec();
+                let rules = ctrl.config().monitor_rules.clone();
+                drop(ctrl);
+
+                rsx! {
+                    div {
+                        class: "settings-card",
+                        div {
+                            class: "card-header",
+                            h2 { "Monitor rules" }
+                            p {
+                                class: "card-description",
+                                "Apply a profile when a monitor is connected or disconnected, e.g. on docking a laptop. When several rules start matching at once, the first one wins"
+                            }
+                        }
+                        if profiles.is_empty() {
+                            p { class: "monitor-details", "Save a profile first to use it in a rule" }
+                        }
+
+                        for (index, rule) in rules.iter().enumerate() {
+                            div {
+                                key: "{index}",
+                                class: "schedule-event",
+                                "When "
+                                select {
+                                    value: "{rule.monitor_id}",
+                                    onchange: {
+                                        let monitors = monitors.clone();
+                                        move |evt: Event<FormData>| {
+                                            if let Some(monitor) = monitors.iter().find(|m| m.id == evt.value()) {
+                                                let (id, name) = (monitor.id.clone(), monitor.name.clone());
+                                                update_monitor_rules(controller, |r| {
+                                                    r[index].monitor_id = id;
+                                                    r[index].monitor_name = name;
+                                                });
+                                            }
+                                        }
+                                    },
+                                    if !monitors.iter().any(|m| m.id == rule.monitor_id) {
+                                        option { value: "{rule.monitor_id}", "{rule.monitor_name} (not connected)" }
+                                    }
+                                    for monitor in monitors.iter() {
+                                        option { key: "{monitor.id}", value: "{monitor.id}", "{monitor.name}" }
+                                    }
+                                }
+                                select {
+                                    value: if rule.connected { "connected" } else { "disconnected" },
+                                    onchange: move |evt| {
+                                        let connected = evt.value() == "connected";
+                                        update_monitor_rules(controller, |r| r[index].connected = connected);
+                                    },
+                                    option { value: "connected", "is connected" }
+                                    option { value: "disconnected", "is disconnected" }
+                                }
+                                " apply "
+                                select {
+                                    value: "{rule.profile_id}",
+                                    onchange: move |evt| {
+                                        if let Ok(id) = evt.value().parse::<u64>() {
+                                            update_monitor_rules(controller, |r| r[index].profile_id = id);
+                                        }
+                                    },
+                                    if !profiles.iter().any(|(id, _)| *id == rule.profile_id) {
+                                        option { value: "{rule.profile_id}", "Deleted profile" }
+                                    }
+                                    for (id, name) in profiles.iter() {
+                                        option { key: "{id}", value: "{id}", "{name}" }
+                                    }
+                                }
+                                button {
+                                    disabled: index == 0,
+                                    title: "Move up, to win ties",
+                                    onclick: move |_| update_monitor_rules(controller, |r| r.swap(index - 1, index)),
+                                    "↑"
+                                }
+                                button {
+                                    class: "delete",
+                                    onclick: move |_| update_monitor_rules(controller, |r| {
+                                        r.remove(index);
+                                    }),
+                                    "Remove"
+                                }
+                            }
+                        }
+
+                        div {
+                            class: "enforce-status",
+                            button {
+                                disabled: profiles.is_empty() || monitors.is_empty(),
+                                onclick: move |_| {
+                                    let (Some((profile_id, _)), Some(monitor)) = (profiles.first(), monitors.last()) else {
+                                        return;
+                                    };
+                                    let rule = MonitorRule {
+                                        monitor_id: monitor.id.clone(),
+                                        monitor_name: monitor.name.clone(),
+                                        connected: true,
+                                        profile_id: *profile_id,
+                                    };
+                                    update_monitor_rules(controller, |r| r.push(rule));
+                                },
+                                "Add rule"
+                            }
+                        }
+                    }
                 }
             }
 
@@ -182,7 +1274,7 @@ pub fn SettingsTab(mut config: Signal<AppConfig>, monitors: Signal<Vec<MonitorIn
                     h2 { "Step size" }
                     p {
                         class: "card-description",
-                        "Configure how much each hotkey press adjusts the values"
+                        "Configure how much each hotkey press adjusts the values, and how quickly steps repeat while the keys are held"
                     }
                 }
 
@@ -191,34 +1283,199 @@ pub fn SettingsTab(mut config: Signal<AppConfig>, monitors: Signal<Vec<MonitorIn
 
                     Slider {
                         label: "Gamma step",
-                        value: config.read().step_size.gamma,
+                        value: controller.read().config().step_size.gamma,
                         min: 0.01,
                         max: 0.5,
                         step: 0.01,
                         on_change: move |value| {
-                            update_step_size(config, |s| s.gamma = value);
+                            update_step_size(controller, |s| s.gamma = value);
                         }
                     }
 
                     Slider {
                         label: "Brightness step",
-                        value: config.read().step_size.brightness,
+                        value: controller.read().config().step_size.brightness,
                         min: 0.01,
                         max: 0.5,
                         step: 0.01,
                         on_change: move |value| {
-                            update_step_size(config, |s| s.brightness = value);
+                            update_step_size(controller, |s| s.brightness = value);
                         }
                     }
 
                     Slider {
                         label: "Contrast step",
-                        value: config.read().step_size.contrast,
+                        value: controller.read().config().step_size.contrast,
+                        min: 0.01,
+                        max: 0.5,
+                        step: 0.01,
+                        on_change: move |value| {
+                            update_step_size(controller, |s| s.contrast = value);
+                        }
+                    }
+
+                    Slider {
+                        label: "Extra dimming step",
+                        value: controller.read().config().step_size.extra_dimming,
                         min: 0.01,
                         max: 0.5,
                         step: 0.01,
                         on_change: move |value| {
-                            update_step_size(config, |s| s.contrast = value);
+                            update_step_size(controller, |s| s.extra_dimming = value);
+                        }
+                    }
+
+                    Slider {
+                        label: "Repeat after holding (ms)",
+                        value: controller.read().config().hotkey_repeat.delay_ms as f32,
+                        min: 100.0,
+                        max: 1000.0,
+                        step: 50.0,
+                        precision: 0,
+                        on_change: move |value: f32| {
+                            let mut ctrl = controller.write();
+                            ctrl.config_mut().hotkey_repeat.delay_ms = value as u64;
+                            let _ = ctrl.save();
+                        }
+                    }
+
+                    Slider {
+                        label: "Repeat every (ms)",
+                        value: controller.read().config().hotkey_repeat.interval_ms as f32,
+                        min: RepeatConfig::MIN_INTERVAL_MS as f32,
+                        max: 500.0,
+                        step: 10.0,
+                        precision: 0,
+                        on_change: move |value: f32| {
+                            let mut ctrl = controller.write();
+                            ctrl.config_mut().hotkey_repeat.interval_ms = value as u64;
+                            let _ = ctrl.save();
+                        }
+                    }
+                }
+            }
+
+            // Transition card
+            div {
+                class: "settings-card",
+                div {
+                    class: "card-header",
+                    h2 { "Transitions" }
+                    p {
+                        class: "card-description",
+                        "Fade smoothly when loading profiles or resetting. Hotkey steps always apply instantly"
+                    }
+                }
+
+                div {
+                    class: "sliders-grid",
+
+                    Slider {
+                        label: "Fade duration (s)",
+                        value: controller.read().config().transition.duration_ms as f32 / 1000.0,
+                        min: 0.0,
+                        max: (TransitionConfig::MAX_DURATION_MS / 1000) as f32,
+                        step: 0.1,
+                        on_change: move |value| update_transition(controller, value),
+                    }
+                }
+            }
+
+            // Enforce card
+            div {
+                class: "settings-card",
+                div {
+                    class: "card-header",
+                    h2 { "Keep enforced" }
+                    p {
+                        class: "card-description",
+                        "Games and capture tools sometimes reset the gamma ramp. Check it periodically and reapply your settings when it changed"
+                    }
+                }
+
+                label {
+                    class: "checkbox-label",
+                    input {
+                        r#type: "checkbox",
+                        checked: controller.read().config().enforce.enabled,
+                        onchange: move |evt| update_enforce(controller, |e| e.enabled = evt.checked()),
+                    }
+                    "Reapply settings when another application changes them"
+                }
+
+                label {
+                    class: "checkbox-label",
+                    input {
+                        r#type: "checkbox",
+                        checked: controller.read().config().verify_applies,
+                        onchange: move |evt| {
+                            let mut ctrl = controller.write();
+                            ctrl.config_mut().verify_applies = evt.checked();
+                            let _ = ctrl.save();
+                        },
+                    }
+                    "Read the ramp back after every change to catch drivers that ignore it"
+                }
+
+                label {
+                    class: "checkbox-label",
+                    title: "Some docks and older drivers only accept ramps for the whole desktop. Without the fallback, applies to those monitors fail instead",
+                    input {
+                        r#type: "checkbox",
+                        checked: !controller.read().config().disable_global_dc_fallback,
+                        onchange: move |evt| {
+                            let mut ctrl = controller.write();
+                            ctrl.set_global_dc_fallback(evt.checked());
+                            let _ = ctrl.save();
+                        },
+                    }
+                    "Apply to every monitor when a monitor can't be adjusted on its own"
+                }
+
+                if controller.read().config().enforce.enabled {
+                    div {
+                        class: "sliders-grid",
+
+                        Slider {
+                            label: "Check interval (s)",
+                            value: controller.read().config().enforce.interval().as_secs_f32(),
+                            min: EnforceConfig::MIN_INTERVAL_MS as f32 / 1000.0,
+                            max: (EnforceConfig::MAX_INTERVAL_MS / 1000) as f32,
+                            step: 0.5,
+                            precision: 1,
+                            on_change: move |value: f32| {
+                                update_enforce(controller, |e| e.interval_ms = (value * 1000.0).round() as u64);
+                            },
+                        }
+                    }
+
+                    {
+                        let ctrl = controller.read();
+                        let paused = ctrl.enforce_paused();
+                        let reapplies = ctrl
+                            .selected_monitor()
+                            .map(|m| ctrl.reapplies(&m.id))
+                            .unwrap_or_default();
+                        drop(ctrl);
+
+                        rsx! {
+                            div {
+                                class: "enforce-status",
+                                button {
+                                    onclick: move |_| controller.write().set_enforce_paused(!paused),
+                                    if paused { "Resume" } else { "Pause" }
+                                }
+                                span {
+                                    match reapplies.last {
+                                        Some(last) => format!(
+                                            "Reapplied {} times on this monitor, last {}s ago",
+                                            reapplies.count,
+                                            last.elapsed().as_secs()
+                                        ),
+                                        None => "Not reapplied on this monitor yet".to_string(),
+                                    }
+                                }
+                            }
                         }
                     }
                 }
@@ -1,2 +1,8 @@
+pub mod backlight;
+pub mod ddc;
 pub mod display;
+pub mod fullscreen;
 pub mod hotkeys;
+pub mod icm;
+pub mod mouse;
+pub mod names;
@@ -0,0 +1,267 @@
+use crate::windows::display::DisplaySettings;
+#[cfg(windows)]
+use crate::windows::display::{GammaError, MonitorInfo};
+#[cfg(windows)]
+use windows::core::{w, BSTR};
+#[cfg(windows)]
+use windows::Win32::System::Com::{
+    CoCreateInstance, CoInitializeEx, CoSetProxyBlanket, CLSCTX_INPROC_SERVER,
+    COINIT_MULTITHREADED, EOAC_NONE, RPC_C_AUTHN_LEVEL_CALL, RPC_C_IMP_LEVEL_IMPERSONATE,
+};
+#[cfg(windows)]
+use windows::Win32::System::Rpc::{RPC_C_AUTHN_WINNT, RPC_C_AUTHZ_NONE};
+#[cfg(windows)]
+use windows::Win32::System::Variant::VARIANT;
+#[cfg(windows)]
+use windows::Win32::System::Wmi::{
+    IWbemClassObject, IWbemLocator, IWbemServices, WbemLocator, WBEM_FLAG_FORWARD_ONLY,
+    WBEM_FLAG_RETURN_IMMEDIATELY, WBEM_FLAG_RETURN_WBEM_COMPLETE, WBEM_INFINITE,
+};
+
+/// Map a brightness setting (-1 to 1) onto a backlight percentage. The default of 0 lands at 50%.
+pub fn brightness_to_percent(brightness: f32) -> u8 {
+    ((brightness.clamp(-1.0, 1.0) + 1.0) * 50.0).round() as u8
+}
+
+/// Map a backlight percentage back onto a brightness setting.
+pub fn percent_to_brightness(percent: u8) -> f32 {
+    percent.min(100) as f32 / 50.0 - 1.0
+}
+
+/// Settings to write to the gamma ramp when brightness is handled by the backlight.
+pub fn ramp_settings(settings: &DisplaySettings) -> DisplaySettings {
+    DisplaySettings {
+        brightness: 0.0,
+        ..*settings
+    }
+}
+
+/// Check whether a WMI brightness instance belongs to a monitor. Instance names like
+/// `DISPLAY\BOE0812\4&2a5ad8b4&0&UID265988_0` are the monitor's device path with a different
+/// separator and an instance suffix.
+pub fn instance_matches(instance_name: &str, monitor_id: &str) -> bool {
+    let device = instance_name
+        .rsplit_once('_')
+        .map_or(instance_name, |(device, _)| device)
+        .replace('\\', "#")
+        .to_uppercase();
+
+    monitor_id
+        .to_uppercase()
+        .contains(&format!("\\\\?\\{}#", device))
+}
+
+#[cfg(windows)]
+fn backlight_error(monitor: &MonitorInfo, e: windows::core::Error) -> GammaError {
+    GammaError::Backlight {
+        monitor: monitor.name.clone(),
+        detail: e.message(),
+    }
+}
+
+/// Connect to the `root\wmi` namespace, where the monitor brightness classes live.
+#[cfg(windows)]
+fn connect() -> windows::core::Result<IWbemServices> {
+    unsafe {
+        // Fails harmlessly on threads where COM is already initialized, like the UI thread
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+
+        let locator: IWbemLocator = CoCreateInstance(&WbemLocator, None, CLSCTX_INPROC_SERVER)?;
+        let services = locator.ConnectServer(
+            &BSTR::from("ROOT\\WMI"),
+            &BSTR::new(),
+            &BSTR::new(),
+            &BSTR::new(),
+            0,
+            &BSTR::new(),
+            None,
+        )?;
+
+        CoSetProxyBlanket(
+            &services,
+            RPC_C_AUTHN_WINNT,
+            RPC_C_AUTHZ_NONE,
+            None,
+            RPC_C_AUTHN_LEVEL_CALL,
+            RPC_C_IMP_LEVEL_IMPERSONATE,
+            None,
+            EOAC_NONE,
+        )?;
+
+        Ok(services)
+    }
+}
+
+/// Run a WQL query and collect every object it returns.
+#[cfg(windows)]
+fn query(services: &IWbemServices, wql: &str) -> windows::core::Result<Vec<IWbemClassObject>> {
+    let enumerator = unsafe {
+        services.ExecQuery(
+            &BSTR::from("WQL"),
+            &BSTR::from(wql),
+            WBEM_FLAG_FORWARD_ONLY | WBEM_FLAG_RETURN_IMMEDIATELY,
+            None,
+        )?
+    };
+
+    let mut objects = Vec::new();
+
+    loop {
+        let mut row = [None];
+        let mut returned = 0;
+
+        unsafe { enumerator.Next(WBEM_INFINITE, &mut row, &mut returned) }.ok()?;
+
+        match row {
+            [Some(object)] if returned == 1 => objects.push(object),
+            _ => return Ok(objects),
+        }
+    }
+}
+
+#[cfg(windows)]
+fn string_property(
+    object: &IWbemClassObject,
+    name: windows::core::PCWSTR,
+) -> windows::core::Result<String> {
+    let mut value = VARIANT::default();
+    unsafe { object.Get(name, 0, &mut value, None, None)? };
+
+    Ok(BSTR::try_from(&value)?.to_string())
+}
+
+/// Find the `WmiMonitorBrightnessMethods` instance for a monitor.
+#[cfg(windows)]
+fn find_methods(
+    services: &IWbemServices,
+    monitor: &MonitorInfo,
+) -> windows::core::Result<Option<IWbemClassObject>> {
+    for object in query(services, "SELECT * FROM WmiMonitorBrightnessMethods")? {
+        if instance_matches(&string_property(&object, w!("InstanceName"))?, &monitor.id) {
+            return Ok(Some(object));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Check whether a monitor's backlight can be set through WMI, as on most laptop panels.
+#[cfg(windows)]
+pub fn is_supported(monitor: &MonitorInfo) -> bool {
+    connect()
+        .and_then(|services| find_methods(&services, monitor))
+        .is_ok_and(|methods| methods.is_some())
+}
+
+/// Call `WmiSetBrightness` on a monitor's brightness methods instance.
+#[cfg(windows)]
+fn set_brightness(
+    services: &IWbemServices,
+    methods: &IWbemClassObject,
+    percent: u8,
+) -> windows::core::Result<()> {
+    unsafe {
+        let mut class = None;
+        services.GetObject(
+            &BSTR::from("WmiMonitorBrightnessMethods"),
+            WBEM_FLAG_RETURN_WBEM_COMPLETE,
+            None,
+            Some(&mut class),
+            None,
+        )?;
+        let class: IWbemClassObject = class.ok_or_else(windows::core::Error::empty)?;
+
+        let mut signature = None;
+        class.GetMethod(
+            w!("WmiSetBrightness"),
+            0,
+            &mut signature,
+            std::ptr::null_mut(),
+        )?;
+        let params = signature
+            .ok_or_else(windows::core::Error::empty)?
+            .SpawnInstance(0)?;
+
+        // WMI expects uint32 as VT_I4 and uint8 as VT_UI1
+        params.Put(w!("Timeout"), 0, &VARIANT::from(0i32), 0)?;
+        params.Put(w!("Brightness"), 0, &VARIANT::from(percent), 0)?;
+
+        let path = string_property(methods, w!("__PATH"))?;
+
+        services.ExecMethod(
+            &BSTR::from(path),
+            &BSTR::from("WmiSetBrightness"),
+            WBEM_FLAG_RETURN_WBEM_COMPLETE,
+            None,
+            &params,
+            None,
+            None,
+        )
+    }
+}
+
+/// Set a monitor's backlight from the brightness setting.
+#[cfg(windows)]
+pub fn apply_settings(monitor: &MonitorInfo, settings: &DisplaySettings) -> Result<(), GammaError> {
+    let services = connect().map_err(|e| backlight_error(monitor, e))?;
+    let methods = find_methods(&services, monitor)
+        .map_err(|e| backlight_error(monitor, e))?
+        .ok_or_else(|| {
+            GammaError::Unsupported(format!("{} has no backlight control", monitor.name))
+        })?;
+
+    set_brightness(
+        &services,
+        &methods,
+        brightness_to_percent(settings.brightness),
+    )
+    .map_err(|e| backlight_error(monitor, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn brightness_maps_onto_percent() {
+        assert_eq!(brightness_to_percent(-1.0), 0);
+        assert_eq!(brightness_to_percent(0.0), 50);
+        assert_eq!(brightness_to_percent(0.5), 75);
+        assert_eq!(brightness_to_percent(3.0), 100);
+    }
+
+    #[test]
+    fn percent_round_trips() {
+        for percent in 0..=100 {
+            assert_eq!(
+                brightness_to_percent(percent_to_brightness(percent)),
+                percent
+            );
+        }
+    }
+
+    #[test]
+    fn ramp_settings_drop_only_brightness() {
+        let settings = DisplaySettings::new(1.8, -0.4, 2.0);
+
+        assert_eq!(
+            ramp_settings(&settings),
+            DisplaySettings::new(1.8, 0.0, 2.0)
+        );
+    }
+
+    #[test]
+    fn instances_match_device_paths() {
+        let id =
+            "\\\\?\\DISPLAY#BOE0812#4&2a5ad8b4&0&UID265988#{e6f07b5f-ee97-4a90-b076-33f57bf4eaa7}";
+
+        assert!(instance_matches(
+            "DISPLAY\\BOE0812\\4&2a5ad8b4&0&UID265988_0",
+            id
+        ));
+        assert!(!instance_matches(
+            "DISPLAY\\BOE0812\\4&2a5ad8b4&0&UID1_0",
+            id
+        ));
+    }
+}
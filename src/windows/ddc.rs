@@ -0,0 +1,262 @@
+use crate::windows::display::DisplaySettings;
+#[cfg(windows)]
+use crate::windows::display::{GammaError, MonitorInfo};
+#[cfg(windows)]
+use windows::Win32::Devices::Display::{
+    CapabilitiesRequestAndCapabilitiesReply, DestroyPhysicalMonitors, GetCapabilitiesStringLength,
+    GetMonitorBrightness, GetMonitorContrast, GetNumberOfPhysicalMonitorsFromHMONITOR,
+    GetPhysicalMonitorsFromHMONITOR, SetMonitorBrightness, SetMonitorContrast, PHYSICAL_MONITOR,
+};
+#[cfg(windows)]
+use windows::Win32::Foundation::{GetLastError, HANDLE};
+
+pub mod capabilities;
+
+/// The range and current value of a monitor's VCP control, as reported over DDC/CI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VcpRange {
+    pub min: u32,
+    pub current: u32,
+    pub max: u32,
+}
+
+impl VcpRange {
+    /// Map a fraction of the range, from 0 to 1, onto a VCP value.
+    fn value_at(&self, fraction: f32) -> u32 {
+        let span = self.max.saturating_sub(self.min) as f32;
+
+        self.min + (fraction.clamp(0.0, 1.0) * span).round() as u32
+    }
+}
+
+/// Map a brightness setting (-1 to 1) onto the monitor's backlight range. The default of 0 lands
+/// in the middle of the range.
+pub fn brightness_to_vcp(brightness: f32, range: &VcpRange) -> u32 {
+    range.value_at((brightness + 1.0) / 2.0)
+}
+
+/// Map a contrast setting (0.1 to 3) onto the monitor's contrast range. The default of 1 lands in
+/// the middle of the range, with the reduced and boosted halves scaled separately.
+pub fn contrast_to_vcp(contrast: f32, range: &VcpRange) -> u32 {
+    let fraction = if contrast <= 1.0 {
+        (contrast - 0.1) / 0.9 * 0.5
+    } else {
+        0.5 + (contrast - 1.0) / 2.0 * 0.5
+    };
+
+    range.value_at(fraction)
+}
+
+/// Settings to write to the gamma ramp when brightness and contrast are handled by the monitor.
+pub fn ramp_settings(settings: &DisplaySettings) -> DisplaySettings {
+    DisplaySettings {
+        gamma: settings.gamma,
+        color_filter: settings.color_filter,
+        invert: settings.invert,
+        temperature: settings.temperature,
+        ..Default::default()
+    }
+}
+
+/// Physical monitor handles behind a display, released on drop.
+#[cfg(windows)]
+struct PhysicalMonitors(Vec<PHYSICAL_MONITOR>);
+
+#[cfg(windows)]
+impl PhysicalMonitors {
+    fn open(monitor: &MonitorInfo) -> Result<Self, GammaError> {
+        let hmonitor = crate::windows::display::find_hmonitor(monitor).ok_or_else(|| {
+            GammaError::MonitorNotFound {
+                id: monitor.id.clone(),
+            }
+        })?;
+
+        let mut count = 0;
+        unsafe { GetNumberOfPhysicalMonitorsFromHMONITOR(hmonitor, &mut count) }
+            .map_err(|e| ddc_error(monitor, "enumerate physical monitors", e))?;
+
+        if count == 0 {
+            return Err(GammaError::Unsupported(format!(
+                "{} has no physical monitor that supports DDC/CI",
+                monitor.name
+            )));
+        }
+
+        let mut handles = vec![PHYSICAL_MONITOR::default(); count as usize];
+        unsafe { GetPhysicalMonitorsFromHMONITOR(hmonitor, &mut handles) }
+            .map_err(|e| ddc_error(monitor, "open physical monitors", e))?;
+
+        Ok(Self(handles))
+    }
+
+    /// Iterate the physical monitor handles. Extended desktops report one per display; clone
+    /// setups report several, which all receive the same settings.
+    fn handles(&self) -> impl Iterator<Item = HANDLE> + '_ {
+        self.0.iter().map(|m| m.hPhysicalMonitor)
+    }
+}
+
+#[cfg(windows)]
+impl Drop for PhysicalMonitors {
+    fn drop(&mut self) {
+        let _ = unsafe { DestroyPhysicalMonitors(&self.0) };
+    }
+}
+
+#[cfg(windows)]
+fn ddc_error(monitor: &MonitorInfo, action: &str, e: impl std::fmt::Debug) -> GammaError {
+    GammaError::Ddc {
+        monitor: monitor.name.clone(),
+        action: action.to_string(),
+        detail: format!("{:?}", e),
+    }
+}
+
+#[cfg(windows)]
+fn brightness_range(handle: HANDLE) -> Option<VcpRange> {
+    let (mut min, mut current, mut max) = (0, 0, 0);
+    let ok = unsafe { GetMonitorBrightness(handle, &mut min, &mut current, &mut max) } != 0;
+
+    ok.then_some(VcpRange { min, current, max })
+}
+
+#[cfg(windows)]
+fn contrast_range(handle: HANDLE) -> Option<VcpRange> {
+    let (mut min, mut current, mut max) = (0, 0, 0);
+    let ok = unsafe { GetMonitorContrast(handle, &mut min, &mut current, &mut max) } != 0;
+
+    ok.then_some(VcpRange { min, current, max })
+}
+
+/// Read the backlight brightness range of a monitor.
+#[cfg(windows)]
+pub fn get_brightness(monitor: &MonitorInfo) -> Result<VcpRange, GammaError> {
+    let physical = PhysicalMonitors::open(monitor)?;
+    let range = physical.handles().find_map(brightness_range);
+
+    range.ok_or_else(|| ddc_error(monitor, "read brightness", unsafe { GetLastError() }))
+}
+
+/// Read the contrast range of a monitor.
+#[cfg(windows)]
+pub fn get_contrast(monitor: &MonitorInfo) -> Result<VcpRange, GammaError> {
+    let physical = PhysicalMonitors::open(monitor)?;
+    let range = physical.handles().find_map(contrast_range);
+
+    range.ok_or_else(|| ddc_error(monitor, "read contrast", unsafe { GetLastError() }))
+}
+
+/// Check whether a monitor's brightness can be controlled over DDC/CI.
+#[cfg(windows)]
+pub fn is_supported(monitor: &MonitorInfo) -> bool {
+    get_brightness(monitor).is_ok()
+}
+
+/// Read a monitor's MCCS capabilities string. The monitor takes up to a few seconds to reply.
+#[cfg(windows)]
+pub fn get_capabilities(monitor: &MonitorInfo) -> Result<String, GammaError> {
+    let physical = PhysicalMonitors::open(monitor)?;
+
+    for handle in physical.handles() {
+        let mut length = 0;
+
+        if unsafe { GetCapabilitiesStringLength(handle, &mut length) } == 0 || length == 0 {
+            continue;
+        }
+
+        let mut buffer = vec![0u8; length as usize];
+
+        if unsafe { CapabilitiesRequestAndCapabilitiesReply(handle, &mut buffer) } == 0 {
+            continue;
+        }
+
+        let end = buffer.iter().position(|&b| b == 0).unwrap_or(buffer.len());
+        return Ok(String::from_utf8_lossy(&buffer[..end]).into_owned());
+    }
+
+    Err(ddc_error(monitor, "read capabilities", unsafe {
+        GetLastError()
+    }))
+}
+
+/// Write brightness and contrast to a monitor over DDC/CI.
+#[cfg(windows)]
+pub fn apply_settings(monitor: &MonitorInfo, settings: &DisplaySettings) -> Result<(), GammaError> {
+    let physical = PhysicalMonitors::open(monitor)?;
+
+    for handle in physical.handles() {
+        let range = brightness_range(handle)
+            .ok_or_else(|| ddc_error(monitor, "read brightness", unsafe { GetLastError() }))?;
+
+        if unsafe { SetMonitorBrightness(handle, brightness_to_vcp(settings.brightness, &range)) }
+            == 0
+        {
+            return Err(ddc_error(monitor, "set brightness", unsafe {
+                GetLastError()
+            }));
+        }
+
+        // Contrast is optional in the MCCS spec; monitors without it keep their own
+        let Some(range) = contrast_range(handle) else {
+            continue;
+        };
+
+        if unsafe { SetMonitorContrast(handle, contrast_to_vcp(settings.contrast, &range)) } == 0 {
+            return Err(ddc_error(monitor, "set contrast", unsafe {
+                GetLastError()
+            }));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PERCENT: VcpRange = VcpRange {
+        min: 0,
+        current: 50,
+        max: 100,
+    };
+
+    #[test]
+    fn brightness_maps_onto_range() {
+        assert_eq!(brightness_to_vcp(-1.0, &PERCENT), 0);
+        assert_eq!(brightness_to_vcp(0.0, &PERCENT), 50);
+        assert_eq!(brightness_to_vcp(1.0, &PERCENT), 100);
+        assert_eq!(brightness_to_vcp(0.5, &PERCENT), 75);
+    }
+
+    #[test]
+    fn contrast_defaults_to_middle() {
+        assert_eq!(contrast_to_vcp(0.1, &PERCENT), 0);
+        assert_eq!(contrast_to_vcp(1.0, &PERCENT), 50);
+        assert_eq!(contrast_to_vcp(3.0, &PERCENT), 100);
+        assert_eq!(contrast_to_vcp(2.0, &PERCENT), 75);
+    }
+
+    #[test]
+    fn values_respect_offset_ranges() {
+        let range = VcpRange {
+            min: 20,
+            current: 40,
+            max: 80,
+        };
+
+        assert_eq!(brightness_to_vcp(-1.0, &range), 20);
+        assert_eq!(brightness_to_vcp(0.0, &range), 50);
+        assert_eq!(brightness_to_vcp(5.0, &range), 80);
+    }
+
+    #[test]
+    fn ramp_settings_keep_only_gamma_and_effects() {
+        let settings = DisplaySettings::new(1.8, -0.4, 2.0);
+
+        assert_eq!(
+            ramp_settings(&settings),
+            DisplaySettings::new(1.8, 0.0, 1.0)
+        );
+    }
+}
@@ -0,0 +1,252 @@
+/// A VCP code a monitor reports, with the values it accepts if it lists them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VcpCapability {
+    pub code: u8,
+    pub values: Vec<u8>,
+}
+
+impl VcpCapability {
+    /// Describe the code, e.g. `10 Brightness`, with its values where they're listed.
+    pub fn describe(&self) -> String {
+        let name = vcp_name(self.code).unwrap_or("Manufacturer specific");
+        let mut description = format!("{:02X} {}", self.code, name);
+
+        if !self.values.is_empty() {
+            let values: Vec<String> = self
+                .values
+                .iter()
+                .map(|&value| match value_name(self.code, value) {
+                    Some(name) => name.to_string(),
+                    None => format!("{:02X}", value),
+                })
+                .collect();
+            description.push_str(&format!(": {}", values.join(", ")));
+        }
+
+        description
+    }
+}
+
+/// What a monitor reports in its MCCS capabilities string.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    pub model: Option<String>,
+    pub mccs_version: Option<String>,
+    pub vcp: Vec<VcpCapability>,
+}
+
+/// Name the VCP codes defined by MCCS that are most commonly supported.
+pub fn vcp_name(code: u8) -> Option<&'static str> {
+    Some(match code {
+        0x02 => "New control value",
+        0x04 => "Restore factory defaults",
+        0x05 => "Restore factory brightness and contrast",
+        0x08 => "Restore factory color",
+        0x0B => "Color temperature increment",
+        0x0C => "Color temperature request",
+        0x10 => "Brightness",
+        0x12 => "Contrast",
+        0x14 => "Color preset",
+        0x16 => "Red gain",
+        0x18 => "Green gain",
+        0x1A => "Blue gain",
+        0x52 => "Active control",
+        0x60 => "Input select",
+        0x62 => "Audio volume",
+        0x6C => "Red black level",
+        0x6E => "Green black level",
+        0x70 => "Blue black level",
+        0x87 => "Sharpness",
+        0x8D => "Audio mute",
+        0xAC => "Horizontal frequency",
+        0xAE => "Vertical frequency",
+        0xB2 => "Sub-pixel layout",
+        0xB6 => "Display technology",
+        0xC0 => "Usage time",
+        0xC6 => "Application enable key",
+        0xC8 => "Display controller",
+        0xC9 => "Firmware level",
+        0xCA => "On-screen display",
+        0xCC => "OSD language",
+        0xD6 => "Power mode",
+        0xDC => "Display mode",
+        0xDF => "VCP version",
+        _ => return None,
+    })
+}
+
+/// Name the values of the color preset and input select codes.
+pub fn value_name(code: u8, value: u8) -> Option<&'static str> {
+    Some(match (code, value) {
+        (0x14, 0x01) => "sRGB",
+        (0x14, 0x02) => "Native",
+        (0x14, 0x03) => "4000K",
+        (0x14, 0x04) => "5000K",
+        (0x14, 0x05) => "6500K",
+        (0x14, 0x06) => "7500K",
+        (0x14, 0x07) => "8200K",
+        (0x14, 0x08) => "9300K",
+        (0x14, 0x09) => "10000K",
+        (0x14, 0x0A) => "11500K",
+        (0x14, 0x0B) => "User 1",
+        (0x14, 0x0C) => "User 2",
+        (0x14, 0x0D) => "User 3",
+        (0x60, 0x01) => "VGA 1",
+        (0x60, 0x02) => "VGA 2",
+        (0x60, 0x03) => "DVI 1",
+        (0x60, 0x04) => "DVI 2",
+        (0x60, 0x0F) => "DisplayPort 1",
+        (0x60, 0x10) => "DisplayPort 2",
+        (0x60, 0x11) => "HDMI 1",
+        (0x60, 0x12) => "HDMI 2",
+        (0x60, 0x1B) => "USB-C",
+        (0xD6, 0x01) => "On",
+        (0xD6, 0x02) => "Standby",
+        (0xD6, 0x03) => "Suspend",
+        (0xD6, 0x04) => "Off",
+        (0xD6, 0x05) => "Off (button)",
+        _ => return None,
+    })
+}
+
+/// Split a capabilities list into its top-level `key(value)` entries. Returns `None` if the
+/// parentheses don't balance.
+fn entries(text: &str) -> Option<Vec<(&str, &str)>> {
+    let mut entries = Vec::new();
+    let mut rest = text.trim();
+
+    while !rest.is_empty() {
+        let open = rest.find('(')?;
+        let key = rest[..open].trim();
+        let mut depth = 0;
+        let mut close = None;
+
+        for (i, c) in rest[open..].char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        close = Some(open + i);
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let close = close?;
+        entries.push((key, &rest[open + 1..close]));
+        rest = rest[close + 1..].trim_start();
+    }
+
+    Some(entries)
+}
+
+/// Parse the codes of a `vcp(...)` entry, e.g. `10 12 60(0F 11)`.
+fn parse_vcp(text: &str) -> Option<Vec<VcpCapability>> {
+    let mut codes: Vec<VcpCapability> = Vec::new();
+    let mut rest = text.trim();
+
+    while !rest.is_empty() {
+        if let Some(values) = rest.strip_prefix('(') {
+            let close = values.find(')')?;
+            let code = codes.last_mut()?;
+            code.values = values[..close]
+                .split_whitespace()
+                .map(|v| u8::from_str_radix(v, 16).ok())
+                .collect::<Option<_>>()?;
+            rest = values[close + 1..].trim_start();
+            continue;
+        }
+
+        // Codes are usually space separated, but some monitors run them together
+        let end = rest
+            .find(|c: char| c.is_whitespace() || c == '(')
+            .unwrap_or(rest.len())
+            .min(2);
+        codes.push(VcpCapability {
+            code: u8::from_str_radix(rest.get(..end)?, 16).ok()?,
+            values: Vec::new(),
+        });
+        rest = rest[end..].trim_start();
+    }
+
+    Some(codes)
+}
+
+/// Parse an MCCS capabilities string, e.g. `(prot(monitor)model(X)vcp(10 12 60(0F 11)))`.
+/// Returns `None` for strings that don't follow the format.
+pub fn parse(text: &str) -> Option<Capabilities> {
+    let text = text.trim_matches(|c: char| c.is_whitespace() || c == '\0');
+    // The outer parentheses are required but often missing
+    let text = match entries(text)?.as_slice() {
+        [("", inner)] => *inner,
+        _ => text,
+    };
+
+    let mut capabilities = Capabilities::default();
+    let mut found_vcp = false;
+
+    for (key, value) in entries(text)? {
+        match key.to_ascii_lowercase().as_str() {
+            "model" => capabilities.model = Some(value.trim().to_string()),
+            "mccs_ver" => capabilities.mccs_version = Some(value.trim().to_string()),
+            "vcp" => {
+                capabilities.vcp = parse_vcp(value)?;
+                found_vcp = true;
+            }
+            _ => {}
+        }
+    }
+
+    found_vcp.then_some(capabilities)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_vcp_codes_and_values() {
+        let capabilities = parse(
+            "(prot(monitor)type(lcd)model(U2720Q)cmds(01 02 03 07 0C E3 F3)vcp(02 04 05 10 12 \
+             14(05 08 0B) 16 18 1A 60(0F 11 1B) D6(01 04) DF)mswhql(1)mccs_ver(2.1))\0",
+        )
+        .unwrap();
+
+        assert_eq!(capabilities.model.as_deref(), Some("U2720Q"));
+        assert_eq!(capabilities.mccs_version.as_deref(), Some("2.1"));
+        assert_eq!(capabilities.vcp.len(), 12);
+
+        let input = capabilities.vcp.iter().find(|c| c.code == 0x60).unwrap();
+        assert_eq!(input.values, vec![0x0F, 0x11, 0x1B]);
+        assert_eq!(
+            input.describe(),
+            "60 Input select: DisplayPort 1, HDMI 1, USB-C"
+        );
+        assert_eq!(capabilities.vcp[3].describe(), "10 Brightness");
+    }
+
+    #[test]
+    fn accepts_missing_outer_parentheses_and_run_together_codes() {
+        let capabilities = parse("model(X)vcp(1012E0(01))").unwrap();
+        let codes: Vec<u8> = capabilities.vcp.iter().map(|c| c.code).collect();
+
+        assert_eq!(codes, vec![0x10, 0x12, 0xE0]);
+        assert_eq!(
+            capabilities.vcp[2].describe(),
+            "E0 Manufacturer specific: 01"
+        );
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert_eq!(parse(""), None);
+        assert_eq!(parse("\u{fffd}\u{fffd}\0"), None);
+        assert_eq!(parse("(vcp(10 12"), None);
+        assert_eq!(parse("(vcp(10 ZZ))"), None);
+        assert_eq!(parse("(model(X))"), None);
+        assert_eq!(parse("(vcp(1é))"), None);
+    }
+}
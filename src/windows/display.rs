@@ -1,14 +1,39 @@
+#[cfg(windows)]
+use crate::backend::{GammaBackend, PowerSource};
+#[cfg(windows)]
+use crate::windows::names::{monitor_labels, DisplayTarget};
 use serde::{Deserialize, Serialize};
-use std::{
-    error::Error,
-    fmt,
-    sync::{Arc, Mutex},
-};
+#[cfg(windows)]
+use std::collections::{HashMap, HashSet};
+use std::{error::Error, fmt, ops::RangeInclusive};
+#[cfg(windows)]
 use windows::core::{BOOL, PCWSTR};
-use windows::Win32::Foundation::{GetLastError, LPARAM, RECT};
+#[cfg(windows)]
+use windows::Win32::Foundation::{
+    GetLastError, ERROR_INVALID_HANDLE, LPARAM, POINT, RECT, WIN32_ERROR,
+};
+#[cfg(windows)]
 use windows::Win32::Graphics::Gdi::{
-    CreateDCW, DeleteDC, EnumDisplayMonitors, GetMonitorInfoW, HDC, HMONITOR, MONITORINFOEXW,
+    EnumDisplayDevicesW, EnumDisplayMonitors, EnumDisplaySettingsW, GetDeviceCaps, GetMonitorInfoW,
+    CM_GAMMA_RAMP, COLORMGMTCAPS, DEVMODEW, DISPLAY_DEVICEW, DISPLAY_DEVICE_ACTIVE, DMDO_180,
+    DMDO_270, DMDO_90, ENUM_CURRENT_SETTINGS, HDC, HMONITOR, MONITORINFOEXW,
 };
+#[cfg(windows)]
+use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+#[cfg(windows)]
+use windows::Win32::UI::HiDpi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI};
+#[cfg(windows)]
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetCursorPos, GetSystemMetrics, EDD_GET_DEVICE_INTERFACE_NAME, SM_REMOTESESSION,
+};
+
+#[cfg(windows)]
+mod dc;
+pub mod lut;
+pub mod ramp_file;
+
+pub use lut::Lut;
+pub use ramp_file::RampFormat;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MonitorInfo {
@@ -16,6 +41,153 @@ pub struct MonitorInfo {
     pub name: String,
     pub device_name: String,
     pub is_primary: bool,
+    /// The id this monitor would have had before ids were made stable, so configs that stored
+    /// it can be migrated.
+    #[serde(default)]
+    pub legacy_id: Option<String>,
+    /// Where the monitor sits on the virtual desktop.
+    #[serde(default)]
+    pub bounds: MonitorRect,
+    /// The part of the bounds not covered by the taskbar and docked toolbars.
+    #[serde(default)]
+    pub work_area: MonitorRect,
+    /// Effective DPI, where 96 is 100% scaling. Zero means unknown.
+    #[serde(default)]
+    pub dpi: u32,
+    /// The monitor's current video mode.
+    #[serde(default)]
+    pub mode: DisplayMode,
+    /// Whether HDR is enabled, in which case Windows may ignore or distort gamma ramps.
+    #[serde(default)]
+    pub is_hdr: bool,
+    /// Whether the adapter accepts gamma ramps at all. Virtual displays, some USB docks and
+    /// remote sessions don't.
+    #[serde(default = "default_supports_gamma")]
+    pub supports_gamma: bool,
+    /// The graphics adapter driving the monitor, e.g. `NVIDIA GeForce RTX 3070`. Empty when
+    /// unknown.
+    #[serde(default)]
+    pub adapter: String,
+}
+
+fn default_supports_gamma() -> bool {
+    true
+}
+
+impl MonitorInfo {
+    /// Check whether a point on the virtual desktop lies on this monitor.
+    pub fn contains_point(&self, x: i32, y: i32) -> bool {
+        self.bounds.contains(x, y)
+    }
+
+    /// Get the display scaling as a factor, e.g. 1.5 for 144 DPI. Unknown DPI counts as 1.
+    pub fn scale_factor(&self) -> f32 {
+        match self.dpi {
+            0 => 1.0,
+            dpi => dpi as f32 / 96.0,
+        }
+    }
+}
+
+/// A rectangle on the virtual desktop, in pixels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MonitorRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl MonitorRect {
+    pub(crate) fn right(&self) -> i32 {
+        self.x + self.width as i32
+    }
+
+    pub(crate) fn bottom(&self) -> i32 {
+        self.y + self.height as i32
+    }
+
+    /// Check whether a point lies inside the rectangle. The right and bottom edges belong to the
+    /// neighbouring rectangle.
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+    }
+}
+
+/// How a monitor's picture is rotated, clockwise from its native landscape orientation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Orientation {
+    #[default]
+    Landscape,
+    Portrait,
+    LandscapeFlipped,
+    PortraitFlipped,
+}
+
+/// A monitor's resolution, refresh rate and rotation. Zero means unknown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DisplayMode {
+    pub width: u32,
+    pub height: u32,
+    pub refresh_rate: u32,
+    #[serde(default)]
+    pub orientation: Orientation,
+}
+
+/// A color effect folded into the gamma ramp.
+///
+/// Ramps map each channel independently, so these can only reweight red, green and blue, never
+/// mix them. Grayscale mutes colors rather than removing them, and the color-blindness presets
+/// approximate how the affected channel fades.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ColorFilter {
+    #[default]
+    None,
+    Grayscale,
+    Deuteranopia,
+    Protanopia,
+}
+
+impl ColorFilter {
+    /// Every filter, in the order they're offered.
+    pub const ALL: [ColorFilter; 4] = [
+        ColorFilter::None,
+        ColorFilter::Grayscale,
+        ColorFilter::Deuteranopia,
+        ColorFilter::Protanopia,
+    ];
+
+    /// Scale applied to the red, green and blue outputs.
+    pub fn channel_gains(&self) -> [f32; 3] {
+        match self {
+            ColorFilter::None => [1.0, 1.0, 1.0],
+            // Rec. 709 luma weights, normalized to green, then halved toward neutral so the
+            // picture stays usable
+            ColorFilter::Grayscale => [0.65, 1.0, 0.58],
+            ColorFilter::Deuteranopia => [1.0, 0.6, 1.0],
+            ColorFilter::Protanopia => [0.45, 0.85, 1.0],
+        }
+    }
+
+    /// Format the filter for display.
+    pub fn format(&self) -> &'static str {
+        match self {
+            ColorFilter::None => "None",
+            ColorFilter::Grayscale => "Grayscale (approximate)",
+            ColorFilter::Deuteranopia => "Deuteranopia simulation",
+            ColorFilter::Protanopia => "Protanopia simulation",
+        }
+    }
+}
+
+/// How the brightness setting changes the ramp.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BrightnessMode {
+    /// Add brightness to every level, which lifts black towards grey when positive.
+    #[default]
+    Offset,
+    /// Scale every level, which moves the white point and keeps black black.
+    PreserveBlack,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -23,6 +195,120 @@ pub struct DisplaySettings {
     pub gamma: f32,
     pub brightness: f32,
     pub contrast: f32,
+    /// Level contrast scales around, from 0 to 1. Levels at the pivot stay put.
+    #[serde(default = "default_contrast_pivot")]
+    pub contrast_pivot: f32,
+    #[serde(default)]
+    pub color_filter: ColorFilter,
+    /// Reverse the ramp so dark becomes light.
+    #[serde(default)]
+    pub invert: bool,
+    /// White point in Kelvin. Neutral is 6500K; lower values are warmer.
+    #[serde(default = "default_temperature")]
+    pub temperature: u32,
+    #[serde(default)]
+    pub brightness_mode: BrightnessMode,
+    /// Output level black maps to, from 0 to 1. Raising it limits the output range, e.g. to
+    /// video levels for a projector.
+    #[serde(default)]
+    pub output_min: f32,
+    /// Output level white maps to, from 0 to 1.
+    #[serde(default = "default_output_max")]
+    pub output_max: f32,
+    /// Tint from green (-1) to magenta (1).
+    #[serde(default)]
+    pub green_magenta: f32,
+    /// Tint from blue (-1) to amber (1).
+    #[serde(default)]
+    pub blue_amber: f32,
+    /// Share of the final output to dim away, from 0 to `MAX_EXTRA_DIMMING`. It dims past what
+    /// brightness allows without crushing shadows.
+    #[serde(default)]
+    pub extra_dimming: f32,
+}
+
+fn default_temperature() -> u32 {
+    NEUTRAL_TEMPERATURE
+}
+
+fn default_output_max() -> f32 {
+    1.0
+}
+
+fn default_contrast_pivot() -> f32 {
+    0.5
+}
+
+/// Most extra dimming allowed, which keeps at least a tenth of the light so the screen stays
+/// readable enough to undo it.
+pub const MAX_EXTRA_DIMMING: f32 = 0.9;
+
+/// The color temperature that leaves the white point unchanged.
+pub const NEUTRAL_TEMPERATURE: u32 = 6500;
+
+/// Coolest supported color temperature.
+pub const MAX_TEMPERATURE: u32 = 10000;
+
+/// Warmest supported color temperature.
+pub const MIN_TEMPERATURE: u32 = 1000;
+
+/// Approximate the red, green and blue gains of a blackbody at `kelvin`, using Tanner Helland's
+/// curve fit and normalized so the neutral temperature is exactly white.
+pub fn temperature_gains(kelvin: u32) -> [f32; 3] {
+    fn blackbody(kelvin: u32) -> [f32; 3] {
+        let t = kelvin.clamp(MIN_TEMPERATURE, MAX_TEMPERATURE) as f32 / 100.0;
+
+        let red = if t <= 66.0 {
+            255.0
+        } else {
+            329.698_73 * (t - 60.0).powf(-0.133_204_76)
+        };
+
+        let green = if t <= 66.0 {
+            99.470_8 * t.ln() - 161.119_57
+        } else {
+            288.122_16 * (t - 60.0).powf(-0.075_514_85)
+        };
+
+        let blue = if t >= 66.0 {
+            255.0
+        } else if t <= 19.0 {
+            0.0
+        } else {
+            138.517_73 * (t - 10.0).ln() - 305.044_8
+        };
+
+        [red, green, blue].map(|c| c.clamp(0.0, 255.0) / 255.0)
+    }
+
+    let neutral = blackbody(NEUTRAL_TEMPERATURE);
+    let gains = blackbody(kelvin);
+
+    std::array::from_fn(|i| (gains[i] / neutral[i]).min(1.0))
+}
+
+/// How far a full tint attenuates the channels it pulls away from.
+const TINT_STRENGTH: f32 = 0.5;
+
+/// Get the red, green and blue gains of a tint. Channels are only ever attenuated, so a tint never
+/// clips.
+pub fn tint_gains(green_magenta: f32, blue_amber: f32) -> [f32; 3] {
+    let green_magenta = green_magenta.clamp(-1.0, 1.0) * TINT_STRENGTH;
+    let blue_amber = blue_amber.clamp(-1.0, 1.0) * TINT_STRENGTH;
+
+    // Magenta dims green, green dims red and blue
+    let magenta = [
+        1.0 - (-green_magenta).max(0.0),
+        1.0 - green_magenta.max(0.0),
+    ];
+    // Amber dims blue, blue dims red and green
+    let amber = [1.0 - (-blue_amber).max(0.0), 1.0 - blue_amber.max(0.0)];
+
+    [
+        magenta[0] * amber[0],
+        magenta[1] * amber[0],
+        magenta[0] * amber[1],
+    ]
 }
 
 impl Default for DisplaySettings {
@@ -31,34 +317,575 @@ impl Default for DisplaySettings {
             gamma: 1.0,
             brightness: 0.0,
             contrast: 1.0,
+            contrast_pivot: 0.5,
+            color_filter: ColorFilter::None,
+            invert: false,
+            temperature: NEUTRAL_TEMPERATURE,
+            brightness_mode: BrightnessMode::Offset,
+            output_min: 0.0,
+            output_max: 1.0,
+            green_magenta: 0.0,
+            blue_amber: 0.0,
+            extra_dimming: 0.0,
         }
     }
 }
 
+/// The gamma range, which `DisplaySettings::new` clamps to.
+pub const GAMMA_RANGE: RangeInclusive<f32> = 0.1..=3.0;
+
+/// The brightness range, which `DisplaySettings::new` clamps to.
+pub const BRIGHTNESS_RANGE: RangeInclusive<f32> = -1.0..=1.0;
+
+/// The contrast range, which `DisplaySettings::new` clamps to.
+pub const CONTRAST_RANGE: RangeInclusive<f32> = 0.1..=3.0;
+
+fn clamp_to(value: f32, range: &RangeInclusive<f32>) -> f32 {
+    value.clamp(*range.start(), *range.end())
+}
+
 impl DisplaySettings {
     pub fn new(gamma: f32, brightness: f32, contrast: f32) -> Self {
         Self {
-            gamma: gamma.clamp(0.1, 3.0),
-            brightness: brightness.clamp(-1.0, 1.0),
-            contrast: contrast.clamp(0.1, 3.0),
+            gamma: clamp_to(gamma, &GAMMA_RANGE),
+            brightness: clamp_to(brightness, &BRIGHTNESS_RANGE),
+            contrast: clamp_to(contrast, &CONTRAST_RANGE),
+            contrast_pivot: 0.5,
+            color_filter: ColorFilter::None,
+            invert: false,
+            temperature: NEUTRAL_TEMPERATURE,
+            brightness_mode: BrightnessMode::Offset,
+            output_min: 0.0,
+            output_max: 1.0,
+            green_magenta: 0.0,
+            blue_amber: 0.0,
+            extra_dimming: 0.0,
         }
     }
+
+    /// Linearly interpolate between two settings, where `t` of 0 yields `a` and 1 yields `b`.
+    /// The color filter, inversion and brightness mode switch halfway.
+    pub fn lerp(a: DisplaySettings, b: DisplaySettings, t: f32) -> DisplaySettings {
+        let t = t.clamp(0.0, 1.0);
+
+        DisplaySettings {
+            gamma: a.gamma + (b.gamma - a.gamma) * t,
+            brightness: a.brightness + (b.brightness - a.brightness) * t,
+            contrast: a.contrast + (b.contrast - a.contrast) * t,
+            contrast_pivot: a.contrast_pivot + (b.contrast_pivot - a.contrast_pivot) * t,
+            color_filter: if t < 0.5 {
+                a.color_filter
+            } else {
+                b.color_filter
+            },
+            invert: if t < 0.5 { a.invert } else { b.invert },
+            brightness_mode: if t < 0.5 {
+                a.brightness_mode
+            } else {
+                b.brightness_mode
+            },
+            temperature: (a.temperature as f32 + (b.temperature as f32 - a.temperature as f32) * t)
+                .round() as u32,
+            output_min: a.output_min + (b.output_min - a.output_min) * t,
+            output_max: a.output_max + (b.output_max - a.output_max) * t,
+            green_magenta: a.green_magenta + (b.green_magenta - a.green_magenta) * t,
+            blue_amber: a.blue_amber + (b.blue_amber - a.blue_amber) * t,
+            extra_dimming: a.extra_dimming + (b.extra_dimming - a.extra_dimming) * t,
+        }
+    }
+
+    /// Clamp every setting into its range, as `new` does for gamma, brightness and contrast.
+    pub fn clamped(self) -> Self {
+        Self {
+            gamma: clamp_to(self.gamma, &GAMMA_RANGE),
+            brightness: clamp_to(self.brightness, &BRIGHTNESS_RANGE),
+            contrast: clamp_to(self.contrast, &CONTRAST_RANGE),
+            contrast_pivot: self.contrast_pivot.clamp(0.0, 1.0),
+            temperature: self.temperature.clamp(MIN_TEMPERATURE, MAX_TEMPERATURE),
+            output_min: self.output_min.clamp(0.0, 1.0),
+            output_max: self.output_max.clamp(0.0, 1.0),
+            green_magenta: self.green_magenta.clamp(-1.0, 1.0),
+            blue_amber: self.blue_amber.clamp(-1.0, 1.0),
+            extra_dimming: self.extra_dimming.clamp(0.0, MAX_EXTRA_DIMMING),
+            ..self
+        }
+    }
+
+    /// Check that gamma, brightness and contrast lie within the ranges `new` clamps to.
+    pub fn validate(&self) -> Result<(), GammaError> {
+        let checks = [
+            ("Gamma", self.gamma, GAMMA_RANGE),
+            ("Brightness", self.brightness, BRIGHTNESS_RANGE),
+            ("Contrast", self.contrast, CONTRAST_RANGE),
+        ];
+
+        for (setting, value, range) in checks {
+            if !range.contains(&value) {
+                return Err(GammaError::OutOfRange {
+                    setting,
+                    min: *range.start(),
+                    max: *range.end(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check whether the output range is empty or reversed, which flattens or inverts the
+    /// picture.
+    pub fn output_range_inverted(&self) -> bool {
+        self.output_min >= self.output_max
+    }
 }
 
+/// Number of entries per color channel in a gamma ramp.
+pub const RAMP_SIZE: usize = 256;
+
+/// A complete gamma ramp in the layout `SetDeviceGammaRamp` expects: 256 red entries, followed by
+/// 256 green and 256 blue entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GammaRamp([u16; RAMP_SIZE * 3]);
+
+impl GammaRamp {
+    /// Wrap raw ramp values.
+    pub fn from_raw(values: [u16; RAMP_SIZE * 3]) -> Self {
+        Self(values)
+    }
+
+    /// Get the raw ramp values.
+    pub fn as_raw(&self) -> &[u16; RAMP_SIZE * 3] {
+        &self.0
+    }
+
+    /// Get the red channel entries.
+    pub fn red(&self) -> &[u16] {
+        &self.0[..RAMP_SIZE]
+    }
+
+    /// Get the green channel entries.
+    pub fn green(&self) -> &[u16] {
+        &self.0[RAMP_SIZE..RAMP_SIZE * 2]
+    }
+
+    /// Get the blue channel entries.
+    pub fn blue(&self) -> &[u16] {
+        &self.0[RAMP_SIZE * 2..]
+    }
+
+    /// Get the largest per-entry difference from another ramp.
+    pub fn max_difference(&self, other: &GammaRamp) -> u16 {
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .map(|(a, b)| a.abs_diff(*b))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// How many input levels of a ramp are flattened into its darkest or brightest output, where
+/// their detail is lost.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Clipping {
+    pub shadows: usize,
+    pub highlights: usize,
+}
+
+impl Clipping {
+    /// Share of clipped levels above which the loss of detail is obvious.
+    pub const SEVERE_PERCENT: f32 = 10.0;
+
+    /// Count the levels that repeat the green channel's lowest or highest output. The one level
+    /// that naturally lands there doesn't count.
+    pub fn of(ramp: &GammaRamp) -> Self {
+        let green = ramp.green();
+        let (Some(&low), Some(&high)) = (green.iter().min(), green.iter().max()) else {
+            return Self::default();
+        };
+
+        let count = |value: u16| green.iter().filter(|&&v| v == value).count() - 1;
+
+        if low == high {
+            return Self {
+                shadows: 0,
+                highlights: RAMP_SIZE - 1,
+            };
+        }
+
+        Self {
+            shadows: count(low),
+            highlights: count(high),
+        }
+    }
+
+    /// Get the clipped share of levels, from 0 to 100.
+    pub fn percent(&self) -> f32 {
+        (self.shadows + self.highlights) as f32 * 100.0 / RAMP_SIZE as f32
+    }
+
+    /// Name the ends that clip, for display.
+    pub fn describe(&self) -> String {
+        match (self.shadows, self.highlights) {
+            (0, 0) => "Nothing is clipped".to_string(),
+            (shadows, 0) => format!("{} levels are crushed into black", shadows),
+            (0, highlights) => format!("{} levels are blown out to white", highlights),
+            (shadows, highlights) => format!(
+                "{} levels are crushed into black and {} blown out to white",
+                shadows, highlights
+            ),
+        }
+    }
+}
+
+/// Largest per-entry difference between two ramps that is still considered the same picture,
+/// roughly one 8-bit output level.
+pub const RAMP_TOLERANCE: u16 = 256;
+
+/// Compute the gamma ramp for the given display settings.
+///
+/// Each input level is raised to `1 / gamma`, scaled around the pivot by `contrast`, offset by
+/// `brightness` (or scaled by `1 + brightness` when preserving black), clamped to the valid
+/// output range and mapped into `output_min..output_max`. Finally each channel is scaled by the
+/// color temperature's, tint's and color filter's gains and by what extra dimming leaves.
+/// Inverting maps entry `i` to what entry `255 - i` would have produced.
+///
+/// The math runs in f64 and each entry's rounding error is carried into the next, so the
+/// average level across neighbouring entries matches the curve and gradients don't band. Entries
+/// never step against the curve, even where clipping flattens it.
+pub fn compute_gamma_ramp(settings: &DisplaySettings) -> GammaRamp {
+    let mut ramp = [0u16; RAMP_SIZE * 3];
+    let filter = settings.color_filter.channel_gains();
+    let white = temperature_gains(settings.temperature);
+    let tint = tint_gains(settings.green_magenta, settings.blue_amber);
+    let dimming = 1.0 - settings.extra_dimming.clamp(0.0, MAX_EXTRA_DIMMING) as f64;
+    let gains: [f64; 3] =
+        std::array::from_fn(|i| filter[i] as f64 * white[i] as f64 * tint[i] as f64 * dimming);
+    let output_min = settings.output_min.clamp(0.0, 1.0) as f64;
+    let output_max = settings.output_max.clamp(0.0, 1.0) as f64;
+    let pivot = settings.contrast_pivot.clamp(0.0, 1.0) as f64;
+    let (gamma, contrast, brightness) = (
+        settings.gamma as f64,
+        settings.contrast as f64,
+        settings.brightness as f64,
+    );
+
+    let levels: [f64; RAMP_SIZE] = std::array::from_fn(|level| {
+        let input = level as f64 / (RAMP_SIZE - 1) as f64;
+        let curve = (input.powf(1.0 / gamma) - pivot) * contrast + pivot;
+        let value = match settings.brightness_mode {
+            BrightnessMode::Offset => curve + brightness,
+            BrightnessMode::PreserveBlack => curve.max(0.0) * (1.0 + brightness),
+        }
+        .clamp(0.0, 1.0);
+
+        output_min + value * (output_max - output_min)
+    });
+
+    for (channel, gain) in gains.iter().enumerate() {
+        let mut carry = 0.0;
+        let mut previous = 0.0;
+
+        for (level, value) in levels.iter().enumerate() {
+            let target = value * gain * 65535.0;
+            let entry = (target + carry).round().clamp(previous, 65535.0);
+
+            // Flat stretches take no carry, so they stay exactly flat
+            carry = if level > 0 && *value == levels[level - 1] {
+                0.0
+            } else {
+                target + carry - entry
+            };
+            previous = entry;
+
+            let i = if settings.invert {
+                RAMP_SIZE - 1 - level
+            } else {
+                level
+            };
+            ramp[i + RAMP_SIZE * channel] = entry as u16;
+        }
+    }
+
+    GammaRamp(ramp)
+}
+
+/// Approximate the display settings that produce a measured ramp.
+///
+/// Ramps written by other tools rarely follow Gammar's formula exactly, so this searches gamma in
+/// slider-sized steps and, for each candidate, solves contrast and brightness by least squares
+/// over the green channel entries that aren't clipped. The candidate with the smallest error
+/// against the full ramp wins.
+pub fn fit_display_settings(ramp: &GammaRamp) -> DisplaySettings {
+    let measured: Vec<f32> = ramp.green().iter().map(|&v| v as f32 / 65535.0).collect();
+    let mut best = (f32::MAX, DisplaySettings::default());
+
+    for step in 10..=300 {
+        let gamma = step as f32 / 100.0;
+        let curve: Vec<f32> = (0..RAMP_SIZE)
+            .map(|i| (i as f32 / (RAMP_SIZE - 1) as f32).powf(1.0 / gamma) - 0.5)
+            .collect();
+
+        // Fit measured = contrast * curve + offset over the unclipped entries
+        let (mut n, mut sx, mut sy, mut sxx, mut sxy) = (0.0, 0.0, 0.0, 0.0, 0.0);
+
+        for (&x, &y) in curve.iter().zip(measured.iter()) {
+            if y > 0.0 && y < 1.0 {
+                n += 1.0;
+                sx += x;
+                sy += y;
+                sxx += x * x;
+                sxy += x * y;
+            }
+        }
+
+        let denominator = n * sxx - sx * sx;
+
+        if n < 2.0 || denominator.abs() < f32::EPSILON {
+            continue;
+        }
+
+        let contrast = (n * sxy - sx * sy) / denominator;
+        let offset = (sy - contrast * sx) / n;
+        let candidate = DisplaySettings::new(gamma, offset - 0.5, contrast);
+
+        let error: f32 = compute_gamma_ramp(&candidate)
+            .green()
+            .iter()
+            .zip(measured.iter())
+            .map(|(&v, &y)| (v as f32 / 65535.0 - y).powi(2))
+            .sum();
+
+        if error < best.0 {
+            best = (error, candidate);
+        }
+    }
+
+    best.1
+}
+
+/// Errors from enumerating monitors and applying settings to them.
 #[derive(Debug)]
-pub struct GammaError(String);
+pub enum GammaError {
+    /// No device context could be created for the monitor's device, even through its adapter.
+    DcCreationFailed {
+        monitor: String,
+        device: String,
+        adapter: String,
+    },
+    /// The driver rejected the gamma ramp with the given Win32 error code.
+    RampRejected { monitor: String, win32: u32 },
+    /// The driver wouldn't return the gamma ramp currently loaded.
+    RampUnreadable { monitor: String, win32: u32 },
+    /// The ramp was rejected while HDR is enabled on the monitor.
+    HdrActive { monitor: String, win32: u32 },
+    /// The ramp strays further from identity than `GdiIcmGammaRange` allows.
+    RangeRestricted { monitor: String },
+    /// The driver reported success, but reading the ramp back showed it wasn't loaded.
+    RampNotApplied { monitor: String },
+    /// No attached monitor has the given id.
+    MonitorNotFound { id: String },
+    /// No saved profile has the given id.
+    ProfileNotFound { id: u64 },
+    /// Another saved profile already has the name.
+    DuplicateProfileName { name: String },
+    /// The profile is locked against changes and deletion.
+    ProfileLocked { name: String },
+    /// A setting lies outside the range the sliders allow.
+    OutOfRange {
+        setting: &'static str,
+        min: f32,
+        max: f32,
+    },
+    /// The platform or monitor doesn't support the operation.
+    Unsupported(String),
+    /// A DDC/CI request to the monitor failed.
+    Ddc {
+        monitor: String,
+        action: String,
+        detail: String,
+    },
+    /// Setting a laptop panel's backlight through WMI failed.
+    Backlight { monitor: String, detail: String },
+    /// A LUT file couldn't be parsed.
+    InvalidLut(String),
+    /// A profiles file couldn't be parsed.
+    InvalidProfiles(String),
+    /// A keybinds file couldn't be parsed.
+    InvalidKeybinds(String),
+    /// Reading or writing a file failed.
+    Io {
+        context: String,
+        source: std::io::Error,
+    },
+    /// Any other failure reported by the platform, e.g. a failed X11 request.
+    Platform(String),
+}
 
 impl fmt::Display for GammaError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Gamma control error: {}", self.0)
+        match self {
+            Self::DcCreationFailed {
+                monitor,
+                device,
+                adapter,
+            } if adapter.is_empty() => write!(
+                f,
+                "Failed to create a device context for {} ({})",
+                monitor, device
+            ),
+            Self::DcCreationFailed {
+                monitor,
+                device,
+                adapter,
+            } => write!(
+                f,
+                "Failed to create a device context for {} ({}) on {}",
+                monitor, device, adapter
+            ),
+            Self::RampRejected { monitor, win32 } => write!(
+                f,
+                "Failed to set gamma ramp for {} (error {})",
+                monitor, win32
+            ),
+            Self::RampUnreadable { monitor, win32 } => write!(
+                f,
+                "Failed to read gamma ramp for {} (error {})",
+                monitor, win32
+            ),
+            Self::HdrActive { monitor, win32 } => write!(
+                f,
+                "Failed to set gamma ramp for {} (error {}). HDR is enabled on this monitor, and \
+                 Windows rejects or ignores gamma ramps while it's on; turn HDR off in Windows \
+                 display settings to use Gammar here",
+                monitor, win32
+            ),
+            Self::RangeRestricted { monitor } => write!(
+                f,
+                "Windows rejected the gamma ramp for {}: it's outside the range Windows allows by \
+                 default. Unlock the full range in Settings (this sets the GdiIcmGammaRange \
+                 registry value) and reboot, or use less extreme settings",
+                monitor
+            ),
+            Self::RampNotApplied { monitor } => write!(
+                f,
+                "The driver accepted but did not apply the ramp for {}. Other color tools, Night \
+                 light or the GPU control panel's color settings may be overriding it; close or \
+                 reset them, update the graphics driver, or try hardware control instead",
+                monitor
+            ),
+            Self::MonitorNotFound { id } => write!(f, "Monitor {} not found", id),
+            Self::ProfileNotFound { id } => write!(f, "Profile {} not found", id),
+            Self::DuplicateProfileName { name } => {
+                write!(f, "A profile named {} already exists", name)
+            }
+            Self::ProfileLocked { name } => {
+                write!(f, "{} is locked; unlock it to change or delete it", name)
+            }
+            Self::OutOfRange { setting, min, max } => {
+                write!(f, "{} must be between {} and {}", setting, min, max)
+            }
+            Self::Ddc {
+                monitor,
+                action,
+                detail,
+            } => write!(f, "DDC/CI failed to {} for {}: {}", action, monitor, detail),
+            Self::Backlight { monitor, detail } => {
+                write!(f, "Failed to set the backlight of {}: {}", monitor, detail)
+            }
+            Self::Io { context, source } => write!(f, "{}: {}", context, source),
+            Self::Unsupported(message)
+            | Self::InvalidLut(message)
+            | Self::InvalidProfiles(message)
+            | Self::InvalidKeybinds(message)
+            | Self::Platform(message) => f.write_str(message),
+        }
     }
 }
 
-impl Error for GammaError {}
+impl Error for GammaError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
 
-pub fn enumerate_monitors() -> Vec<MonitorInfo> {
-    let monitors: Arc<Mutex<Vec<MonitorInfo>>> = Arc::new(Mutex::new(Vec::new()));
-    let monitors_clone = monitors.clone();
+/// Find a monitor by id, or the primary monitor (falling back to the first one) when no id is
+/// given. Legacy ids are resolved too.
+pub fn find_monitor(monitors: &[MonitorInfo], id: Option<&str>) -> Option<MonitorInfo> {
+    if let Some(id) = id {
+        return monitors
+            .iter()
+            .find(|m| m.id == id)
+            .or_else(|| monitors.iter().find(|m| m.legacy_id.as_deref() == Some(id)))
+            .cloned();
+    }
+
+    monitors
+        .iter()
+        .find(|m| m.is_primary)
+        .cloned()
+        .or_else(|| monitors.first().cloned())
+}
+
+/// Describe a monitor's mode and where it sits relative to the primary monitor, e.g.
+/// "2560×1440 @ 144Hz, left of primary".
+pub fn describe_monitor(monitor: &MonitorInfo, monitors: &[MonitorInfo]) -> String {
+    let mut parts = Vec::new();
+
+    let mode = &monitor.mode;
+    if mode.width > 0 && mode.height > 0 {
+        parts.push(if mode.refresh_rate > 0 {
+            format!("{}×{} @ {}Hz", mode.width, mode.height, mode.refresh_rate)
+        } else {
+            format!("{}×{}", mode.width, mode.height)
+        });
+    }
+
+    let primary = monitors.iter().find(|m| m.is_primary && m.id != monitor.id);
+
+    if let Some(primary) = primary.filter(|_| !monitor.is_primary) {
+        let (bounds, origin) = (&monitor.bounds, &primary.bounds);
+
+        let placement = if bounds.right() <= origin.x {
+            Some("left of primary")
+        } else if bounds.x >= origin.right() {
+            Some("right of primary")
+        } else if bounds.bottom() <= origin.y {
+            Some("above primary")
+        } else if bounds.y >= origin.bottom() {
+            Some("below primary")
+        } else {
+            None
+        };
+
+        parts.extend(placement.map(str::to_string));
+    }
+
+    if !monitor.adapter.is_empty() {
+        parts.push(format!("on {}", monitor.adapter));
+    }
+
+    parts.join(", ")
+}
+
+/// A display monitor handle along with its GDI device name.
+#[cfg(windows)]
+struct MonitorHandle {
+    hmonitor: HMONITOR,
+    device_name: String,
+    is_primary: bool,
+    bounds: MonitorRect,
+    work_area: MonitorRect,
+    dpi: u32,
+}
+
+/// List the handles of all display monitors.
+#[cfg(windows)]
+fn monitor_handles() -> Vec<MonitorHandle> {
+    let mut handles: Vec<MonitorHandle> = Vec::new();
 
     extern "system" fn monitor_enum_proc(
         hmonitor: HMONITOR,
@@ -66,7 +893,7 @@ pub fn enumerate_monitors() -> Vec<MonitorInfo> {
         _rect: *mut RECT,
         lparam: LPARAM,
     ) -> BOOL {
-        let monitors = unsafe { &*(lparam.0 as *const Arc<Mutex<Vec<MonitorInfo>>>) };
+        let handles = unsafe { &mut *(lparam.0 as *mut Vec<MonitorHandle>) };
 
         let mut monitor_info = MONITORINFOEXW {
             monitorInfo: windows::Win32::Graphics::Gdi::MONITORINFO {
@@ -80,24 +907,23 @@ pub fn enumerate_monitors() -> Vec<MonitorInfo> {
             unsafe { GetMonitorInfoW(hmonitor, &mut monitor_info.monitorInfo as *mut _ as *mut _) };
 
         if info_result.as_bool() {
-            let device_name_end = monitor_info
-                .szDevice
-                .iter()
-                .position(|&c| c == 0)
-                .unwrap_or(monitor_info.szDevice.len());
-
-            let device_name = String::from_utf16_lossy(&monitor_info.szDevice[..device_name_end]);
-            let is_primary = (monitor_info.monitorInfo.dwFlags & 1) != 0;
-
-            monitors.lock().unwrap().push(MonitorInfo {
-                id: format!("{:?}", hmonitor.0 as usize),
-                name: if is_primary {
-                    format!("{} (Primary)", device_name)
-                } else {
-                    device_name.clone()
-                },
-                device_name,
-                is_primary,
+            let rect = |rect: RECT| MonitorRect {
+                x: rect.left,
+                y: rect.top,
+                width: (rect.right - rect.left) as u32,
+                height: (rect.bottom - rect.top) as u32,
+            };
+
+            let (mut dpi, mut dpi_y) = (0, 0);
+            let _ = unsafe { GetDpiForMonitor(hmonitor, MDT_EFFECTIVE_DPI, &mut dpi, &mut dpi_y) };
+
+            handles.push(MonitorHandle {
+                hmonitor,
+                device_name: from_wide(&monitor_info.szDevice),
+                is_primary: (monitor_info.monitorInfo.dwFlags & 1) != 0,
+                bounds: rect(monitor_info.monitorInfo.rcMonitor),
+                work_area: rect(monitor_info.monitorInfo.rcWork),
+                dpi,
             });
         }
 
@@ -109,74 +935,999 @@ pub fn enumerate_monitors() -> Vec<MonitorInfo> {
             None,
             None,
             Some(monitor_enum_proc),
-            LPARAM(&monitors_clone as *const _ as isize),
+            LPARAM(&mut handles as *mut _ as isize),
         )
     };
 
-    let mut result = monitors.lock().unwrap().clone();
-    result.sort_by(|a, b| b.is_primary.cmp(&a.is_primary));
+    handles
+}
+
+/// Convert a nul-terminated UTF-16 buffer to a string.
+#[cfg(windows)]
+pub(crate) fn from_wide(buffer: &[u16]) -> String {
+    let end = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
 
-    result
+    String::from_utf16_lossy(&buffer[..end])
 }
 
-pub fn apply_display_settings_to_monitor(
-    settings: DisplaySettings,
-    monitor: &MonitorInfo,
-) -> Result<(), GammaError> {
-    #[link(name = "gdi32")]
-    extern "system" {
-        fn SetDeviceGammaRamp(hdc: *mut std::ffi::c_void, lpRamp: *const u16) -> i32;
+/// Convert a string to a nul-terminated UTF-16 buffer.
+#[cfg(windows)]
+fn to_wide(value: &str) -> Vec<u16> {
+    value.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+/// Get the device interface path of the active monitor attached to a GDI display device, e.g.
+/// `\\?\DISPLAY#DEL40F4#5&2b4d0f0&0&UID4353#{e6f07b5f-...}`. It's built from the EDID
+/// manufacturer and product code plus the connector, so unlike an HMONITOR it survives reboots
+/// and re-enumeration.
+#[cfg(windows)]
+fn monitor_device_path(device_name: &str) -> Option<String> {
+    let device_name_wide = to_wide(device_name);
+
+    for index in 0.. {
+        let mut device = DISPLAY_DEVICEW {
+            cb: std::mem::size_of::<DISPLAY_DEVICEW>() as u32,
+            ..Default::default()
+        };
+
+        let found = unsafe {
+            EnumDisplayDevicesW(
+                PCWSTR(device_name_wide.as_ptr()),
+                index,
+                &mut device,
+                EDD_GET_DEVICE_INTERFACE_NAME,
+            )
+        };
+
+        if !found.as_bool() {
+            return None;
+        }
+
+        if device.StateFlags.0 & DISPLAY_DEVICE_ACTIVE.0 != 0 {
+            return Some(from_wide(&device.DeviceID));
+        }
     }
 
-    // Build gamma ramp array
-    let mut ramp = [0u16; 768];
+    None
+}
+
+/// Map each graphics adapter output's GDI device name, e.g. `\\.\DISPLAY1`, to the name of the
+/// adapter behind it. On hybrid laptops this tells the integrated and discrete GPU apart.
+#[cfg(windows)]
+fn adapter_names() -> HashMap<String, String> {
+    let mut adapters = HashMap::new();
+
+    for index in 0.. {
+        let mut device = DISPLAY_DEVICEW {
+            cb: std::mem::size_of::<DISPLAY_DEVICEW>() as u32,
+            ..Default::default()
+        };
 
-    for i in 0..256 {
-        let value = ((((i as f32 / 255.0).powf(1.0 / settings.gamma) - 0.5) * settings.contrast
-            + 0.5
-            + settings.brightness)
-            .clamp(0.0, 1.0)
-            * 65535.0) as u16;
+        if !unsafe { EnumDisplayDevicesW(PCWSTR::null(), index, &mut device, 0) }.as_bool() {
+            break;
+        }
 
-        ramp[i] = value;
-        ramp[i + 256] = value;
-        ramp[i + 512] = value;
+        adapters.insert(
+            from_wide(&device.DeviceName),
+            from_wide(&device.DeviceString),
+        );
     }
 
-    // Convert device name to wide string
-    let device_name_wide: Vec<u16> = monitor
-        .device_name
-        .encode_utf16()
-        .chain(std::iter::once(0))
-        .collect();
+    adapters
+}
+
+/// Read the current video mode of a GDI display device.
+#[cfg(windows)]
+fn display_mode(device_name: &str) -> DisplayMode {
+    let device_name_wide = to_wide(device_name);
+    let mut devmode = DEVMODEW {
+        dmSize: std::mem::size_of::<DEVMODEW>() as u16,
+        ..Default::default()
+    };
 
-    let hdc = unsafe {
-        CreateDCW(
+    let found = unsafe {
+        EnumDisplaySettingsW(
             PCWSTR(device_name_wide.as_ptr()),
-            PCWSTR::null(),
-            PCWSTR::null(),
-            None,
+            ENUM_CURRENT_SETTINGS,
+            &mut devmode,
         )
     };
 
-    if hdc.is_invalid() {
-        return Err(GammaError(format!(
-            "Failed to create DC for monitor: {}",
-            monitor.name
-        )));
+    if !found.as_bool() {
+        return DisplayMode::default();
+    }
+
+    DisplayMode {
+        width: devmode.dmPelsWidth,
+        height: devmode.dmPelsHeight,
+        // 0 and 1 both mean "hardware default"
+        refresh_rate: if devmode.dmDisplayFrequency > 1 {
+            devmode.dmDisplayFrequency
+        } else {
+            0
+        },
+        orientation: match unsafe { devmode.Anonymous1.Anonymous2.dmDisplayOrientation } {
+            DMDO_90 => Orientation::Portrait,
+            DMDO_180 => Orientation::LandscapeFlipped,
+            DMDO_270 => Orientation::PortraitFlipped,
+            _ => Orientation::Landscape,
+        },
+    }
+}
+
+/// Find the display monitor handle for a monitor.
+#[cfg(windows)]
+pub(crate) fn find_hmonitor(monitor: &MonitorInfo) -> Option<HMONITOR> {
+    monitor_handles()
+        .into_iter()
+        .find(|handle| handle.device_name == monitor.device_name)
+        .map(|handle| handle.hmonitor)
+}
+
+#[cfg(windows)]
+pub fn enumerate_monitors() -> Vec<MonitorInfo> {
+    let mut handles = monitor_handles();
+    handles.sort_by_key(|handle| std::cmp::Reverse(handle.is_primary));
+
+    let mut targets = crate::windows::names::display_targets();
+
+    // Mirrored outputs sometimes get an HMONITOR each for the same device. Keep one entry per
+    // device so an apply writes it once, and count the others as clones
+    let mut devices = HashSet::new();
+    handles.retain(|handle| {
+        let first = devices.insert(handle.device_name.clone());
+
+        if !first {
+            targets
+                .entry(handle.device_name.clone())
+                .or_default()
+                .clones += 1;
+        }
+
+        first
+    });
+
+    // Fall back to the GDI device name for monitors that don't report a model
+    let targets: Vec<DisplayTarget> = handles
+        .iter()
+        .map(|handle| {
+            let mut target = targets.remove(&handle.device_name).unwrap_or_default();
+
+            if target.model.is_empty() {
+                target.model = handle.device_name.clone();
+            }
+
+            target
+        })
+        .collect();
+
+    let adapters = adapter_names();
+
+    let mut monitors: Vec<MonitorInfo> = handles
+        .into_iter()
+        .zip(monitor_labels(&targets))
+        .zip(&targets)
+        .map(|((handle, label), target)| MonitorInfo {
+            id: monitor_device_path(&handle.device_name)
+                .unwrap_or_else(|| handle.device_name.clone()),
+            name: if handle.is_primary {
+                format!("{} (Primary)", label)
+            } else {
+                label
+            },
+            legacy_id: Some(format!("{:?}", handle.hmonitor.0 as usize)),
+            bounds: handle.bounds,
+            work_area: handle.work_area,
+            dpi: handle.dpi,
+            mode: display_mode(&handle.device_name),
+            adapter: adapters
+                .get(&handle.device_name)
+                .cloned()
+                .unwrap_or_default(),
+            device_name: handle.device_name,
+            is_primary: handle.is_primary,
+            is_hdr: target.hdr,
+            supports_gamma: true,
+        })
+        .collect();
+
+    dc::retain_attached(&monitors);
+
+    for monitor in &mut monitors {
+        monitor.supports_gamma = supports_gamma_ramps(monitor);
     }
 
-    let result = unsafe { SetDeviceGammaRamp(hdc.0, ramp.as_ptr()) };
-    let _ = unsafe { DeleteDC(hdc) };
+    monitors
+}
+
+/// Check whether the driver reports gamma ramp support for the monitor's device. When no DC can
+/// be created, support is assumed so the apply reports the actual failure.
+#[cfg(windows)]
+fn supports_gamma_ramps(monitor: &MonitorInfo) -> bool {
+    dc::with_dc(monitor, |hdc| {
+        let caps = unsafe { GetDeviceCaps(Some(hdc), COLORMGMTCAPS) } as u32;
+
+        caps & CM_GAMMA_RAMP != 0
+    })
+    .unwrap_or(true)
+}
 
-    if result != 0 {
+#[cfg(windows)]
+pub fn apply_display_settings_to_monitor(
+    settings: DisplaySettings,
+    monitor: &MonitorInfo,
+) -> Result<(), GammaError> {
+    apply_gamma_ramp_to_monitor(&compute_gamma_ramp(&settings), monitor)
+}
+
+#[cfg(windows)]
+#[link(name = "gdi32")]
+extern "system" {
+    fn SetDeviceGammaRamp(hdc: *mut std::ffi::c_void, lpRamp: *const u16) -> i32;
+    fn GetDeviceGammaRamp(hdc: *mut std::ffi::c_void, lpRamp: *mut u16) -> i32;
+}
+
+/// Run a GDI call against the monitor's cached DC, retrying once with a new DC if the cached one
+/// went stale, e.g. after a display reconfiguration.
+#[cfg(windows)]
+fn retry_with_fresh_dc<T>(
+    monitor: &MonitorInfo,
+    call: impl Fn() -> Result<Result<T, WIN32_ERROR>, GammaError>,
+) -> Result<Result<T, WIN32_ERROR>, GammaError> {
+    match call()? {
+        Err(ERROR_INVALID_HANDLE) => {
+            dc::invalidate(&monitor.device_name);
+            call()
+        }
+        result => Ok(result),
+    }
+}
+
+/// Release the device contexts cached for applying ramps. Called when the backend is dropped on
+/// shutdown.
+#[cfg(windows)]
+pub fn release_device_contexts() {
+    dc::release_all();
+}
+
+/// Read the gamma ramp currently loaded for a monitor.
+#[cfg(windows)]
+pub fn get_display_gamma_ramp(monitor: &MonitorInfo) -> Result<[u16; RAMP_SIZE * 3], GammaError> {
+    let read = || {
+        dc::with_dc(monitor, |hdc| {
+            let mut ramp = [0u16; RAMP_SIZE * 3];
+
+            if unsafe { GetDeviceGammaRamp(hdc.0, ramp.as_mut_ptr()) } != 0 {
+                Ok(ramp)
+            } else {
+                Err(unsafe { GetLastError() })
+            }
+        })
+    };
+
+    let error_code = match retry_with_fresh_dc(monitor, read)? {
+        Ok(ramp) => return Ok(ramp),
+        Err(error_code) => error_code,
+    };
+
+    Err(GammaError::RampUnreadable {
+        monitor: monitor.name.clone(),
+        win32: error_code.0,
+    })
+}
+
+#[cfg(windows)]
+pub fn apply_gamma_ramp_to_monitor(
+    ramp: &GammaRamp,
+    monitor: &MonitorInfo,
+) -> Result<(), GammaError> {
+    let set = || {
+        dc::with_dc(monitor, |hdc| {
+            if unsafe { SetDeviceGammaRamp(hdc.0, ramp.as_raw().as_ptr()) } != 0 {
+                Ok(())
+            } else {
+                Err(unsafe { GetLastError() })
+            }
+        })
+    };
+
+    let Err(error_code) = retry_with_fresh_dc(monitor, set)? else {
         return Ok(());
+    };
+
+    if !crate::windows::icm::gamma_range_unlocked()
+        && crate::windows::icm::exceeds_default_range(ramp)
+    {
+        return Err(crate::windows::icm::range_restricted_error(monitor));
+    }
+
+    if monitor.is_hdr {
+        return Err(GammaError::HdrActive {
+            monitor: monitor.name.clone(),
+            win32: error_code.0,
+        });
+    }
+
+    Err(GammaError::RampRejected {
+        monitor: monitor.name.clone(),
+        win32: error_code.0,
+    })
+}
+
+/// Write ramps to several monitors at once, one thread per monitor, so they all change in the
+/// same frame instead of one after another.
+#[cfg(windows)]
+pub fn apply_gamma_ramps(
+    batch: &[(&MonitorInfo, &GammaRamp)],
+) -> HashMap<String, Result<(), GammaError>> {
+    if let [(monitor, ramp)] = batch {
+        return HashMap::from([(
+            monitor.id.clone(),
+            apply_gamma_ramp_to_monitor(ramp, monitor),
+        )]);
+    }
+
+    std::thread::scope(|scope| {
+        let applies: Vec<_> = batch
+            .iter()
+            .map(|&(monitor, ramp)| {
+                (
+                    monitor,
+                    scope.spawn(move || apply_gamma_ramp_to_monitor(ramp, monitor)),
+                )
+            })
+            .collect();
+
+        applies
+            .into_iter()
+            .map(|(monitor, apply)| {
+                let result = apply
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+
+                (monitor.id.clone(), result)
+            })
+            .collect()
+    })
+}
+
+/// Gamma backend using the Windows GDI API.
+#[cfg(windows)]
+pub struct WindowsBackend;
+
+#[cfg(windows)]
+impl Drop for WindowsBackend {
+    fn drop(&mut self) {
+        release_device_contexts();
+    }
+}
+
+#[cfg(windows)]
+impl GammaBackend for WindowsBackend {
+    fn enumerate_monitors(&self) -> Vec<MonitorInfo> {
+        enumerate_monitors()
+    }
+
+    fn apply_ramp(&self, monitor: &MonitorInfo, ramp: &GammaRamp) -> Result<(), GammaError> {
+        apply_gamma_ramp_to_monitor(ramp, monitor)
+    }
+
+    fn apply_ramps(
+        &self,
+        batch: &[(&MonitorInfo, &GammaRamp)],
+    ) -> HashMap<String, Result<(), GammaError>> {
+        apply_gamma_ramps(batch)
+    }
+
+    fn read_ramp(&self, monitor: &MonitorInfo) -> Result<GammaRamp, GammaError> {
+        get_display_gamma_ramp(monitor).map(GammaRamp::from_raw)
+    }
+
+    fn supports_hardware_control(&self, monitor: &MonitorInfo) -> bool {
+        crate::windows::ddc::is_supported(monitor)
+    }
+
+    fn applied_globally(&self, monitor: &MonitorInfo) -> bool {
+        dc::is_global(monitor)
+    }
+
+    fn set_global_fallback(&self, enabled: bool) {
+        dc::set_global_fallback(enabled);
+    }
+
+    fn read_capabilities(&self, monitor: &MonitorInfo) -> Result<String, GammaError> {
+        crate::windows::ddc::get_capabilities(monitor)
+    }
+
+    fn supports_backlight(&self, monitor: &MonitorInfo) -> bool {
+        crate::windows::backlight::is_supported(monitor)
+    }
+
+    fn apply_backlight(
+        &self,
+        monitor: &MonitorInfo,
+        settings: &DisplaySettings,
+    ) -> Result<(), GammaError> {
+        crate::windows::backlight::apply_settings(monitor, settings)
+    }
+
+    fn gamma_range_unlocked(&self) -> bool {
+        crate::windows::icm::gamma_range_unlocked()
+    }
+
+    fn is_remote_session(&self) -> bool {
+        unsafe { GetSystemMetrics(SM_REMOTESESSION) != 0 }
+    }
+
+    fn power_source(&self) -> Option<PowerSource> {
+        let mut status = SYSTEM_POWER_STATUS::default();
+        unsafe { GetSystemPowerStatus(&mut status) }.ok()?;
+
+        // 128 flags a machine without a battery, and 255 an unknown status
+        if status.BatteryFlag == 128 || status.BatteryFlag == 255 {
+            return None;
+        }
+
+        match status.ACLineStatus {
+            0 => Some(PowerSource::Battery),
+            1 => Some(PowerSource::Ac),
+            _ => None,
+        }
+    }
+
+    fn cursor_position(&self) -> Option<(i32, i32)> {
+        let mut point = POINT::default();
+        unsafe { GetCursorPos(&mut point) }.ok()?;
+
+        Some((point.x, point.y))
+    }
+
+    fn unlock_gamma_range(&self) -> Result<(), GammaError> {
+        crate::windows::icm::unlock_gamma_range()
+    }
+
+    fn apply_hardware(
+        &self,
+        monitor: &MonitorInfo,
+        settings: &DisplaySettings,
+    ) -> Result<(), GammaError> {
+        crate::windows::ddc::apply_settings(monitor, settings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_monotonic(channel: &[u16]) -> bool {
+        channel.windows(2).all(|pair| pair[0] <= pair[1])
+    }
+
+    #[test]
+    fn identity_settings_produce_linear_ramp() {
+        let ramp = compute_gamma_ramp(&DisplaySettings::default());
+
+        for (i, &value) in ramp.red().iter().enumerate() {
+            let expected = i as i32 * 257;
+            assert!(
+                (value as i32 - expected).abs() <= 1,
+                "entry {i}: expected ~{expected}, got {value}"
+            );
+        }
+    }
+
+    #[test]
+    fn identity_endpoints_are_exact() {
+        let ramp = compute_gamma_ramp(&DisplaySettings::default());
+
+        assert_eq!(ramp.red()[0], 0);
+        assert_eq!(ramp.red()[RAMP_SIZE - 1], 65535);
+    }
+
+    #[test]
+    fn channels_are_identical() {
+        let ramp = compute_gamma_ramp(&DisplaySettings::new(1.8, 0.1, 1.2));
+
+        assert_eq!(ramp.red(), ramp.green());
+        assert_eq!(ramp.red(), ramp.blue());
+    }
+
+    #[test]
+    fn high_brightness_clamps_to_white() {
+        let ramp = compute_gamma_ramp(&DisplaySettings::new(1.0, 1.0, 1.0));
+
+        assert!(ramp.red()[RAMP_SIZE / 2..].iter().all(|&v| v == 65535));
+    }
+
+    #[test]
+    fn low_brightness_clamps_to_black() {
+        let ramp = compute_gamma_ramp(&DisplaySettings::new(1.0, -1.0, 1.0));
+
+        assert!(ramp.red()[..RAMP_SIZE / 2].iter().all(|&v| v == 0));
+    }
+
+    #[test]
+    fn high_contrast_clips_both_ends() {
+        let ramp = compute_gamma_ramp(&DisplaySettings::new(1.0, 0.0, 3.0));
+
+        assert_eq!(ramp.red()[0], 0);
+        assert_eq!(ramp.red()[40], 0);
+        assert_eq!(ramp.red()[215], 65535);
+        assert_eq!(ramp.red()[RAMP_SIZE - 1], 65535);
+    }
+
+    #[test]
+    fn extreme_gamma_keeps_endpoints() {
+        for gamma in [0.1, 3.0] {
+            let ramp = compute_gamma_ramp(&DisplaySettings::new(gamma, 0.0, 1.0));
+
+            assert_eq!(ramp.red()[0], 0, "gamma {gamma}");
+            assert_eq!(ramp.red()[RAMP_SIZE - 1], 65535, "gamma {gamma}");
+        }
+    }
+
+    #[test]
+    fn known_points_match_expected_values() {
+        // (settings, input index, expected output) computed from the formula in f64
+        let cases = [
+            (DisplaySettings::new(2.0, 0.0, 1.0), 64, 32831),
+            (DisplaySettings::new(2.2, 0.0, 1.0), 128, 47908),
+            (DisplaySettings::new(0.5, 0.0, 1.0), 128, 16512),
+            (DisplaySettings::new(1.0, 0.5, 1.0), 0, 32767),
+            (DisplaySettings::new(1.0, -0.25, 1.0), 255, 49151),
+            (DisplaySettings::new(1.0, 0.0, 2.0), 96, 16576),
+            (DisplaySettings::new(1.0, 0.0, 0.5), 0, 16383),
+        ];
+
+        for (settings, index, expected) in cases {
+            let value = compute_gamma_ramp(&settings).red()[index] as i32;
+
+            assert!(
+                (value - expected).abs() <= 1,
+                "{settings:?} at {index}: expected ~{expected}, got {value}"
+            );
+        }
+    }
+
+    #[test]
+    fn ramp_is_monotonic_for_sane_parameters() {
+        for gamma in [0.1, 0.5, 1.0, 1.8, 2.2, 3.0] {
+            for brightness in [-1.0, -0.5, 0.0, 0.5, 1.0] {
+                for contrast in [0.1, 0.5, 1.0, 2.0, 3.0] {
+                    for brightness_mode in [BrightnessMode::Offset, BrightnessMode::PreserveBlack] {
+                        let settings = DisplaySettings {
+                            brightness_mode,
+                            ..DisplaySettings::new(gamma, brightness, contrast)
+                        };
+                        let ramp = compute_gamma_ramp(&settings);
+
+                        assert!(is_monotonic(ramp.red()), "{settings:?}");
+                    }
+                }
+            }
+        }
     }
 
-    let error_code = unsafe { GetLastError() };
+    #[test]
+    fn higher_gamma_brightens_midtones() {
+        let low = compute_gamma_ramp(&DisplaySettings::new(0.8, 0.0, 1.0));
+        let high = compute_gamma_ramp(&DisplaySettings::new(2.0, 0.0, 1.0));
 
-    Err(GammaError(format!(
-        "Failed to set gamma ramp for {}: {:?}",
-        monitor.name, error_code
-    )))
+        assert!(high.red()[128] > low.red()[128]);
+    }
+
+    #[test]
+    fn max_difference_between_ramps() {
+        let identity = compute_gamma_ramp(&DisplaySettings::default());
+        let bright = compute_gamma_ramp(&DisplaySettings::new(1.0, 0.1, 1.0));
+
+        assert_eq!(identity.max_difference(&identity), 0);
+        assert!(identity.max_difference(&bright) > RAMP_TOLERANCE);
+    }
+
+    #[test]
+    fn fit_recovers_settings() {
+        let cases = [
+            DisplaySettings::default(),
+            DisplaySettings::new(2.2, 0.0, 1.0),
+            DisplaySettings::new(0.7, 0.15, 0.8),
+            DisplaySettings::new(1.5, -0.3, 1.4),
+        ];
+
+        for settings in cases {
+            let fitted = fit_display_settings(&compute_gamma_ramp(&settings));
+            let refit = compute_gamma_ramp(&fitted);
+
+            assert!(
+                refit.max_difference(&compute_gamma_ramp(&settings)) <= RAMP_TOLERANCE,
+                "{settings:?} fitted as {fitted:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn fit_handles_flat_ramp() {
+        let black = GammaRamp::from_raw([0; RAMP_SIZE * 3]);
+
+        // Nothing to fit against; any result is fine as long as it's valid
+        let fitted = fit_display_settings(&black);
+        assert_eq!(
+            fitted,
+            DisplaySettings::new(fitted.gamma, fitted.brightness, fitted.contrast)
+        );
+    }
+
+    fn placed(id: &str, is_primary: bool, x: i32, y: i32) -> MonitorInfo {
+        MonitorInfo {
+            id: id.to_string(),
+            name: id.to_string(),
+            device_name: id.to_string(),
+            is_primary,
+            legacy_id: None,
+            bounds: MonitorRect {
+                x,
+                y,
+                width: 2560,
+                height: 1440,
+            },
+            work_area: MonitorRect::default(),
+            dpi: 0,
+            mode: DisplayMode {
+                width: 2560,
+                height: 1440,
+                refresh_rate: 144,
+                orientation: Orientation::Landscape,
+            },
+            is_hdr: false,
+            supports_gamma: true,
+            adapter: String::new(),
+        }
+    }
+
+    #[test]
+    fn describe_monitor_placement() {
+        let monitors = [
+            placed("primary", true, 0, 0),
+            placed("left", false, -2560, 0),
+            placed("right", false, 2560, 200),
+            placed("above", false, 0, -1440),
+        ];
+
+        let describe = |i: usize| describe_monitor(&monitors[i], &monitors);
+
+        assert_eq!(describe(0), "2560×1440 @ 144Hz");
+        assert_eq!(describe(1), "2560×1440 @ 144Hz, left of primary");
+        assert_eq!(describe(2), "2560×1440 @ 144Hz, right of primary");
+        assert_eq!(describe(3), "2560×1440 @ 144Hz, above primary");
+    }
+
+    #[test]
+    fn describe_monitor_names_adapter() {
+        let mut monitor = placed("external", true, 0, 0);
+        monitor.adapter = "NVIDIA GeForce RTX 3070".to_string();
+
+        assert_eq!(
+            describe_monitor(&monitor, &[]),
+            "2560×1440 @ 144Hz, on NVIDIA GeForce RTX 3070"
+        );
+    }
+
+    #[test]
+    fn describe_monitor_without_mode() {
+        let mut monitor = placed("clone", false, 0, 0);
+        monitor.mode = DisplayMode::default();
+
+        assert_eq!(
+            describe_monitor(&monitor, &[placed("primary", true, 0, 0)]),
+            ""
+        );
+    }
+
+    #[test]
+    fn monitor_info_without_geometry_deserializes() {
+        let json = r#"{"id":"1","name":"One","device_name":"DISPLAY1","is_primary":true}"#;
+        let monitor: MonitorInfo = serde_json::from_str(json).unwrap();
+
+        assert_eq!(monitor.bounds, MonitorRect::default());
+        assert_eq!(monitor.mode, DisplayMode::default());
+        assert_eq!(monitor.mode.orientation, Orientation::Landscape);
+        assert_eq!(monitor.scale_factor(), 1.0);
+        assert!(!monitor.is_hdr);
+    }
+
+    #[test]
+    fn contains_point_excludes_far_edges() {
+        let monitor = placed("left", false, -2560, 0);
+
+        assert!(monitor.contains_point(-2560, 0));
+        assert!(monitor.contains_point(-1, 1439));
+        assert!(!monitor.contains_point(0, 0));
+        assert!(!monitor.contains_point(-100, 1440));
+    }
+
+    #[test]
+    fn lerp_interpolates_each_field() {
+        let a = DisplaySettings::new(1.0, -0.5, 0.5);
+        let b = DisplaySettings::new(2.0, 0.5, 1.5);
+
+        assert_eq!(DisplaySettings::lerp(a, b, 0.0), a);
+        assert_eq!(DisplaySettings::lerp(a, b, 1.0), b);
+        assert_eq!(
+            DisplaySettings::lerp(a, b, 0.5),
+            DisplaySettings::new(1.5, 0.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn output_range_limits_levels() {
+        let settings = DisplaySettings {
+            output_min: 16.0 / 255.0,
+            output_max: 235.0 / 255.0,
+            ..Default::default()
+        };
+        let ramp = compute_gamma_ramp(&settings);
+
+        assert!(ramp.red()[0].abs_diff(16 * 257) <= 1);
+        assert!(ramp.red()[255].abs_diff(235 * 257) <= 1);
+        assert!(is_monotonic(ramp.red()));
+        assert!(!settings.output_range_inverted());
+    }
+
+    #[test]
+    fn clipping_counts_flattened_levels() {
+        assert_eq!(
+            Clipping::of(&compute_gamma_ramp(&DisplaySettings::default())),
+            Clipping::default()
+        );
+
+        let clipping = Clipping::of(&compute_gamma_ramp(&DisplaySettings::new(1.0, 0.25, 2.0)));
+        assert!(clipping.shadows > 0 && clipping.highlights > clipping.shadows);
+        assert!(clipping.percent() > Clipping::SEVERE_PERCENT);
+
+        let inverted = DisplaySettings {
+            invert: true,
+            ..DisplaySettings::new(1.0, 0.25, 2.0)
+        };
+        assert_eq!(Clipping::of(&compute_gamma_ramp(&inverted)), clipping);
+    }
+
+    #[test]
+    fn contrast_keeps_pivot_level() {
+        let settings = DisplaySettings {
+            contrast: 2.0,
+            contrast_pivot: 0.2,
+            ..Default::default()
+        };
+        let ramp = compute_gamma_ramp(&settings);
+
+        // Level 51 is exactly 0.2
+        assert!(ramp.green()[51].abs_diff(13107) <= 1);
+        assert!(
+            ramp.green()[128]
+                > compute_gamma_ramp(&DisplaySettings::new(1.0, 0.0, 2.0)).green()[128]
+        );
+
+        let json = r#"{"gamma":1.0,"brightness":0.0,"contrast":1.0}"#;
+        let settings: DisplaySettings = serde_json::from_str(json).unwrap();
+        assert_eq!(settings.contrast_pivot, 0.5);
+    }
+
+    #[test]
+    fn tint_pulls_channels_apart() {
+        assert_eq!(tint_gains(0.0, 0.0), [1.0, 1.0, 1.0]);
+
+        let [red, green, blue] = tint_gains(1.0, 0.0);
+        assert!(green < red && red == blue);
+
+        let [red, green, blue] = tint_gains(0.0, 1.0);
+        assert!(blue < red && red == green);
+
+        let [red, green, blue] = tint_gains(-0.5, -0.5);
+        assert!(red < green && red < blue);
+        assert!(tint_gains(-3.0, 3.0).iter().all(|&gain| gain > 0.0));
+    }
+
+    #[test]
+    fn ramps_stay_monotonic_across_settings() {
+        for gamma in [0.3, 0.5, 1.0, 1.8, 3.0] {
+            for brightness in [-0.8, 0.0, 0.6] {
+                for contrast in [0.5, 1.0, 2.5] {
+                    for brightness_mode in [BrightnessMode::Offset, BrightnessMode::PreserveBlack] {
+                        for invert in [false, true] {
+                            let settings = DisplaySettings {
+                                brightness_mode,
+                                invert,
+                                temperature: 3400,
+                                ..DisplaySettings::new(gamma, brightness, contrast)
+                            };
+                            let ramp = compute_gamma_ramp(&settings);
+
+                            for channel in [ramp.red(), ramp.green(), ramp.blue()] {
+                                let monotonic = channel.windows(2).all(|pair| match invert {
+                                    false => pair[0] <= pair[1],
+                                    true => pair[0] >= pair[1],
+                                });
+                                assert!(monotonic, "{:?}", settings);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn ramps_track_the_curve_within_one_step() {
+        for gamma in [0.3, 0.7, 1.4, 2.2] {
+            let ramp = compute_gamma_ramp(&DisplaySettings::new(gamma, 0.0, 1.0));
+            let ideal: Vec<f64> = (0..RAMP_SIZE)
+                .map(|i| (i as f64 / 255.0).powf(1.0 / gamma as f64) * 65535.0)
+                .collect();
+
+            let largest_step = ideal.windows(2).map(|w| w[1] - w[0]).fold(0.0, f64::max);
+            assert!(ramp
+                .green()
+                .windows(2)
+                .all(|w| (w[1] - w[0]) as f64 <= largest_step.ceil() + 1.0));
+
+            assert!(ramp
+                .green()
+                .iter()
+                .zip(&ideal)
+                .all(|(&entry, &ideal)| (entry as f64 - ideal).abs() <= 1.0));
+
+            // Rounding errors are carried forward rather than accumulating
+            let drift: f64 =
+                ramp.green().iter().map(|&v| v as f64).sum::<f64>() - ideal.iter().sum::<f64>();
+            assert!(drift.abs() <= 0.5, "gamma {}: drift {}", gamma, drift);
+        }
+    }
+
+    #[test]
+    fn validate_matches_the_clamps_of_new() {
+        assert!(DisplaySettings::new(3.0, -1.0, 0.1).validate().is_ok());
+
+        let error = DisplaySettings {
+            gamma: 3.05,
+            ..Default::default()
+        }
+        .validate()
+        .unwrap_err();
+        assert_eq!(error.to_string(), "Gamma must be between 0.1 and 3");
+
+        let nan = DisplaySettings {
+            contrast: f32::NAN,
+            ..Default::default()
+        };
+        assert!(matches!(
+            nan.validate(),
+            Err(GammaError::OutOfRange {
+                setting: "Contrast",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn extra_dimming_scales_output_and_is_capped() {
+        let dimmed = compute_gamma_ramp(&DisplaySettings {
+            extra_dimming: 0.5,
+            ..Default::default()
+        });
+        let full = compute_gamma_ramp(&DisplaySettings::default());
+
+        assert_eq!(dimmed.green()[0], 0);
+        assert_eq!(dimmed.green()[255], full.green()[255] / 2);
+
+        let capped = compute_gamma_ramp(&DisplaySettings {
+            extra_dimming: 1.0,
+            ..Default::default()
+        });
+        let floor = ((1.0 - MAX_EXTRA_DIMMING) * 65535.0) as u16;
+        assert!(capped.green()[255].abs_diff(floor) <= 1);
+    }
+
+    #[test]
+    fn color_filter_scales_channels() {
+        let settings = DisplaySettings {
+            color_filter: ColorFilter::Protanopia,
+            ..Default::default()
+        };
+        let ramp = compute_gamma_ramp(&settings);
+        let [red, green, blue] = ColorFilter::Protanopia.channel_gains();
+
+        assert!(ramp.red()[255].abs_diff((red * 65535.0).round() as u16) <= 1);
+        assert!(ramp.green()[255].abs_diff((green * 65535.0).round() as u16) <= 1);
+        assert!(ramp.blue()[255].abs_diff((blue * 65535.0).round() as u16) <= 1);
+        assert!(ramp.red().iter().zip(ramp.blue()).all(|(r, b)| r <= b));
+    }
+
+    #[test]
+    fn no_filter_keeps_channels_identical() {
+        let ramp = compute_gamma_ramp(&DisplaySettings::new(1.4, 0.1, 1.2));
+
+        assert_eq!(ramp.red(), ramp.green());
+        assert_eq!(ramp.green(), ramp.blue());
+    }
+
+    #[test]
+    fn neutral_temperature_is_white() {
+        assert_eq!(temperature_gains(NEUTRAL_TEMPERATURE), [1.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn warm_temperatures_cut_blue_first() {
+        let [red, green, blue] = temperature_gains(2700);
+
+        assert_eq!(red, 1.0);
+        assert!(green < 1.0 && blue < green);
+        assert!(temperature_gains(3400)[2] > blue);
+    }
+
+    #[test]
+    fn invert_reverses_ramp() {
+        let settings = DisplaySettings::new(1.6, 0.1, 1.3);
+        let inverted = compute_gamma_ramp(&DisplaySettings {
+            invert: true,
+            ..settings
+        });
+        let normal = compute_gamma_ramp(&settings);
+
+        for i in 0..RAMP_SIZE {
+            assert_eq!(inverted.red()[i], normal.red()[RAMP_SIZE - 1 - i]);
+        }
+    }
+
+    #[test]
+    fn settings_without_filter_deserialize() {
+        let json = r#"{"gamma":1.2,"brightness":0.0,"contrast":1.0}"#;
+        let settings: DisplaySettings = serde_json::from_str(json).unwrap();
+
+        assert_eq!(settings.color_filter, ColorFilter::None);
+        assert!(!settings.invert);
+        assert_eq!(settings.temperature, NEUTRAL_TEMPERATURE);
+        assert_eq!(settings.brightness_mode, BrightnessMode::Offset);
+        assert_eq!((settings.output_min, settings.output_max), (0.0, 1.0));
+    }
+
+    #[test]
+    fn lerp_clamps_t() {
+        let a = DisplaySettings::default();
+        let b = DisplaySettings::new(2.0, 0.5, 2.0);
+
+        assert_eq!(DisplaySettings::lerp(a, b, -1.0), a);
+        assert_eq!(DisplaySettings::lerp(a, b, 3.0), b);
+    }
+
+    #[test]
+    fn io_errors_expose_their_source() {
+        let error = GammaError::Io {
+            context: "Failed to save config".to_string(),
+            source: std::io::Error::new(std::io::ErrorKind::PermissionDenied, "access denied"),
+        };
+
+        assert_eq!(error.to_string(), "Failed to save config: access denied");
+        assert!(error.source().is_some());
+        assert!(GammaError::MonitorNotFound {
+            id: "1".to_string()
+        }
+        .source()
+        .is_none());
+    }
+
+    #[test]
+    fn preserve_black_keeps_black_and_dims_white() {
+        let settings = DisplaySettings {
+            brightness_mode: BrightnessMode::PreserveBlack,
+            ..DisplaySettings::new(1.0, -0.3, 1.0)
+        };
+        let ramp = compute_gamma_ramp(&settings);
+
+        assert_eq!(ramp.green()[0], 0);
+        assert!(ramp.green()[255].abs_diff((0.7 * 65535.0_f32).round() as u16) <= 1);
+
+        // The offset model lifts black instead
+        let lifted = compute_gamma_ramp(&DisplaySettings::new(1.0, 0.3, 1.0));
+        assert!(lifted.green()[0] > 0);
+    }
 }
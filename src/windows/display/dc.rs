@@ -0,0 +1,139 @@
+use crate::windows::display::{to_wide, GammaError, MonitorInfo};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, LazyLock, Mutex, MutexGuard, PoisonError,
+    },
+};
+use windows::core::{w, PCWSTR};
+use windows::Win32::Graphics::Gdi::{CreateDCW, DeleteDC, HDC};
+
+/// A device context owned by the cache, deleted on drop.
+struct CachedDc {
+    hdc: HDC,
+    monitor_id: String,
+    /// Whether this is the generic display DC, which applies to every monitor at once.
+    global: bool,
+    /// Held while the DC is in use. DCs of different monitors can be used concurrently.
+    in_use: Mutex<()>,
+}
+
+// SAFETY: DCs from CreateDCW aren't bound to the thread that created them, and `in_use`
+// serializes every use
+unsafe impl Send for CachedDc {}
+unsafe impl Sync for CachedDc {}
+
+impl Drop for CachedDc {
+    fn drop(&mut self) {
+        let _ = unsafe { DeleteDC(self.hdc) };
+    }
+}
+
+/// Device contexts keyed by device name. Creating one on every apply is slow enough to stutter
+/// while dragging a slider. A DC released while in use is deleted once the user is done.
+static CACHE: LazyLock<Mutex<HashMap<String, Arc<CachedDc>>>> = LazyLock::new(Default::default);
+
+/// Whether to fall back to the generic display DC when no DC can be created for a device.
+static GLOBAL_FALLBACK: AtomicBool = AtomicBool::new(true);
+
+fn cache() -> MutexGuard<'static, HashMap<String, Arc<CachedDc>>> {
+    CACHE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn create_with(driver: PCWSTR, device: PCWSTR) -> Option<HDC> {
+    let hdc = unsafe { CreateDCW(driver, device, PCWSTR::null(), None) };
+
+    (!hdc.is_invalid()).then_some(hdc)
+}
+
+/// Create a DC for the monitor's device. Outputs of a secondary GPU, like the external ports of
+/// hybrid laptops, sometimes only accept the device name through the display driver, so that's
+/// tried next. Some docks and older drivers accept neither, leaving the generic display DC,
+/// which applies to every monitor.
+fn create(monitor: &MonitorInfo) -> Result<Arc<CachedDc>, GammaError> {
+    let device_name_wide = to_wide(&monitor.device_name);
+    let device = PCWSTR(device_name_wide.as_ptr());
+    let cached = |hdc, global| {
+        Arc::new(CachedDc {
+            hdc,
+            monitor_id: monitor.id.clone(),
+            global,
+            in_use: Mutex::new(()),
+        })
+    };
+
+    if let Some(hdc) =
+        create_with(device, PCWSTR::null()).or_else(|| create_with(w!("DISPLAY"), device))
+    {
+        return Ok(cached(hdc, false));
+    }
+
+    GLOBAL_FALLBACK
+        .load(Ordering::Relaxed)
+        .then(|| create_with(w!("DISPLAY"), PCWSTR::null()))
+        .flatten()
+        .map(|hdc| cached(hdc, true))
+        .ok_or_else(|| GammaError::DcCreationFailed {
+            monitor: monitor.name.clone(),
+            device: monitor.device_name.clone(),
+            adapter: monitor.adapter.clone(),
+        })
+}
+
+/// Run `f` with a device context for the monitor, creating one on first use. The DC stays locked
+/// meanwhile, so calls for the same monitor are serialized while other monitors proceed.
+pub(crate) fn with_dc<T>(monitor: &MonitorInfo, f: impl FnOnce(HDC) -> T) -> Result<T, GammaError> {
+    let dc = {
+        let mut cache = cache();
+
+        match cache.entry(monitor.device_name.clone()) {
+            Entry::Occupied(entry) if entry.get().monitor_id == monitor.id => entry.get().clone(),
+            // The device name was reassigned to another monitor
+            Entry::Occupied(mut entry) => {
+                entry.insert(create(monitor)?);
+
+                entry.get().clone()
+            }
+            Entry::Vacant(entry) => entry.insert(create(monitor)?).clone(),
+        }
+    };
+
+    let _in_use = dc.in_use.lock().unwrap_or_else(PoisonError::into_inner);
+
+    Ok(f(dc.hdc))
+}
+
+/// Release the cached DC for a device, e.g. after the driver reported it invalid.
+pub(crate) fn invalidate(device_name: &str) {
+    cache().remove(device_name);
+}
+
+/// Release the DCs of devices that are no longer attached or now belong to another monitor.
+pub(crate) fn retain_attached(monitors: &[MonitorInfo]) {
+    cache().retain(|device_name, dc| {
+        monitors
+            .iter()
+            .any(|m| &m.device_name == device_name && m.id == dc.monitor_id)
+    });
+}
+
+/// Check whether the monitor's cached DC is the generic display DC.
+pub(crate) fn is_global(monitor: &MonitorInfo) -> bool {
+    cache()
+        .get(&monitor.device_name)
+        .is_some_and(|dc| dc.global && dc.monitor_id == monitor.id)
+}
+
+/// Allow or forbid falling back to the generic display DC, releasing any DC created under the
+/// previous choice.
+pub(crate) fn set_global_fallback(enabled: bool) {
+    if GLOBAL_FALLBACK.swap(enabled, Ordering::Relaxed) != enabled {
+        cache().retain(|_, dc| !dc.global);
+    }
+}
+
+/// Release every cached DC.
+pub(crate) fn release_all() {
+    cache().clear();
+}
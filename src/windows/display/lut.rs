@@ -0,0 +1,306 @@
+use crate::windows::display::{GammaError, GammaRamp, RAMP_SIZE};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+mod icc;
+
+/// A per-channel calibration lookup table, resampled to one entry per ramp level with values
+/// from 0 to 1.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Lut {
+    /// Where the table came from, for display.
+    pub name: String,
+    pub red: Vec<f32>,
+    pub green: Vec<f32>,
+    pub blue: Vec<f32>,
+}
+
+impl Lut {
+    /// Load a LUT file, picking the parser from its extension.
+    pub fn load(path: &Path) -> Result<Self, GammaError> {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+
+        let contents = fs::read(path).map_err(|source| GammaError::Io {
+            context: format!("Failed to read {}", name),
+            source,
+        })?;
+
+        let extension = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        let text = || {
+            String::from_utf8(contents.clone())
+                .map_err(|_| GammaError::InvalidLut(format!("{} is not a text file", name)))
+        };
+
+        match extension.as_str() {
+            "cube" => Self::parse_cube(&name, &text()?),
+            "csv" => Self::parse_csv(&name, &text()?),
+            "icc" | "icm" => Self::parse_icc(&name, &contents),
+            _ => Err(GammaError::InvalidLut(format!(
+                "{} is not a .cube, .csv, .icc or .icm LUT",
+                name
+            ))),
+        }
+    }
+
+    /// Parse the calibration curve from an ICC profile's `vcgt` tag, e.g. one written by
+    /// DisplayCAL.
+    pub fn parse_icc(name: &str, profile: &[u8]) -> Result<Self, GammaError> {
+        Self::from_rows(name, &icc::vcgt_rows(name, profile)?)
+    }
+
+    /// Parse a 1D `.cube` file. 3D LUTs and custom input domains are rejected.
+    pub fn parse_cube(name: &str, contents: &str) -> Result<Self, GammaError> {
+        let mut size = None;
+        let mut rows = Vec::new();
+
+        for (number, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            let error = |message: &str| {
+                GammaError::InvalidLut(format!("{} line {}: {}", name, number + 1, message))
+            };
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut fields = line.split_whitespace();
+            let keyword = fields.next().unwrap_or_default();
+
+            match keyword {
+                "TITLE" => {}
+                "LUT_3D_SIZE" => {
+                    return Err(error("3D LUTs aren't supported; export a 1D LUT instead"));
+                }
+                "LUT_1D_SIZE" => {
+                    let value = fields
+                        .next()
+                        .and_then(|v| v.parse::<usize>().ok())
+                        .filter(|&v| v >= 2)
+                        .ok_or_else(|| error("LUT_1D_SIZE must be a number of at least 2"))?;
+                    size = Some(value);
+                }
+                "DOMAIN_MIN" | "DOMAIN_MAX" => {
+                    let expected = if keyword == "DOMAIN_MIN" { 0.0 } else { 1.0 };
+                    let values =
+                        parse_triplet(fields).ok_or_else(|| error("expected 3 numbers"))?;
+
+                    if values.iter().any(|&v| v != expected) {
+                        return Err(error("only the default 0 to 1 input domain is supported"));
+                    }
+                }
+                _ if keyword
+                    .chars()
+                    .next()
+                    .is_some_and(|c| c.is_ascii_alphabetic()) =>
+                {
+                    return Err(error(&format!("unknown keyword {}", keyword)));
+                }
+                _ => {
+                    let row = parse_triplet(line.split_whitespace())
+                        .ok_or_else(|| error("expected 3 numbers"))?;
+                    rows.push(row);
+                }
+            }
+        }
+
+        let size =
+            size.ok_or_else(|| GammaError::InvalidLut(format!("{} has no LUT_1D_SIZE", name)))?;
+
+        if rows.len() != size {
+            return Err(GammaError::InvalidLut(format!(
+                "{} declares {} entries but has {}",
+                name,
+                size,
+                rows.len()
+            )));
+        }
+
+        Self::from_rows(name, &rows)
+    }
+
+    /// Parse a CSV with one `R,G,B` row per level. Values may be 0 to 1, 0 to 255 or 0 to 65535;
+    /// the range is inferred from the largest value. A header row is skipped.
+    pub fn parse_csv(name: &str, contents: &str) -> Result<Self, GammaError> {
+        let mut rows = Vec::new();
+
+        for (number, line) in contents.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            match parse_triplet(line.split(',').map(str::trim)) {
+                Some(row) => rows.push(row),
+                // Allow a header row
+                None if rows.is_empty() && number == 0 => {}
+                None => {
+                    return Err(GammaError::InvalidLut(format!(
+                        "{} line {}: expected 3 comma-separated numbers",
+                        name,
+                        number + 1
+                    )))
+                }
+            }
+        }
+
+        let max = rows.iter().flatten().copied().fold(0.0, f32::max);
+        let scale = if max > 255.0 {
+            65535.0
+        } else if max > 1.0 {
+            255.0
+        } else {
+            1.0
+        };
+
+        let rows: Vec<[f32; 3]> = rows.iter().map(|row| row.map(|v| v / scale)).collect();
+
+        Self::from_rows(name, &rows)
+    }
+
+    fn from_rows(name: &str, rows: &[[f32; 3]]) -> Result<Self, GammaError> {
+        if rows.len() < 2 {
+            return Err(GammaError::InvalidLut(format!(
+                "{} needs at least 2 entries, found {}",
+                name,
+                rows.len()
+            )));
+        }
+
+        if rows.iter().flatten().any(|&v| !(0.0..=1.0).contains(&v)) {
+            return Err(GammaError::InvalidLut(format!(
+                "{} has values outside the 0 to 1 range",
+                name
+            )));
+        }
+
+        let channel = |i: usize| resample(&rows.iter().map(|row| row[i]).collect::<Vec<_>>());
+
+        Ok(Self {
+            name: name.to_string(),
+            red: channel(0),
+            green: channel(1),
+            blue: channel(2),
+        })
+    }
+
+    /// Pass a ramp through the table, so the LUT applies after Gammar's own adjustments.
+    pub fn apply(&self, ramp: &GammaRamp) -> GammaRamp {
+        let mut values = *ramp.as_raw();
+
+        for (channel, table) in [&self.red, &self.green, &self.blue].into_iter().enumerate() {
+            for value in &mut values[RAMP_SIZE * channel..RAMP_SIZE * (channel + 1)] {
+                *value = (sample(table, *value as f32 / 65535.0) * 65535.0).round() as u16;
+            }
+        }
+
+        GammaRamp::from_raw(values)
+    }
+}
+
+fn parse_triplet<'a>(mut fields: impl Iterator<Item = &'a str>) -> Option<[f32; 3]> {
+    let mut row = [0.0; 3];
+
+    for value in &mut row {
+        *value = fields.next()?.parse().ok()?;
+    }
+
+    fields.next().is_none().then_some(row)
+}
+
+/// Linearly interpolate a table at a position from 0 to 1. Tables too short to interpolate, as a
+/// hand-edited config may hold, pass the position through.
+fn sample(table: &[f32], position: f32) -> f32 {
+    if table.len() < 2 {
+        return position.clamp(0.0, 1.0);
+    }
+
+    let scaled = position.clamp(0.0, 1.0) * (table.len() - 1) as f32;
+    let index = (scaled.floor() as usize).min(table.len() - 2);
+    let t = scaled - index as f32;
+
+    table[index] + (table[index + 1] - table[index]) * t
+}
+
+/// Resample a table to one entry per ramp level.
+fn resample(table: &[f32]) -> Vec<f32> {
+    (0..RAMP_SIZE)
+        .map(|i| sample(table, i as f32 / (RAMP_SIZE - 1) as f32))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::windows::display::{compute_gamma_ramp, DisplaySettings};
+
+    #[test]
+    fn short_tables_pass_ramps_through() {
+        let lut = Lut {
+            name: "truncated".to_string(),
+            red: Vec::new(),
+            green: vec![0.5],
+            blue: Vec::new(),
+        };
+        let ramp = compute_gamma_ramp(&DisplaySettings::new(1.4, 0.1, 1.0));
+
+        assert_eq!(lut.apply(&ramp), ramp);
+        assert_eq!(sample(&[], 0.25), 0.25);
+        assert_eq!(sample(&[0.5], 1.5), 1.0);
+    }
+
+    #[test]
+    fn parses_cube_and_interpolates() {
+        let cube = "TITLE \"test\"\n# comment\nLUT_1D_SIZE 3\n0 0 0\n0.25 0.5 0.75\n1 1 1\n";
+        let lut = Lut::parse_cube("test.cube", cube).unwrap();
+
+        assert_eq!(lut.red.len(), RAMP_SIZE);
+        assert_eq!(lut.red[0], 0.0);
+        assert_eq!(lut.blue[RAMP_SIZE - 1], 1.0);
+        assert!((sample(&lut.red, 0.5) - 0.25).abs() < 0.01);
+        assert!((sample(&lut.green, 0.5) - 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn rejects_malformed_cubes() {
+        let wrong_size = Lut::parse_cube("a.cube", "LUT_1D_SIZE 4\n0 0 0\n1 1 1\n");
+        assert!(wrong_size
+            .unwrap_err()
+            .to_string()
+            .contains("declares 4 entries but has 2"));
+
+        let three_d = Lut::parse_cube("a.cube", "LUT_3D_SIZE 17\n");
+        assert!(three_d.unwrap_err().to_string().contains("3D LUTs"));
+
+        let bad_row = Lut::parse_cube("a.cube", "LUT_1D_SIZE 2\n0 0\n1 1 1\n");
+        assert!(bad_row.unwrap_err().to_string().contains("line 2"));
+    }
+
+    #[test]
+    fn parses_csv_in_8_bit_range() {
+        let csv: String = std::iter::once("r,g,b".to_string())
+            .chain((0..256).map(|i| format!("{},{},{}", i, i, 255 - i)))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let lut = Lut::parse_csv("test.csv", &csv).unwrap();
+
+        assert_eq!(lut.green[255], 1.0);
+        assert_eq!(lut.blue[0], 1.0);
+        assert_eq!(lut.blue[255], 0.0);
+    }
+
+    #[test]
+    fn identity_lut_leaves_ramp_unchanged() {
+        let lut = Lut::parse_cube("id.cube", "LUT_1D_SIZE 2\n0 0 0\n1 1 1\n").unwrap();
+        let ramp = compute_gamma_ramp(&DisplaySettings::new(1.3, 0.1, 1.1));
+
+        assert!(lut.apply(&ramp).max_difference(&ramp) <= 1);
+    }
+}
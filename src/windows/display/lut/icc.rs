@@ -0,0 +1,193 @@
+use crate::windows::display::{GammaError, RAMP_SIZE};
+
+/// Size of the ICC profile header, after which the tag table starts.
+const HEADER_SIZE: usize = 128;
+
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(
+        bytes.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(
+        bytes.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+fn read_s15_fixed16(bytes: &[u8], offset: usize) -> Option<f32> {
+    Some(read_u32(bytes, offset)? as i32 as f32 / 65536.0)
+}
+
+/// Find a tag's data in an ICC profile.
+fn find_tag<'a>(
+    name: &str,
+    profile: &'a [u8],
+    signature: &[u8; 4],
+) -> Result<Option<&'a [u8]>, GammaError> {
+    let invalid = || GammaError::InvalidLut(format!("{} is not a valid ICC profile", name));
+
+    if profile.get(36..40) != Some(b"acsp") {
+        return Err(invalid());
+    }
+
+    let count = read_u32(profile, HEADER_SIZE).ok_or_else(invalid)? as usize;
+
+    for i in 0..count {
+        let entry = HEADER_SIZE + 4 + i * 12;
+        let tag = profile.get(entry..entry + 4).ok_or_else(invalid)?;
+
+        if tag != signature {
+            continue;
+        }
+
+        let offset = read_u32(profile, entry + 4).ok_or_else(invalid)? as usize;
+        let size = read_u32(profile, entry + 8).ok_or_else(invalid)? as usize;
+
+        return profile
+            .get(offset..offset.saturating_add(size))
+            .map(Some)
+            .ok_or_else(invalid);
+    }
+
+    Ok(None)
+}
+
+/// Extract the per-channel calibration curve from an ICC profile's `vcgt` tag, as rows of red,
+/// green and blue values from 0 to 1. Both the table and the formula variants are supported.
+pub(super) fn vcgt_rows(name: &str, profile: &[u8]) -> Result<Vec<[f32; 3]>, GammaError> {
+    let tag = find_tag(name, profile, b"vcgt")?.ok_or_else(|| {
+        GammaError::InvalidLut(format!(
+            "{} has no calibration curve (vcgt tag); calibrate with a tool that embeds one",
+            name
+        ))
+    })?;
+    let malformed = || GammaError::InvalidLut(format!("{} has a malformed vcgt tag", name));
+
+    if tag.get(..4) != Some(b"vcgt") {
+        return Err(malformed());
+    }
+
+    match read_u32(tag, 8).ok_or_else(malformed)? {
+        // Table: channel count, entries per channel and bytes per entry, then the entries of
+        // each channel in turn
+        0 => {
+            let channels = read_u16(tag, 12).ok_or_else(malformed)? as usize;
+            let count = read_u16(tag, 14).ok_or_else(malformed)? as usize;
+            let entry_size = read_u16(tag, 16).ok_or_else(malformed)? as usize;
+
+            if !matches!(channels, 1 | 3) || !matches!(entry_size, 1 | 2) || count < 2 {
+                return Err(malformed());
+            }
+
+            let entry = |channel: usize, i: usize| {
+                let offset = 18 + (channel * count + i) * entry_size;
+
+                match entry_size {
+                    1 => tag.get(offset).map(|&v| v as f32 / 255.0),
+                    _ => read_u16(tag, offset).map(|v| v as f32 / 65535.0),
+                }
+            };
+
+            (0..count)
+                .map(|i| {
+                    let mut row = [0.0; 3];
+
+                    for (channel, value) in row.iter_mut().enumerate() {
+                        // A single channel applies to all three
+                        *value = entry(channel % channels, i).ok_or_else(malformed)?;
+                    }
+
+                    Ok(row)
+                })
+                .collect()
+        }
+        // Formula: gamma, minimum and maximum for each channel
+        1 => {
+            let mut parameters = [[0.0; 3]; 3];
+
+            for (channel, values) in parameters.iter_mut().enumerate() {
+                for (i, value) in values.iter_mut().enumerate() {
+                    *value =
+                        read_s15_fixed16(tag, 12 + (channel * 3 + i) * 4).ok_or_else(malformed)?;
+                }
+            }
+
+            Ok((0..RAMP_SIZE)
+                .map(|i| {
+                    let input = i as f32 / (RAMP_SIZE - 1) as f32;
+
+                    parameters.map(|[gamma, min, max]| {
+                        (min + (max - min) * input.powf(gamma)).clamp(0.0, 1.0)
+                    })
+                })
+                .collect())
+        }
+        kind => Err(GammaError::InvalidLut(format!(
+            "{} has an unknown vcgt type {}",
+            name, kind
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Wrap a vcgt tag in the smallest profile the parser accepts.
+    fn profile_with(vcgt: &[u8]) -> Vec<u8> {
+        let mut profile = vec![0u8; HEADER_SIZE];
+        profile[36..40].copy_from_slice(b"acsp");
+        profile.extend(1u32.to_be_bytes());
+        profile.extend(b"vcgt");
+        profile.extend((HEADER_SIZE as u32 + 16).to_be_bytes());
+        profile.extend((vcgt.len() as u32).to_be_bytes());
+        profile.extend(vcgt);
+        profile
+    }
+
+    #[test]
+    fn parses_formula_vcgt() {
+        let mut vcgt = b"vcgt\0\0\0\0".to_vec();
+        vcgt.extend(1u32.to_be_bytes());
+
+        for [gamma, min, max] in [[1.0f32, 0.0, 1.0], [2.0, 0.0, 1.0], [1.0, 0.1, 0.9]] {
+            for value in [gamma, min, max] {
+                vcgt.extend(((value * 65536.0) as i32).to_be_bytes());
+            }
+        }
+
+        let rows = vcgt_rows("formula.icc", &profile_with(&vcgt)).unwrap();
+
+        assert_eq!(rows.len(), RAMP_SIZE);
+        assert_eq!(rows[RAMP_SIZE - 1][0], 1.0);
+        assert!((rows[128][1] - 0.25).abs() < 0.01);
+        assert!((rows[0][2] - 0.1).abs() < 0.001);
+    }
+
+    #[test]
+    fn single_channel_table_applies_to_all() {
+        let mut vcgt = b"vcgt\0\0\0\0".to_vec();
+        vcgt.extend(0u32.to_be_bytes());
+        vcgt.extend([0, 1, 0, 2, 0, 1, 0, 255]);
+
+        let rows = vcgt_rows("mono.icc", &profile_with(&vcgt)).unwrap();
+
+        assert_eq!(rows, vec![[0.0; 3], [1.0; 3]]);
+    }
+
+    #[test]
+    fn reports_missing_and_malformed_tags() {
+        let mut no_vcgt = vec![0u8; HEADER_SIZE + 4];
+        no_vcgt[36..40].copy_from_slice(b"acsp");
+        let error = vcgt_rows("plain.icc", &no_vcgt).unwrap_err().to_string();
+        assert!(error.contains("no calibration curve"));
+
+        let error = vcgt_rows("text.icc", b"not a profile").unwrap_err();
+        assert!(error.to_string().contains("not a valid ICC profile"));
+
+        let truncated = profile_with(b"vcgt\0\0\0\0\0\0\0\0\0\x03\x01\0\0\x02");
+        let error = vcgt_rows("short.icc", &truncated).unwrap_err();
+        assert!(error.to_string().contains("malformed vcgt"));
+    }
+}
@@ -0,0 +1,265 @@
+use crate::windows::display::{GammaError, GammaRamp, RAMP_SIZE};
+use std::{fs, path::Path};
+
+/// A file format gamma ramps can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RampFormat {
+    /// A minimal ICC display profile carrying the ramp in a `vcgt` tag.
+    Icc,
+    /// One `R,G,B` row per level with values from 0 to 1, loadable as a LUT.
+    Csv,
+    /// The 256 red, green and blue entries as little-endian 16-bit values.
+    Raw,
+}
+
+impl RampFormat {
+    /// Pick the format from a file extension, if it's one of ours.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_string_lossy().to_lowercase();
+
+        match extension.as_str() {
+            "icc" | "icm" => Some(Self::Icc),
+            "csv" => Some(Self::Csv),
+            "bin" | "raw" => Some(Self::Raw),
+            _ => None,
+        }
+    }
+
+    /// Get the extension files in this format are saved with.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Icc => "icc",
+            Self::Csv => "csv",
+            Self::Raw => "bin",
+        }
+    }
+
+    /// Encode a ramp in this format. The description names the profile in ICC files.
+    pub fn encode(&self, ramp: &GammaRamp, description: &str) -> Vec<u8> {
+        match self {
+            Self::Icc => encode_icc(ramp, description),
+            Self::Csv => encode_csv(ramp).into_bytes(),
+            Self::Raw => ramp.as_raw().iter().flat_map(|v| v.to_le_bytes()).collect(),
+        }
+    }
+}
+
+/// Suggest a file name for a monitor's exported ramp, keeping only characters that are safe in
+/// file names.
+pub fn file_name(monitor_name: &str, format: RampFormat) -> String {
+    let name: Vec<String> = monitor_name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    format!("gammar-{}.{}", name.join("-"), format.extension())
+}
+
+/// Write a ramp to a file in the format its extension picks. Paths without an extension get an
+/// ICC profile and the `.icc` extension.
+pub fn save(ramp: &GammaRamp, path: &Path, description: &str) -> Result<(), GammaError> {
+    let (format, path) = match RampFormat::from_path(path) {
+        Some(format) => (format, path.to_path_buf()),
+        None if path.extension().is_none() => (RampFormat::Icc, path.with_extension("icc")),
+        None => {
+            return Err(GammaError::Unsupported(format!(
+                "Can't export to {}; use .icc, .icm, .csv or .bin",
+                path.display()
+            )))
+        }
+    };
+
+    fs::write(&path, format.encode(ramp, description)).map_err(|source| GammaError::Io {
+        context: format!("Failed to write {}", path.display()),
+        source,
+    })
+}
+
+fn encode_csv(ramp: &GammaRamp) -> String {
+    let mut csv = String::from("R,G,B\n");
+
+    for i in 0..RAMP_SIZE {
+        let [red, green, blue] =
+            [ramp.red(), ramp.green(), ramp.blue()].map(|channel| channel[i] as f32 / 65535.0);
+
+        csv.push_str(&format!("{:.6},{:.6},{:.6}\n", red, green, blue));
+    }
+
+    csv
+}
+
+/// Size of the ICC profile header.
+const ICC_HEADER_SIZE: usize = 128;
+
+/// The D50 white point of the ICC profile connection space.
+const D50: [f32; 3] = [0.9642, 1.0, 0.8249];
+
+fn s15_fixed16(value: f32) -> [u8; 4] {
+    ((value * 65536.0).round() as i32).to_be_bytes()
+}
+
+/// Build a version 2 `textDescriptionType` tag with only the ASCII description.
+fn description_tag(text: &str) -> Vec<u8> {
+    let ascii: Vec<u8> = text
+        .chars()
+        .map(|c| if c.is_ascii() { c as u8 } else { b'?' })
+        .collect();
+
+    let mut tag = b"desc\0\0\0\0".to_vec();
+    tag.extend(((ascii.len() + 1) as u32).to_be_bytes());
+    tag.extend(&ascii);
+    tag.push(0);
+    // Empty Unicode and ScriptCode descriptions
+    tag.extend([0; 4 + 4 + 2 + 1 + 67]);
+    tag
+}
+
+fn text_tag(text: &str) -> Vec<u8> {
+    let mut tag = b"text\0\0\0\0".to_vec();
+    tag.extend(text.bytes().filter(u8::is_ascii));
+    tag.push(0);
+    tag
+}
+
+fn xyz_tag(xyz: [f32; 3]) -> Vec<u8> {
+    let mut tag = b"XYZ \0\0\0\0".to_vec();
+    tag.extend(xyz.iter().flat_map(|&v| s15_fixed16(v)));
+    tag
+}
+
+/// Build a `vcgt` tag holding the ramp as a table of 16-bit entries per channel.
+fn vcgt_tag(ramp: &GammaRamp) -> Vec<u8> {
+    let mut tag = b"vcgt\0\0\0\0".to_vec();
+    // Table type, 3 channels of RAMP_SIZE 2-byte entries
+    tag.extend(0u32.to_be_bytes());
+    tag.extend(3u16.to_be_bytes());
+    tag.extend((RAMP_SIZE as u16).to_be_bytes());
+    tag.extend(2u16.to_be_bytes());
+    tag.extend(ramp.as_raw().iter().flat_map(|v| v.to_be_bytes()));
+    tag
+}
+
+/// Build a minimal version 2 display profile whose only calibration data is the ramp's `vcgt`
+/// tag.
+fn encode_icc(ramp: &GammaRamp, description: &str) -> Vec<u8> {
+    let tags: [(&[u8; 4], Vec<u8>); 4] = [
+        (b"desc", description_tag(description)),
+        (b"cprt", text_tag("No copyright, use freely")),
+        (b"wtpt", xyz_tag(D50)),
+        (b"vcgt", vcgt_tag(ramp)),
+    ];
+
+    let table_size = 4 + tags.len() * 12;
+    let mut table = (tags.len() as u32).to_be_bytes().to_vec();
+    let mut data = Vec::new();
+
+    for (signature, tag) in &tags {
+        let offset = ICC_HEADER_SIZE + table_size + data.len();
+
+        table.extend(*signature);
+        table.extend((offset as u32).to_be_bytes());
+        table.extend((tag.len() as u32).to_be_bytes());
+
+        data.extend(tag);
+        // Tags start on 4-byte boundaries
+        data.resize(data.len().next_multiple_of(4), 0);
+    }
+
+    let size = ICC_HEADER_SIZE + table.len() + data.len();
+    let mut header = [0u8; ICC_HEADER_SIZE];
+
+    header[0..4].copy_from_slice(&(size as u32).to_be_bytes());
+    header[8..12].copy_from_slice(&0x0210_0000u32.to_be_bytes());
+    header[12..16].copy_from_slice(b"mntr");
+    header[16..20].copy_from_slice(b"RGB ");
+    header[20..24].copy_from_slice(b"XYZ ");
+    header[36..40].copy_from_slice(b"acsp");
+
+    for (i, value) in D50.iter().enumerate() {
+        header[68 + i * 4..72 + i * 4].copy_from_slice(&s15_fixed16(*value));
+    }
+
+    header[80..84].copy_from_slice(b"GMMR");
+
+    [header.as_slice(), &table, &data].concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::windows::display::{compute_gamma_ramp, DisplaySettings, Lut};
+
+    fn tag<'a>(profile: &'a [u8], signature: &[u8; 4]) -> &'a [u8] {
+        let count = u32::from_be_bytes(profile[128..132].try_into().unwrap()) as usize;
+
+        (0..count)
+            .map(|i| &profile[132 + i * 12..144 + i * 12])
+            .find(|entry| &entry[..4] == signature)
+            .map(|entry| {
+                let offset = u32::from_be_bytes(entry[4..8].try_into().unwrap()) as usize;
+                let size = u32::from_be_bytes(entry[8..12].try_into().unwrap()) as usize;
+
+                &profile[offset..offset + size]
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn icc_profile_carries_the_ramp() {
+        let ramp = compute_gamma_ramp(&DisplaySettings::new(1.6, 0.1, 1.2));
+        let profile = RampFormat::Icc.encode(&ramp, "Test");
+
+        assert_eq!(
+            u32::from_be_bytes(profile[0..4].try_into().unwrap()) as usize,
+            profile.len()
+        );
+        assert_eq!(&profile[36..40], b"acsp");
+
+        let vcgt = tag(&profile, b"vcgt");
+        let entries: Vec<u16> = vcgt[18..]
+            .chunks(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+            .collect();
+
+        assert_eq!(entries, ramp.as_raw());
+    }
+
+    #[test]
+    fn csv_export_loads_back_as_lut() {
+        let ramp = compute_gamma_ramp(&DisplaySettings::new(0.8, -0.9, 1.0));
+        let csv = String::from_utf8(RampFormat::Csv.encode(&ramp, "")).unwrap();
+        let lut = Lut::parse_csv("ramp.csv", &csv).unwrap();
+
+        assert!(lut
+            .green
+            .iter()
+            .zip(ramp.green())
+            .all(|(&value, &entry)| (value * 65535.0).round() as u16 == entry));
+    }
+
+    #[test]
+    fn icc_export_loads_back_as_lut() {
+        let ramp = compute_gamma_ramp(&DisplaySettings::new(1.6, 0.1, 1.2));
+        let lut = Lut::parse_icc("ramp.icc", &RampFormat::Icc.encode(&ramp, "Test")).unwrap();
+
+        assert!(lut
+            .blue
+            .iter()
+            .zip(ramp.blue())
+            .all(|(&value, &entry)| (value * 65535.0).round() as u16 == entry));
+    }
+
+    #[test]
+    fn file_names_are_sanitized() {
+        assert_eq!(
+            file_name("DELL U2720Q (Primary)", RampFormat::Icc),
+            "gammar-DELL-U2720Q-Primary.icc"
+        );
+        assert_eq!(
+            RampFormat::from_path(Path::new("curve.ICM")),
+            Some(RampFormat::Icc)
+        );
+        assert_eq!(RampFormat::from_path(Path::new("curve.txt")), None);
+    }
+}
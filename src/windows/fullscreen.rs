@@ -0,0 +1,113 @@
+use crate::windows::display::MonitorRect;
+#[cfg(windows)]
+use windows::Win32::Foundation::RECT;
+#[cfg(windows)]
+use windows::Win32::Graphics::Gdi::{
+    GetMonitorInfoW, MonitorFromWindow, MONITORINFO, MONITOR_DEFAULTTONULL,
+};
+#[cfg(windows)]
+use windows::Win32::UI::Shell::{SHQueryUserNotificationState, QUNS_RUNNING_D3D_FULL_SCREEN};
+#[cfg(windows)]
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetClassNameW, GetForegroundWindow, GetShellWindow, GetWindowRect, IsZoomed,
+};
+
+/// Check whether a window covers the whole of a monitor. Windows may overhang it, as maximized
+/// ones do by their borders.
+pub fn covers_monitor(window: MonitorRect, monitor: MonitorRect) -> bool {
+    window.x <= monitor.x
+        && window.y <= monitor.y
+        && window.right() >= monitor.right()
+        && window.bottom() >= monitor.bottom()
+}
+
+#[cfg(windows)]
+fn to_rect(rect: RECT) -> MonitorRect {
+    MonitorRect {
+        x: rect.left,
+        y: rect.top,
+        width: (rect.right - rect.left).max(0) as u32,
+        height: (rect.bottom - rect.top).max(0) as u32,
+    }
+}
+
+/// Check whether the focused window is a fullscreen app: a game in exclusive fullscreen, or a
+/// borderless window covering its monitor. Maximized windows cover the monitor too when the
+/// taskbar hides itself, so they only count with `include_maximized`.
+#[cfg(windows)]
+pub fn fullscreen_app_focused(include_maximized: bool) -> bool {
+    let exclusive = unsafe { SHQueryUserNotificationState() }
+        .is_ok_and(|state| state == QUNS_RUNNING_D3D_FULL_SCREEN);
+    if exclusive {
+        return true;
+    }
+
+    unsafe {
+        let window = GetForegroundWindow();
+        if window.is_invalid() || window == GetShellWindow() {
+            return false;
+        }
+
+        // The desktop behind the icons covers the monitor as well
+        let mut class = [0u16; 16];
+        let length = GetClassNameW(window, &mut class).max(0) as usize;
+        let class = String::from_utf16_lossy(&class[..length]);
+        if class == "Progman" || class == "WorkerW" {
+            return false;
+        }
+
+        if !include_maximized && IsZoomed(window).as_bool() {
+            return false;
+        }
+
+        let mut window_rect = RECT::default();
+        if GetWindowRect(window, &mut window_rect).is_err() {
+            return false;
+        }
+
+        let monitor = MonitorFromWindow(window, MONITOR_DEFAULTTONULL);
+        let mut info = MONITORINFO {
+            cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+            ..Default::default()
+        };
+        if monitor.is_invalid() || !GetMonitorInfoW(monitor, &mut info).as_bool() {
+            return false;
+        }
+
+        covers_monitor(to_rect(window_rect), to_rect(info.rcMonitor))
+    }
+}
+
+/// Check whether the focused window is a fullscreen app. Only Windows can tell, so this is never
+/// the case elsewhere.
+#[cfg(not(windows))]
+pub fn fullscreen_app_focused(_include_maximized: bool) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: i32, y: i32, width: u32, height: u32) -> MonitorRect {
+        MonitorRect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn only_windows_over_the_whole_monitor_cover_it() {
+        let monitor = rect(1920, 0, 2560, 1440);
+
+        assert!(covers_monitor(monitor, monitor));
+        // Maximized windows overhang by their borders
+        assert!(covers_monitor(rect(1912, -8, 2576, 1456), monitor));
+        // Leaving room for the taskbar
+        assert!(!covers_monitor(rect(1920, 0, 2560, 1400), monitor));
+        // Fullscreen on the neighbouring monitor
+        assert!(!covers_monitor(rect(0, 0, 1920, 1080), monitor));
+    }
+}
@@ -1,4 +1,182 @@
+use crate::windows::display::GammaError;
 use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs, path::Path, time::Duration};
+
+/// How long a chord's second key is waited for after its first stroke.
+pub const CHORD_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Version of the keybinds files written by [`save_keybinds`].
+pub const KEYBINDS_FILE_VERSION: u32 = 2;
+
+/// A standalone file of keybinds, for carrying them between installs without the rest of the
+/// config. Fields added in later versions must default, so older files keep loading.
+#[derive(Serialize, Deserialize)]
+struct KeybindsFile {
+    version: u32,
+    #[serde(
+        serialize_with = "crate::serialize_keybinds",
+        deserialize_with = "crate::deserialize_keybinds"
+    )]
+    keybinds: HashMap<HotkeyAction, Vec<KeybindConfig>>,
+    /// The names of the profiles bound actions load, by id, as ids differ between installs.
+    /// Added in version 2.
+    #[serde(default)]
+    profile_names: HashMap<u64, String>,
+}
+
+/// Keybinds read by [`load_keybinds`].
+pub struct LoadedKeybinds {
+    pub keybinds: HashMap<HotkeyAction, Vec<KeybindConfig>>,
+    /// Actions left out as no profile here has the name of the one they load.
+    pub missing_profiles: usize,
+}
+
+/// Write keybinds to a standalone file, along with the names `profile_name` gives the profiles
+/// they load.
+pub fn save_keybinds(
+    keybinds: &HashMap<HotkeyAction, Vec<KeybindConfig>>,
+    profile_name: impl Fn(u64) -> Option<String>,
+    path: &Path,
+) -> Result<(), GammaError> {
+    let file = KeybindsFile {
+        version: KEYBINDS_FILE_VERSION,
+        keybinds: keybinds.clone(),
+        profile_names: keybinds
+            .keys()
+            .filter_map(|action| action.profile_id())
+            .filter_map(|id| Some((id, profile_name(id)?)))
+            .collect(),
+    };
+    let json = serde_json::to_string_pretty(&file).unwrap_or_default();
+
+    fs::write(path, json).map_err(|source| GammaError::Io {
+        context: format!("Failed to write {}", path.display()),
+        source,
+    })
+}
+
+/// Read the keybinds of a file written by [`save_keybinds`]. Actions loading a profile are moved
+/// to the id `profile_id` finds for its name, and left out when it finds none.
+pub fn load_keybinds(
+    path: &Path,
+    profile_id: impl Fn(&str) -> Option<u64>,
+) -> Result<LoadedKeybinds, GammaError> {
+    let contents = fs::read_to_string(path).map_err(|source| GammaError::Io {
+        context: format!("Failed to read {}", path.display()),
+        source,
+    })?;
+    let file = serde_json::from_str::<KeybindsFile>(&contents).map_err(|e| {
+        GammaError::InvalidKeybinds(format!("{} isn't a keybinds file: {}", path.display(), e))
+    })?;
+
+    let count = file.keybinds.len();
+    let keybinds: HashMap<_, _> = file
+        .keybinds
+        .into_iter()
+        .filter_map(|(action, mut bindings)| {
+            for keybind in &mut bindings {
+                keybind.repair();
+            }
+
+            let Some(id) = action.profile_id() else {
+                return Some((action, bindings));
+            };
+            let local = profile_id(file.profile_names.get(&id)?)?;
+            Some((action.with_profile_id(local), bindings))
+        })
+        .collect();
+
+    Ok(LoadedKeybinds {
+        missing_profiles: count - keybinds.len(),
+        keybinds,
+    })
+}
+
+/// Find the binding of an action other than `except` on the same key combination as `keybind`,
+/// as the action and the binding's position among its bindings.
+pub fn binding_using(
+    keybinds: &HashMap<HotkeyAction, Vec<KeybindConfig>>,
+    keybind: &KeybindConfig,
+    except: HotkeyAction,
+) -> Option<(HotkeyAction, usize)> {
+    keybinds
+        .iter()
+        .filter(|(action, _)| **action != except)
+        .find_map(|(action, bindings)| {
+            let index = bindings
+                .iter()
+                .position(|kb| kb.same_combination(keybind))?;
+            Some((*action, index))
+        })
+}
+
+/// Set an action's binding at `index`, adding it as an alternate when `index` is past its last
+/// binding.
+pub fn set_binding(
+    keybinds: &mut HashMap<HotkeyAction, Vec<KeybindConfig>>,
+    action: HotkeyAction,
+    index: usize,
+    keybind: KeybindConfig,
+) {
+    let bindings = keybinds.entry(action).or_default();
+
+    match bindings.get_mut(index) {
+        Some(binding) => *binding = keybind,
+        None => bindings.push(keybind),
+    }
+}
+
+/// Remove an action's binding at `index`, and the action's entry with its last binding.
+pub fn remove_binding(
+    keybinds: &mut HashMap<HotkeyAction, Vec<KeybindConfig>>,
+    action: HotkeyAction,
+    index: usize,
+) {
+    if let Some(bindings) = keybinds.get_mut(&action) {
+        if index < bindings.len() {
+            bindings.remove(index);
+        }
+        if bindings.is_empty() {
+            keybinds.remove(&action);
+        }
+    }
+}
+
+/// Find the imported actions with a binding on a key combination an existing binding of another
+/// action uses.
+pub fn keybind_conflicts(
+    existing: &HashMap<HotkeyAction, Vec<KeybindConfig>>,
+    imported: &HashMap<HotkeyAction, Vec<KeybindConfig>>,
+) -> Vec<HotkeyAction> {
+    imported
+        .iter()
+        .filter(|(action, bindings)| {
+            bindings
+                .iter()
+                .any(|keybind| binding_using(existing, keybind, **action).is_some())
+        })
+        .map(|(action, _)| *action)
+        .collect()
+}
+
+/// Merge imported keybinds into existing ones, replacing the bindings of the same actions.
+/// Conflicting ones, see [`keybind_conflicts`], are left out. Returns how many were merged.
+pub fn merge_keybinds(
+    existing: &mut HashMap<HotkeyAction, Vec<KeybindConfig>>,
+    imported: HashMap<HotkeyAction, Vec<KeybindConfig>>,
+) -> usize {
+    let conflicts = keybind_conflicts(existing, &imported);
+    let mut merged = 0;
+
+    for (action, bindings) in imported {
+        if !conflicts.contains(&action) {
+            existing.insert(action, bindings);
+            merged += 1;
+        }
+    }
+
+    merged
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum HotkeyAction {
@@ -8,8 +186,30 @@ pub enum HotkeyAction {
     DecreaseBrightness,
     IncreaseContrast,
     DecreaseContrast,
+    /// Dim past the brightness minimum, by the extra dimming step.
+    IncreaseDimming,
+    DecreaseDimming,
     Reset,
-    LoadProfile(usize),
+    /// Reset only gamma, keeping the other settings.
+    ResetGamma,
+    ResetBrightness,
+    ResetContrast,
+    /// Go back to the settings from before the last profile load or reset.
+    RestorePrevious,
+    /// Load the profile with this id.
+    LoadProfile(u64),
+    /// Load the profile with this id, or return to the settings from before it was loaded.
+    ToggleProfile(u64),
+    ToggleFilter,
+    ToggleInvert,
+    /// Set the color temperature, in Kelvin.
+    ApplyTemperaturePreset(u32),
+    /// Select the monitor at this position in the enumeration, so later hotkeys adjust it.
+    SelectMonitor(usize),
+    /// Select the next attached monitor in enumeration order, wrapping around.
+    NextMonitor,
+    /// Bring the Gammar window to the front, or hide it when it's already there.
+    ToggleWindow,
 }
 
 impl HotkeyAction {
@@ -22,48 +222,395 @@ impl HotkeyAction {
             HotkeyAction::DecreaseBrightness => "Decrease brightness".to_string(),
             HotkeyAction::IncreaseContrast => "Increase contrast".to_string(),
             HotkeyAction::DecreaseContrast => "Decrease contrast".to_string(),
+            HotkeyAction::IncreaseDimming => "Increase extra dimming".to_string(),
+            HotkeyAction::DecreaseDimming => "Decrease extra dimming".to_string(),
             HotkeyAction::Reset => "Reset to default".to_string(),
-            HotkeyAction::LoadProfile(index) => format!("Load profile {}", index + 1),
+            HotkeyAction::ResetGamma => "Reset gamma".to_string(),
+            HotkeyAction::ResetBrightness => "Reset brightness".to_string(),
+            HotkeyAction::ResetContrast => "Reset contrast".to_string(),
+            HotkeyAction::RestorePrevious => "Restore previous settings".to_string(),
+            HotkeyAction::LoadProfile(id) => format!("Load profile #{}", id),
+            HotkeyAction::ToggleProfile(id) => format!("Toggle profile #{}", id),
+            HotkeyAction::ToggleFilter => "Toggle color filter".to_string(),
+            HotkeyAction::ToggleInvert => "Toggle invert colors".to_string(),
+            HotkeyAction::ApplyTemperaturePreset(kelvin) => {
+                format!("Apply {}K temperature", kelvin)
+            }
+            HotkeyAction::SelectMonitor(index) => format!("Select monitor #{}", index + 1),
+            HotkeyAction::NextMonitor => "Select next monitor".to_string(),
+            HotkeyAction::ToggleWindow => "Show or hide the window".to_string(),
+        }
+    }
+
+    /// Check whether the action repeats while its keys are held, as steps do. Resets and loads
+    /// happen once per press.
+    pub fn repeats(&self) -> bool {
+        matches!(
+            self,
+            HotkeyAction::IncreaseGamma
+                | HotkeyAction::DecreaseGamma
+                | HotkeyAction::IncreaseBrightness
+                | HotkeyAction::DecreaseBrightness
+                | HotkeyAction::IncreaseContrast
+                | HotkeyAction::DecreaseContrast
+                | HotkeyAction::IncreaseDimming
+                | HotkeyAction::DecreaseDimming
+        )
+    }
+
+    /// The key the action is saved under in keybind maps: its serde form, so unit variants are
+    /// their name and others JSON, e.g. `{"LoadProfile":2}`.
+    pub fn to_key(&self) -> String {
+        match serde_json::to_value(self) {
+            Ok(serde_json::Value::String(name)) => name,
+            Ok(value) => value.to_string(),
+            Err(_) => String::new(),
+        }
+    }
+
+    /// Read an action from a keybind map key, including keys saved by older versions like
+    /// `LoadProfile(2)`.
+    pub fn from_key(key: &str) -> Option<Self> {
+        let value = serde_json::from_str(key).unwrap_or_else(|_| Self::legacy_key_value(key));
+
+        serde_json::from_value(value).ok()
+    }
+
+    /// Turn a legacy `Name` or `Name(argument)` key into the serde form of the same action.
+    fn legacy_key_value(key: &str) -> serde_json::Value {
+        let Some((name, argument)) = key.strip_suffix(')').and_then(|k| k.split_once('(')) else {
+            return serde_json::Value::String(key.to_string());
+        };
+
+        match argument.parse::<serde_json::Number>() {
+            Ok(number) => serde_json::json!({ name: number }),
+            Err(_) => serde_json::Value::Null,
+        }
+    }
+
+    /// Get the id of the profile the action loads, if it loads one.
+    pub fn profile_id(&self) -> Option<u64> {
+        match self {
+            HotkeyAction::LoadProfile(id) | HotkeyAction::ToggleProfile(id) => Some(*id),
+            _ => None,
         }
     }
+
+    /// Get the same action for another profile. Actions that don't load one stay as they are.
+    pub fn with_profile_id(self, id: u64) -> Self {
+        match self {
+            HotkeyAction::LoadProfile(_) => HotkeyAction::LoadProfile(id),
+            HotkeyAction::ToggleProfile(_) => HotkeyAction::ToggleProfile(id),
+            action => action,
+        }
+    }
+}
+
+/// Punctuation keys, as their DOM `code`, the token stored for them and the label shown.
+const PUNCTUATION_KEYS: [(&str, &str, &str); 9] = [
+    ("Semicolon", "SEMICOLON", ";"),
+    ("Quote", "QUOTE", "'"),
+    ("BracketLeft", "BRACKETLEFT", "["),
+    ("BracketRight", "BRACKETRIGHT", "]"),
+    ("Comma", "COMMA", ","),
+    ("Period", "PERIOD", "."),
+    ("Slash", "SLASH", "/"),
+    ("Backquote", "BACKQUOTE", "`"),
+    ("Backslash", "BACKSLASH", "\\"),
+];
+
+/// Numpad keys, in the same form. Their tokens are the `Code` names global shortcuts parse, so
+/// they stay apart from the main keyboard's digits and Enter.
+const NUMPAD_KEYS: [(&str, &str, &str); 17] = [
+    ("Numpad0", "Numpad0", "Num 0"),
+    ("Numpad1", "Numpad1", "Num 1"),
+    ("Numpad2", "Numpad2", "Num 2"),
+    ("Numpad3", "Numpad3", "Num 3"),
+    ("Numpad4", "Numpad4", "Num 4"),
+    ("Numpad5", "Numpad5", "Num 5"),
+    ("Numpad6", "Numpad6", "Num 6"),
+    ("Numpad7", "Numpad7", "Num 7"),
+    ("Numpad8", "Numpad8", "Num 8"),
+    ("Numpad9", "Numpad9", "Num 9"),
+    ("NumpadAdd", "NumpadAdd", "Num +"),
+    ("NumpadSubtract", "NumpadSubtract", "Num -"),
+    ("NumpadMultiply", "NumpadMultiply", "Num *"),
+    ("NumpadDivide", "NumpadDivide", "Num /"),
+    ("NumpadDecimal", "NumpadDecimal", "Num ."),
+    ("NumpadEnter", "NumpadEnter", "Num Enter"),
+    ("NumpadEqual", "NumpadEqual", "Num ="),
+];
+
+/// Numpad tokens stored by older versions, with the token replacing each.
+const LEGACY_NUMPAD_TOKENS: [(&str, &str); 5] = [
+    ("NumpadPLUS", "NumpadAdd"),
+    ("NumpadSUBTRACT", "NumpadSubtract"),
+    ("NumpadMULTIPLY", "NumpadMultiply"),
+    ("NumpadDIVIDE", "NumpadDivide"),
+    ("NumpadDECIMAL", "NumpadDecimal"),
+];
+
+/// Media, browser and launch keys, in the same form. Those without a token global shortcuts
+/// accept (the browser and launch keys) keep their code, so they're reported as unsupported when
+/// recorded rather than ignored.
+const EXTENDED_KEYS: [(&str, &str, &str); 21] = [
+    ("MediaPlayPause", "MEDIAPLAYPAUSE", "Play/Pause"),
+    ("MediaStop", "MEDIASTOP", "Stop"),
+    ("MediaTrackNext", "MEDIATRACKNEXT", "Next track"),
+    ("MediaTrackPrevious", "MEDIATRACKPREV", "Previous track"),
+    ("AudioVolumeUp", "AUDIOVOLUMEUP", "Volume up"),
+    ("AudioVolumeDown", "AUDIOVOLUMEDOWN", "Volume down"),
+    ("AudioVolumeMute", "AUDIOVOLUMEMUTE", "Mute"),
+    ("PrintScreen", "PRINTSCREEN", "Print Screen"),
+    ("ScrollLock", "SCROLLLOCK", "Scroll Lock"),
+    ("Pause", "PAUSE", "Pause"),
+    ("BrowserBack", "BrowserBack", "Browser back"),
+    ("BrowserForward", "BrowserForward", "Browser forward"),
+    ("BrowserHome", "BrowserHome", "Browser home"),
+    ("BrowserRefresh", "BrowserRefresh", "Browser refresh"),
+    ("BrowserSearch", "BrowserSearch", "Browser search"),
+    ("BrowserFavorites", "BrowserFavorites", "Favorites"),
+    ("BrowserStop", "BrowserStop", "Browser stop"),
+    ("LaunchMail", "LaunchMail", "Mail"),
+    ("LaunchApp1", "LaunchApp1", "My Computer"),
+    ("LaunchApp2", "LaunchApp2", "Calculator"),
+    ("MediaSelect", "MediaSelect", "Media select"),
+];
+
+/// Keys recognized by their DOM `code`, as `(code, token, label)`.
+fn code_keys() -> impl Iterator<Item = &'static (&'static str, &'static str, &'static str)> {
+    PUNCTUATION_KEYS
+        .iter()
+        .chain(NUMPAD_KEYS.iter())
+        .chain(EXTENDED_KEYS.iter())
+}
+
+/// The keybinds a fresh install starts with, one per action, all on Ctrl + Alt + Shift. Ctrl + Alt
+/// alone is taken by too much, like graphics drivers rotating the screen on its arrows and remote
+/// desktop sessions on Ctrl + Alt + End.
+pub fn default_keybinds() -> HashMap<HotkeyAction, Vec<KeybindConfig>> {
+    let ctrl_alt_shift = |key: &str| {
+        vec![KeybindConfig::new(
+            vec!["Ctrl".into(), "Alt".into(), "Shift".into()],
+            key.into(),
+        )]
+    };
+
+    HashMap::from([
+        (HotkeyAction::IncreaseGamma, ctrl_alt_shift("PAGEUP")),
+        (HotkeyAction::DecreaseGamma, ctrl_alt_shift("PAGEDOWN")),
+        (HotkeyAction::IncreaseBrightness, ctrl_alt_shift("UP")),
+        (HotkeyAction::DecreaseBrightness, ctrl_alt_shift("DOWN")),
+        (HotkeyAction::IncreaseContrast, ctrl_alt_shift("RIGHT")),
+        (HotkeyAction::DecreaseContrast, ctrl_alt_shift("LEFT")),
+        (HotkeyAction::Reset, ctrl_alt_shift("HOME")),
+        (HotkeyAction::RestorePrevious, ctrl_alt_shift("END")),
+    ])
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KeybindConfig {
     pub modifiers: Vec<String>,
     pub key: String,
+    /// The key pressed on its own after the first stroke, making the keybind a chord like
+    /// "Ctrl + Alt + G, B".
+    #[serde(default)]
+    pub then: Option<String>,
 }
 
 impl KeybindConfig {
     pub fn new(modifiers: Vec<String>, key: String) -> Self {
-        Self { modifiers, key }
+        Self {
+            modifiers,
+            key,
+            then: None,
+        }
+    }
+
+    /// Spell a modifier the way keybinds store and show it, e.g. "Meta" or "super" as "Win".
+    fn canonical_modifier(name: &str) -> Option<&'static str> {
+        match name.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" => Some("Ctrl"),
+            "shift" => Some("Shift"),
+            "alt" | "option" => Some("Alt"),
+            "win" | "windows" | "meta" | "super" | "cmd" | "command" => Some("Win"),
+            "altgr" | "altgraph" => Some("AltGr"),
+            _ => None,
+        }
     }
 
-    /// Convert to Dioxus shortcut format: "Ctrl+Shift+F1"
+    /// Record a key pressed while capturing a keybind if it's a modifier, returning whether it
+    /// was. `key` is the DOM event's, which tells AltGr apart from the right Alt of layouts
+    /// without it, as both have the code "AltRight".
+    ///
+    /// AltGr is kept as its own modifier, as it types characters on layouts that have it. Windows
+    /// reports it as a left Ctrl followed by AltGr, and it acts as Ctrl + Alt, so neither is
+    /// recorded alongside it.
+    pub fn capture_modifier(modifiers: &mut Vec<String>, key: &str) -> bool {
+        let name = match key {
+            "AltGraph" => "AltGr",
+            "Control" => "Ctrl",
+            "Shift" => "Shift",
+            "Alt" => "Alt",
+            "Meta" => "Win",
+            _ => return false,
+        };
+        let alt_gr = modifiers.iter().any(|m| m == "AltGr");
+
+        if name == "AltGr" {
+            modifiers.retain(|m| m != "Ctrl" && m != "Alt");
+        } else if alt_gr && (name == "Ctrl" || name == "Alt") {
+            return true;
+        }
+        if !modifiers.iter().any(|m| m == name) {
+            modifiers.push(name.to_string());
+        }
+
+        true
+    }
+
+    /// Convert to Dioxus shortcut format: "Ctrl+Shift+F1". The Windows key is shown as "Win",
+    /// but global_hotkey only parses it as "Super", and AltGr registers as the Ctrl + Alt it
+    /// sends. Chords give their first stroke.
     pub fn to_shortcut_string(&self) -> String {
-        let mut parts = self.modifiers.clone();
+        let mut parts: Vec<String> = self
+            .modifiers
+            .iter()
+            .map(|m| match Self::canonical_modifier(m) {
+                Some("Win") => "Super".to_string(),
+                Some("AltGr") => "Ctrl+Alt".to_string(),
+                _ => m.clone(),
+            })
+            .collect();
 
         parts.push(self.key.clone());
         parts.join("+")
     }
 
+    /// Repair keybinds saved by older versions or edited by hand, so they register. Returns
+    /// whether anything changed.
+    pub fn repair(&mut self) -> bool {
+        let modifiers = self.repair_modifiers();
+
+        match LEGACY_NUMPAD_TOKENS
+            .iter()
+            .find(|(legacy, _)| *legacy == self.key)
+        {
+            Some((_, token)) => {
+                self.key = token.to_string();
+                true
+            }
+            None => modifiers,
+        }
+    }
+
+    /// Respell modifiers saved under another name, like "Super" from working around the Windows
+    /// key not registering, and drop repeats. Returns whether anything changed.
+    fn repair_modifiers(&mut self) -> bool {
+        let mut repaired: Vec<String> = Vec::new();
+
+        for modifier in &self.modifiers {
+            let name = Self::canonical_modifier(modifier).map_or(modifier.clone(), str::to_string);
+
+            if !repaired.contains(&name) {
+                repaired.push(name);
+            }
+        }
+
+        let changed = repaired != self.modifiers;
+        self.modifiers = repaired;
+        changed
+    }
+
     /// Format KeybindConfig to a user-friendly string
     pub fn format(&self) -> String {
-        let mods = self.modifiers.join(" + ");
+        let label = |key: &str| {
+            code_keys()
+                .find(|(_, token, _)| *token == key)
+                .map_or(key.to_string(), |(_, _, label)| label.to_string())
+        };
+        let mut formatted = label(&self.key);
 
-        if mods.is_empty() {
-            return self.key.clone();
+        if !self.modifiers.is_empty() {
+            formatted = format!("{} + {}", self.modifiers.join(" + "), formatted);
+        }
+        if let Some(then) = &self.then {
+            formatted = format!("{}, {}", formatted, label(then));
         }
 
-        format!("{} + {}", mods, self.key)
+        formatted
+    }
+
+    /// Check whether two keybinds are the same key combination, whatever order their modifiers
+    /// were pressed in. A chord's first stroke counts as the same as a plain keybind on those
+    /// keys, which would fire instead of the chord starting, but chords sharing a first stroke
+    /// differ by their second key.
+    pub fn same_combination(&self, other: &KeybindConfig) -> bool {
+        let then = match (&self.then, &other.then) {
+            (Some(then), Some(other_then)) => then.eq_ignore_ascii_case(other_then),
+            _ => true,
+        };
+
+        self.key.eq_ignore_ascii_case(&other.key)
+            && self.held_modifiers() == other.held_modifiers()
+            && then
+    }
+
+    /// The modifiers the keybind holds down, lowercase and sorted, with AltGr as Ctrl + Alt.
+    fn held_modifiers(&self) -> Vec<String> {
+        let mut mods: Vec<String> = self
+            .modifiers
+            .iter()
+            .flat_map(|m| match Self::canonical_modifier(m) {
+                Some("AltGr") => vec!["ctrl".to_string(), "alt".to_string()],
+                _ => vec![m.to_lowercase()],
+            })
+            .collect();
+        mods.sort();
+        mods.dedup();
+        mods
+    }
+
+    /// Check whether the keybind's modifiers leave keys typing text: none, or AltGr or Ctrl + Alt
+    /// alone, which Windows treats alike.
+    fn modifiers_type_text(&self) -> bool {
+        let held = self.held_modifiers();
+
+        held.is_empty() || held == ["alt", "ctrl"]
+    }
+
+    /// Check whether the keybind is a letter, digit, punctuation or space key without modifiers,
+    /// or with AltGr or Ctrl + Alt alone. Registering one takes the key from every app, so it
+    /// can't be typed anymore.
+    pub fn blocks_typing(&self) -> bool {
+        let types_text = (self.key.len() == 1
+            && self.key.chars().all(|c| c.is_ascii_alphanumeric()))
+            || self.key == "SPACE"
+            || PUNCTUATION_KEYS
+                .iter()
+                .any(|(_, token, _)| *token == self.key);
+
+        self.modifiers_type_text() && types_text
     }
 
     /// Normalize a single key string to standard representation
     pub fn normalize_key(key: &str) -> String {
+        // Punctuation as typed, keys named like their code, and already normalized keys
+        if let Some((_, token, _)) =
+            code_keys().find(|(code, token, label)| [*code, *token, *label].contains(&key))
+        {
+            return token.to_string();
+        }
+
         match key {
             // Function keys
-            "F1" | "F2" | "F3" | "F4" | "F5" | "F6" | "F7" | "F8" | "F9" | "F10" | "F11"
-            | "F12" => key.to_uppercase(),
+            k if k
+                .strip_prefix('F')
+                .and_then(|n| n.parse::<u8>().ok())
+                .is_some_and(|n| (1..=24).contains(&n)) =>
+            {
+                k.to_string()
+            }
             // Arrow keys
             "ArrowUp" => "UP".to_string(),
             "ArrowDown" => "DOWN".to_string(),
@@ -77,35 +624,25 @@ impl KeybindConfig {
             "Insert" => "INSERT".to_string(),
             "Delete" => "DELETE".to_string(),
             "Backspace" => "BACKSPACE".to_string(),
-            "Enter" => "RETURN".to_string(),
+            "Enter" => "ENTER".to_string(),
             "Tab" => "TAB".to_string(),
-            "Space" => "SPACE".to_string(),
+            "Space" | " " => "SPACE".to_string(),
             // Alphanumeric keys
             k if k.len() == 1 && k.chars().next().unwrap().is_alphanumeric() => k.to_uppercase(),
-            "+" => "PLUS".to_string(),
+            // "+" shares the physical key with "=" on most layouts
+            "+" | "=" => "EQUAL".to_string(),
             "-" => "MINUS".to_string(),
+            // Already normalized keys
+            "UP" | "DOWN" | "LEFT" | "RIGHT" | "PAGEUP" | "PAGEDOWN" | "HOME" | "END"
+            | "INSERT" | "DELETE" | "BACKSPACE" | "ENTER" | "TAB" | "SPACE" | "EQUAL" | "MINUS" => {
+                key.to_string()
+            }
             _ => String::new(),
         }
     }
 
     /// Normalize key using both key and code to handle numpad and digit keys correctly
     pub fn normalize_key_with_code(key: &str, code: &str) -> String {
-        // Check for numpad keys first using code
-        if code.starts_with("Numpad") {
-            let numpad_key = code.strip_prefix("Numpad").unwrap();
-            match numpad_key {
-                "0" | "1" | "2" | "3" | "4" | "5" | "6" | "7" | "8" | "9" => {
-                    return format!("Numpad{}", numpad_key);
-                }
-                "Add" => return "NumpadPLUS".to_string(),
-                "Subtract" => return "NumpadMINUS".to_string(),
-                "Multiply" => return "NumpadMULTIPLY".to_string(),
-                "Divide" => return "NumpadDIVIDE".to_string(),
-                "Decimal" => return "NumpadDECIMAL".to_string(),
-                _ => {}
-            }
-        }
-
         // For digit keys, use the code to get the actual digit
         // This handles Shift+Number combinations where key returns "!" instead of "1"
         if code.starts_with("Digit") {
@@ -121,7 +658,747 @@ impl KeybindConfig {
             }
         }
 
+        // Punctuation, numpad and extended keys come from the code too, as the key depends on the
+        // layout, Shift and Num Lock
+        if let Some((_, token, _)) = code_keys().find(|(c, _, _)| *c == code) {
+            return token.to_string();
+        }
+
         // Fall back to regular key normalization
         Self::normalize_key(key)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use global_hotkey::hotkey::HotKey;
+    use proptest::{prelude::*, sample};
+    use std::str::FromStr;
+
+    /// DOM `key` values the capture overlay can receive
+    const DOM_KEYS: &[&str] = &[
+        "F1",
+        "F2",
+        "F3",
+        "F4",
+        "F5",
+        "F6",
+        "F7",
+        "F8",
+        "F9",
+        "F10",
+        "F11",
+        "F12",
+        "ArrowUp",
+        "ArrowDown",
+        "ArrowLeft",
+        "ArrowRight",
+        "PageUp",
+        "PageDown",
+        "Home",
+        "End",
+        "Insert",
+        "Delete",
+        "Backspace",
+        "Enter",
+        "Tab",
+        " ",
+        "a",
+        "Z",
+        "0",
+        "7",
+        "!",
+        "@",
+        "+",
+        "-",
+        "=",
+        ";",
+        "/",
+        "Escape",
+        "Control",
+        "Shift",
+        "Alt",
+        "Meta",
+        "Unidentified",
+    ];
+
+    /// DOM `code` values the capture overlay can receive
+    const DOM_CODES: &[&str] = &[
+        "",
+        "KeyA",
+        "KeyQ",
+        "KeyZ",
+        "Digit0",
+        "Digit1",
+        "Digit9",
+        "Numpad0",
+        "Numpad5",
+        "Numpad9",
+        "NumpadAdd",
+        "NumpadSubtract",
+        "NumpadMultiply",
+        "NumpadDivide",
+        "NumpadDecimal",
+        "NumpadEnter",
+        "NumpadEqual",
+        "F1",
+        "F12",
+        "ArrowUp",
+        "PageDown",
+        "Space",
+        "Enter",
+        "Minus",
+        "Equal",
+        "Semicolon",
+    ];
+
+    #[test]
+    fn normalize_key_mappings() {
+        let cases = [
+            ("F1", "F1"),
+            ("F12", "F12"),
+            ("ArrowUp", "UP"),
+            ("ArrowDown", "DOWN"),
+            ("ArrowLeft", "LEFT"),
+            ("ArrowRight", "RIGHT"),
+            ("PageUp", "PAGEUP"),
+            ("PageDown", "PAGEDOWN"),
+            ("Home", "HOME"),
+            ("End", "END"),
+            ("Insert", "INSERT"),
+            ("Delete", "DELETE"),
+            ("Backspace", "BACKSPACE"),
+            ("Enter", "ENTER"),
+            ("Tab", "TAB"),
+            ("Space", "SPACE"),
+            (" ", "SPACE"),
+            ("a", "A"),
+            ("Z", "Z"),
+            ("5", "5"),
+            ("+", "EQUAL"),
+            ("=", "EQUAL"),
+            ("-", "MINUS"),
+            ("Escape", ""),
+            ("Control", ""),
+            ("!", ""),
+        ];
+
+        for (key, expected) in cases {
+            assert_eq!(KeybindConfig::normalize_key(key), expected, "key {key:?}");
+        }
+    }
+
+    /// One of every action. Adding a variant fails to compile here until it's listed, so its
+    /// persistence is tested.
+    fn every_action() -> Vec<HotkeyAction> {
+        use HotkeyAction::*;
+
+        let actions = vec![
+            IncreaseGamma,
+            DecreaseGamma,
+            IncreaseBrightness,
+            DecreaseBrightness,
+            IncreaseContrast,
+            DecreaseContrast,
+            IncreaseDimming,
+            DecreaseDimming,
+            Reset,
+            ResetGamma,
+            ResetBrightness,
+            ResetContrast,
+            RestorePrevious,
+            LoadProfile(2),
+            ToggleProfile(u64::MAX),
+            ToggleFilter,
+            ToggleInvert,
+            ApplyTemperaturePreset(4500),
+            SelectMonitor(1),
+            NextMonitor,
+            ToggleWindow,
+        ];
+
+        for action in &actions {
+            match action {
+                IncreaseGamma
+                | DecreaseGamma
+                | IncreaseBrightness
+                | DecreaseBrightness
+                | IncreaseContrast
+                | DecreaseContrast
+                | IncreaseDimming
+                | DecreaseDimming
+                | Reset
+                | ResetGamma
+                | ResetBrightness
+                | ResetContrast
+                | RestorePrevious
+                | LoadProfile(_)
+                | ToggleProfile(_)
+                | ToggleFilter
+                | ToggleInvert
+                | ApplyTemperaturePreset(_)
+                | SelectMonitor(_)
+                | NextMonitor
+                | ToggleWindow => {}
+            }
+        }
+
+        actions
+    }
+
+    #[test]
+    fn every_action_round_trips_through_its_key() {
+        for action in every_action() {
+            let key = action.to_key();
+
+            assert_eq!(HotkeyAction::from_key(&key), Some(action), "{key}");
+        }
+
+        assert_eq!(HotkeyAction::Reset.to_key(), "Reset");
+        assert_eq!(
+            HotkeyAction::LoadProfile(2).to_key(),
+            r#"{"LoadProfile":2}"#
+        );
+    }
+
+    #[test]
+    fn every_action_round_trips_through_a_saved_config() {
+        let config = crate::AppConfig {
+            keybinds: every_action()
+                .into_iter()
+                .map(|action| {
+                    (
+                        action,
+                        vec![KeybindConfig::new(Vec::new(), "F1".to_string())],
+                    )
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        let json = serde_json::to_string(&config).unwrap();
+        let reloaded: crate::AppConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(reloaded.keybinds.len(), every_action().len());
+        assert!(every_action()
+            .iter()
+            .all(|action| reloaded.keybinds.contains_key(action)));
+    }
+
+    #[test]
+    fn legacy_keys_still_load() {
+        let cases = [
+            ("IncreaseGamma", Some(HotkeyAction::IncreaseGamma)),
+            ("LoadProfile(2)", Some(HotkeyAction::LoadProfile(2))),
+            ("ToggleProfile(7)", Some(HotkeyAction::ToggleProfile(7))),
+            (
+                "ApplyTemperaturePreset(4500)",
+                Some(HotkeyAction::ApplyTemperaturePreset(4500)),
+            ),
+            ("SelectMonitor(0)", Some(HotkeyAction::SelectMonitor(0))),
+            ("LoadProfile(x)", None),
+            ("LoadProfile", None),
+            ("Reset(1)", None),
+            ("Unknown", None),
+        ];
+
+        for (key, expected) in cases {
+            assert_eq!(HotkeyAction::from_key(key), expected, "{key}");
+        }
+    }
+
+    #[test]
+    fn only_unmodified_typing_keys_block_typing() {
+        let bare = |key: &str| KeybindConfig::new(Vec::new(), key.to_string());
+
+        for key in ["G", "7", "SPACE", "SEMICOLON"] {
+            assert!(bare(key).blocks_typing(), "{key}");
+        }
+        for key in ["F5", "HOME", "PAGEUP", "Numpad7", "MEDIAPLAYPAUSE"] {
+            assert!(!bare(key).blocks_typing(), "{key}");
+        }
+
+        let shift_g = KeybindConfig::new(vec!["Shift".to_string()], "G".to_string());
+        assert!(!shift_g.blocks_typing());
+    }
+
+    #[test]
+    fn every_modifier_combination_parses() {
+        let modifiers = ["Ctrl", "Shift", "Alt", "Win"];
+
+        for mask in 0..1 << modifiers.len() {
+            let held: Vec<String> = (0..modifiers.len())
+                .filter(|i| mask & (1 << i) != 0)
+                .map(|i| modifiers[i].to_string())
+                .collect();
+            let keybind = KeybindConfig::new(held, "F5".to_string());
+            let shortcut = keybind.to_shortcut_string();
+
+            assert!(
+                HotKey::from_str(&shortcut).is_ok(),
+                "{} does not parse",
+                shortcut
+            );
+        }
+
+        // Still shown as the Windows key
+        let win = KeybindConfig::new(vec!["Win".to_string()], "F5".to_string());
+        assert_eq!(win.format(), "Win + F5");
+    }
+
+    #[test]
+    fn capture_keeps_alt_gr_apart_from_ctrl_and_alt() {
+        let capture = |keys: &[&str]| {
+            let mut modifiers = Vec::new();
+            for key in keys {
+                assert!(KeybindConfig::capture_modifier(&mut modifiers, key));
+            }
+            modifiers
+        };
+
+        // Windows sends a left Ctrl ahead of AltGr, and again as AltGr repeats while held
+        assert_eq!(capture(&["Control", "AltGraph"]), ["AltGr"]);
+        assert_eq!(
+            capture(&["Control", "AltGraph", "Control", "AltGraph"]),
+            ["AltGr"]
+        );
+        assert_eq!(
+            capture(&["Shift", "Control", "AltGraph"]),
+            ["Shift", "AltGr"]
+        );
+        // The right Alt of layouts without AltGr is plain Alt
+        assert_eq!(capture(&["Control", "Alt"]), ["Ctrl", "Alt"]);
+        assert_eq!(capture(&["Meta", "Meta"]), ["Win"]);
+
+        assert!(!KeybindConfig::capture_modifier(&mut Vec::new(), "q"));
+    }
+
+    #[test]
+    fn alt_gr_keybinds_register_as_ctrl_alt() {
+        let alt_gr = KeybindConfig::new(vec!["AltGr".to_string()], "Q".to_string());
+        let ctrl_alt =
+            KeybindConfig::new(vec!["Alt".to_string(), "Ctrl".to_string()], "Q".to_string());
+
+        assert_eq!(alt_gr.format(), "AltGr + Q");
+        assert_eq!(
+            HotKey::from_str(&alt_gr.to_shortcut_string()).unwrap(),
+            HotKey::from_str(&ctrl_alt.to_shortcut_string()).unwrap()
+        );
+        assert!(alt_gr.same_combination(&ctrl_alt));
+
+        // AltGr with a letter types a character on layouts that have it, as does Ctrl + Alt
+        assert!(alt_gr.blocks_typing());
+        assert!(ctrl_alt.blocks_typing());
+        let ctrl_alt_shift = KeybindConfig::new(
+            vec!["Ctrl".to_string(), "Alt".to_string(), "Shift".to_string()],
+            "Q".to_string(),
+        );
+        assert!(!ctrl_alt_shift.blocks_typing());
+        let alt_gr_f5 = KeybindConfig::new(vec!["AltGr".to_string()], "F5".to_string());
+        assert!(!alt_gr_f5.blocks_typing());
+
+        let mut respelled = KeybindConfig::new(vec!["AltGraph".to_string()], "Q".to_string());
+        assert!(respelled.repair());
+        assert_eq!(respelled.modifiers, ["AltGr"]);
+    }
+
+    #[test]
+    fn repair_respells_modifier_aliases() {
+        let mut keybind = KeybindConfig::new(
+            vec![
+                "control".into(),
+                "Super".into(),
+                "Meta".into(),
+                "Alt".into(),
+            ],
+            "F5".to_string(),
+        );
+
+        assert!(keybind.repair());
+        assert_eq!(keybind.modifiers, ["Ctrl", "Win", "Alt"]);
+        assert!(!keybind.repair());
+    }
+
+    #[test]
+    fn numpad_keys_round_trip_through_hotkey_parsing() {
+        for (code, token, label) in NUMPAD_KEYS {
+            let keybind = KeybindConfig::new(
+                vec!["Ctrl".to_string()],
+                KeybindConfig::normalize_key_with_code("", code),
+            );
+
+            assert_eq!(keybind.key, token);
+            assert_eq!(keybind.format(), format!("Ctrl + {}", label));
+            assert_eq!(
+                HotKey::from_str(&keybind.to_shortcut_string())
+                    .unwrap()
+                    .key
+                    .to_string(),
+                code
+            );
+        }
+    }
+
+    #[test]
+    fn repair_migrates_legacy_numpad_tokens() {
+        for (legacy, token) in LEGACY_NUMPAD_TOKENS {
+            let mut keybind = KeybindConfig::new(vec!["Ctrl".to_string()], legacy.to_string());
+
+            assert!(keybind.repair());
+            assert_eq!(keybind.key, token);
+        }
+    }
+
+    #[test]
+    fn default_keybinds_parse_and_dont_conflict() {
+        let defaults: Vec<KeybindConfig> = default_keybinds().into_values().flatten().collect();
+
+        for (i, keybind) in defaults.iter().enumerate() {
+            let shortcut = keybind.to_shortcut_string();
+            assert!(
+                HotKey::from_str(&shortcut).is_ok(),
+                "{} does not parse",
+                shortcut
+            );
+            assert_ne!(keybind.held_modifiers(), ["alt", "ctrl"], "{}", shortcut);
+            assert!(
+                defaults[i + 1..]
+                    .iter()
+                    .all(|other| !other.same_combination(keybind)),
+                "{} is used twice",
+                shortcut
+            );
+        }
+    }
+
+    #[test]
+    fn punctuation_round_trips_through_hotkey_parsing() {
+        for (code, token, label) in PUNCTUATION_KEYS {
+            // Shift changes the key reported, but not the code
+            for key in [label, "?"] {
+                assert_eq!(KeybindConfig::normalize_key_with_code(key, code), token);
+            }
+            assert_eq!(KeybindConfig::normalize_key(label), token);
+
+            let keybind = KeybindConfig::new(vec!["Ctrl".to_string()], token.to_string());
+            let parsed = HotKey::from_str(&keybind.to_shortcut_string()).unwrap();
+            assert_eq!(
+                parsed,
+                HotKey::from_str(&format!("Ctrl+{}", label)).unwrap()
+            );
+            assert_eq!(keybind.format(), format!("Ctrl + {}", label));
+        }
+    }
+
+    #[test]
+    fn extended_keys_are_captured_and_labelled() {
+        let capture = |key: &str, code: &str| {
+            let token = KeybindConfig::normalize_key_with_code(key, code);
+            KeybindConfig::new(vec!["Ctrl".to_string()], token)
+        };
+
+        let play = capture("MediaPlayPause", "MediaPlayPause");
+        assert!(HotKey::from_str(&play.to_shortcut_string()).is_ok());
+        assert_eq!(play.format(), "Ctrl + Play/Pause");
+
+        // Some keyboards report media keys without a code
+        let volume = capture("AudioVolumeUp", "");
+        assert!(HotKey::from_str(&volume.to_shortcut_string()).is_ok());
+        assert_eq!(volume.format(), "Ctrl + Volume up");
+
+        let f13 = capture("F13", "F13");
+        assert!(HotKey::from_str(&f13.to_shortcut_string()).is_ok());
+
+        // Keys global shortcuts don't support are recorded, so they can be reported
+        let calculator = capture("LaunchApplication2", "LaunchApp2");
+        assert_eq!(calculator.format(), "Ctrl + Calculator");
+        assert!(HotKey::from_str(&calculator.to_shortcut_string()).is_err());
+
+        for (_, token, _) in EXTENDED_KEYS
+            .iter()
+            .filter(|(code, token, _)| code != token)
+        {
+            assert!(
+                HotKey::from_str(&format!("Ctrl+{}", token)).is_ok(),
+                "{}",
+                token
+            );
+        }
+    }
+
+    fn ctrl(key: &str) -> KeybindConfig {
+        KeybindConfig::new(vec!["Ctrl".to_string()], key.to_string())
+    }
+
+    fn bindings(keybinds: &[(HotkeyAction, &[&str])]) -> HashMap<HotkeyAction, Vec<KeybindConfig>> {
+        keybinds
+            .iter()
+            .map(|(action, keys)| (*action, keys.iter().map(|key| ctrl(key)).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn keybinds_survive_an_export_and_import() {
+        let keybinds = bindings(&[
+            (HotkeyAction::IncreaseGamma, &["UP", "NumpadAdd"]),
+            (HotkeyAction::ApplyTemperaturePreset(4500), &["F2"]),
+        ]);
+        let path =
+            std::env::temp_dir().join(format!("gammar-keybinds-{}.json", std::process::id()));
+
+        save_keybinds(&keybinds, |_| None, &path).unwrap();
+        let loaded = load_keybinds(&path, |_| None).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(loaded.missing_profiles, 0);
+        assert_eq!(loaded.keybinds.len(), keybinds.len());
+        for (action, bindings) in &keybinds {
+            let loaded = &loaded.keybinds[action];
+            assert_eq!(loaded.len(), bindings.len(), "{:?}", action);
+            for (loaded, keybind) in loaded.iter().zip(bindings) {
+                assert!(loaded.same_combination(keybind), "{:?}", action);
+            }
+        }
+    }
+
+    #[test]
+    fn profile_keybinds_import_by_profile_name() {
+        let keybinds = bindings(&[
+            (HotkeyAction::LoadProfile(7), &["F1"]),
+            (HotkeyAction::ToggleProfile(7), &["F2"]),
+            (HotkeyAction::LoadProfile(8), &["F3"]),
+            (HotkeyAction::LoadProfile(9), &["F4"]),
+        ]);
+        let path = std::env::temp_dir().join(format!(
+            "gammar-profile-keybinds-{}.json",
+            std::process::id()
+        ));
+
+        // Profile 9 was deleted before exporting, so it has no name
+        let exported = |id| {
+            [(7, "Night"), (8, "Movie")]
+                .into_iter()
+                .find(|(i, _)| *i == id)
+        };
+        save_keybinds(
+            &keybinds,
+            |id| exported(id).map(|(_, name)| name.into()),
+            &path,
+        )
+        .unwrap();
+        // Here, only Night exists, under another id
+        let loaded = load_keybinds(&path, |name| (name == "Night").then_some(2)).unwrap();
+        let _ = fs::remove_file(&path);
+
+        let mut actions: Vec<_> = loaded.keybinds.keys().copied().collect();
+        actions.sort_by_key(|action| action.to_key());
+        assert_eq!(
+            actions,
+            [HotkeyAction::LoadProfile(2), HotkeyAction::ToggleProfile(2)]
+        );
+        assert_eq!(loaded.keybinds[&HotkeyAction::LoadProfile(2)][0].key, "F1");
+        assert_eq!(loaded.missing_profiles, 2);
+    }
+
+    #[test]
+    fn merging_keybinds_leaves_out_conflicts() {
+        let mut existing = bindings(&[
+            (HotkeyAction::IncreaseGamma, &["UP"]),
+            (HotkeyAction::Reset, &["HOME", "END"]),
+        ]);
+        let imported = bindings(&[
+            // Replaces the existing keybind of the same action
+            (HotkeyAction::IncreaseGamma, &["PAGEUP"]),
+            // An alternate is taken by one of Reset's
+            (HotkeyAction::ToggleInvert, &["F7", "END"]),
+            (HotkeyAction::DecreaseGamma, &["PAGEDOWN"]),
+        ]);
+
+        assert_eq!(
+            keybind_conflicts(&existing, &imported),
+            vec![HotkeyAction::ToggleInvert]
+        );
+        assert_eq!(merge_keybinds(&mut existing, imported), 2);
+        assert_eq!(existing[&HotkeyAction::IncreaseGamma][0].key, "PAGEUP");
+        assert!(existing.contains_key(&HotkeyAction::DecreaseGamma));
+        assert!(!existing.contains_key(&HotkeyAction::ToggleInvert));
+    }
+
+    #[test]
+    fn alternate_bindings_are_set_found_and_removed() {
+        let mut keybinds = bindings(&[(HotkeyAction::Reset, &["HOME"])]);
+        let brightness = HotkeyAction::IncreaseBrightness;
+
+        set_binding(&mut keybinds, brightness, 0, ctrl("UP"));
+        set_binding(&mut keybinds, brightness, 1, ctrl("NumpadAdd"));
+        set_binding(&mut keybinds, brightness, 0, ctrl("PAGEUP"));
+        let keys: Vec<&str> = keybinds[&brightness]
+            .iter()
+            .map(|kb| kb.key.as_str())
+            .collect();
+        assert_eq!(keys, ["PAGEUP", "NumpadAdd"]);
+
+        // Every binding of every other action counts
+        assert_eq!(
+            binding_using(&keybinds, &ctrl("NumpadAdd"), HotkeyAction::Reset),
+            Some((brightness, 1))
+        );
+        assert_eq!(
+            binding_using(&keybinds, &ctrl("NumpadAdd"), brightness),
+            None
+        );
+
+        remove_binding(&mut keybinds, brightness, 0);
+        assert_eq!(keybinds[&brightness][0].key, "NumpadAdd");
+        remove_binding(&mut keybinds, brightness, 0);
+        assert!(!keybinds.contains_key(&brightness));
+    }
+
+    #[test]
+    fn single_keybinds_load_as_one_binding() {
+        let json = serde_json::json!({
+            "version": KEYBINDS_FILE_VERSION,
+            "keybinds": {
+                "Reset": ctrl("HOME"),
+                "IncreaseGamma": [ctrl("UP"), ctrl("NumpadAdd")],
+                "DecreaseGamma": [],
+            },
+        });
+        let file: KeybindsFile = serde_json::from_value(json).unwrap();
+
+        assert_eq!(file.keybinds[&HotkeyAction::Reset].len(), 1);
+        assert_eq!(file.keybinds[&HotkeyAction::IncreaseGamma].len(), 2);
+        assert!(!file.keybinds.contains_key(&HotkeyAction::DecreaseGamma));
+
+        // A single binding is still saved as one keybind, which older versions read
+        let saved = serde_json::to_value(&file).unwrap();
+        assert_eq!(saved["keybinds"]["Reset"]["key"], "HOME");
+        assert!(saved["keybinds"]["IncreaseGamma"].is_array());
+    }
+
+    #[test]
+    fn only_steps_repeat() {
+        assert!(HotkeyAction::IncreaseGamma.repeats());
+        assert!(HotkeyAction::DecreaseDimming.repeats());
+        assert!(!HotkeyAction::Reset.repeats());
+        assert!(!HotkeyAction::LoadProfile(1).repeats());
+        assert!(!HotkeyAction::ToggleInvert.repeats());
+    }
+
+    #[test]
+    fn chords_format_and_clash_on_both_strokes() {
+        let chord = |then: &str| KeybindConfig {
+            then: Some(then.to_string()),
+            ..KeybindConfig::new(vec!["Ctrl".into(), "Alt".into()], "G".into())
+        };
+        let plain = KeybindConfig::new(vec!["Alt".into(), "Ctrl".into()], "G".into());
+
+        assert_eq!(chord("B").format(), "Ctrl + Alt + G, B");
+        assert_eq!(chord("SEMICOLON").format(), "Ctrl + Alt + G, ;");
+        assert_eq!(chord("B").to_shortcut_string(), "Ctrl+Alt+G");
+
+        assert!(chord("B").same_combination(&chord("b")));
+        assert!(!chord("B").same_combination(&chord("N")));
+        // The plain keybind would fire on the chord's first stroke
+        assert!(chord("B").same_combination(&plain));
+        assert!(plain.same_combination(&chord("B")));
+    }
+
+    #[test]
+    fn same_combination_ignores_modifier_order() {
+        let keybind = |mods: &[&str], key: &str| {
+            KeybindConfig::new(
+                mods.iter().map(|m| m.to_string()).collect(),
+                key.to_string(),
+            )
+        };
+
+        assert!(
+            keybind(&["Ctrl", "Shift"], "F1").same_combination(&keybind(&["Shift", "Ctrl"], "F1"))
+        );
+        assert!(keybind(&["Ctrl"], "A").same_combination(&keybind(&["ctrl"], "a")));
+        assert!(!keybind(&["Ctrl", "Shift"], "F1").same_combination(&keybind(&["Ctrl"], "F1")));
+        assert!(!keybind(&["Ctrl"], "F1").same_combination(&keybind(&["Ctrl"], "F2")));
+    }
+
+    #[test]
+    fn normalize_key_with_code_mappings() {
+        let cases = [
+            // Numpad keys are identified by code regardless of key
+            ("7", "Numpad7", "Numpad7"),
+            ("Home", "Numpad7", "Numpad7"),
+            ("0", "Numpad0", "Numpad0"),
+            ("+", "NumpadAdd", "NumpadAdd"),
+            ("-", "NumpadSubtract", "NumpadSubtract"),
+            ("*", "NumpadMultiply", "NumpadMultiply"),
+            ("/", "NumpadDivide", "NumpadDivide"),
+            ("Delete", "NumpadDecimal", "NumpadDecimal"),
+            ("Enter", "NumpadEnter", "NumpadEnter"),
+            // Shift+digit reports the shifted character as key
+            ("!", "Digit1", "1"),
+            ("@", "Digit2", "2"),
+            // Letters come from the code, independent of layout and case
+            ("a", "KeyA", "A"),
+            ("Q", "KeyQ", "Q"),
+            // Everything else falls back to the key
+            ("ArrowUp", "ArrowUp", "UP"),
+            ("F5", "F5", "F5"),
+            ("Enter", "Enter", "ENTER"),
+        ];
+
+        for (key, code, expected) in cases {
+            assert_eq!(
+                KeybindConfig::normalize_key_with_code(key, code),
+                expected,
+                "key {key:?}, code {code:?}"
+            );
+        }
+    }
+
+    fn key_strategy() -> impl Strategy<Value = String> {
+        prop_oneof![
+            sample::select(DOM_KEYS).prop_map(str::to_string),
+            "[ -~]{1,3}",
+        ]
+    }
+
+    fn code_strategy() -> impl Strategy<Value = String> {
+        sample::select(DOM_CODES).prop_map(str::to_string)
+    }
+
+    fn modifier_strategy() -> impl Strategy<Value = Vec<String>> {
+        sample::subsequence(vec!["Ctrl", "Shift", "Alt", "Win"], 0..=4)
+            .prop_map(|mods| mods.into_iter().map(str::to_string).collect())
+    }
+
+    proptest! {
+        #[test]
+        fn normalized_keys_parse_with_any_modifiers(
+            key in key_strategy(),
+            code in code_strategy(),
+            modifiers in modifier_strategy(),
+        ) {
+            let normalized = KeybindConfig::normalize_key_with_code(&key, &code);
+            prop_assume!(!normalized.is_empty());
+
+            let shortcut = KeybindConfig::new(modifiers, normalized).to_shortcut_string();
+            prop_assert!(HotKey::from_str(&shortcut).is_ok(), "{} does not parse", shortcut);
+        }
+
+        #[test]
+        fn normalize_key_is_idempotent(key in key_strategy()) {
+            let normalized = KeybindConfig::normalize_key(&key);
+            prop_assume!(!normalized.is_empty());
+
+            prop_assert_eq!(KeybindConfig::normalize_key(&normalized), normalized);
+        }
+
+        #[test]
+        fn normalize_key_with_code_is_idempotent(key in key_strategy(), code in code_strategy()) {
+            let normalized = KeybindConfig::normalize_key_with_code(&key, &code);
+            prop_assume!(!normalized.is_empty());
+
+            prop_assert_eq!(KeybindConfig::normalize_key_with_code(&normalized, ""), normalized);
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use crate::windows::display::{GammaError, GammaRamp, MonitorInfo, RAMP_SIZE};
+#[cfg(windows)]
+use std::sync::OnceLock;
+#[cfg(windows)]
+use windows::{
+    core::w,
+    Win32::{
+        Foundation::ERROR_SUCCESS,
+        System::Registry::{RegGetValueW, HKEY_LOCAL_MACHINE, RRF_RT_REG_DWORD},
+        UI::{Shell::ShellExecuteW, WindowsAndMessaging::SW_HIDE},
+    },
+};
+
+/// Largest distance from the identity ramp Windows accepts by default, as a fraction of the
+/// output range. Ramps that stray further are rejected unless `GdiIcmGammaRange` is set.
+pub const DEFAULT_MAX_DEVIATION: f32 = 0.5;
+
+/// The `GdiIcmGammaRange` value that lifts the restriction entirely.
+pub const UNLOCKED_GAMMA_RANGE: u32 = 256;
+
+/// Check whether a ramp strays further from identity than Windows allows by default.
+pub fn exceeds_default_range(ramp: &GammaRamp) -> bool {
+    let limit = DEFAULT_MAX_DEVIATION * 65535.0;
+
+    ramp.as_raw().iter().enumerate().any(|(i, &value)| {
+        let identity = (i % RAMP_SIZE) as f32 / (RAMP_SIZE - 1) as f32 * 65535.0;
+
+        (value as f32 - identity).abs() > limit
+    })
+}
+
+/// The error reported when a ramp is rejected because of the default range restriction.
+pub fn range_restricted_error(monitor: &MonitorInfo) -> GammaError {
+    GammaError::RangeRestricted {
+        monitor: monitor.name.clone(),
+    }
+}
+
+/// Check whether the full gamma range is unlocked. The registry is only read once, since
+/// changes need a reboot to take effect anyway.
+#[cfg(windows)]
+pub fn gamma_range_unlocked() -> bool {
+    static UNLOCKED: OnceLock<bool> = OnceLock::new();
+
+    *UNLOCKED.get_or_init(|| {
+        let mut value = 0u32;
+        let mut size = std::mem::size_of::<u32>() as u32;
+
+        let result = unsafe {
+            RegGetValueW(
+                HKEY_LOCAL_MACHINE,
+                w!("SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion\\ICM"),
+                w!("GdiIcmGammaRange"),
+                RRF_RT_REG_DWORD,
+                None,
+                Some(&mut value as *mut u32 as *mut _),
+                Some(&mut size),
+            )
+        };
+
+        result == ERROR_SUCCESS && value >= UNLOCKED_GAMMA_RANGE
+    })
+}
+
+/// Set `GdiIcmGammaRange` through an elevated `reg.exe`, which shows a UAC prompt. The new range
+/// only applies after a reboot.
+#[cfg(windows)]
+pub fn unlock_gamma_range() -> Result<(), GammaError> {
+    let result = unsafe {
+        ShellExecuteW(
+            None,
+            w!("runas"),
+            w!("reg.exe"),
+            w!("add \"HKLM\\SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion\\ICM\" /v GdiIcmGammaRange /t REG_DWORD /d 256 /f"),
+            None,
+            SW_HIDE,
+        )
+    };
+
+    // Values up to 32 are error codes
+    if result.0 as usize <= 32 {
+        return Err(GammaError::Platform(
+            "Couldn't unlock the gamma range: administrator access was declined or failed"
+                .to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::windows::display::{compute_gamma_ramp, DisplaySettings};
+
+    #[test]
+    fn moderate_settings_fit_default_range() {
+        let ramp = compute_gamma_ramp(&DisplaySettings::new(1.4, -0.2, 1.2));
+
+        assert!(!exceeds_default_range(&ramp));
+    }
+
+    #[test]
+    fn extreme_brightness_exceeds_default_range() {
+        let ramp = compute_gamma_ramp(&DisplaySettings::new(1.0, 0.8, 1.0));
+
+        assert!(exceeds_default_range(&ramp));
+    }
+}
@@ -0,0 +1,336 @@
+use crate::windows::{
+    display::GammaError,
+    hotkeys::{HotkeyAction, KeybindConfig},
+};
+#[cfg(windows)]
+use std::{
+    sync::{mpsc, Mutex},
+    thread::{self, JoinHandle},
+};
+#[cfg(windows)]
+use windows::Win32::Foundation::{LPARAM, LRESULT, WPARAM};
+#[cfg(windows)]
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+#[cfg(windows)]
+use windows::Win32::System::Threading::GetCurrentThreadId;
+#[cfg(windows)]
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    GetAsyncKeyState, VIRTUAL_KEY, VK_CONTROL, VK_LWIN, VK_MENU, VK_RWIN, VK_SHIFT,
+};
+#[cfg(windows)]
+use windows::Win32::UI::WindowsAndMessaging::{
+    CallNextHookEx, GetMessageW, PostThreadMessageW, SetWindowsHookExW, UnhookWindowsHookEx,
+    HC_ACTION, MSG, MSLLHOOKSTRUCT, WH_MOUSE_LL, WM_MBUTTONDOWN, WM_MOUSEWHEEL, WM_QUIT,
+    WM_XBUTTONDOWN, XBUTTON1, XBUTTON2,
+};
+
+/// A mouse input that can be bound like a key, together with modifiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MouseInput {
+    WheelUp,
+    WheelDown,
+    MiddleClick,
+    /// The first extra button, usually "back".
+    Mouse4,
+    /// The second extra button, usually "forward".
+    Mouse5,
+}
+
+impl MouseInput {
+    pub const ALL: [MouseInput; 5] = [
+        MouseInput::WheelUp,
+        MouseInput::WheelDown,
+        MouseInput::MiddleClick,
+        MouseInput::Mouse4,
+        MouseInput::Mouse5,
+    ];
+
+    /// The name stored as a keybind's key.
+    pub fn key_name(&self) -> &'static str {
+        match self {
+            MouseInput::WheelUp => "WheelUp",
+            MouseInput::WheelDown => "WheelDown",
+            MouseInput::MiddleClick => "MiddleClick",
+            MouseInput::Mouse4 => "Mouse4",
+            MouseInput::Mouse5 => "Mouse5",
+        }
+    }
+
+    /// Parse a keybind's key, if it names a mouse input.
+    pub fn from_key_name(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|input| input.key_name() == key)
+    }
+}
+
+/// Modifier keys, as held when a mouse binding is used.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    pub win: bool,
+}
+
+impl Modifiers {
+    /// Read the modifiers of a keybind, e.g. `["Ctrl", "Alt"]`.
+    pub fn from_names(names: &[String]) -> Self {
+        let has = |name: &str| names.iter().any(|n| n.eq_ignore_ascii_case(name));
+
+        // AltGr holds Ctrl and Alt down
+        let alt_gr = has("AltGr");
+
+        Self {
+            ctrl: has("Ctrl") || alt_gr,
+            shift: has("Shift"),
+            alt: has("Alt") || alt_gr,
+            win: has("Win"),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A modifier and mouse input combination bound to an action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MouseBinding {
+    pub modifiers: Modifiers,
+    pub input: MouseInput,
+    pub action: HotkeyAction,
+}
+
+impl MouseBinding {
+    /// Make a binding from a keybind whose key is a mouse input. Mouse inputs need a modifier,
+    /// so a bare wheel or click keeps working normally.
+    pub fn from_keybind(action: HotkeyAction, keybind: &KeybindConfig) -> Option<Self> {
+        let input = MouseInput::from_key_name(&keybind.key)?;
+        let modifiers = Modifiers::from_names(&keybind.modifiers);
+
+        (!modifiers.is_empty()).then_some(Self {
+            modifiers,
+            input,
+            action,
+        })
+    }
+}
+
+/// Find the action bound to a mouse input with exactly these modifiers held.
+pub fn find_action(
+    bindings: &[MouseBinding],
+    input: MouseInput,
+    modifiers: Modifiers,
+) -> Option<HotkeyAction> {
+    bindings
+        .iter()
+        .find(|b| b.input == input && b.modifiers == modifiers)
+        .map(|b| b.action)
+}
+
+#[cfg(windows)]
+type ActionHandler = Box<dyn Fn(HotkeyAction) + Send>;
+
+/// The bindings and handler the hook procedure matches against. The procedure can't take
+/// arguments, so they're global.
+#[cfg(windows)]
+static HOOK_STATE: Mutex<Option<(Vec<MouseBinding>, ActionHandler)>> = Mutex::new(None);
+
+/// The thread running the hook, with its message loop, and its id to stop it with.
+#[cfg(windows)]
+static HOOK_THREAD: Mutex<Option<(u32, JoinHandle<()>)>> = Mutex::new(None);
+
+#[cfg(windows)]
+fn key_held(key: VIRTUAL_KEY) -> bool {
+    unsafe { GetAsyncKeyState(key.0 as i32) < 0 }
+}
+
+#[cfg(windows)]
+unsafe extern "system" fn hook_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code == HC_ACTION as i32 {
+        let info = unsafe { &*(lparam.0 as *const MSLLHOOKSTRUCT) };
+        let high_word = (info.mouseData >> 16) as u16;
+        let input = match wparam.0 as u32 {
+            WM_MOUSEWHEEL if (high_word as i16) > 0 => Some(MouseInput::WheelUp),
+            WM_MOUSEWHEEL => Some(MouseInput::WheelDown),
+            WM_MBUTTONDOWN => Some(MouseInput::MiddleClick),
+            WM_XBUTTONDOWN if high_word == XBUTTON1 => Some(MouseInput::Mouse4),
+            WM_XBUTTONDOWN if high_word == XBUTTON2 => Some(MouseInput::Mouse5),
+            _ => None,
+        };
+
+        if let Some(input) = input {
+            let modifiers = Modifiers {
+                ctrl: key_held(VK_CONTROL),
+                shift: key_held(VK_SHIFT),
+                alt: key_held(VK_MENU),
+                win: key_held(VK_LWIN) || key_held(VK_RWIN),
+            };
+
+            if let Ok(state) = HOOK_STATE.lock() {
+                if let Some((bindings, handler)) = state.as_ref() {
+                    if let Some(action) = find_action(bindings, input, modifiers) {
+                        handler(action);
+                        // Used bindings aren't passed on, like keyboard shortcuts
+                        return LRESULT(1);
+                    }
+                }
+            }
+        }
+    }
+
+    unsafe { CallNextHookEx(None, code, wparam, lparam) }
+}
+
+/// Listen for mouse bindings, calling `on_action` from the hook's thread when one is used.
+///
+/// This installs a low-level mouse hook, which every mouse event passes through, so it's only
+/// installed while there are bindings. Setting no bindings removes it.
+#[cfg(windows)]
+pub fn set_bindings(
+    bindings: Vec<MouseBinding>,
+    on_action: impl Fn(HotkeyAction) + Send + 'static,
+) -> Result<(), GammaError> {
+    let mut thread = HOOK_THREAD.lock().unwrap_or_else(|e| e.into_inner());
+
+    if bindings.is_empty() {
+        if let Some((thread_id, handle)) = thread.take() {
+            unsafe {
+                let _ = PostThreadMessageW(thread_id, WM_QUIT, WPARAM(0), LPARAM(0));
+            }
+            let _ = handle.join();
+        }
+
+        *HOOK_STATE.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        return Ok(());
+    }
+
+    *HOOK_STATE.lock().unwrap_or_else(|e| e.into_inner()) = Some((bindings, Box::new(on_action)));
+
+    if thread.is_some() {
+        return Ok(());
+    }
+
+    let (started, result) = mpsc::channel();
+    let handle = thread::Builder::new()
+        .name("gammar-mouse".to_string())
+        .spawn(move || unsafe {
+            let module = GetModuleHandleW(None).ok().map(Into::into);
+            let hook = match SetWindowsHookExW(WH_MOUSE_LL, Some(hook_proc), module, 0) {
+                Ok(hook) => hook,
+                Err(e) => {
+                    let _ = started.send(Err(e.to_string()));
+                    return;
+                }
+            };
+            let _ = started.send(Ok(GetCurrentThreadId()));
+
+            // The hook is called through this thread's message loop
+            let mut msg = MSG::default();
+            while GetMessageW(&mut msg, None, 0, 0).as_bool() {}
+
+            let _ = UnhookWindowsHookEx(hook);
+        })
+        .map_err(|e| GammaError::Platform(format!("Failed to start the mouse hook: {}", e)))?;
+
+    match result.recv() {
+        Ok(Ok(thread_id)) => {
+            *thread = Some((thread_id, handle));
+            Ok(())
+        }
+        Ok(Err(detail)) => {
+            let _ = handle.join();
+            Err(GammaError::Platform(format!(
+                "Failed to install the mouse hook: {}",
+                detail
+            )))
+        }
+        Err(_) => Err(GammaError::Platform(
+            "The mouse hook stopped unexpectedly".to_string(),
+        )),
+    }
+}
+
+/// Listen for mouse bindings. Only Windows supports them, so this fails unless there are none.
+#[cfg(not(windows))]
+pub fn set_bindings(
+    bindings: Vec<MouseBinding>,
+    _on_action: impl Fn(HotkeyAction) + Send + 'static,
+) -> Result<(), GammaError> {
+    if bindings.is_empty() {
+        Ok(())
+    } else {
+        Err(GammaError::Unsupported(
+            "Mouse bindings are only supported on Windows".to_string(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keybind(modifiers: &[&str], key: &str) -> KeybindConfig {
+        KeybindConfig::new(
+            modifiers.iter().map(|m| m.to_string()).collect(),
+            key.to_string(),
+        )
+    }
+
+    #[test]
+    fn mouse_keybinds_become_bindings() {
+        let binding = MouseBinding::from_keybind(
+            HotkeyAction::IncreaseBrightness,
+            &keybind(&["Ctrl", "Alt"], "WheelUp"),
+        )
+        .unwrap();
+
+        assert_eq!(binding.input, MouseInput::WheelUp);
+        assert!(binding.modifiers.ctrl && binding.modifiers.alt && !binding.modifiers.shift);
+
+        // Keyboard keys and unmodified mouse inputs aren't mouse bindings
+        let action = HotkeyAction::Reset;
+        assert_eq!(
+            MouseBinding::from_keybind(action, &keybind(&["Ctrl"], "F1")),
+            None
+        );
+        assert_eq!(
+            MouseBinding::from_keybind(action, &keybind(&[], "Mouse4")),
+            None
+        );
+    }
+
+    #[test]
+    fn find_action_needs_exact_modifiers() {
+        let bindings = [
+            MouseBinding::from_keybind(HotkeyAction::IncreaseGamma, &keybind(&["Ctrl"], "WheelUp"))
+                .unwrap(),
+            MouseBinding::from_keybind(
+                HotkeyAction::IncreaseBrightness,
+                &keybind(&["Ctrl", "Shift"], "WheelUp"),
+            )
+            .unwrap(),
+        ];
+        let ctrl = Modifiers {
+            ctrl: true,
+            ..Default::default()
+        };
+        let ctrl_shift = Modifiers {
+            shift: true,
+            ..ctrl
+        };
+
+        assert_eq!(
+            find_action(&bindings, MouseInput::WheelUp, ctrl),
+            Some(HotkeyAction::IncreaseGamma)
+        );
+        assert_eq!(
+            find_action(&bindings, MouseInput::WheelUp, ctrl_shift),
+            Some(HotkeyAction::IncreaseBrightness)
+        );
+        assert_eq!(find_action(&bindings, MouseInput::WheelDown, ctrl), None);
+        assert_eq!(
+            find_action(&bindings, MouseInput::WheelUp, Modifiers::default()),
+            None
+        );
+    }
+}
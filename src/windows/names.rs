@@ -0,0 +1,255 @@
+#[cfg(windows)]
+use crate::windows::display::from_wide;
+use std::collections::HashMap;
+#[cfg(windows)]
+use windows::Win32::Devices::Display::{
+    DisplayConfigGetDeviceInfo, GetDisplayConfigBufferSizes, QueryDisplayConfig,
+    DISPLAYCONFIG_DEVICE_INFO_GET_ADVANCED_COLOR_INFO, DISPLAYCONFIG_DEVICE_INFO_GET_SOURCE_NAME,
+    DISPLAYCONFIG_DEVICE_INFO_GET_TARGET_NAME, DISPLAYCONFIG_DEVICE_INFO_HEADER,
+    DISPLAYCONFIG_GET_ADVANCED_COLOR_INFO, DISPLAYCONFIG_MODE_INFO,
+    DISPLAYCONFIG_OUTPUT_TECHNOLOGY_DISPLAYPORT_EMBEDDED,
+    DISPLAYCONFIG_OUTPUT_TECHNOLOGY_DISPLAYPORT_EXTERNAL,
+    DISPLAYCONFIG_OUTPUT_TECHNOLOGY_DISPLAYPORT_USB_TUNNEL, DISPLAYCONFIG_OUTPUT_TECHNOLOGY_DVI,
+    DISPLAYCONFIG_OUTPUT_TECHNOLOGY_HD15, DISPLAYCONFIG_OUTPUT_TECHNOLOGY_HDMI,
+    DISPLAYCONFIG_OUTPUT_TECHNOLOGY_INTERNAL, DISPLAYCONFIG_PATH_INFO,
+    DISPLAYCONFIG_SOURCE_DEVICE_NAME, DISPLAYCONFIG_TARGET_DEVICE_NAME,
+    DISPLAYCONFIG_VIDEO_OUTPUT_TECHNOLOGY, QDC_ONLY_ACTIVE_PATHS,
+};
+#[cfg(windows)]
+use windows::Win32::Foundation::ERROR_SUCCESS;
+
+/// The model name a monitor reports in its EDID, the connector it's attached through, whether
+/// it's showing HDR, and how many other monitors mirror it in Duplicate mode.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DisplayTarget {
+    pub model: String,
+    pub connector: Option<String>,
+    pub hdr: bool,
+    pub clones: usize,
+}
+
+/// Build a display label for each monitor. Models that appear once are used as-is; repeated
+/// models get their connector appended, or an index when the connectors match too. Mirrored
+/// outputs, which share one entry, are marked as duplicated.
+pub fn monitor_labels(targets: &[DisplayTarget]) -> Vec<String> {
+    let mut models: HashMap<&str, usize> = HashMap::new();
+    let mut connectors: HashMap<(&str, Option<&str>), usize> = HashMap::new();
+
+    for target in targets {
+        *models.entry(&target.model).or_default() += 1;
+        *connectors
+            .entry((&target.model, target.connector.as_deref()))
+            .or_default() += 1;
+    }
+
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+
+    targets
+        .iter()
+        .map(|target| {
+            let index = seen.entry(&target.model).or_default();
+            *index += 1;
+
+            let label = if models[target.model.as_str()] == 1 {
+                target.model.clone()
+            } else {
+                match target.connector.as_deref() {
+                    Some(connector)
+                        if connectors[&(target.model.as_str(), Some(connector))] == 1 =>
+                    {
+                        format!("{} ({})", target.model, connector)
+                    }
+                    _ => format!("{} #{}", target.model, index),
+                }
+            };
+
+            if target.clones > 0 {
+                format!("{} (duplicated)", label)
+            } else {
+                label
+            }
+        })
+        .collect()
+}
+
+#[cfg(windows)]
+fn connector_name(technology: DISPLAYCONFIG_VIDEO_OUTPUT_TECHNOLOGY) -> Option<&'static str> {
+    match technology {
+        DISPLAYCONFIG_OUTPUT_TECHNOLOGY_HD15 => Some("VGA"),
+        DISPLAYCONFIG_OUTPUT_TECHNOLOGY_DVI => Some("DVI"),
+        DISPLAYCONFIG_OUTPUT_TECHNOLOGY_HDMI => Some("HDMI"),
+        DISPLAYCONFIG_OUTPUT_TECHNOLOGY_DISPLAYPORT_EXTERNAL
+        | DISPLAYCONFIG_OUTPUT_TECHNOLOGY_DISPLAYPORT_EMBEDDED => Some("DisplayPort"),
+        DISPLAYCONFIG_OUTPUT_TECHNOLOGY_DISPLAYPORT_USB_TUNNEL => Some("USB-C"),
+        DISPLAYCONFIG_OUTPUT_TECHNOLOGY_INTERNAL => Some("Internal"),
+        _ => None,
+    }
+}
+
+/// Check whether advanced color (HDR) is enabled on a display path.
+#[cfg(windows)]
+fn is_hdr_enabled(path: &DISPLAYCONFIG_PATH_INFO) -> bool {
+    let mut info = DISPLAYCONFIG_GET_ADVANCED_COLOR_INFO {
+        header: DISPLAYCONFIG_DEVICE_INFO_HEADER {
+            r#type: DISPLAYCONFIG_DEVICE_INFO_GET_ADVANCED_COLOR_INFO,
+            size: std::mem::size_of::<DISPLAYCONFIG_GET_ADVANCED_COLOR_INFO>() as u32,
+            adapterId: path.targetInfo.adapterId,
+            id: path.targetInfo.id,
+        },
+        ..Default::default()
+    };
+
+    if unsafe { DisplayConfigGetDeviceInfo(&mut info.header) } != 0 {
+        return false;
+    }
+
+    // Bit 0 is advancedColorSupported, bit 1 advancedColorEnabled
+    unsafe { info.Anonymous.value & 0b10 != 0 }
+}
+
+/// Look up the target behind every active monitor, keyed by GDI device name such as
+/// `\\.\DISPLAY1`. The model is empty for monitors without a friendly name, like some
+/// projectors and virtual displays.
+#[cfg(windows)]
+pub fn display_targets() -> HashMap<String, DisplayTarget> {
+    let mut names = HashMap::new();
+
+    let (mut path_count, mut mode_count) = (0, 0);
+    if unsafe {
+        GetDisplayConfigBufferSizes(QDC_ONLY_ACTIVE_PATHS, &mut path_count, &mut mode_count)
+    } != ERROR_SUCCESS
+    {
+        return names;
+    }
+
+    let mut paths = vec![DISPLAYCONFIG_PATH_INFO::default(); path_count as usize];
+    let mut modes = vec![DISPLAYCONFIG_MODE_INFO::default(); mode_count as usize];
+
+    let result = unsafe {
+        QueryDisplayConfig(
+            QDC_ONLY_ACTIVE_PATHS,
+            &mut path_count,
+            paths.as_mut_ptr(),
+            &mut mode_count,
+            modes.as_mut_ptr(),
+            None,
+        )
+    };
+
+    if result != ERROR_SUCCESS {
+        return names;
+    }
+
+    paths.truncate(path_count as usize);
+
+    for path in &paths {
+        let mut source = DISPLAYCONFIG_SOURCE_DEVICE_NAME {
+            header: DISPLAYCONFIG_DEVICE_INFO_HEADER {
+                r#type: DISPLAYCONFIG_DEVICE_INFO_GET_SOURCE_NAME,
+                size: std::mem::size_of::<DISPLAYCONFIG_SOURCE_DEVICE_NAME>() as u32,
+                adapterId: path.sourceInfo.adapterId,
+                id: path.sourceInfo.id,
+            },
+            ..Default::default()
+        };
+
+        let mut target = DISPLAYCONFIG_TARGET_DEVICE_NAME {
+            header: DISPLAYCONFIG_DEVICE_INFO_HEADER {
+                r#type: DISPLAYCONFIG_DEVICE_INFO_GET_TARGET_NAME,
+                size: std::mem::size_of::<DISPLAYCONFIG_TARGET_DEVICE_NAME>() as u32,
+                adapterId: path.targetInfo.adapterId,
+                id: path.targetInfo.id,
+            },
+            ..Default::default()
+        };
+
+        let found = unsafe {
+            DisplayConfigGetDeviceInfo(&mut source.header) == 0
+                && DisplayConfigGetDeviceInfo(&mut target.header) == 0
+        };
+
+        if !found {
+            continue;
+        }
+
+        // Clone setups map several targets onto one source; the first one names it
+        names
+            .entry(from_wide(&source.viewGdiDeviceName))
+            .and_modify(|existing: &mut DisplayTarget| existing.clones += 1)
+            .or_insert(DisplayTarget {
+                model: from_wide(&target.monitorFriendlyDeviceName),
+                connector: connector_name(target.outputTechnology).map(str::to_string),
+                hdr: is_hdr_enabled(path),
+                clones: 0,
+            });
+    }
+
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(model: &str, connector: Option<&str>) -> DisplayTarget {
+        DisplayTarget {
+            model: model.to_string(),
+            connector: connector.map(str::to_string),
+            hdr: false,
+            clones: 0,
+        }
+    }
+
+    #[test]
+    fn unique_models_are_used_as_is() {
+        let labels = monitor_labels(&[
+            target("Dell U2720Q", Some("DisplayPort")),
+            target("LG 27GL850", Some("HDMI")),
+        ]);
+
+        assert_eq!(labels, ["Dell U2720Q", "LG 27GL850"]);
+    }
+
+    #[test]
+    fn repeated_models_get_their_connector() {
+        let labels = monitor_labels(&[
+            target("Dell U2720Q", Some("DisplayPort")),
+            target("Dell U2720Q", Some("HDMI")),
+        ]);
+
+        assert_eq!(labels, ["Dell U2720Q (DisplayPort)", "Dell U2720Q (HDMI)"]);
+    }
+
+    #[test]
+    fn repeated_connectors_fall_back_to_an_index() {
+        let labels = monitor_labels(&[
+            target("Dell U2720Q", Some("DisplayPort")),
+            target("Dell U2720Q", Some("DisplayPort")),
+            target("Dell U2720Q", Some("HDMI")),
+            target("LG 27GL850", None),
+        ]);
+
+        assert_eq!(
+            labels,
+            [
+                "Dell U2720Q #1",
+                "Dell U2720Q #2",
+                "Dell U2720Q (HDMI)",
+                "LG 27GL850"
+            ]
+        );
+    }
+
+    #[test]
+    fn mirrored_outputs_are_marked_duplicated() {
+        let labels = monitor_labels(&[
+            DisplayTarget {
+                clones: 1,
+                ..target("Dell U2720Q", Some("DisplayPort"))
+            },
+            target("LG 27GL850", None),
+        ]);
+
+        assert_eq!(labels, ["Dell U2720Q (duplicated)", "LG 27GL850"]);
+    }
+}
@@ -0,0 +1,885 @@
+use crate::{
+    backend::GammaBackend,
+    windows::{
+        backlight, ddc,
+        display::{
+            compute_gamma_ramp, DisplaySettings, GammaError, GammaRamp, Lut, MonitorInfo,
+            RAMP_TOLERANCE,
+        },
+    },
+};
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    sync::{
+        mpsc::{self, Receiver, RecvTimeoutError, Sender},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+/// How urgently an apply should be performed relative to applies queued for other monitors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ApplyPriority {
+    /// Background work such as scheduled changes.
+    Low,
+    /// Interactive changes from the settings and profiles tabs.
+    Normal,
+    /// Hotkey presses, which should feel instant.
+    High,
+}
+
+/// A request to write display settings to a monitor.
+#[derive(Debug, Clone)]
+pub struct ApplyRequest {
+    pub monitor: MonitorInfo,
+    pub settings: DisplaySettings,
+    pub priority: ApplyPriority,
+    /// Fade from the monitor's applied settings over this duration instead of applying at once.
+    pub transition: Option<Duration>,
+    /// Which of the monitor's own controls take over part of the settings.
+    pub hardware_control: HardwareControl,
+    /// Calibration table to pass the ramp through.
+    pub lut: Option<Arc<Lut>>,
+    /// Read the ramp back once written and fail if the driver didn't load it.
+    pub verify: bool,
+}
+
+/// Where brightness and contrast are applied, when not all through the gamma ramp.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HardwareControl {
+    /// Everything goes through the gamma ramp.
+    #[default]
+    None,
+    /// Brightness and contrast go to the monitor over DDC/CI.
+    Ddc,
+    /// Brightness goes to the panel backlight, as on laptops.
+    Backlight,
+}
+
+impl HardwareControl {
+    /// Describe where brightness is applied, for display.
+    pub fn format(&self) -> &'static str {
+        match self {
+            HardwareControl::None => "gamma ramp",
+            HardwareControl::Ddc => "monitor controls (DDC/CI)",
+            HardwareControl::Backlight => "panel backlight (WMI)",
+        }
+    }
+
+    /// Get the settings to write to the ramp once the hardware has taken its part.
+    pub fn ramp_settings(&self, settings: &DisplaySettings) -> DisplaySettings {
+        match self {
+            HardwareControl::None => *settings,
+            HardwareControl::Ddc => ddc::ramp_settings(settings),
+            HardwareControl::Backlight => backlight::ramp_settings(settings),
+        }
+    }
+}
+
+/// The outcome of an apply performed by the worker.
+#[derive(Debug)]
+pub struct ApplyResult {
+    pub monitor: MonitorInfo,
+    pub settings: DisplaySettings,
+    pub result: Result<(), GammaError>,
+    /// Whether the ramp could only be written to every monitor at once, not just this one.
+    pub global: bool,
+}
+
+/// Pending applies, holding at most one request per monitor.
+///
+/// A request for a monitor that already has one queued replaces it, keeping the higher of the
+/// two priorities, so only the latest settings are ever written. Requests are taken highest
+/// priority first and in arrival order within a priority.
+#[derive(Debug, Default)]
+pub struct ApplyQueue {
+    pending: Vec<ApplyRequest>,
+}
+
+impl ApplyQueue {
+    /// Create a new, empty ApplyQueue.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a request, replacing any stale request for the same monitor.
+    pub fn push(&mut self, request: ApplyRequest) {
+        let existing = self
+            .pending
+            .iter_mut()
+            .find(|pending| pending.monitor.id == request.monitor.id);
+
+        match existing {
+            Some(existing) => {
+                let priority = existing.priority.max(request.priority);
+                *existing = ApplyRequest {
+                    priority,
+                    ..request
+                };
+            }
+            None => self.pending.push(request),
+        }
+    }
+
+    /// Take the next request to perform.
+    pub fn pop(&mut self) -> Option<ApplyRequest> {
+        let (index, _) = self
+            .pending
+            .iter()
+            .enumerate()
+            .max_by_key(|(index, request)| (request.priority, Reverse(*index)))?;
+
+        Some(self.pending.remove(index))
+    }
+
+    /// Get the number of queued requests.
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Check whether the queue is empty.
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+/// How often a monitor's ramp was found overwritten and reapplied.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Reapplies {
+    pub count: usize,
+    pub last: Option<Instant>,
+}
+
+enum Message {
+    Apply(Box<ApplyRequest>),
+    Enforce,
+    Flush(Sender<()>),
+    Shutdown,
+}
+
+/// Background thread that performs every hardware apply.
+///
+/// Requests are coalesced per monitor while the worker is busy, fades are stepped between
+/// requests, and results are reported back through [`ApplyWorker::poll_results`].
+pub struct ApplyWorker {
+    sender: Sender<Message>,
+    results: Receiver<ApplyResult>,
+    applied: Arc<Mutex<HashMap<String, DisplaySettings>>>,
+    reapplies: Arc<Mutex<HashMap<String, Reapplies>>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl ApplyWorker {
+    /// Spawn a worker thread applying through the given backend.
+    pub fn spawn(backend: Arc<dyn GammaBackend>) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let (result_sender, results) = mpsc::channel();
+        let applied = Arc::new(Mutex::new(HashMap::new()));
+        let reapplies = Arc::new(Mutex::new(HashMap::new()));
+
+        let state = WorkerState {
+            backend,
+            receiver,
+            results: result_sender,
+            applied: applied.clone(),
+            reapplies: reapplies.clone(),
+            queue: ApplyQueue::new(),
+            fades: HashMap::new(),
+            hardware_applied: HashMap::new(),
+            written: HashMap::new(),
+            enforce_requested: false,
+            flush_waiters: Vec::new(),
+            running: true,
+            last_batch: None,
+        };
+
+        let handle = thread::Builder::new()
+            .name("gammar-apply".to_string())
+            .spawn(move || state.run())
+            .expect("failed to spawn apply worker");
+
+        Self {
+            sender,
+            results,
+            applied,
+            reapplies,
+            handle: Some(handle),
+        }
+    }
+
+    /// Queue an apply request.
+    pub fn enqueue(&self, request: ApplyRequest) {
+        let _ = self.sender.send(Message::Apply(Box::new(request)));
+    }
+
+    /// Check whether the ramps last written were overwritten by something else, and reapply the
+    /// ones that were. The check waits until nothing is queued or fading.
+    pub fn enforce(&self) {
+        let _ = self.sender.send(Message::Enforce);
+    }
+
+    /// Get how often a monitor's ramp was found overwritten and reapplied.
+    pub fn reapplies(&self, monitor_id: &str) -> Reapplies {
+        self.reapplies
+            .lock()
+            .unwrap()
+            .get(monitor_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Block until every request queued so far has been applied and any fades have finished.
+    pub fn flush(&self) {
+        let (sender, receiver) = mpsc::channel();
+
+        if self.sender.send(Message::Flush(sender)).is_ok() {
+            let _ = receiver.recv();
+        }
+    }
+
+    /// Take the results of applies completed since the last call.
+    pub fn poll_results(&self) -> Vec<ApplyResult> {
+        self.results.try_iter().collect()
+    }
+
+    /// Get the settings last successfully applied to a monitor.
+    pub fn applied_settings(&self, monitor_id: &str) -> Option<DisplaySettings> {
+        self.applied.lock().unwrap().get(monitor_id).copied()
+    }
+
+    /// Forget applied settings for monitors that no longer match the predicate.
+    pub fn retain_applied(&self, mut keep: impl FnMut(&str) -> bool) {
+        self.applied.lock().unwrap().retain(|id, _| keep(id));
+    }
+}
+
+impl Drop for ApplyWorker {
+    fn drop(&mut self) {
+        let _ = self.sender.send(Message::Shutdown);
+
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Interval between ramp updates while fading, and the shortest interval between applies.
+/// Requests arriving faster, like slider drags, coalesce into the latest one.
+const FADE_FRAME: Duration = Duration::from_millis(16);
+
+struct Fade {
+    monitor: MonitorInfo,
+    from: DisplaySettings,
+    to: DisplaySettings,
+    started: Instant,
+    duration: Duration,
+    hardware_control: HardwareControl,
+    lut: Option<Arc<Lut>>,
+    verify: bool,
+}
+
+/// A ramp to write as part of a batch.
+struct Write<'a> {
+    monitor: &'a MonitorInfo,
+    settings: DisplaySettings,
+    hardware_control: HardwareControl,
+    lut: Option<&'a Lut>,
+}
+
+struct WorkerState {
+    backend: Arc<dyn GammaBackend>,
+    receiver: Receiver<Message>,
+    results: Sender<ApplyResult>,
+    applied: Arc<Mutex<HashMap<String, DisplaySettings>>>,
+    reapplies: Arc<Mutex<HashMap<String, Reapplies>>>,
+    queue: ApplyQueue,
+    fades: HashMap<String, Fade>,
+    hardware_applied: HashMap<String, (HardwareControl, DisplaySettings)>,
+    /// The ramp last written to each monitor, to notice when something else replaces it.
+    written: HashMap<String, (MonitorInfo, GammaRamp)>,
+    enforce_requested: bool,
+    flush_waiters: Vec<Sender<()>>,
+    running: bool,
+    /// When queued requests were last applied.
+    last_batch: Option<Instant>,
+}
+
+impl WorkerState {
+    fn run(mut self) {
+        while self.running {
+            // Wait for work (or the next fade frame), then pick up everything else that arrived
+            // in the meantime so stale requests coalesce before anything is applied
+            let message = if self.fades.is_empty() {
+                match self.receiver.recv() {
+                    Ok(message) => Some(message),
+                    Err(_) => break,
+                }
+            } else {
+                match self.receiver.recv_timeout(FADE_FRAME) {
+                    Ok(message) => Some(message),
+                    Err(RecvTimeoutError::Timeout) => None,
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            };
+
+            if let Some(message) = message {
+                self.handle(message);
+            }
+
+            self.drain();
+            self.pace();
+
+            let requests: Vec<ApplyRequest> = std::iter::from_fn(|| self.queue.pop()).collect();
+            self.start(requests);
+
+            self.step_fades();
+
+            // Anything queued or fading rewrites the ramp anyway
+            if self.enforce_requested && self.fades.is_empty() {
+                self.enforce_requested = false;
+                self.enforce();
+            }
+
+            if self.fades.is_empty() {
+                for waiter in self.flush_waiters.drain(..) {
+                    let _ = waiter.send(());
+                }
+            }
+        }
+    }
+
+    /// Hold queued requests until a frame has passed since the last batch, so a burst of
+    /// requests for a monitor only writes the latest.
+    fn pace(&mut self) {
+        if self.queue.is_empty() {
+            return;
+        }
+
+        if let Some(wait) = self
+            .last_batch
+            .and_then(|last| FADE_FRAME.checked_sub(last.elapsed()))
+        {
+            thread::sleep(wait);
+            self.drain();
+        }
+
+        self.last_batch = Some(Instant::now());
+    }
+
+    fn drain(&mut self) {
+        while let Ok(message) = self.receiver.try_recv() {
+            self.handle(message);
+        }
+    }
+
+    fn handle(&mut self, message: Message) {
+        match message {
+            Message::Apply(request) => self.queue.push(*request),
+            Message::Enforce => self.enforce_requested = true,
+            Message::Flush(waiter) => self.flush_waiters.push(waiter),
+            Message::Shutdown => self.running = false,
+        }
+    }
+
+    /// Apply a batch of requests, or start fading towards them. Any fade already running on a
+    /// monitor is cancelled and the new one continues from wherever it got to.
+    ///
+    /// Hardware brightness and contrast are written once up front rather than faded, as DDC/CI
+    /// and WMI writes are far too slow to step every frame.
+    fn start(&mut self, requests: Vec<ApplyRequest>) {
+        let mut immediate = Vec::new();
+
+        for request in requests {
+            if request.hardware_control == HardwareControl::None {
+                self.hardware_applied.remove(&request.monitor.id);
+            } else if let Err(e) =
+                self.apply_hardware(&request.monitor, request.settings, request.hardware_control)
+            {
+                self.fades.remove(&request.monitor.id);
+                self.report(request.monitor, request.settings, Err(e));
+                continue;
+            }
+
+            let from = self
+                .applied
+                .lock()
+                .unwrap()
+                .get(&request.monitor.id)
+                .copied();
+
+            match (request.transition, from) {
+                (Some(duration), Some(from)) if from != request.settings => {
+                    self.fades.insert(
+                        request.monitor.id.clone(),
+                        Fade {
+                            monitor: request.monitor,
+                            from,
+                            to: request.settings,
+                            started: Instant::now(),
+                            duration,
+                            hardware_control: request.hardware_control,
+                            lut: request.lut,
+                            verify: request.verify,
+                        },
+                    );
+                }
+                _ => {
+                    self.fades.remove(&request.monitor.id);
+                    immediate.push(request);
+                }
+            }
+        }
+
+        let writes: Vec<Write> = immediate
+            .iter()
+            .map(|request| Write {
+                monitor: &request.monitor,
+                settings: request.settings,
+                hardware_control: request.hardware_control,
+                lut: request.lut.as_deref(),
+            })
+            .collect();
+        let results = self.apply(&writes);
+
+        for (request, result) in immediate.into_iter().zip(results) {
+            let result = result.and_then(|_| self.verify(&request.monitor, request.verify));
+            self.report(request.monitor, request.settings, result);
+        }
+    }
+
+    /// Write the next frame of every fade, all monitors at once.
+    fn step_fades(&mut self) {
+        let mut finished = Vec::new();
+        let fades = std::mem::take(&mut self.fades);
+
+        let frames: Vec<(f32, DisplaySettings)> = fades
+            .values()
+            .map(|fade| {
+                let t = fade.started.elapsed().as_secs_f32() / fade.duration.as_secs_f32();
+                (t, DisplaySettings::lerp(fade.from, fade.to, t))
+            })
+            .collect();
+        let writes: Vec<Write> = fades
+            .values()
+            .zip(&frames)
+            .map(|(fade, &(_, settings))| Write {
+                monitor: &fade.monitor,
+                settings,
+                hardware_control: fade.hardware_control,
+                lut: fade.lut.as_deref(),
+            })
+            .collect();
+        let results = self.apply(&writes);
+
+        for ((id, fade), ((t, settings), result)) in
+            fades.iter().zip(frames.into_iter().zip(results))
+        {
+            // Only the end of a fade, or a failure that aborts it, is worth reporting
+            if t >= 1.0 {
+                let result = result.and_then(|_| self.verify(&fade.monitor, fade.verify));
+                finished.push((id.clone(), settings, result));
+            } else if result.is_err() {
+                finished.push((id.clone(), settings, result));
+            }
+        }
+
+        self.fades = fades;
+
+        for (id, settings, result) in finished {
+            if let Some(fade) = self.fades.remove(&id) {
+                self.report(fade.monitor, settings, result);
+            }
+        }
+    }
+
+    /// Write the ramps of a batch through the backend in one go, so every monitor in it changes
+    /// in the same frame. Results are in the order of `writes`.
+    fn apply(&mut self, writes: &[Write]) -> Vec<Result<(), GammaError>> {
+        let ramps: Vec<GammaRamp> = writes
+            .iter()
+            .map(|write| {
+                let ramp =
+                    compute_gamma_ramp(&write.hardware_control.ramp_settings(&write.settings));
+
+                match write.lut {
+                    Some(lut) => lut.apply(&ramp),
+                    None => ramp,
+                }
+            })
+            .collect();
+
+        let batch: Vec<(&MonitorInfo, &GammaRamp)> = writes
+            .iter()
+            .map(|write| write.monitor)
+            .zip(&ramps)
+            .collect();
+        let mut results = if batch.is_empty() {
+            HashMap::new()
+        } else {
+            self.backend.apply_ramps(&batch)
+        };
+
+        writes
+            .iter()
+            .zip(ramps)
+            .map(|(write, ramp)| {
+                let monitor = write.monitor;
+                let result = results.remove(&monitor.id).unwrap_or_else(|| {
+                    Err(GammaError::MonitorNotFound {
+                        id: monitor.id.clone(),
+                    })
+                });
+
+                if result.is_ok() {
+                    self.written
+                        .insert(monitor.id.clone(), (monitor.clone(), ramp));
+                    self.applied
+                        .lock()
+                        .unwrap()
+                        .insert(monitor.id.clone(), write.settings);
+                }
+
+                result
+            })
+            .collect()
+    }
+
+    /// When requested, check that the ramp last written to a monitor is the one loaded. Drivers
+    /// that can't be read back are given the benefit of the doubt.
+    fn verify(&self, monitor: &MonitorInfo, requested: bool) -> Result<(), GammaError> {
+        let Some((_, written)) = self.written.get(&monitor.id).filter(|_| requested) else {
+            return Ok(());
+        };
+
+        match self.backend.read_ramp(monitor) {
+            Ok(loaded) if loaded.max_difference(written) > RAMP_TOLERANCE => {
+                Err(GammaError::RampNotApplied {
+                    monitor: monitor.name.clone(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Reapply every written ramp that no longer matches the one loaded, e.g. because a game
+    /// reset it. Reading a ramp is cheap, so this costs little when nothing changed.
+    fn enforce(&mut self) {
+        let applied = self.applied.lock().unwrap().clone();
+
+        // Monitors that were unplugged since are forgotten
+        self.written.retain(|id, _| applied.contains_key(id));
+
+        let overwritten: Vec<(MonitorInfo, GammaRamp)> = self
+            .written
+            .values()
+            .filter(|(monitor, ramp)| {
+                self.backend
+                    .read_ramp(monitor)
+                    .is_ok_and(|loaded| loaded.max_difference(ramp) > RAMP_TOLERANCE)
+            })
+            .cloned()
+            .collect();
+
+        for (monitor, ramp) in overwritten {
+            if let Err(e) = self.backend.apply_ramp(&monitor, &ramp) {
+                self.report(monitor.clone(), applied[&monitor.id], Err(e));
+                continue;
+            }
+
+            let mut reapplies = self.reapplies.lock().unwrap();
+            let entry = reapplies.entry(monitor.id.clone()).or_default();
+
+            entry.count += 1;
+            entry.last = Some(Instant::now());
+
+            println!(
+                "Gamma ramp of {} was overwritten by another application; reapplied ({} times \
+                 so far)",
+                monitor.name, entry.count
+            );
+        }
+    }
+
+    /// Write brightness and contrast to the monitor's own controls, skipping the write when they
+    /// haven't changed since the last one.
+    fn apply_hardware(
+        &mut self,
+        monitor: &MonitorInfo,
+        settings: DisplaySettings,
+        hardware_control: HardwareControl,
+    ) -> Result<(), GammaError> {
+        let unchanged = self
+            .hardware_applied
+            .get(&monitor.id)
+            .is_some_and(|(control, last)| {
+                *control == hardware_control
+                    && match hardware_control {
+                        // The backlight only takes whole percentages
+                        HardwareControl::Backlight => {
+                            backlight::brightness_to_percent(last.brightness)
+                                == backlight::brightness_to_percent(settings.brightness)
+                        }
+                        _ => {
+                            last.brightness == settings.brightness
+                                && last.contrast == settings.contrast
+                        }
+                    }
+            });
+
+        if unchanged {
+            return Ok(());
+        }
+
+        match hardware_control {
+            HardwareControl::None => {}
+            HardwareControl::Ddc => self.backend.apply_hardware(monitor, &settings)?,
+            HardwareControl::Backlight => self.backend.apply_backlight(monitor, &settings)?,
+        }
+
+        self.hardware_applied
+            .insert(monitor.id.clone(), (hardware_control, settings));
+
+        Ok(())
+    }
+
+    fn report(
+        &self,
+        monitor: MonitorInfo,
+        settings: DisplaySettings,
+        result: Result<(), GammaError>,
+    ) {
+        let global = result.is_ok() && self.backend.applied_globally(&monitor);
+
+        let _ = self.results.send(ApplyResult {
+            monitor,
+            settings,
+            result,
+            global,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        backend::mock::{monitor, MockBackend},
+        windows::display::compute_gamma_ramp,
+    };
+
+    fn request(id: &str, gamma: f32, priority: ApplyPriority) -> ApplyRequest {
+        ApplyRequest {
+            monitor: monitor(id, id, false),
+            settings: DisplaySettings::new(gamma, 0.0, 1.0),
+            priority,
+            transition: None,
+            hardware_control: HardwareControl::None,
+            lut: None,
+            verify: false,
+        }
+    }
+
+    fn fade(id: &str, gamma: f32, millis: u64) -> ApplyRequest {
+        ApplyRequest {
+            transition: Some(Duration::from_millis(millis)),
+            ..request(id, gamma, ApplyPriority::Normal)
+        }
+    }
+
+    #[test]
+    fn queue_coalesces_per_monitor() {
+        let mut queue = ApplyQueue::new();
+
+        queue.push(request("1", 1.1, ApplyPriority::Normal));
+        queue.push(request("2", 1.2, ApplyPriority::Normal));
+        queue.push(request("1", 1.3, ApplyPriority::Normal));
+
+        assert_eq!(queue.len(), 2);
+
+        let first = queue.pop().unwrap();
+        assert_eq!(first.monitor.id, "1");
+        assert_eq!(first.settings.gamma, 1.3);
+        assert_eq!(queue.pop().unwrap().monitor.id, "2");
+        assert!(queue.pop().is_none());
+    }
+
+    #[test]
+    fn queue_orders_by_priority_then_arrival() {
+        let mut queue = ApplyQueue::new();
+
+        queue.push(request("1", 1.0, ApplyPriority::Low));
+        queue.push(request("2", 1.0, ApplyPriority::Normal));
+        queue.push(request("3", 1.0, ApplyPriority::High));
+        queue.push(request("4", 1.0, ApplyPriority::Normal));
+
+        let order: Vec<_> = std::iter::from_fn(|| queue.pop())
+            .map(|r| r.monitor.id)
+            .collect();
+
+        assert_eq!(order, ["3", "2", "4", "1"]);
+    }
+
+    #[test]
+    fn coalescing_keeps_highest_priority() {
+        let mut queue = ApplyQueue::new();
+
+        queue.push(request("1", 1.0, ApplyPriority::Low));
+        queue.push(request("2", 1.0, ApplyPriority::High));
+        queue.push(request("1", 2.0, ApplyPriority::High));
+        queue.push(request("2", 2.0, ApplyPriority::Low));
+
+        let first = queue.pop().unwrap();
+        let second = queue.pop().unwrap();
+
+        assert_eq!(
+            (first.monitor.id.as_str(), first.priority),
+            ("1", ApplyPriority::High)
+        );
+        assert_eq!(
+            (second.monitor.id.as_str(), second.priority),
+            ("2", ApplyPriority::High)
+        );
+        assert_eq!(second.settings.gamma, 2.0);
+    }
+
+    #[test]
+    fn worker_applies_latest_settings() {
+        let backend = MockBackend::single();
+        let worker = ApplyWorker::spawn(Arc::new(backend.clone()));
+
+        for step in 1..=20 {
+            worker.enqueue(request(
+                "1",
+                1.0 + step as f32 * 0.05,
+                ApplyPriority::Normal,
+            ));
+        }
+
+        worker.flush();
+
+        let applied = backend.applied();
+        let expected = compute_gamma_ramp(&DisplaySettings::new(2.0, 0.0, 1.0));
+
+        assert!(!applied.is_empty() && applied.len() <= 20);
+        assert_eq!(applied.last().unwrap().1, expected);
+        assert_eq!(worker.applied_settings("1").unwrap().gamma, 2.0);
+    }
+
+    #[test]
+    fn worker_reports_results() {
+        let backend = MockBackend::multi();
+        backend.fail_monitor("2");
+        let worker = ApplyWorker::spawn(Arc::new(backend));
+
+        worker.enqueue(request("1", 1.5, ApplyPriority::Normal));
+        worker.flush();
+        worker.enqueue(request("2", 1.5, ApplyPriority::Normal));
+        worker.flush();
+
+        let results = worker.poll_results();
+
+        assert_eq!(results.len(), 2);
+        assert!(results[0].result.is_ok());
+        assert!(results[1].result.is_err());
+        assert!(worker.applied_settings("2").is_none());
+        assert!(worker.poll_results().is_empty());
+    }
+
+    #[test]
+    fn fades_on_several_monitors_step_together() {
+        let backend = MockBackend::multi();
+        let worker = ApplyWorker::spawn(Arc::new(backend.clone()));
+
+        for id in ["1", "2", "3"] {
+            worker.enqueue(request(id, 1.0, ApplyPriority::Normal));
+        }
+        worker.flush();
+        for id in ["1", "2", "3"] {
+            worker.enqueue(fade(id, 2.0, 100));
+        }
+        worker.flush();
+
+        assert!(backend.batch_sizes().contains(&3));
+        assert_eq!(worker.poll_results().len(), 6);
+    }
+
+    #[test]
+    fn fade_steps_towards_target() {
+        let backend = MockBackend::single();
+        let worker = ApplyWorker::spawn(Arc::new(backend.clone()));
+
+        worker.enqueue(request("1", 1.0, ApplyPriority::Normal));
+        worker.flush();
+        worker.enqueue(fade("1", 2.0, 100));
+        worker.flush();
+
+        let applied = backend.applied();
+        let target = compute_gamma_ramp(&DisplaySettings::new(2.0, 0.0, 1.0));
+
+        assert!(applied.len() > 2, "expected intermediate steps");
+        assert_eq!(applied.last().unwrap().1, target);
+        assert_eq!(worker.applied_settings("1").unwrap().gamma, 2.0);
+
+        // One result for the initial apply and one for the end of the fade
+        assert_eq!(worker.poll_results().len(), 2);
+    }
+
+    #[test]
+    fn fade_without_applied_settings_applies_at_once() {
+        let backend = MockBackend::single();
+        let worker = ApplyWorker::spawn(Arc::new(backend.clone()));
+
+        worker.enqueue(fade("1", 2.0, 10_000));
+        worker.flush();
+
+        assert_eq!(backend.apply_count(), 1);
+    }
+
+    #[test]
+    fn new_target_cancels_running_fade() {
+        let backend = MockBackend::single();
+        let worker = ApplyWorker::spawn(Arc::new(backend.clone()));
+
+        worker.enqueue(request("1", 1.0, ApplyPriority::Normal));
+        worker.flush();
+        worker.enqueue(fade("1", 3.0, 10_000));
+        thread::sleep(Duration::from_millis(50));
+        worker.enqueue(request("1", 0.5, ApplyPriority::High));
+
+        // Returns promptly because the long fade was cancelled
+        worker.flush();
+
+        assert_eq!(worker.applied_settings("1").unwrap().gamma, 0.5);
+        assert_eq!(
+            backend.applied().last().unwrap().1,
+            compute_gamma_ramp(&DisplaySettings::new(0.5, 0.0, 1.0))
+        );
+    }
+
+    #[test]
+    fn enforce_reapplies_overwritten_ramp() {
+        let backend = MockBackend::single();
+        let worker = ApplyWorker::spawn(Arc::new(backend.clone()));
+
+        worker.enqueue(request("1", 1.5, ApplyPriority::Normal));
+        worker.flush();
+
+        // Nothing changed, so nothing is reapplied
+        worker.enforce();
+        worker.flush();
+        assert_eq!(backend.apply_count(), 1);
+        assert_eq!(worker.reapplies("1").count, 0);
+
+        backend.set_hardware_ramp("1", compute_gamma_ramp(&DisplaySettings::default()));
+        worker.enforce();
+        worker.flush();
+
+        let expected = compute_gamma_ramp(&DisplaySettings::new(1.5, 0.0, 1.0));
+        assert_eq!(backend.apply_count(), 2);
+        assert_eq!(backend.applied().last().unwrap().1, expected);
+        assert_eq!(worker.reapplies("1").count, 1);
+        assert!(worker.reapplies("1").last.is_some());
+    }
+}
@@ -0,0 +1,506 @@
+use gammar::{
+    backend::{
+        mock::{monitor, MockBackend},
+        GammaBackend,
+    },
+    controller::GammaController,
+    windows::{
+        display::{
+            compute_gamma_ramp, find_monitor, DisplaySettings, GammaError, Lut, MonitorInfo,
+        },
+        hotkeys::HotkeyAction,
+    },
+    AppConfig,
+};
+
+fn config_with_monitor(id: &str) -> AppConfig {
+    AppConfig {
+        selected_monitor_id: id.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn find_monitor_prefers_primary_without_id() {
+    let monitors = [
+        monitor("1", "\\\\.\\DISPLAY1", false),
+        monitor("2", "\\\\.\\DISPLAY2", true),
+    ];
+
+    assert_eq!(find_monitor(&monitors, None).unwrap().id, "2");
+}
+
+#[test]
+fn find_monitor_falls_back_to_first_without_primary() {
+    let monitors = [
+        monitor("1", "\\\\.\\DISPLAY1", false),
+        monitor("2", "\\\\.\\DISPLAY2", false),
+    ];
+
+    assert_eq!(find_monitor(&monitors, None).unwrap().id, "1");
+}
+
+#[test]
+fn find_monitor_with_unknown_id_finds_nothing() {
+    let monitors = [monitor("1", "\\\\.\\DISPLAY1", true)];
+
+    assert!(find_monitor(&monitors, Some("7")).is_none());
+    assert!(find_monitor(&[], None).is_none());
+}
+
+fn with_legacy_id(monitor: MonitorInfo, legacy_id: &str) -> MonitorInfo {
+    MonitorInfo {
+        legacy_id: Some(legacy_id.to_string()),
+        ..monitor
+    }
+}
+
+#[test]
+fn find_monitor_resolves_legacy_ids() {
+    let monitors = [
+        with_legacy_id(
+            monitor("\\\\?\\DISPLAY#DEL40F4#1", "\\\\.\\DISPLAY1", true),
+            "65537",
+        ),
+        with_legacy_id(
+            monitor("\\\\?\\DISPLAY#DEL40F4#2", "\\\\.\\DISPLAY2", false),
+            "131073",
+        ),
+    ];
+
+    let found = find_monitor(&monitors, Some("131073")).unwrap();
+    assert_eq!(found.device_name, "\\\\.\\DISPLAY2");
+
+    let found = find_monitor(&monitors, Some("\\\\?\\DISPLAY#DEL40F4#2")).unwrap();
+    assert_eq!(found.device_name, "\\\\.\\DISPLAY2");
+}
+
+#[test]
+fn legacy_selection_migrates_to_stable_id() {
+    let stable_id = "\\\\?\\DISPLAY#DEL40F4#2";
+    let backend = MockBackend::new(vec![
+        monitor("\\\\?\\DISPLAY#DEL40F4#1", "\\\\.\\DISPLAY1", true),
+        with_legacy_id(monitor(stable_id, "\\\\.\\DISPLAY2", false), "131073"),
+    ]);
+    let controller = GammaController::new(config_with_monitor("131073"), Box::new(backend));
+
+    assert_eq!(controller.config().selected_monitor_id, stable_id);
+}
+
+#[test]
+fn single_monitor_is_selected_by_default() {
+    let controller = GammaController::new(AppConfig::default(), Box::new(MockBackend::single()));
+
+    assert_eq!(controller.config().selected_monitor_id, "1");
+    assert_eq!(controller.monitors().len(), 1);
+}
+
+#[test]
+fn configured_secondary_monitor_is_kept() {
+    let controller = GammaController::new(config_with_monitor("3"), Box::new(MockBackend::multi()));
+
+    assert_eq!(controller.selected_monitor().unwrap().id, "3");
+}
+
+#[test]
+fn stale_selection_migrates_to_primary() {
+    let controller =
+        GammaController::new(config_with_monitor("42"), Box::new(MockBackend::multi()));
+
+    assert_eq!(controller.config().selected_monitor_id, "1");
+}
+
+#[test]
+fn missing_primary_selects_first_monitor() {
+    let controller = GammaController::new(
+        AppConfig::default(),
+        Box::new(MockBackend::primary_missing()),
+    );
+
+    assert_eq!(controller.config().selected_monitor_id, "1");
+}
+
+#[test]
+fn duplicate_device_names_are_addressed_by_id() {
+    let backend = MockBackend::duplicate_device_names();
+    let mut controller = GammaController::new(AppConfig::default(), Box::new(backend.clone()));
+    let dim = DisplaySettings::new(1.0, -0.4, 1.0);
+
+    controller.set_settings("2", dim).unwrap();
+    controller.flush();
+
+    let applied = backend.applied();
+    assert_eq!(applied.len(), 1);
+    assert_eq!(applied[0].0, "2");
+    assert_eq!(controller.applied_settings("2"), Some(dim));
+    assert_eq!(controller.applied_settings("1"), None);
+}
+
+#[test]
+fn lut_is_applied_beneath_settings_and_cleared() {
+    let backend = MockBackend::single();
+    let mut controller = GammaController::new(AppConfig::default(), Box::new(backend.clone()));
+    let lut = Lut::parse_cube("warm.cube", "LUT_1D_SIZE 2\n0 0 0\n1 0.9 0.7\n").unwrap();
+    let settings = DisplaySettings::new(1.2, 0.0, 1.0);
+
+    controller.set_current_settings(settings).unwrap();
+    controller.set_lut("1", Some(lut.clone())).unwrap();
+    controller.flush();
+
+    let expected = lut.apply(&compute_gamma_ramp(&settings));
+    assert_eq!(backend.applied().last().unwrap().1, expected);
+    assert!(controller.hardware_mismatch().is_none());
+
+    controller.set_lut("1", None).unwrap();
+    controller.flush();
+
+    assert_eq!(
+        backend.applied().last().unwrap().1,
+        compute_gamma_ramp(&settings)
+    );
+    assert!(controller.lut("1").is_none());
+}
+
+#[test]
+fn restricted_gamma_range_rejects_extreme_ramps() {
+    let backend = MockBackend::single();
+    backend.restrict_gamma_range();
+    let mut controller = GammaController::new(AppConfig::default(), Box::new(backend.clone()));
+
+    assert!(!controller.gamma_range_unlocked());
+
+    controller
+        .set_current_settings(DisplaySettings::new(1.0, 0.9, 1.0))
+        .unwrap();
+    controller.flush();
+
+    assert!(controller.exceeds_gamma_range());
+    let results = controller.poll_results();
+    let error = results[0].result.as_ref().unwrap_err();
+    assert!(
+        matches!(error, GammaError::RangeRestricted { .. }),
+        "{error}"
+    );
+    assert!(error.to_string().contains("GdiIcmGammaRange"), "{error}");
+
+    controller.unlock_gamma_range().unwrap();
+    assert_eq!(backend.unlock_requests(), 1);
+}
+
+#[test]
+fn enforcing_reapplies_overwritten_ramps_unless_paused() {
+    let backend = MockBackend::single();
+    let mut config = AppConfig::default();
+    config.enforce.enabled = true;
+    let mut controller = GammaController::new(config, Box::new(backend.clone()));
+
+    let settings = DisplaySettings::new(1.4, 0.0, 1.0);
+    controller.set_current_settings(settings).unwrap();
+    controller.flush();
+
+    let foreign = compute_gamma_ramp(&DisplaySettings::default());
+
+    controller.set_enforce_paused(true);
+    backend.set_hardware_ramp("1", foreign);
+    controller.enforce();
+    controller.flush();
+    assert_eq!(controller.reapplies("1").count, 0);
+
+    controller.set_enforce_paused(false);
+    controller.enforce();
+    controller.flush();
+    assert_eq!(controller.reapplies("1").count, 1);
+    assert_eq!(
+        backend.applied().last().unwrap().1,
+        compute_gamma_ramp(&settings)
+    );
+}
+
+#[test]
+fn remote_session_skips_enforcing_until_back_on_console() {
+    let backend = MockBackend::single();
+    backend.set_remote_session(true);
+    let mut config = AppConfig::default();
+    config.enforce.enabled = true;
+    config.current_settings = DisplaySettings::new(1.4, 0.0, 1.0);
+    let mut controller = GammaController::new(config, Box::new(backend.clone()));
+
+    assert!(controller.remote_session());
+
+    controller.enforce();
+    controller.flush();
+    assert_eq!(backend.apply_count(), 0);
+
+    backend.set_remote_session(false);
+    assert!(controller.session_changed());
+    assert!(!controller.refresh_session());
+    controller.flush();
+
+    assert_eq!(
+        backend.applied().last().unwrap().1,
+        compute_gamma_ramp(&DisplaySettings::new(1.4, 0.0, 1.0))
+    );
+}
+
+#[test]
+fn verified_applies_catch_ignored_ramps() {
+    let backend = MockBackend::single();
+    backend.ignore_ramps("1");
+    let mut config = config_with_monitor("1");
+    config.current_settings = DisplaySettings::new(1.4, 0.0, 1.0);
+    let mut controller = GammaController::new(config, Box::new(backend.clone()));
+
+    controller.apply_current().unwrap();
+    controller.flush();
+    assert!(controller.poll_results().iter().all(|r| r.result.is_ok()));
+
+    controller.apply_current_verified().unwrap();
+    controller.flush();
+    let results = controller.poll_results();
+
+    assert!(matches!(
+        results.last().unwrap().result,
+        Err(GammaError::RampNotApplied { .. })
+    ));
+}
+
+#[test]
+fn global_dc_fallback_is_reported_and_can_be_disabled() {
+    let backend = MockBackend::multi();
+    backend.require_global_dc("2");
+    let mut config = config_with_monitor("2");
+    config.current_settings = DisplaySettings::new(1.4, 0.0, 1.0);
+    let mut controller = GammaController::new(config, Box::new(backend.clone()));
+
+    controller.apply_current().unwrap();
+    controller.flush();
+    let result = controller.poll_results().pop().unwrap();
+
+    assert!(result.result.is_ok() && result.global);
+    let primary = find_monitor(controller.monitors(), Some("1")).unwrap();
+    assert_eq!(
+        backend.read_ramp(&primary).unwrap(),
+        compute_gamma_ramp(&DisplaySettings::new(1.4, 0.0, 1.0))
+    );
+
+    controller.set_global_dc_fallback(false);
+    controller.apply_current().unwrap();
+    controller.flush();
+    let result = controller.poll_results().pop().unwrap();
+
+    assert!(!result.global);
+    assert!(matches!(
+        result.result,
+        Err(GammaError::DcCreationFailed { .. })
+    ));
+    assert!(controller.config().disable_global_dc_fallback);
+}
+
+#[test]
+fn selecting_unknown_monitor_reports_not_found() {
+    let mut controller =
+        GammaController::new(AppConfig::default(), Box::new(MockBackend::single()));
+
+    let error = controller.select_monitor("9").unwrap_err();
+
+    assert!(matches!(error, GammaError::MonitorNotFound { ref id } if id == "9"));
+}
+
+#[test]
+fn no_monitors_leaves_selection_untouched() {
+    let controller = GammaController::new(
+        config_with_monitor("2"),
+        Box::new(MockBackend::new(Vec::new())),
+    );
+
+    assert_eq!(controller.config().selected_monitor_id, "2");
+    assert!(controller.selected_monitor().is_none());
+}
+
+#[test]
+fn unplugging_selected_monitor_falls_back_to_primary() {
+    let backend = MockBackend::multi();
+    let mut controller = GammaController::new(config_with_monitor("2"), Box::new(backend.clone()));
+
+    backend.queue_topologies([vec![
+        monitor("1", "\\\\.\\DISPLAY1", true),
+        monitor("3", "\\\\.\\DISPLAY3", false),
+    ]]);
+    controller.refresh_monitors();
+
+    assert_eq!(controller.monitors().len(), 2);
+    assert_eq!(controller.config().selected_monitor_id, "1");
+}
+
+#[test]
+fn hot_plug_sequence_updates_monitor_list() {
+    let backend = MockBackend::single();
+    let mut controller = GammaController::new(AppConfig::default(), Box::new(backend.clone()));
+
+    backend.queue_topologies([
+        vec![
+            monitor("1", "\\\\.\\DISPLAY1", true),
+            monitor("2", "\\\\.\\DISPLAY2", false),
+        ],
+        vec![monitor("2", "\\\\.\\DISPLAY2", true)],
+    ]);
+
+    controller.refresh_monitors();
+    assert_eq!(controller.monitors().len(), 2);
+    assert_eq!(controller.config().selected_monitor_id, "1");
+
+    controller.refresh_monitors();
+    assert_eq!(controller.monitors().len(), 1);
+    assert_eq!(controller.config().selected_monitor_id, "2");
+
+    // The last topology sticks once the queue is drained
+    controller.refresh_monitors();
+    assert_eq!(controller.monitors()[0].id, "2");
+}
+
+#[test]
+fn unplugging_forgets_applied_settings() {
+    let backend = MockBackend::multi();
+    let mut controller = GammaController::new(AppConfig::default(), Box::new(backend.clone()));
+
+    controller
+        .set_settings("3", DisplaySettings::new(2.0, 0.0, 1.0))
+        .unwrap();
+
+    backend.set_monitors(vec![monitor("1", "\\\\.\\DISPLAY1", true)]);
+    controller.refresh_monitors();
+
+    assert_eq!(controller.applied_settings("3"), None);
+}
+
+#[test]
+fn monitor_without_gamma_support_is_skipped() {
+    let backend = MockBackend::new(vec![
+        monitor("1", "\\\\.\\DISPLAY1", true),
+        MonitorInfo {
+            supports_gamma: false,
+            ..monitor("2", "\\\\.\\DISPLAY2", false)
+        },
+    ]);
+    let mut controller = GammaController::new(AppConfig::default(), Box::new(backend.clone()));
+
+    controller.select_monitor("2").unwrap();
+    assert!(controller.gamma_controls_unavailable());
+
+    let before = controller.config().current_settings;
+    assert!(controller.adjust(HotkeyAction::IncreaseGamma).is_err());
+    assert_eq!(controller.config().current_settings, before);
+
+    controller.set_apply_to_all_monitors(true).unwrap();
+    controller
+        .set_current_settings(DisplaySettings::new(1.4, 0.0, 1.0))
+        .unwrap();
+    controller.flush();
+
+    assert!(controller
+        .poll_results()
+        .iter()
+        .all(|r| r.monitor.id == "1"));
+}
+
+#[test]
+fn failing_monitor_reports_error() {
+    let backend = MockBackend::multi();
+    backend.fail_monitor("2");
+    let mut controller = GammaController::new(AppConfig::default(), Box::new(backend.clone()));
+
+    controller
+        .set_settings("2", DisplaySettings::default())
+        .unwrap();
+    controller
+        .set_settings("1", DisplaySettings::default())
+        .unwrap();
+    controller.flush();
+
+    let results = controller.poll_results();
+    let failed = results.iter().find(|r| r.monitor.id == "2").unwrap();
+    let succeeded = results.iter().find(|r| r.monitor.id == "1").unwrap();
+
+    assert!(failed.result.is_err());
+    assert!(succeeded.result.is_ok());
+    assert_eq!(backend.apply_count(), 1);
+}
+
+#[test]
+fn unplugging_selected_monitor_reports_it_lost() {
+    let backend = MockBackend::multi();
+    let mut controller = GammaController::new(config_with_monitor("2"), Box::new(backend.clone()));
+
+    backend.set_monitors(vec![monitor("1", "\\\\.\\DISPLAY1", true)]);
+    let change = controller.refresh_monitors();
+
+    assert_eq!(change.lost.unwrap().id, "2");
+    assert_eq!(change.restored, None);
+    assert_eq!(controller.config().selected_monitor_id, "1");
+}
+
+#[test]
+fn returning_monitor_is_reselected_and_reapplied() {
+    let backend = MockBackend::multi();
+    let settings = DisplaySettings::new(1.6, -0.1, 1.2);
+    let config = AppConfig {
+        current_settings: settings,
+        ..config_with_monitor("2")
+    };
+    let mut controller = GammaController::new(config, Box::new(backend.clone()));
+
+    backend.queue_topologies([
+        vec![monitor("1", "\\\\.\\DISPLAY1", true)],
+        vec![
+            monitor("1", "\\\\.\\DISPLAY1", true),
+            monitor("2", "\\\\.\\DISPLAY2", false),
+        ],
+    ]);
+
+    controller.refresh_monitors();
+    assert_eq!(controller.config().selected_monitor_id, "1");
+
+    let change = controller.refresh_monitors();
+    controller.flush();
+
+    assert_eq!(change.restored.unwrap().id, "2");
+    assert_eq!(controller.config().selected_monitor_id, "2");
+    assert_eq!(controller.applied_settings("2"), Some(settings));
+}
+
+#[test]
+fn manual_selection_forgets_displaced_monitor() {
+    let backend = MockBackend::multi();
+    let mut controller = GammaController::new(config_with_monitor("2"), Box::new(backend.clone()));
+
+    backend.queue_topologies([
+        vec![
+            monitor("1", "\\\\.\\DISPLAY1", true),
+            monitor("3", "\\\\.\\DISPLAY3", false),
+        ],
+        vec![
+            monitor("1", "\\\\.\\DISPLAY1", true),
+            monitor("2", "\\\\.\\DISPLAY2", false),
+            monitor("3", "\\\\.\\DISPLAY3", false),
+        ],
+    ]);
+
+    controller.refresh_monitors();
+    controller.select_monitor("3").unwrap();
+
+    let change = controller.refresh_monitors();
+
+    assert_eq!(change, Default::default());
+    assert_eq!(controller.config().selected_monitor_id, "3");
+}
+
+#[test]
+fn topology_changes_are_detected() {
+    let backend = MockBackend::single();
+    let controller = GammaController::new(AppConfig::default(), Box::new(backend.clone()));
+
+    assert!(!controller.topology_changed());
+
+    backend.set_monitors(Vec::new());
+    assert!(controller.topology_changed());
+}